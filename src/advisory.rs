@@ -0,0 +1,98 @@
+#[cfg(feature = "pricing")]
+use crate::auth::Role;
+#[cfg(feature = "pricing")]
+use crate::ledger::Transaction;
+use crate::money::Money;
+#[cfg(feature = "pricing")]
+use crate::performance::value_as_of;
+use crate::period::Period;
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+#[cfg(feature = "pricing")]
+use crate::PortfolioError;
+use crate::{Portfolio, PortfolioResult};
+#[cfg(feature = "pricing")]
+use chrono::NaiveTime;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub trait FeeSchedule {
+    fn fee_for(&self, average_balance: &Money, year_fraction: Decimal) -> PortfolioResult<Money>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AumFee {
+    pub annual_rate: Decimal,
+}
+
+impl FeeSchedule for AumFee {
+    fn fee_for(&self, average_balance: &Money, year_fraction: Decimal) -> PortfolioResult<Money> {
+        average_balance.checked_mul(self.annual_rate * year_fraction)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct AdvisoryFee {
+    pub period: Period,
+    pub average_balance: Money,
+    pub amount: Money,
+    pub date: DateTime<Utc>,
+}
+
+#[cfg(feature = "pricing")]
+impl Portfolio {
+    pub fn average_balance(
+        &self,
+        period: &Period,
+        prices: &PriceHistory,
+    ) -> PortfolioResult<Money> {
+        let mut total = Money::zero(self.config.base_currency);
+        for date in period.iter_days() {
+            total = total.checked_add(&value_as_of(self, prices, date)?)?;
+        }
+        let days = Decimal::from(period.days().max(1));
+        Ok(Money::new(total.amount / days, total.currency))
+    }
+
+    pub fn accrue_advisory_fee(
+        &mut self,
+        schedule: &impl FeeSchedule,
+        year: i32,
+        month: u32,
+        prices: &PriceHistory,
+    ) -> PortfolioResult<AdvisoryFee> {
+        self.authorize(Role::Trader)?;
+        let period = Period::month(year, month).ok_or_else(|| {
+            PortfolioError::InvalidConfig(format!("invalid month {year}-{month}"))
+        })?;
+        if self.advisory_fees.iter().any(|fee| fee.period == period) {
+            return Err(PortfolioError::FeeAlreadyAccrued(period.start));
+        }
+        let average_balance = self.average_balance(&period, prices)?;
+        let next_day = period.end.succ_opt().unwrap_or(period.end);
+        let year_fraction = self.config.day_count.year_fraction(period.start, next_day);
+        let amount = schedule.fee_for(&average_balance, year_fraction)?;
+        let amount = Money::new(
+            amount.amount.round_dp(amount.currency.minor_units()),
+            amount.currency,
+        );
+        self.validate_amount(&amount)?;
+        let fee = AdvisoryFee {
+            period,
+            average_balance,
+            amount,
+            date: period.end.and_time(NaiveTime::MIN).and_utc(),
+        };
+        self.commit_entry(Transaction::AdvisoryFee { fee: fee.clone() })?;
+        Ok(fee)
+    }
+}
+
+impl Portfolio {
+    pub fn advisory_fees(&self) -> &[AdvisoryFee] {
+        &self.advisory_fees
+    }
+}
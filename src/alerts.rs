@@ -0,0 +1,255 @@
+use crate::auth::Role;
+#[cfg(feature = "pricing")]
+use crate::events::PortfolioEvent;
+#[cfg(feature = "pricing")]
+use crate::money::Money;
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+#[cfg(feature = "pricing")]
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub type AlertId = u64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum AlertCondition {
+    PriceAbove {
+        symbol: String,
+        price: Decimal,
+    },
+    PriceBelow {
+        symbol: String,
+        price: Decimal,
+    },
+    DrawdownFromHigh {
+        symbol: String,
+        percent: Decimal,
+    },
+    PercentBelowBasis {
+        symbol: String,
+        percent: Decimal,
+    },
+    AllocationDrift {
+        symbol: String,
+        target_percent: Decimal,
+        tolerance_percent: Decimal,
+    },
+    DistributionReceived {
+        symbol: String,
+    },
+    All(Vec<AlertCondition>),
+    Any(Vec<AlertCondition>),
+}
+
+impl AlertCondition {
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            AlertCondition::PriceAbove { symbol, .. }
+            | AlertCondition::PriceBelow { symbol, .. }
+            | AlertCondition::DrawdownFromHigh { symbol, .. }
+            | AlertCondition::PercentBelowBasis { symbol, .. }
+            | AlertCondition::AllocationDrift { symbol, .. }
+            | AlertCondition::DistributionReceived { symbol } => Some(symbol),
+            AlertCondition::All(conditions) | AlertCondition::Any(conditions) => {
+                conditions.iter().find_map(AlertCondition::symbol)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum AlertState {
+    Armed,
+    Triggered,
+    Acknowledged,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Alert {
+    pub id: AlertId,
+    pub name: String,
+    pub condition: AlertCondition,
+    pub state: AlertState,
+}
+
+#[cfg(feature = "pricing")]
+fn percent_change(from: Decimal, to: Decimal) -> Decimal {
+    if from.is_zero() {
+        return Decimal::ZERO;
+    }
+    (to - from) / from * Decimal::ONE_HUNDRED
+}
+
+impl Portfolio {
+    pub fn add_alert(&mut self, name: &str, condition: AlertCondition) -> PortfolioResult<AlertId> {
+        self.authorize(Role::Trader)?;
+        let id = self.alerts.last().map_or(0, |alert| alert.id + 1);
+        self.alerts.push(Alert {
+            id,
+            name: name.to_string(),
+            condition,
+            state: AlertState::Armed,
+        });
+        Ok(id)
+    }
+
+    pub fn alerts(&self) -> &[Alert] {
+        &self.alerts
+    }
+
+    fn alert_mut(&mut self, id: AlertId) -> PortfolioResult<&mut Alert> {
+        self.alerts
+            .iter_mut()
+            .find(|alert| alert.id == id)
+            .ok_or(PortfolioError::UnknownAlert(id))
+    }
+
+    pub fn acknowledge_alert(&mut self, id: AlertId) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        let alert = self.alert_mut(id)?;
+        if alert.state == AlertState::Triggered {
+            alert.state = AlertState::Acknowledged;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "pricing")]
+impl Portfolio {
+    fn average_cost(&self, symbol: &str) -> Option<Decimal> {
+        let lots = self.lots.get(symbol)?;
+        let shares: u32 = lots.iter().map(|lot| lot.shares).sum();
+        if shares == 0 {
+            return None;
+        }
+        let basis: Decimal = lots.iter().map(|lot| lot.cost_basis.amount).sum();
+        Some(basis / Decimal::from(shares))
+    }
+
+    fn weight_as_of(
+        &self,
+        symbol: &str,
+        prices: &PriceHistory,
+        date: NaiveDate,
+    ) -> PortfolioResult<Decimal> {
+        let mut total = Money::zero(self.config.base_currency);
+        let mut position = Money::zero(self.config.base_currency);
+        for traded in self.traded_symbols() {
+            let shares = self.get_share_count_as_of(traded, date);
+            if shares == 0 {
+                continue;
+            }
+            let value = prices.price_on(traded, date)?.checked_mul(shares.into())?;
+            total = total.checked_add(&value)?;
+            if traded == symbol {
+                position = value;
+            }
+        }
+        if total.is_zero() {
+            return Ok(Decimal::ZERO);
+        }
+        Ok(position.amount / total.amount * Decimal::ONE_HUNDRED)
+    }
+
+    fn alert_condition_holds(
+        &self,
+        condition: &AlertCondition,
+        prices: &PriceHistory,
+        date: NaiveDate,
+    ) -> PortfolioResult<bool> {
+        Ok(match condition {
+            AlertCondition::PriceAbove { symbol, price } => {
+                prices.price_on(symbol, date)?.amount > *price
+            }
+            AlertCondition::PriceBelow { symbol, price } => {
+                prices.price_on(symbol, date)?.amount < *price
+            }
+            AlertCondition::DrawdownFromHigh { symbol, percent } => {
+                let current = prices.price_on(symbol, date)?.amount;
+                let high = prices
+                    .series(symbol)
+                    .take_while(|(day, _)| *day <= date)
+                    .map(|(_, close)| close.amount)
+                    .max()
+                    .unwrap_or(current);
+                -percent_change(high, current) >= *percent
+            }
+            AlertCondition::PercentBelowBasis { symbol, percent } => {
+                match self.average_cost(symbol) {
+                    Some(cost) => {
+                        -percent_change(cost, prices.price_on(symbol, date)?.amount) >= *percent
+                    }
+                    None => false,
+                }
+            }
+            AlertCondition::AllocationDrift {
+                symbol,
+                target_percent,
+                tolerance_percent,
+            } => {
+                (self.weight_as_of(symbol, prices, date)? - target_percent).abs()
+                    > *tolerance_percent
+            }
+            AlertCondition::DistributionReceived { symbol } => self
+                .get_capital_gain_distributions(symbol)
+                .iter()
+                .any(|distribution| distribution.date.date_naive() == date),
+            AlertCondition::All(conditions) => {
+                for condition in conditions {
+                    if !self.alert_condition_holds(condition, prices, date)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            AlertCondition::Any(conditions) => {
+                for condition in conditions {
+                    if self.alert_condition_holds(condition, prices, date)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+        })
+    }
+
+    pub fn check_alerts(
+        &mut self,
+        prices: &PriceHistory,
+        date: NaiveDate,
+    ) -> PortfolioResult<Vec<AlertId>> {
+        self.authorize(Role::Trader)?;
+        let mut transitions = Vec::new();
+        for alert in &self.alerts {
+            let holds = self.alert_condition_holds(&alert.condition, prices, date)?;
+            let next = match (alert.state, holds) {
+                (AlertState::Armed, true) => AlertState::Triggered,
+                (AlertState::Acknowledged, false) => AlertState::Armed,
+                (state, _) => state,
+            };
+            if next != alert.state {
+                transitions.push((alert.id, next));
+            }
+        }
+        let mut triggered = Vec::new();
+        for (id, state) in transitions {
+            let alert = self.alert_mut(id)?;
+            alert.state = state;
+            if state == AlertState::Triggered {
+                let event = PortfolioEvent::Alert {
+                    symbol: alert.condition.symbol().unwrap_or_default().to_string(),
+                    message: alert.name.clone(),
+                };
+                self.publish(event);
+                triggered.push(id);
+            }
+        }
+        Ok(triggered)
+    }
+}
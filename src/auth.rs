@@ -0,0 +1,168 @@
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Actor(String);
+
+impl Actor {
+    pub fn new(user: &str) -> Self {
+        Self(user.to_string())
+    }
+
+    pub fn user(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Actor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Role {
+    Viewer,
+    Trader,
+    Owner,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct AccessControl {
+    owner: Actor,
+    members: BTreeMap<Actor, Role>,
+}
+
+impl AccessControl {
+    pub fn new(owner: Actor) -> Self {
+        Self {
+            owner,
+            members: BTreeMap::new(),
+        }
+    }
+
+    pub fn owner(&self) -> &Actor {
+        &self.owner
+    }
+
+    pub fn members(&self) -> &BTreeMap<Actor, Role> {
+        &self.members
+    }
+
+    pub fn role_of(&self, actor: &Actor) -> Option<Role> {
+        if *actor == self.owner {
+            return Some(Role::Owner);
+        }
+        self.members.get(actor).copied()
+    }
+
+    pub fn require(&self, actor: &Actor, role: Role) -> PortfolioResult<()> {
+        match self.role_of(actor) {
+            Some(granted) if granted >= role => Ok(()),
+            _ => Err(PortfolioError::PermissionDenied(actor.to_string())),
+        }
+    }
+}
+
+pub struct Acting<'a> {
+    portfolio: &'a mut Portfolio,
+    actor: Actor,
+    previous: Option<Actor>,
+}
+
+impl Acting<'_> {
+    pub fn actor(&self) -> &Actor {
+        &self.actor
+    }
+
+    pub fn view(&self) -> &Portfolio {
+        self.portfolio
+    }
+
+    pub fn grant(&mut self, member: Actor, role: Role) -> PortfolioResult<()> {
+        self.portfolio.authorize(Role::Owner)?;
+        let access = self.portfolio.access_mut()?;
+        if member != access.owner {
+            access.members.insert(member, role);
+        }
+        Ok(())
+    }
+
+    pub fn revoke(&mut self, member: &Actor) -> PortfolioResult<()> {
+        self.portfolio.authorize(Role::Owner)?;
+        self.portfolio.access_mut()?.members.remove(member);
+        Ok(())
+    }
+}
+
+impl Deref for Acting<'_> {
+    type Target = Portfolio;
+
+    fn deref(&self) -> &Portfolio {
+        self.portfolio
+    }
+}
+
+impl DerefMut for Acting<'_> {
+    fn deref_mut(&mut self) -> &mut Portfolio {
+        self.portfolio
+    }
+}
+
+impl Drop for Acting<'_> {
+    fn drop(&mut self) {
+        self.portfolio.actor = self.previous.take();
+    }
+}
+
+impl Portfolio {
+    pub fn with_owner(owner: Actor) -> Self {
+        let mut portfolio = Self::new();
+        portfolio.access = Some(AccessControl::new(owner));
+        portfolio
+    }
+
+    pub fn access_control(&self) -> Option<&AccessControl> {
+        self.access.as_ref()
+    }
+
+    fn access_mut(&mut self) -> PortfolioResult<&mut AccessControl> {
+        self.access.as_mut().ok_or(PortfolioError::NoAccessControl)
+    }
+
+    pub fn role_of(&self, actor: &Actor) -> Option<Role> {
+        self.access.as_ref()?.role_of(actor)
+    }
+
+    pub fn require_role(&self, actor: &Actor, role: Role) -> PortfolioResult<()> {
+        match &self.access {
+            Some(access) => access.require(actor, role),
+            None => Err(PortfolioError::NoAccessControl),
+        }
+    }
+
+    pub(crate) fn authorize(&self, role: Role) -> PortfolioResult<()> {
+        match (&self.access, &self.actor) {
+            (None, _) => Ok(()),
+            (Some(access), Some(actor)) => access.require(actor, role),
+            (Some(_), None) => Err(PortfolioError::ActorRequired),
+        }
+    }
+
+    pub fn acting_as(&mut self, actor: &Actor) -> PortfolioResult<Acting<'_>> {
+        self.require_role(actor, Role::Viewer)?;
+        let previous = self.actor.replace(actor.clone());
+        Ok(Acting {
+            portfolio: self,
+            actor: actor.clone(),
+            previous,
+        })
+    }
+}
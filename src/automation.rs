@@ -0,0 +1,185 @@
+use crate::auth::Role;
+use crate::events::PortfolioEvent;
+use crate::money::Money;
+use crate::Quotes;
+use crate::{Portfolio, PortfolioError, PortfolioResult, TransactionId};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)
+)]
+pub enum Condition {
+    PriceAbove { symbol: String, price: Decimal },
+    PriceBelow { symbol: String, price: Decimal },
+    WeightAbove { symbol: String, percent: Decimal },
+    WeightBelow { symbol: String, percent: Decimal },
+    SharesBelow { symbol: String, shares: u32 },
+    All { conditions: Vec<Condition> },
+    Any { conditions: Vec<Condition> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)
+)]
+pub enum Action {
+    Buy { symbol: String, shares: u32 },
+    Sell { symbol: String, shares: u32 },
+    Alert { symbol: String, message: String },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RuleMode {
+    #[default]
+    Propose,
+    Execute,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct Rule {
+    pub name: String,
+    pub when: Condition,
+    pub then: Action,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mode: RuleMode,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+#[cfg(feature = "toml")]
+impl RuleSet {
+    pub fn from_toml_str(contents: &str) -> PortfolioResult<Self> {
+        toml::from_str(contents).map_err(|e| PortfolioError::InvalidConfig(e.to_string()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ActionStatus {
+    Proposed,
+    Executed(Option<TransactionId>),
+    Failed(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AutomationOutcome {
+    pub rule: String,
+    pub action: Action,
+    pub status: ActionStatus,
+}
+
+fn quote<'a>(quotes: &'a Quotes, symbol: &str) -> PortfolioResult<&'a Money> {
+    quotes
+        .get(symbol)
+        .ok_or_else(|| PortfolioError::MissingPrice(symbol.to_string()))
+}
+
+impl Portfolio {
+    pub fn add_automation_rule(&mut self, rule: Rule) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        self.automation_rules.push(rule);
+        Ok(())
+    }
+
+    pub fn automation_rules(&self) -> &[Rule] {
+        &self.automation_rules
+    }
+
+    fn weight_percent(&self, symbol: &str, quotes: &Quotes) -> PortfolioResult<Decimal> {
+        let total = self.market_value(quotes)?;
+        if total.is_zero() {
+            return Ok(Decimal::ZERO);
+        }
+        let value =
+            quote(quotes, symbol)?.checked_mul(self.get_signed_share_count(symbol).into())?;
+        Ok(value.amount / total.amount * Decimal::ONE_HUNDRED)
+    }
+
+    fn condition_holds(&self, condition: &Condition, quotes: &Quotes) -> PortfolioResult<bool> {
+        Ok(match condition {
+            Condition::PriceAbove { symbol, price } => quote(quotes, symbol)?.amount > *price,
+            Condition::PriceBelow { symbol, price } => quote(quotes, symbol)?.amount < *price,
+            Condition::WeightAbove { symbol, percent } => {
+                self.weight_percent(symbol, quotes)? > *percent
+            }
+            Condition::WeightBelow { symbol, percent } => {
+                self.weight_percent(symbol, quotes)? < *percent
+            }
+            Condition::SharesBelow { symbol, shares } => self.get_share_count(symbol) < *shares,
+            Condition::All { conditions } => {
+                for condition in conditions {
+                    if !self.condition_holds(condition, quotes)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            Condition::Any { conditions } => {
+                for condition in conditions {
+                    if self.condition_holds(condition, quotes)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+        })
+    }
+
+    fn execute_action(&mut self, action: &Action, quotes: &Quotes) -> ActionStatus {
+        let result = match action {
+            Action::Buy { symbol, shares } => quote(quotes, symbol)
+                .and_then(|price| self.purchase_at(symbol, *shares, *price))
+                .map(|confirmation| Some(confirmation.transaction_id)),
+            Action::Sell { symbol, shares } => quote(quotes, symbol)
+                .and_then(|price| self.sell_at(symbol, *shares, *price))
+                .map(|confirmation| Some(confirmation.transaction_id)),
+            Action::Alert { symbol, message } => {
+                self.publish(PortfolioEvent::Alert {
+                    symbol: symbol.clone(),
+                    message: message.clone(),
+                });
+                Ok(None)
+            }
+        };
+        match result {
+            Ok(transaction_id) => ActionStatus::Executed(transaction_id),
+            Err(error) => ActionStatus::Failed(self.localize_error(&error)),
+        }
+    }
+
+    pub fn run_automation(&mut self, quotes: &Quotes) -> PortfolioResult<Vec<AutomationOutcome>> {
+        self.authorize(Role::Trader)?;
+        let rules = self.automation_rules.clone();
+        let mut outcomes = Vec::new();
+        for rule in rules {
+            if !self.condition_holds(&rule.when, quotes)? {
+                continue;
+            }
+            let status = match rule.mode {
+                RuleMode::Propose => ActionStatus::Proposed,
+                RuleMode::Execute => self.execute_action(&rule.then, quotes),
+            };
+            outcomes.push(AutomationOutcome {
+                rule: rule.name,
+                action: rule.then,
+                status,
+            });
+        }
+        Ok(outcomes)
+    }
+}
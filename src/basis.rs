@@ -0,0 +1,116 @@
+use crate::auth::Role;
+use crate::gains::HoldingTerm;
+use crate::ledger::Transaction;
+use crate::lots::Lot;
+use crate::money::Money;
+use crate::{Portfolio, PortfolioError, PortfolioResult, TransactionId};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ReturnOfCapital {
+    pub date: DateTime<Utc>,
+    pub per_share_amount: Money,
+    pub basis_reduction: Money,
+    pub realized_gain: Money,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct BrokerBasis {
+    pub cost_basis: Money,
+    pub covered: bool,
+}
+
+pub(crate) struct AppliedAdjustment {
+    pub adjustment: ReturnOfCapital,
+    pub long_term_gain: Money,
+}
+
+pub(crate) fn reduce_basis(
+    lots: &mut [Lot],
+    per_share_amount: &Money,
+    date: DateTime<Utc>,
+) -> PortfolioResult<(Money, Money, Money)> {
+    let mut basis_reduction = Money::zero(per_share_amount.currency);
+    let mut realized_gain = Money::zero(per_share_amount.currency);
+    let mut long_term_gain = Money::zero(per_share_amount.currency);
+    for lot in lots.iter_mut() {
+        let distribution = per_share_amount.checked_mul(lot.shares.into())?;
+        let reduction = if distribution.amount > lot.cost_basis.amount {
+            lot.cost_basis
+        } else {
+            distribution
+        };
+        let gain = distribution.checked_sub(&reduction)?;
+        realized_gain = realized_gain.checked_add(&gain)?;
+        if HoldingTerm::for_lot(lot, date) == HoldingTerm::LongTerm {
+            long_term_gain = long_term_gain.checked_add(&gain)?;
+        }
+        basis_reduction = basis_reduction.checked_add(&reduction)?;
+        lot.cost_basis = lot.cost_basis.checked_sub(&reduction)?;
+    }
+    Ok((basis_reduction, realized_gain, long_term_gain))
+}
+
+impl Portfolio {
+    pub fn apply_return_of_capital(
+        &mut self,
+        symbol: &str,
+        per_share_amount: Money,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<ReturnOfCapital> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&per_share_amount)?;
+        if self.lots.get(symbol).is_none_or(|lots| lots.is_empty()) {
+            return Err(PortfolioError::NoOpenLots);
+        }
+        let adjustment = self
+            .apply_adjustment(symbol, per_share_amount, date)?
+            .adjustment;
+        self.record_entry(Transaction::ReturnOfCapital {
+            symbol: symbol.to_string(),
+            sequence: self.next_transaction_id,
+            adjustment: adjustment.clone(),
+        });
+        Ok(adjustment)
+    }
+
+    pub(crate) fn apply_adjustment(
+        &mut self,
+        symbol: &str,
+        per_share_amount: Money,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<AppliedAdjustment> {
+        let lots = self.lots.entry(symbol.to_string()).or_default();
+        let (basis_reduction, realized_gain, long_term_gain) =
+            reduce_basis(lots, &per_share_amount, date)?;
+        let adjustment = ReturnOfCapital {
+            date,
+            per_share_amount,
+            basis_reduction,
+            realized_gain,
+        };
+        self.return_of_capital
+            .entry(symbol.to_string())
+            .or_default()
+            .push(adjustment.clone());
+        Ok(AppliedAdjustment {
+            adjustment,
+            long_term_gain,
+        })
+    }
+
+    pub fn broker_basis_of(&self, id: TransactionId) -> Option<BrokerBasis> {
+        self.broker_basis.get(&id).copied()
+    }
+
+    pub fn get_return_of_capital_history(&self, symbol: &str) -> &[ReturnOfCapital] {
+        self.return_of_capital
+            .get(symbol)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+}
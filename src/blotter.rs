@@ -0,0 +1,223 @@
+use crate::lots::Acquisition;
+use crate::money::Money;
+use crate::numeric::MoneyAccumulator;
+use crate::{Portfolio, PortfolioResult, TransactionId, TransactionType};
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlotterActivity {
+    Trade {
+        transaction_type: TransactionType,
+        shares: u32,
+        price: Option<Money>,
+    },
+    StockDividend {
+        shares: u32,
+    },
+    Dividend {
+        amount: Money,
+    },
+    CapitalGainDistribution {
+        amount: Money,
+    },
+    ReturnOfCapital {
+        amount: Money,
+    },
+    Deposit {
+        amount: Money,
+    },
+    Withdrawal {
+        amount: Money,
+    },
+    Fee {
+        amount: Money,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlotterEntry {
+    pub time: DateTime<Utc>,
+    pub symbol: Option<String>,
+    pub transaction_id: Option<TransactionId>,
+    pub activity: BlotterActivity,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlotterSummary {
+    pub trades: usize,
+    pub shares_bought: u64,
+    pub shares_sold: u64,
+    pub bought: Money,
+    pub sold: Money,
+    pub income: Money,
+    pub net_cash: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Blotter {
+    pub date: NaiveDate,
+    pub entries: Vec<BlotterEntry>,
+    pub summary: BlotterSummary,
+}
+
+impl BlotterEntry {
+    fn new(time: DateTime<Utc>, symbol: Option<&str>, activity: BlotterActivity) -> Self {
+        Self {
+            time,
+            symbol: symbol.map(str::to_string),
+            transaction_id: None,
+            activity,
+        }
+    }
+}
+
+fn summarize(
+    entries: &[BlotterEntry],
+    mut income: MoneyAccumulator,
+) -> PortfolioResult<BlotterSummary> {
+    let zero = income.total()?;
+    let mut summary = BlotterSummary {
+        trades: 0,
+        shares_bought: 0,
+        shares_sold: 0,
+        bought: zero,
+        sold: zero,
+        income: zero,
+        net_cash: zero,
+    };
+    for entry in entries {
+        match &entry.activity {
+            BlotterActivity::Trade {
+                transaction_type,
+                shares,
+                price,
+            } => {
+                summary.trades += 1;
+                let value = match price {
+                    Some(price) => price.checked_mul((*shares).into())?,
+                    None => zero,
+                };
+                match transaction_type {
+                    TransactionType::Purchase => {
+                        summary.shares_bought += u64::from(*shares);
+                        summary.bought = summary.bought.checked_add(&value)?;
+                        summary.net_cash = summary.net_cash.checked_sub(&value)?;
+                    }
+                    TransactionType::Sell => {
+                        summary.shares_sold += u64::from(*shares);
+                        summary.sold = summary.sold.checked_add(&value)?;
+                        summary.net_cash = summary.net_cash.checked_add(&value)?;
+                    }
+                }
+            }
+            BlotterActivity::StockDividend { .. } => {}
+            BlotterActivity::Dividend { amount }
+            | BlotterActivity::CapitalGainDistribution { amount } => {
+                income.add(amount)?;
+                summary.net_cash = summary.net_cash.checked_add(amount)?;
+            }
+            BlotterActivity::ReturnOfCapital { amount } | BlotterActivity::Deposit { amount } => {
+                summary.net_cash = summary.net_cash.checked_add(amount)?;
+            }
+            BlotterActivity::Withdrawal { amount } | BlotterActivity::Fee { amount } => {
+                summary.net_cash = summary.net_cash.checked_sub(amount)?;
+            }
+        }
+    }
+    summary.income = income.total()?;
+    Ok(summary)
+}
+
+impl Portfolio {
+    pub fn blotter(&self, date: NaiveDate) -> PortfolioResult<Blotter> {
+        let on_day = |time: &DateTime<Utc>| time.date_naive() == date;
+        let mut entries = Vec::new();
+        for (symbol, record) in self.journal() {
+            if record.trade_date() != date {
+                continue;
+            }
+            let activity = match self.acquisition_of(record.id) {
+                Acquisition::StockDividend => BlotterActivity::StockDividend {
+                    shares: record.shares,
+                },
+                _ => BlotterActivity::Trade {
+                    transaction_type: record.transaction_type.clone(),
+                    shares: record.shares,
+                    price: record.price,
+                },
+            };
+            entries.push(BlotterEntry {
+                transaction_id: Some(record.id),
+                ..BlotterEntry::new(record.date, Some(symbol), activity)
+            });
+        }
+        for symbol in self.traded_symbols() {
+            for dividend in self.get_dividends(symbol) {
+                if on_day(&dividend.date) {
+                    let amount = dividend.amount()?;
+                    entries.push(BlotterEntry::new(
+                        dividend.date,
+                        Some(symbol),
+                        BlotterActivity::Dividend { amount },
+                    ));
+                }
+            }
+            for distribution in self.get_capital_gain_distributions(symbol) {
+                if on_day(&distribution.date) {
+                    let amount = distribution
+                        .short_term
+                        .checked_add(&distribution.long_term)?;
+                    entries.push(BlotterEntry::new(
+                        distribution.date,
+                        Some(symbol),
+                        BlotterActivity::CapitalGainDistribution { amount },
+                    ));
+                }
+            }
+            for adjustment in self.get_return_of_capital_history(symbol) {
+                if on_day(&adjustment.date) {
+                    let amount = adjustment
+                        .basis_reduction
+                        .checked_add(&adjustment.realized_gain)?;
+                    entries.push(BlotterEntry::new(
+                        adjustment.date,
+                        Some(symbol),
+                        BlotterActivity::ReturnOfCapital { amount },
+                    ));
+                }
+            }
+        }
+        for transfer in self.deposits.iter().filter(|t| on_day(&t.date)) {
+            entries.push(BlotterEntry::new(
+                transfer.date,
+                None,
+                BlotterActivity::Deposit {
+                    amount: transfer.amount,
+                },
+            ));
+        }
+        for transfer in self.withdrawals.iter().filter(|t| on_day(&t.date)) {
+            entries.push(BlotterEntry::new(
+                transfer.date,
+                None,
+                BlotterActivity::Withdrawal {
+                    amount: transfer.amount,
+                },
+            ));
+        }
+        for fee in self.advisory_fees().iter().filter(|fee| on_day(&fee.date)) {
+            entries.push(BlotterEntry::new(
+                fee.date,
+                None,
+                BlotterActivity::Fee { amount: fee.amount },
+            ));
+        }
+        entries.sort_by_key(|entry| entry.time);
+        let summary = summarize(&entries, self.money_accumulator())?;
+        Ok(Blotter {
+            date,
+            entries,
+            summary,
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{PortfolioError, PortfolioResult};
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::BTreeSet;
+
+pub trait HolidayCalendar: Send + Sync {
+    fn is_holiday(&self, date: NaiveDate) -> bool;
+
+    fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(date)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WeekendsOnly;
+
+impl HolidayCalendar for WeekendsOnly {
+    fn is_holiday(&self, _date: NaiveDate) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixedHolidays {
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl FixedHolidays {
+    pub fn new(holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        Self {
+            holidays: holidays.into_iter().collect(),
+        }
+    }
+
+    pub fn add(&mut self, date: NaiveDate) {
+        self.holidays.insert(date);
+    }
+}
+
+impl HolidayCalendar for FixedHolidays {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date)
+    }
+}
+
+pub fn next_trading_day(
+    date: NaiveDate,
+    calendar: &(impl HolidayCalendar + ?Sized),
+) -> PortfolioResult<NaiveDate> {
+    date.iter_days()
+        .skip(1)
+        .find(|day| calendar.is_trading_day(*day))
+        .ok_or(PortfolioError::NoTradingDay(date))
+}
+
+pub fn trading_session(
+    date: NaiveDate,
+    calendar: &(impl HolidayCalendar + ?Sized),
+) -> PortfolioResult<NaiveDate> {
+    if calendar.is_trading_day(date) {
+        return Ok(date);
+    }
+    next_trading_day(date, calendar)
+}
+
+pub fn trading_days_between(
+    start: NaiveDate,
+    end: NaiveDate,
+    calendar: &(impl HolidayCalendar + ?Sized),
+) -> u32 {
+    let count = start
+        .iter_days()
+        .skip(1)
+        .take_while(|day| *day <= end)
+        .filter(|day| calendar.is_trading_day(*day))
+        .count();
+    u32::try_from(count).unwrap_or(u32::MAX)
+}
+
+pub fn add_trading_days(
+    date: NaiveDate,
+    days: u64,
+    calendar: &(impl HolidayCalendar + ?Sized),
+) -> PortfolioResult<NaiveDate> {
+    (0..days).try_fold(date, |day, _| next_trading_day(day, calendar))
+}
@@ -0,0 +1,162 @@
+use crate::alerts::Alert;
+use crate::auth::AccessControl;
+use crate::automation::Rule;
+use crate::config::PortfolioConfig;
+use crate::execution::{BrokerOrderId, PendingOrder};
+use crate::external::ExternalPosition;
+use crate::goals::Goal;
+use crate::instruments::{Instrument, InstrumentRegistry};
+#[cfg(feature = "import")]
+use crate::integrity::ImportedTransaction;
+use crate::ledger::Transaction;
+use crate::liabilities::Liability;
+use crate::manual_assets::ManualAsset;
+use crate::snapshots::ValuationSnapshot;
+use crate::versions::{Version, VersionedEvent};
+use crate::{Portfolio, PortfolioResult, TradeConfirmation};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "toml")]
+mod file;
+
+pub const SCHEMA_VERSION: u32 = 3;
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+struct CanonicalPortfolio {
+    #[serde(default)]
+    transactions: Vec<Transaction>,
+    #[serde(default)]
+    external_positions: Vec<ExternalPosition>,
+    #[serde(default)]
+    manual_assets: Vec<ManualAsset>,
+    #[serde(default)]
+    liabilities: Vec<Liability>,
+    #[serde(default)]
+    instruments: BTreeMap<String, Instrument>,
+    #[serde(default)]
+    goals: Vec<Goal>,
+    #[serde(default)]
+    alerts: Vec<Alert>,
+    #[serde(default)]
+    automation_rules: Vec<Rule>,
+    #[serde(default)]
+    snapshots: Vec<ValuationSnapshot>,
+    #[cfg(feature = "import")]
+    #[serde(default)]
+    queued_transactions: Vec<ImportedTransaction>,
+    #[serde(default)]
+    confirmations_by_key: BTreeMap<String, TradeConfirmation>,
+    #[serde(default)]
+    pending_orders: BTreeMap<BrokerOrderId, PendingOrder>,
+    #[serde(default)]
+    applied_fills: BTreeSet<String>,
+    #[serde(default)]
+    access: Option<AccessControl>,
+    #[serde(default)]
+    version: Version,
+    #[serde(default)]
+    changes: Vec<VersionedEvent>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ConfiguredPortfolio {
+    #[serde(default)]
+    config: PortfolioConfig,
+    #[serde(flatten)]
+    portfolio: CanonicalPortfolio,
+}
+
+impl Portfolio {
+    fn canonical_form(&self) -> CanonicalPortfolio {
+        CanonicalPortfolio {
+            transactions: self.ledger.transactions().to_vec(),
+            external_positions: {
+                let mut positions = self.external_positions.clone();
+                positions.sort_by(|a, b| (&a.account, &a.symbol).cmp(&(&b.account, &b.symbol)));
+                positions
+            },
+            manual_assets: self.manual_assets.clone(),
+            liabilities: self.liabilities.clone(),
+            instruments: self
+                .instruments
+                .entries()
+                .map(|(symbol, instrument)| (symbol.clone(), instrument.clone()))
+                .collect(),
+            goals: self.goals.clone(),
+            alerts: self.alerts.clone(),
+            automation_rules: self.automation_rules.clone(),
+            snapshots: self.snapshots.values().cloned().collect(),
+            #[cfg(feature = "import")]
+            queued_transactions: self.queued_transactions.clone(),
+            confirmations_by_key: self
+                .confirmations_by_key
+                .iter()
+                .map(|(key, confirmation)| (key.clone(), confirmation.clone()))
+                .collect(),
+            pending_orders: self
+                .pending_orders
+                .iter()
+                .map(|(id, order)| (id.clone(), order.clone()))
+                .collect(),
+            applied_fills: self.applied_fills.clone(),
+            access: self.access.clone(),
+            version: self.version,
+            changes: self.changes.clone(),
+        }
+    }
+
+    fn from_canonical(
+        canonical: CanonicalPortfolio,
+        config: PortfolioConfig,
+    ) -> PortfolioResult<Self> {
+        let mut instruments = InstrumentRegistry::new();
+        for (symbol, instrument) in canonical.instruments {
+            instruments.insert(symbol, instrument);
+        }
+        let mut portfolio = Portfolio::with_config(config);
+        portfolio.instruments = instruments;
+        portfolio.restore_events(canonical.transactions)?;
+        portfolio.external_positions = canonical.external_positions;
+        portfolio.manual_assets = canonical.manual_assets;
+        portfolio.liabilities = canonical.liabilities;
+        portfolio.goals = canonical.goals;
+        portfolio.alerts = canonical.alerts;
+        portfolio.automation_rules = canonical.automation_rules;
+        portfolio.snapshots = canonical
+            .snapshots
+            .into_iter()
+            .map(|snapshot| (snapshot.date, snapshot))
+            .collect();
+        #[cfg(feature = "import")]
+        {
+            portfolio.queued_transactions = canonical.queued_transactions;
+        }
+        portfolio.confirmations_by_key = canonical.confirmations_by_key.into_iter().collect();
+        portfolio.pending_orders = canonical.pending_orders.into_iter().collect();
+        portfolio.applied_fills = canonical.applied_fills;
+        portfolio.access = canonical.access;
+        portfolio.version = canonical
+            .version
+            .max(portfolio.ledger.transactions().len() as Version);
+        portfolio.changes = canonical.changes;
+        Ok(portfolio)
+    }
+}
+
+impl Serialize for Portfolio {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ConfiguredPortfolio {
+            config: self.config.clone(),
+            portfolio: self.canonical_form(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Portfolio {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let stored = ConfiguredPortfolio::deserialize(deserializer)?;
+        Portfolio::from_canonical(stored.portfolio, stored.config).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,301 @@
+use super::{CanonicalPortfolio, SCHEMA_VERSION};
+use crate::advisory::AdvisoryFee;
+use crate::basis::{BrokerBasis, ReturnOfCapital};
+use crate::cash::CashTransfer;
+use crate::config::PortfolioConfig;
+use crate::dividends::Dividend;
+use crate::income::CapitalGainDistribution;
+use crate::ledger::{Trade, Transaction};
+use crate::lots::{Acquisition, SelectedLot};
+use crate::money::Money;
+use crate::reversal::Reversal;
+use crate::{Portfolio, PortfolioError, PortfolioResult, PurchaseRecord, TransactionId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct AcquisitionEntry {
+    transaction_id: TransactionId,
+    #[serde(flatten)]
+    acquisition: Acquisition,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct LotSelectionEntry {
+    transaction_id: TransactionId,
+    lots: Vec<SelectedLot>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct BrokerBasisEntry {
+    transaction_id: TransactionId,
+    #[serde(flatten)]
+    basis: BrokerBasis,
+}
+
+#[derive(Deserialize)]
+struct SymbolEntry<T> {
+    symbol: String,
+    #[serde(flatten)]
+    value: T,
+}
+
+#[derive(Deserialize)]
+struct TagEntry {
+    transaction_id: TransactionId,
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct CanonicalPortfolioV1 {
+    #[serde(default)]
+    records: Vec<SymbolEntry<PurchaseRecord>>,
+    #[serde(default)]
+    acquisitions: Vec<AcquisitionEntry>,
+    #[serde(default)]
+    lot_selections: Vec<LotSelectionEntry>,
+    #[serde(default)]
+    broker_basis: Vec<BrokerBasisEntry>,
+    #[serde(default)]
+    return_of_capital: Vec<SymbolEntry<ReturnOfCapital>>,
+    #[serde(default)]
+    dividends: Vec<SymbolEntry<Dividend>>,
+    #[serde(flatten)]
+    rest: CanonicalPortfolioV2,
+}
+
+#[derive(Deserialize)]
+struct CanonicalPortfolioV2 {
+    #[serde(default)]
+    shares_on_loan: BTreeMap<String, u32>,
+    #[serde(default)]
+    lending_income: BTreeMap<String, Money>,
+    #[serde(default)]
+    capital_gain_distributions: Vec<SymbolEntry<CapitalGainDistribution>>,
+    #[serde(default)]
+    reversals: Vec<Reversal>,
+    #[serde(default)]
+    deposits: Vec<CashTransfer>,
+    #[serde(default)]
+    withdrawals: Vec<CashTransfer>,
+    #[serde(default)]
+    advisory_fees: Vec<AdvisoryFee>,
+    #[serde(default)]
+    tags: Vec<TagEntry>,
+    #[serde(flatten)]
+    rest: CanonicalPortfolio,
+}
+
+impl CanonicalPortfolioV1 {
+    fn migrate(self) -> CanonicalPortfolioV2 {
+        let mut acquisitions: HashMap<TransactionId, Acquisition> = self
+            .acquisitions
+            .into_iter()
+            .map(|entry| (entry.transaction_id, entry.acquisition))
+            .collect();
+        let mut lot_selections: HashMap<TransactionId, Vec<SelectedLot>> = self
+            .lot_selections
+            .into_iter()
+            .map(|entry| (entry.transaction_id, entry.lots))
+            .collect();
+        let basis = self
+            .broker_basis
+            .into_iter()
+            .map(|entry| Transaction::BrokerBasis {
+                transaction_id: entry.transaction_id,
+                basis: entry.basis,
+            });
+        let trades = self.records.into_iter().map(|entry| {
+            let mut trade = Trade::from_record(&entry.symbol, entry.value);
+            trade.acquisition = acquisitions.remove(&trade.record.id).unwrap_or_default();
+            trade.lot_selection = lot_selections.remove(&trade.record.id);
+            Transaction::Trade(trade)
+        });
+        let adjustments =
+            self.return_of_capital
+                .into_iter()
+                .map(|entry| Transaction::ReturnOfCapital {
+                    symbol: entry.symbol,
+                    sequence: TransactionId::MAX,
+                    adjustment: entry.value,
+                });
+        let dividends = self
+            .dividends
+            .into_iter()
+            .map(|entry| Transaction::Dividend {
+                symbol: entry.symbol,
+                dividend: entry.value,
+            });
+        CanonicalPortfolioV2 {
+            rest: CanonicalPortfolio {
+                transactions: basis
+                    .chain(trades)
+                    .chain(adjustments)
+                    .chain(dividends)
+                    .collect(),
+                ..self.rest.rest
+            },
+            ..self.rest
+        }
+    }
+}
+
+impl CanonicalPortfolioV2 {
+    fn migrate(self) -> CanonicalPortfolio {
+        let mut transactions = self.rest.transactions;
+        let latest = transactions
+            .iter()
+            .filter_map(Transaction::date)
+            .max()
+            .unwrap_or(DateTime::<Utc>::UNIX_EPOCH);
+        let loans =
+            self.shares_on_loan
+                .into_iter()
+                .map(|(symbol, shares)| Transaction::LendShares {
+                    symbol,
+                    shares,
+                    date: latest,
+                    sequence: TransactionId::MAX,
+                });
+        let income =
+            self.lending_income
+                .into_iter()
+                .map(|(symbol, income)| Transaction::LendingIncome {
+                    symbol,
+                    income,
+                    date: latest,
+                    sequence: TransactionId::MAX,
+                });
+        let distributions = self.capital_gain_distributions.into_iter().map(|entry| {
+            Transaction::CapitalGainDistribution {
+                symbol: entry.symbol,
+                distribution: entry.value,
+            }
+        });
+        let reversals = self
+            .reversals
+            .into_iter()
+            .map(|reversal| Transaction::Reversal { reversal });
+        let deposits = self
+            .deposits
+            .into_iter()
+            .map(|transfer| Transaction::Deposit { transfer });
+        let withdrawals = self
+            .withdrawals
+            .into_iter()
+            .map(|transfer| Transaction::Withdrawal { transfer });
+        let fees = self
+            .advisory_fees
+            .into_iter()
+            .map(|fee| Transaction::AdvisoryFee { fee });
+        let tags = self.tags.into_iter().flat_map(|entry| {
+            entry.tags.into_iter().map(move |tag| Transaction::Tag {
+                transaction_id: entry.transaction_id,
+                tag,
+            })
+        });
+        transactions.extend(
+            loans
+                .chain(income)
+                .chain(distributions)
+                .chain(reversals)
+                .chain(deposits)
+                .chain(withdrawals)
+                .chain(fees)
+                .chain(tags),
+        );
+        CanonicalPortfolio {
+            transactions,
+            ..self.rest
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct Envelope<T> {
+    schema_version: u32,
+    #[serde(default)]
+    config: PortfolioConfig,
+    portfolio: T,
+}
+
+fn invalid(error: impl ToString) -> PortfolioError {
+    PortfolioError::InvalidSerializedPortfolio(error.to_string())
+}
+
+fn corrupt(error: impl ToString) -> PortfolioError {
+    PortfolioError::Corrupt(error.to_string())
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> PortfolioError {
+    PortfolioError::Io(format!("{}: {error}", path.display()))
+}
+
+fn staging_path(path: &Path) -> PortfolioResult<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| PortfolioError::Io(format!("{} is not a file path", path.display())))?;
+    let staging = format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    );
+    Ok(path.with_file_name(staging))
+}
+
+fn migrate(schema_version: u32, portfolio: toml::Value) -> PortfolioResult<CanonicalPortfolio> {
+    match schema_version {
+        1 => portfolio
+            .try_into()
+            .map(|v1: CanonicalPortfolioV1| v1.migrate().migrate())
+            .map_err(corrupt),
+        2 => portfolio
+            .try_into()
+            .map(CanonicalPortfolioV2::migrate)
+            .map_err(corrupt),
+        3 => portfolio.try_into().map_err(corrupt),
+        _ => Err(corrupt(format!(
+            "unsupported schema version {schema_version}"
+        ))),
+    }
+}
+
+impl Portfolio {
+    pub fn canonical_bytes(&self) -> PortfolioResult<Vec<u8>> {
+        toml::to_string(&self.canonical_form())
+            .map(String::into_bytes)
+            .map_err(invalid)
+    }
+
+    pub fn from_canonical_bytes(bytes: &[u8], config: PortfolioConfig) -> PortfolioResult<Self> {
+        let contents = std::str::from_utf8(bytes).map_err(invalid)?;
+        let canonical: CanonicalPortfolio = toml::from_str(contents).map_err(invalid)?;
+        Self::from_canonical(canonical, config)
+    }
+
+    pub fn save_to(&self, path: impl AsRef<Path>) -> PortfolioResult<()> {
+        let path = path.as_ref();
+        let envelope = Envelope {
+            schema_version: SCHEMA_VERSION,
+            config: self.config.clone(),
+            portfolio: self.canonical_form(),
+        };
+        let contents = toml::to_string(&envelope).map_err(invalid)?;
+        let staging = staging_path(path)?;
+        std::fs::write(&staging, contents).map_err(|e| io_error(&staging, e))?;
+        std::fs::rename(&staging, path).map_err(|e| {
+            let _ = std::fs::remove_file(&staging);
+            io_error(path, e)
+        })
+    }
+
+    pub fn load_from(path: impl AsRef<Path>) -> PortfolioResult<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| io_error(path, e))?;
+        let envelope: Envelope<toml::Value> = toml::from_str(&contents).map_err(corrupt)?;
+        let canonical = migrate(envelope.schema_version, envelope.portfolio)?;
+        Self::from_canonical(canonical, envelope.config).map_err(corrupt)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::auth::Role;
+use crate::ledger::Transaction;
+use crate::money::Money;
+use crate::{Portfolio, PortfolioResult};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct CashTransfer {
+    pub amount: Money,
+    pub date: DateTime<Utc>,
+}
+
+impl Portfolio {
+    pub fn record_deposit(&mut self, amount: Money, date: DateTime<Utc>) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&amount)?;
+        self.commit_entry(Transaction::Deposit {
+            transfer: CashTransfer { amount, date },
+        })
+    }
+
+    pub fn record_withdrawal(&mut self, amount: Money, date: DateTime<Utc>) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&amount)?;
+        self.commit_entry(Transaction::Withdrawal {
+            transfer: CashTransfer { amount, date },
+        })
+    }
+
+    pub fn record_interest(&mut self, amount: Money, date: DateTime<Utc>) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&amount)?;
+        self.commit_entry(Transaction::Interest {
+            transfer: CashTransfer { amount, date },
+        })
+    }
+
+    pub fn deposits(&self) -> &[CashTransfer] {
+        &self.deposits
+    }
+
+    pub fn withdrawals(&self) -> &[CashTransfer] {
+        &self.withdrawals
+    }
+
+    pub fn interest(&self) -> &[CashTransfer] {
+        &self.interest
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::{Mutex, PoisonError};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> DateTime<Utc> + Send + Sync,
+{
+    fn now(&self) -> DateTime<Utc> {
+        self()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+#[derive(Debug)]
+pub struct StepClock {
+    next: Mutex<DateTime<Utc>>,
+    step: Duration,
+}
+
+impl StepClock {
+    pub fn new(start: DateTime<Utc>, step: Duration) -> Self {
+        Self {
+            next: Mutex::new(start),
+            step,
+        }
+    }
+}
+
+impl Clock for StepClock {
+    fn now(&self) -> DateTime<Utc> {
+        let mut next = self.next.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = *next;
+        *next = now + self.step;
+        now
+    }
+}
@@ -0,0 +1,190 @@
+use crate::i18n::Locale;
+use crate::money::Currency;
+use crate::numeric::NumericBackend;
+use crate::period::{DayCountConvention, FiscalYear};
+#[cfg(feature = "toml")]
+use crate::{PortfolioError, PortfolioResult};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "toml")]
+use std::path::Path;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CostBasisMethod {
+    #[default]
+    Fifo,
+    Lifo,
+    AverageCost,
+    HighestCost,
+    MinimizeTax,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Jurisdiction {
+    #[default]
+    Us,
+    Uk,
+    Canada,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RoundingMode {
+    #[default]
+    HalfEven,
+    HalfUp,
+    Truncate,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DateGranularity {
+    #[default]
+    Timestamp,
+    Daily,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FutureDatedPolicy {
+    Reject,
+    #[default]
+    Warn,
+    Queue,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ExDividendPolicy {
+    Reject,
+    #[default]
+    Warn,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AccountType {
+    #[default]
+    Taxable,
+    TraditionalIra,
+    RothIra,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct RoundingPolicy {
+    pub mode: RoundingMode,
+    pub decimal_places: u32,
+}
+
+impl Default for RoundingPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RoundingMode::default(),
+            decimal_places: 2,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct RuleSettings {
+    pub max_shares_per_trade: Option<u32>,
+    pub allow_short_selling: bool,
+    pub future_dated: FutureDatedPolicy,
+    pub ex_dividend: ExDividendPolicy,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct SnapshotRetention {
+    pub daily_days: i64,
+    pub weekly_days: i64,
+    pub monthly_days: Option<i64>,
+}
+
+impl Default for SnapshotRetention {
+    fn default() -> Self {
+        Self {
+            daily_days: 90,
+            weekly_days: 730,
+            monthly_days: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct StorageSettings {
+    pub path: Option<PathBuf>,
+    pub snapshot_retention: SnapshotRetention,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct TaxSettings {
+    pub short_term_rate: Decimal,
+    pub long_term_rate: Decimal,
+    pub wash_sale_days: i64,
+    pub fiscal_year: FiscalYear,
+}
+
+impl Default for TaxSettings {
+    fn default() -> Self {
+        Self {
+            short_term_rate: Decimal::new(24, 2),
+            long_term_rate: Decimal::new(15, 2),
+            wash_sale_days: 30,
+            fiscal_year: FiscalYear::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct PortfolioConfig {
+    pub account_type: AccountType,
+    pub jurisdiction: Jurisdiction,
+    pub cost_basis_method: CostBasisMethod,
+    pub rounding: RoundingPolicy,
+    pub numeric_backend: NumericBackend,
+    pub base_currency: Currency,
+    pub locale: Locale,
+    pub day_count: DayCountConvention,
+    pub date_granularity: DateGranularity,
+    pub rules: RuleSettings,
+    pub storage: StorageSettings,
+    pub tax: TaxSettings,
+}
+
+#[cfg(feature = "toml")]
+impl PortfolioConfig {
+    pub fn from_path(path: impl AsRef<Path>) -> PortfolioResult<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            PortfolioError::InvalidConfig(format!("cannot read {}: {e}", path.display()))
+        })?;
+        Self::from_toml_str(&contents)
+    }
+
+    pub fn from_toml_str(contents: &str) -> PortfolioResult<Self> {
+        toml::from_str(contents).map_err(|e| PortfolioError::InvalidConfig(e.to_string()))
+    }
+}
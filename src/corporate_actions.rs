@@ -0,0 +1,328 @@
+use crate::auth::Role;
+use crate::gains::HoldingTerm;
+use crate::ledger::Transaction;
+use crate::lots::LotId;
+use crate::money::Money;
+use crate::position::Position;
+#[cfg(feature = "pricing")]
+use crate::prices::{self, PriceHistory, SuspectedSplit};
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "action", rename_all = "snake_case"))]
+pub enum CorporateAction {
+    Split {
+        symbol: String,
+        date: NaiveDate,
+        numerator: u32,
+        denominator: u32,
+        #[cfg_attr(feature = "serde", serde(default))]
+        cash_in_lieu_price: Option<Money>,
+    },
+    Dividend {
+        symbol: String,
+        date: NaiveDate,
+        per_share: Money,
+    },
+    Rename {
+        symbol: String,
+        date: NaiveDate,
+        new_symbol: String,
+    },
+}
+
+impl CorporateAction {
+    pub fn symbol(&self) -> &str {
+        match self {
+            CorporateAction::Split { symbol, .. }
+            | CorporateAction::Dividend { symbol, .. }
+            | CorporateAction::Rename { symbol, .. } => symbol,
+        }
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            CorporateAction::Split { date, .. }
+            | CorporateAction::Dividend { date, .. }
+            | CorporateAction::Rename { date, .. } => *date,
+        }
+    }
+
+    fn effective(&self) -> DateTime<Utc> {
+        self.date().and_time(NaiveTime::MIN).and_utc()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct StockSplit {
+    pub date: DateTime<Utc>,
+    pub numerator: u32,
+    pub denominator: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cash_in_lieu_price: Option<Money>,
+}
+
+impl StockSplit {
+    pub(crate) fn rescale(&self, shares: i64) -> i64 {
+        let scaled = i128::from(shares) * i128::from(self.numerator) / i128::from(self.denominator);
+        i64::try_from(scaled).unwrap_or(if scaled < 0 { i64::MIN } else { i64::MAX })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CashInLieu {
+    pub lot_id: LotId,
+    pub date: DateTime<Utc>,
+    pub proceeds: Money,
+    pub gain: Money,
+    pub term: HoldingTerm,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CorporateActionReport {
+    pub applied: Vec<CorporateAction>,
+    pub skipped: Vec<CorporateAction>,
+}
+
+fn rekey<V>(map: &mut HashMap<String, V>, from: &str, to: &str) {
+    if let Some(value) = map.remove(from) {
+        map.insert(to.to_string(), value);
+    }
+}
+
+impl Portfolio {
+    pub fn rename_symbol(&mut self, from: &str, to: &str) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        if !self.purchase_records.contains_key(from) {
+            return Err(PortfolioError::NoSymbolHistory);
+        }
+        if self.purchase_records.contains_key(to) || self.holdings.contains_key(to) {
+            return Err(PortfolioError::InvalidCorporateAction(format!(
+                "{to} already has history"
+            )));
+        }
+        let date = self.ledger.latest_date(from).unwrap_or_else(|| self.now());
+        self.record_entry(Transaction::RenameSymbol {
+            from: from.to_string(),
+            to: to.to_string(),
+            date,
+            sequence: self.next_transaction_id,
+        });
+        self.rekey_projections(from, to);
+        self.instruments.rename(from, to);
+        Ok(())
+    }
+
+    pub(crate) fn rekey_projections(&mut self, from: &str, to: &str) {
+        rekey(&mut self.holdings, from, to);
+        rekey(&mut self.purchase_records, from, to);
+        rekey(&mut self.lots, from, to);
+        rekey(&mut self.lot_consolidations, from, to);
+        rekey(&mut self.return_of_capital, from, to);
+        rekey(&mut self.dividends, from, to);
+        rekey(&mut self.capital_gain_distributions, from, to);
+        rekey(&mut self.shares_on_loan, from, to);
+        rekey(&mut self.lending_income, from, to);
+        rekey(&mut self.splits, from, to);
+    }
+
+    pub fn record_split(
+        &mut self,
+        symbol: &str,
+        split: StockSplit,
+    ) -> PortfolioResult<Vec<CashInLieu>> {
+        self.authorize(Role::Trader)?;
+        if let Some(price) = &split.cash_in_lieu_price {
+            self.validate_amount(price)?;
+        }
+        if self.lots.get(symbol).is_none_or(|lots| lots.is_empty()) {
+            return Err(PortfolioError::NoOpenLots);
+        }
+        let cash_in_lieu = self.apply_split(symbol, &split)?;
+        self.record_entry(Transaction::Split {
+            symbol: symbol.to_string(),
+            sequence: self.next_transaction_id,
+            split,
+        });
+        Ok(cash_in_lieu)
+    }
+
+    pub(crate) fn apply_split(
+        &mut self,
+        symbol: &str,
+        split: &StockSplit,
+    ) -> PortfolioResult<Vec<CashInLieu>> {
+        let StockSplit {
+            date,
+            numerator,
+            denominator,
+            cash_in_lieu_price,
+        } = *split;
+        let invalid = |detail: String| PortfolioError::InvalidCorporateAction(detail);
+        if numerator == 0 || denominator == 0 {
+            return Err(invalid(format!(
+                "invalid split ratio {numerator}:{denominator}"
+            )));
+        }
+        let denominator = u64::from(denominator);
+        let mut lots = self.lots.get(symbol).cloned().unwrap_or_default();
+        let scaled: Vec<u64> = lots
+            .iter()
+            .map(|lot| u64::from(lot.shares) * u64::from(numerator))
+            .collect();
+        let mut shares: Vec<u64> = scaled.iter().map(|scaled| scaled / denominator).collect();
+        let whole = scaled.iter().sum::<u64>() / denominator;
+        let extra = usize::try_from(whole - shares.iter().sum::<u64>())
+            .map_err(|_| PortfolioError::Overflow)?;
+        let mut fractional: Vec<usize> = (0..lots.len())
+            .filter(|&index| !scaled[index].is_multiple_of(denominator))
+            .collect();
+        fractional.sort_by_key(|&index| Reverse(scaled[index] % denominator));
+        let (rounded_up, remaining) = fractional.split_at(extra);
+        for &index in rounded_up {
+            shares[index] += 1;
+        }
+
+        let mut cash_in_lieu = Vec::new();
+        let mut leftover = scaled.iter().sum::<u64>() % denominator;
+        if leftover > 0 {
+            let price = cash_in_lieu_price.ok_or_else(|| {
+                invalid(format!(
+                    "split {numerator}:{denominator} of {symbol} leaves a fractional share without a cash-in-lieu price"
+                ))
+            })?;
+            for &index in remaining {
+                if leftover == 0 {
+                    break;
+                }
+                let cashed = leftover.min(scaled[index] % denominator);
+                leftover -= cashed;
+                let lot = &mut lots[index];
+                let proceeds = Money::new(
+                    (price.amount * Decimal::from(cashed) / Decimal::from(denominator))
+                        .round_dp(price.currency.minor_units()),
+                    price.currency,
+                );
+                let basis = Money::new(
+                    (lot.cost_basis.amount * Decimal::from(cashed) / Decimal::from(scaled[index]))
+                        .round_dp(lot.cost_basis.currency.minor_units()),
+                    lot.cost_basis.currency,
+                );
+                lot.cost_basis = lot.cost_basis.checked_sub(&basis)?;
+                cash_in_lieu.push(CashInLieu {
+                    lot_id: lot.id,
+                    date,
+                    proceeds,
+                    gain: proceeds.checked_sub(&basis)?,
+                    term: HoldingTerm::for_lot(lot, date),
+                });
+            }
+        }
+        for (lot, shares) in lots.iter_mut().zip(shares) {
+            lot.shares = u32::try_from(shares).map_err(|_| PortfolioError::Overflow)?;
+        }
+        lots.retain(|lot| lot.shares > 0);
+
+        let position = Position::from_signed(split.rescale(self.get_signed_share_count(symbol)))
+            .ok_or(PortfolioError::Overflow)?;
+        self.lots.insert(symbol.to_string(), lots);
+        self.holdings.insert(symbol.to_string(), position);
+        if let Some(on_loan) = self.shares_on_loan.get_mut(symbol) {
+            *on_loan = u32::try_from(split.rescale(i64::from(*on_loan)))
+                .map_err(|_| PortfolioError::Overflow)?;
+        }
+        self.recall_excess_loans();
+        self.splits
+            .entry(symbol.to_string())
+            .or_default()
+            .push(*split);
+        Ok(cash_in_lieu)
+    }
+
+    pub fn get_splits(&self, symbol: &str) -> &[StockSplit] {
+        self.splits
+            .get(symbol)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "pricing")]
+    pub fn suspected_splits(&self, history: &PriceHistory) -> Vec<SuspectedSplit> {
+        prices::detect_splits(history)
+            .into_iter()
+            .filter(|split| {
+                split
+                    .date
+                    .pred_opt()
+                    .is_some_and(|previous| self.get_share_count_as_of(&split.symbol, previous) > 0)
+            })
+            .collect()
+    }
+
+    pub fn apply_corporate_actions(
+        &mut self,
+        mut actions: Vec<CorporateAction>,
+    ) -> PortfolioResult<CorporateActionReport> {
+        self.authorize(Role::Trader)?;
+        actions.sort_by_key(CorporateAction::date);
+        let snapshot = self.clone();
+        let mut report = CorporateActionReport::default();
+        for action in actions {
+            match self.apply_corporate_action(&action) {
+                Ok(true) => report.applied.push(action),
+                Ok(false) => report.skipped.push(action),
+                Err(error) => {
+                    *self = snapshot;
+                    return Err(error);
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    fn apply_corporate_action(&mut self, action: &CorporateAction) -> PortfolioResult<bool> {
+        let held = self.get_share_count_as_of(action.symbol(), action.date());
+        match action {
+            CorporateAction::Rename {
+                symbol, new_symbol, ..
+            } => {
+                if !self.purchase_records.contains_key(symbol) {
+                    return Ok(false);
+                }
+                self.rename_symbol(symbol, new_symbol)?;
+            }
+            _ if held == 0 => return Ok(false),
+            CorporateAction::Split {
+                symbol,
+                numerator,
+                denominator,
+                cash_in_lieu_price,
+                ..
+            } => {
+                self.record_split(
+                    symbol,
+                    StockSplit {
+                        date: action.effective(),
+                        numerator: *numerator,
+                        denominator: *denominator,
+                        cash_in_lieu_price: *cash_in_lieu_price,
+                    },
+                )?;
+            }
+            CorporateAction::Dividend {
+                symbol, per_share, ..
+            } => {
+                self.record_dividend(symbol, *per_share, action.effective(), None)?;
+            }
+        }
+        Ok(true)
+    }
+}
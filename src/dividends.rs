@@ -0,0 +1,211 @@
+use crate::auth::Role;
+use crate::config::ExDividendPolicy;
+use crate::ledger::Transaction;
+use crate::money::Money;
+use crate::period::Period;
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+use chrono::{DateTime, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const TRAILING_DAYS: i64 = 365;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Dividend {
+    pub date: DateTime<Utc>,
+    pub per_share: Money,
+    pub shares: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ex_date: Option<NaiveDate>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ineligible_shares: u32,
+}
+
+impl Dividend {
+    pub fn amount(&self) -> PortfolioResult<Money> {
+        self.per_share.checked_mul(self.shares.into())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DividendFrequency {
+    Monthly,
+    Quarterly,
+    SemiAnnual,
+    Annual,
+}
+
+impl DividendFrequency {
+    fn from_interval_days(days: i64) -> Self {
+        match days {
+            ..=45 => DividendFrequency::Monthly,
+            46..=135 => DividendFrequency::Quarterly,
+            136..=270 => DividendFrequency::SemiAnnual,
+            _ => DividendFrequency::Annual,
+        }
+    }
+
+    pub fn payments_per_year(self) -> u32 {
+        match self {
+            DividendFrequency::Monthly => 12,
+            DividendFrequency::Quarterly => 4,
+            DividendFrequency::SemiAnnual => 2,
+            DividendFrequency::Annual => 1,
+        }
+    }
+
+    fn interval(self) -> Months {
+        Months::new(12 / self.payments_per_year())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DividendRate {
+    pub per_payment: Money,
+    pub frequency: DividendFrequency,
+    pub trailing_annual: Money,
+    pub last_paid: NaiveDate,
+}
+
+impl DividendRate {
+    pub fn forward_annual(&self) -> PortfolioResult<Money> {
+        self.per_payment
+            .checked_mul(self.frequency.payments_per_year().into())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectedDividend {
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub amount: Money,
+}
+
+impl Portfolio {
+    pub fn record_dividend(
+        &mut self,
+        symbol: &str,
+        per_share: Money,
+        date: DateTime<Utc>,
+        ex_date: Option<NaiveDate>,
+    ) -> PortfolioResult<Dividend> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&per_share)?;
+        let shares = self.get_share_count_as_of(symbol, date.date_naive());
+        if shares == 0 {
+            return Err(PortfolioError::NoOpenLots);
+        }
+        let ineligible_shares = match ex_date {
+            Some(ex_date) => {
+                let eligible = ex_date.pred_opt().map_or(0, |record_date| {
+                    self.get_share_count_as_of(symbol, record_date)
+                });
+                let ineligible = shares.saturating_sub(eligible);
+                if ineligible > 0 && self.config.rules.ex_dividend == ExDividendPolicy::Reject {
+                    return Err(PortfolioError::IneligibleDividend(ex_date));
+                }
+                ineligible
+            }
+            None => 0,
+        };
+        let dividend = Dividend {
+            date,
+            per_share,
+            shares,
+            ex_date,
+            ineligible_shares,
+        };
+        self.apply_dividend(symbol, &dividend);
+        self.record_entry(Transaction::Dividend {
+            symbol: symbol.to_string(),
+            dividend: dividend.clone(),
+        });
+        Ok(dividend)
+    }
+
+    pub(crate) fn apply_dividend(&mut self, symbol: &str, dividend: &Dividend) {
+        let history = self.dividends.entry(symbol.to_string()).or_default();
+        let index = history.partition_point(|existing| existing.date <= dividend.date);
+        history.insert(index, dividend.clone());
+    }
+
+    pub fn get_dividends(&self, symbol: &str) -> &[Dividend] {
+        self.dividends
+            .get(symbol)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn infer_dividend_rate(&self, symbol: &str) -> PortfolioResult<Option<DividendRate>> {
+        let history = self.get_dividends(symbol);
+        let [.., previous, latest] = history else {
+            return Ok(None);
+        };
+        let last_paid = latest.date.date_naive();
+        let interval = (last_paid - previous.date.date_naive()).num_days();
+        let trailing: Vec<Money> = history
+            .iter()
+            .filter(|dividend| (last_paid - dividend.date.date_naive()).num_days() < TRAILING_DAYS)
+            .map(|dividend| dividend.per_share)
+            .collect();
+        Ok(Some(DividendRate {
+            per_payment: latest.per_share,
+            frequency: DividendFrequency::from_interval_days(interval),
+            trailing_annual: Money::checked_sum(latest.per_share.currency, &trailing)?,
+            last_paid,
+        }))
+    }
+
+    pub fn yield_on_cost(&self, symbol: &str) -> PortfolioResult<Option<Decimal>> {
+        let Some(average_price) = self.average_price(symbol)? else {
+            return Ok(None);
+        };
+        if average_price.is_zero() {
+            return Ok(None);
+        }
+        let today = self.now().date_naive();
+        let trailing = self.get_dividends(symbol).iter().filter(|dividend| {
+            let age = (today - dividend.date.date_naive()).num_days();
+            (0..TRAILING_DAYS).contains(&age)
+        });
+        let trailing_annual = Money::checked_sum(
+            average_price.currency,
+            trailing.map(|dividend| &dividend.per_share),
+        )?;
+        Ok(Some(
+            trailing_annual.amount / average_price.amount * Decimal::ONE_HUNDRED,
+        ))
+    }
+
+    pub fn projected_dividends(&self, period: &Period) -> PortfolioResult<Vec<ProjectedDividend>> {
+        let mut projected = Vec::new();
+        for symbol in self.traded_symbols() {
+            let shares = self.get_share_count(symbol);
+            if shares == 0 {
+                continue;
+            }
+            let Some(rate) = self.infer_dividend_rate(symbol)? else {
+                continue;
+            };
+            let amount = rate.per_payment.checked_mul(shares.into())?;
+            let mut date = rate.last_paid;
+            while let Some(next) = date.checked_add_months(rate.frequency.interval()) {
+                if next > period.end {
+                    break;
+                }
+                if next >= period.start {
+                    projected.push(ProjectedDividend {
+                        symbol: symbol.to_string(),
+                        date: next,
+                        amount,
+                    });
+                }
+                date = next;
+            }
+        }
+        projected.sort_by(|a, b| (a.date, &a.symbol).cmp(&(b.date, &b.symbol)));
+        Ok(projected)
+    }
+}
@@ -0,0 +1,217 @@
+use crate::auth::Role;
+use crate::ledger::Trade;
+use crate::money::Money;
+use crate::{Portfolio, PortfolioResult, TradeConfirmation, TransactionId, TransactionType};
+use chrono::{DateTime, Months, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct EsppPurchase {
+    pub offering_date: DateTime<Utc>,
+    pub offering_fmv: Money,
+    pub purchase_date: DateTime<Utc>,
+    pub purchase_fmv: Money,
+    pub discount_percent: Decimal,
+}
+
+impl EsppPurchase {
+    fn discounted(&self, fmv: &Money) -> PortfolioResult<Money> {
+        fmv.checked_mul(Decimal::ONE - self.discount_percent / Decimal::ONE_HUNDRED)
+    }
+
+    pub fn purchase_price(&self) -> PortfolioResult<Money> {
+        self.offering_fmv.ensure_same_currency(&self.purchase_fmv)?;
+        let lookback = if self.offering_fmv.amount < self.purchase_fmv.amount {
+            &self.offering_fmv
+        } else {
+            &self.purchase_fmv
+        };
+        self.discounted(lookback)
+    }
+
+    pub fn disposition(&self, sold: DateTime<Utc>) -> EsppDisposition {
+        let held_from_offering = self.offering_date.checked_add_months(Months::new(24));
+        let held_from_purchase = self.purchase_date.checked_add_months(Months::new(12));
+        match (held_from_offering, held_from_purchase) {
+            (Some(offering), Some(purchase)) if sold > offering && sold > purchase => {
+                EsppDisposition::Qualifying
+            }
+            _ => EsppDisposition::Disqualifying,
+        }
+    }
+
+    fn ordinary_income_per_share(
+        &self,
+        disposition: EsppDisposition,
+        sale_price: &Money,
+    ) -> PortfolioResult<Money> {
+        let purchase_price = self.purchase_price()?;
+        match disposition {
+            EsppDisposition::Disqualifying => self.purchase_fmv.checked_sub(&purchase_price),
+            EsppDisposition::Qualifying => {
+                let actual_gain = sale_price.checked_sub(&purchase_price)?;
+                let offering_discount = self
+                    .offering_fmv
+                    .checked_sub(&self.discounted(&self.offering_fmv)?)?;
+                let income = actual_gain.amount.min(offering_discount.amount);
+                Ok(Money::new(
+                    income.max(Decimal::ZERO),
+                    purchase_price.currency,
+                ))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EsppDisposition {
+    Qualifying,
+    Disqualifying,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum EquityAward {
+    RsuVest {
+        symbol: String,
+        shares: u32,
+        fmv: Money,
+        date: DateTime<Utc>,
+    },
+    Espp {
+        symbol: String,
+        shares: u32,
+        purchase: EsppPurchase,
+    },
+}
+
+impl EquityAward {
+    pub fn symbol(&self) -> &str {
+        match self {
+            EquityAward::RsuVest { symbol, .. } | EquityAward::Espp { symbol, .. } => symbol,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EsppSale {
+    pub sale_id: TransactionId,
+    pub purchase_id: TransactionId,
+    pub symbol: String,
+    pub shares: u32,
+    pub sold: DateTime<Utc>,
+    pub disposition: EsppDisposition,
+    pub proceeds: Money,
+    pub ordinary_income: Money,
+    pub adjusted_basis: Money,
+    pub capital_gain: Money,
+}
+
+impl Portfolio {
+    pub fn record_rsu_vest(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        fmv: Money,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        let mut trade = Trade::new(symbol, shares, TransactionType::Purchase, Some(fmv), date);
+        trade.equity_award = Some(Box::new(EquityAward::RsuVest {
+            symbol: symbol.to_string(),
+            shares,
+            fmv,
+            date,
+        }));
+        self.record_trade(trade)
+    }
+
+    pub fn record_espp_purchase(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        purchase: EsppPurchase,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        let price = purchase.purchase_price()?;
+        let mut trade = Trade::new(
+            symbol,
+            shares,
+            TransactionType::Purchase,
+            Some(price),
+            purchase.purchase_date,
+        );
+        trade.equity_award = Some(Box::new(EquityAward::Espp {
+            symbol: symbol.to_string(),
+            shares,
+            purchase,
+        }));
+        self.record_trade(trade)
+    }
+
+    pub fn equity_awards(&self) -> impl Iterator<Item = (TransactionId, &EquityAward)> {
+        self.equity_awards.iter().map(|(id, award)| (*id, award))
+    }
+
+    pub fn rsu_vest_income(&self) -> PortfolioResult<Money> {
+        let mut income = self.money_accumulator();
+        for award in self.equity_awards.values() {
+            if let EquityAward::RsuVest { shares, fmv, .. } = award {
+                income.add(&fmv.checked_mul((*shares).into())?)?;
+            }
+        }
+        income.total()
+    }
+
+    pub fn espp_sales(&self) -> PortfolioResult<Vec<EsppSale>> {
+        let mut symbols: Vec<&str> = self
+            .equity_awards
+            .values()
+            .filter(|award| matches!(award, EquityAward::Espp { .. }))
+            .map(EquityAward::symbol)
+            .collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        let mut sales = Vec::new();
+        for symbol in symbols {
+            for (record, confirmation) in self.replay_symbol(symbol)?.trades {
+                let Some(sale_price) = record.price else {
+                    continue;
+                };
+                for gain in &confirmation.lot_gains {
+                    let purchase_id = gain.consumption.sequence;
+                    let Some(EquityAward::Espp { purchase, .. }) =
+                        self.equity_awards.get(&purchase_id)
+                    else {
+                        continue;
+                    };
+                    let shares = gain.consumption.shares;
+                    let disposition = purchase.disposition(record.date);
+                    let ordinary_income = purchase
+                        .ordinary_income_per_share(disposition, &sale_price)?
+                        .checked_mul(shares.into())?;
+                    let adjusted_basis =
+                        gain.consumption.cost_basis.checked_add(&ordinary_income)?;
+                    sales.push(EsppSale {
+                        sale_id: record.id,
+                        purchase_id,
+                        symbol: symbol.to_string(),
+                        shares,
+                        sold: record.date,
+                        disposition,
+                        proceeds: gain.proceeds,
+                        ordinary_income,
+                        capital_gain: gain.proceeds.checked_sub(&adjusted_basis)?,
+                        adjusted_basis,
+                    });
+                }
+            }
+        }
+        Ok(sales)
+    }
+}
@@ -0,0 +1,82 @@
+use crate::equity::EquityAward;
+use crate::fx::TradeFx;
+use crate::ledger::Transaction;
+use crate::lots::{Acquisition, SelectedLot};
+use crate::money::Money;
+use crate::{Portfolio, TransactionId, TransactionType};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum PortfolioEvent {
+    Transaction {
+        transaction_id: TransactionId,
+        symbol: String,
+        transaction_type: TransactionType,
+        shares: u32,
+        price: Option<Money>,
+        date: DateTime<Utc>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        fx: Option<TradeFx>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        net_amount: Option<Money>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        acquisition: Acquisition,
+        #[cfg_attr(feature = "serde", serde(default))]
+        lot_selection: Option<Vec<SelectedLot>>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        equity_award: Option<Box<EquityAward>>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        reverses: Option<TransactionId>,
+    },
+    OrderFilled {
+        transaction_id: TransactionId,
+        symbol: String,
+        idempotency_key: Option<String>,
+    },
+    Alert {
+        symbol: String,
+        message: String,
+    },
+    LedgerEntry {
+        entry: Transaction,
+    },
+}
+
+impl PortfolioEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PortfolioEvent::Transaction { .. } => "transaction",
+            PortfolioEvent::OrderFilled { .. } => "order_filled",
+            PortfolioEvent::Alert { .. } => "alert",
+            PortfolioEvent::LedgerEntry { .. } => "ledger_entry",
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        match self {
+            PortfolioEvent::Transaction { symbol, .. }
+            | PortfolioEvent::OrderFilled { symbol, .. }
+            | PortfolioEvent::Alert { symbol, .. } => symbol,
+            PortfolioEvent::LedgerEntry { entry } => entry.symbol().unwrap_or_default(),
+        }
+    }
+}
+
+impl Portfolio {
+    pub fn subscribe(&mut self) -> Receiver<PortfolioEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    pub(crate) fn publish(&mut self, event: PortfolioEvent) {
+        self.record_change(&event);
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
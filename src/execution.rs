@@ -0,0 +1,153 @@
+use crate::auth::Role;
+use crate::calendar;
+use crate::events::PortfolioEvent;
+use crate::money::Money;
+use crate::{
+    Order, Portfolio, PortfolioError, PortfolioResult, TradeConfirmation, TransactionType,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "alpaca")]
+pub mod alpaca;
+pub mod paper;
+
+pub type BrokerOrderId = String;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fill {
+    pub fill_id: String,
+    pub order_id: BrokerOrderId,
+    pub symbol: String,
+    pub transaction_type: TransactionType,
+    pub shares: u32,
+    pub price: Money,
+    pub filled_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct PendingOrder {
+    pub order: Order,
+    pub filled_shares: u32,
+    pub expires_on: NaiveDate,
+}
+
+impl PendingOrder {
+    pub fn remaining_shares(&self) -> u32 {
+        self.order.shares.saturating_sub(self.filled_shares)
+    }
+
+    pub fn is_expired(&self, as_of: NaiveDate) -> bool {
+        as_of > self.expires_on
+    }
+}
+
+pub trait Broker {
+    fn place_order(&mut self, order: &Order) -> PortfolioResult<BrokerOrderId>;
+
+    fn fills(&mut self, order_id: &str) -> PortfolioResult<Vec<Fill>>;
+}
+
+impl Portfolio {
+    pub fn route_order(
+        &mut self,
+        broker: &mut impl Broker,
+        order: Order,
+    ) -> PortfolioResult<BrokerOrderId> {
+        self.authorize(Role::Trader)?;
+        Self::validate_share_count(order.shares)?;
+        self.validate_trade_limit(order.shares)?;
+        self.validate_lot_size(&order.symbol, order.shares, &order.transaction_type)?;
+        self.validate_not_halted(&order.symbol)?;
+        let expires_on =
+            calendar::trading_session(self.now().date_naive(), self.calendar.as_ref())?;
+        let order_id = broker.place_order(&order)?;
+        self.pending_orders.insert(
+            order_id.clone(),
+            PendingOrder {
+                order,
+                filled_shares: 0,
+                expires_on,
+            },
+        );
+        Ok(order_id)
+    }
+
+    pub fn pending_order(&self, order_id: &str) -> Option<&PendingOrder> {
+        self.pending_orders.get(order_id)
+    }
+
+    pub fn collect_fills(
+        &mut self,
+        broker: &mut impl Broker,
+        order_id: &str,
+    ) -> PortfolioResult<Vec<TradeConfirmation>> {
+        self.authorize(Role::Trader)?;
+        let mut confirmations = Vec::new();
+        for fill in broker.fills(order_id)? {
+            if self.applied_fills.contains(&fill.fill_id) {
+                continue;
+            }
+            if let Some(pending) = self.pending_orders.get(order_id) {
+                if pending.is_expired(fill.filled_at.date_naive()) {
+                    return Err(PortfolioError::BrokerError(format!(
+                        "fill {} arrived after order {order_id} expired on {}",
+                        fill.fill_id, pending.expires_on
+                    )));
+                }
+                if fill.shares > pending.remaining_shares() {
+                    return Err(PortfolioError::BrokerError(format!(
+                        "fill {} exceeds the {} shares remaining on order {order_id}",
+                        fill.fill_id,
+                        pending.remaining_shares()
+                    )));
+                }
+            }
+            let confirmation = self.transact(
+                &fill.symbol,
+                fill.shares,
+                fill.transaction_type,
+                Some(fill.price),
+                fill.filled_at,
+            )?;
+            self.applied_fills.insert(fill.fill_id);
+            let idempotency_key = self.pending_orders.get_mut(order_id).and_then(|pending| {
+                pending.filled_shares += fill.shares;
+                pending.order.idempotency_key.clone()
+            });
+            self.publish(PortfolioEvent::OrderFilled {
+                transaction_id: confirmation.transaction_id,
+                symbol: confirmation.symbol.clone(),
+                idempotency_key,
+            });
+            confirmations.push(confirmation);
+        }
+        let today = self.now().date_naive();
+        if self
+            .pending_orders
+            .get(order_id)
+            .is_some_and(|pending| pending.remaining_shares() == 0 || pending.is_expired(today))
+        {
+            self.pending_orders.remove(order_id);
+        }
+        Ok(confirmations)
+    }
+
+    pub fn expire_orders(&mut self) -> PortfolioResult<Vec<BrokerOrderId>> {
+        self.authorize(Role::Trader)?;
+        let today = self.now().date_naive();
+        let mut expired: Vec<BrokerOrderId> = self
+            .pending_orders
+            .iter()
+            .filter(|(_, pending)| pending.is_expired(today))
+            .map(|(order_id, _)| order_id.clone())
+            .collect();
+        expired.sort_unstable();
+        for order_id in &expired {
+            self.pending_orders.remove(order_id);
+        }
+        Ok(expired)
+    }
+}
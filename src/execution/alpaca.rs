@@ -0,0 +1,136 @@
+use crate::execution::{Broker, BrokerOrderId, Fill};
+use crate::money::{Currency, Money};
+use crate::{Order, PortfolioError, PortfolioResult, TransactionType};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+pub const PAPER_URL: &str = "https://paper-api.alpaca.markets";
+const ACTIVITIES_PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+struct OrderResponse {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct FillActivity {
+    id: String,
+    order_id: String,
+    symbol: String,
+    side: String,
+    qty: Decimal,
+    price: Decimal,
+    transaction_time: DateTime<Utc>,
+}
+
+pub struct AlpacaBroker {
+    agent: ureq::Agent,
+    base_url: String,
+    key_id: String,
+    secret_key: String,
+}
+
+fn broker_error(error: impl ToString) -> PortfolioError {
+    PortfolioError::BrokerError(error.to_string())
+}
+
+impl AlpacaBroker {
+    pub fn new(base_url: &str, key_id: &str, secret_key: &str) -> Self {
+        Self {
+            agent: ureq::Agent::new_with_defaults(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            key_id: key_id.to_string(),
+            secret_key: secret_key.to_string(),
+        }
+    }
+
+    pub fn paper(key_id: &str, secret_key: &str) -> Self {
+        Self::new(PAPER_URL, key_id, secret_key)
+    }
+
+    fn fill_activities_page(&self, page_token: Option<&str>) -> PortfolioResult<Vec<FillActivity>> {
+        let mut request = self
+            .agent
+            .get(format!("{}/v2/account/activities/FILL", self.base_url))
+            .header("APCA-API-KEY-ID", &self.key_id)
+            .header("APCA-API-SECRET-KEY", &self.secret_key)
+            .query("page_size", ACTIVITIES_PAGE_SIZE.to_string());
+        if let Some(token) = page_token {
+            request = request.query("page_token", token);
+        }
+        request
+            .call()
+            .and_then(|mut response| response.body_mut().read_json())
+            .map_err(broker_error)
+    }
+
+    fn fill_activities(&self) -> PortfolioResult<Vec<FillActivity>> {
+        let mut activities: Vec<FillActivity> = Vec::new();
+        loop {
+            let page_token = activities.last().map(|activity| activity.id.clone());
+            let page = self.fill_activities_page(page_token.as_deref())?;
+            let done = page.len() < ACTIVITIES_PAGE_SIZE;
+            activities.extend(page);
+            if done {
+                return Ok(activities);
+            }
+        }
+    }
+}
+
+impl Broker for AlpacaBroker {
+    fn place_order(&mut self, order: &Order) -> PortfolioResult<BrokerOrderId> {
+        let side = match order.transaction_type {
+            TransactionType::Purchase => "buy",
+            TransactionType::Sell => "sell",
+        };
+        let mut body = serde_json::json!({
+            "symbol": order.symbol,
+            "qty": order.shares.to_string(),
+            "side": side,
+            "type": "market",
+            "time_in_force": "day",
+        });
+        if let Some(price) = order.price {
+            body["type"] = "limit".into();
+            body["limit_price"] = price.amount.to_string().into();
+        }
+        if let Some(key) = &order.idempotency_key {
+            body["client_order_id"] = key.clone().into();
+        }
+        let response: OrderResponse = self
+            .agent
+            .post(format!("{}/v2/orders", self.base_url))
+            .header("APCA-API-KEY-ID", &self.key_id)
+            .header("APCA-API-SECRET-KEY", &self.secret_key)
+            .send_json(body)
+            .and_then(|mut response| response.body_mut().read_json())
+            .map_err(broker_error)?;
+        Ok(response.id)
+    }
+
+    fn fills(&mut self, order_id: &str) -> PortfolioResult<Vec<Fill>> {
+        self.fill_activities()?
+            .into_iter()
+            .filter(|activity| activity.order_id == order_id)
+            .map(|activity| {
+                let transaction_type = match activity.side.as_str() {
+                    "buy" => TransactionType::Purchase,
+                    _ => TransactionType::Sell,
+                };
+                let shares = u32::try_from(activity.qty)
+                    .map_err(|_| broker_error(format!("fractional fill {}", activity.id)))?;
+                Ok(Fill {
+                    fill_id: activity.id,
+                    order_id: activity.order_id,
+                    symbol: activity.symbol,
+                    transaction_type,
+                    shares,
+                    price: Money::new(activity.price, Currency::Usd),
+                    filled_at: activity.transaction_time,
+                })
+            })
+            .collect()
+    }
+}
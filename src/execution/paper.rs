@@ -0,0 +1,120 @@
+use crate::execution::{Broker, BrokerOrderId, Fill};
+use crate::money::Money;
+use crate::Quotes;
+use crate::{Order, PortfolioError, PortfolioResult, TransactionType};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+struct PaperOrder {
+    order: Order,
+    fills: Vec<Fill>,
+}
+
+impl PaperOrder {
+    fn remaining_shares(&self) -> u32 {
+        self.order.shares - self.fills.iter().map(|fill| fill.shares).sum::<u32>()
+    }
+}
+
+pub struct PaperBroker {
+    quotes: Quotes,
+    slippage: Decimal,
+    max_fill_shares: Option<u32>,
+    orders: HashMap<BrokerOrderId, PaperOrder>,
+    next_order: u64,
+    clock: fn() -> DateTime<Utc>,
+}
+
+impl PaperBroker {
+    pub fn new(quotes: Quotes) -> Self {
+        Self {
+            quotes,
+            slippage: Decimal::ZERO,
+            max_fill_shares: None,
+            orders: HashMap::new(),
+            next_order: 0,
+            clock: Utc::now,
+        }
+    }
+
+    pub fn with_slippage(mut self, slippage: Decimal) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    pub fn with_partial_fills(mut self, max_fill_shares: u32) -> Self {
+        self.max_fill_shares = Some(max_fill_shares);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: fn() -> DateTime<Utc>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn set_quote(&mut self, symbol: &str, price: Money) {
+        self.quotes.insert(symbol.to_string(), price);
+    }
+
+    fn execution_price(&self, order: &Order) -> Option<Money> {
+        let quote = self.quotes.get(&order.symbol)?;
+        let adjustment = match order.transaction_type {
+            TransactionType::Purchase => Decimal::ONE + self.slippage,
+            TransactionType::Sell => Decimal::ONE - self.slippage,
+        };
+        let price = Money::new(quote.amount * adjustment, quote.currency);
+        let within_limit = order
+            .price
+            .is_none_or(|limit| match order.transaction_type {
+                TransactionType::Purchase => price.amount <= limit.amount,
+                TransactionType::Sell => price.amount >= limit.amount,
+            });
+        within_limit.then_some(price)
+    }
+}
+
+impl Broker for PaperBroker {
+    fn place_order(&mut self, order: &Order) -> PortfolioResult<BrokerOrderId> {
+        self.next_order += 1;
+        let order_id = format!("paper-{}", self.next_order);
+        self.orders.insert(
+            order_id.clone(),
+            PaperOrder {
+                order: order.clone(),
+                fills: Vec::new(),
+            },
+        );
+        Ok(order_id)
+    }
+
+    fn fills(&mut self, order_id: &str) -> PortfolioResult<Vec<Fill>> {
+        let paper = self
+            .orders
+            .get(order_id)
+            .ok_or_else(|| PortfolioError::BrokerError(format!("unknown order {order_id}")))?;
+        let remaining = paper.remaining_shares();
+        let price = self.execution_price(&paper.order);
+        if let (true, Some(price)) = (remaining > 0, price) {
+            let shares = self
+                .max_fill_shares
+                .map_or(remaining, |max| max.min(remaining));
+            let filled_at = (self.clock)();
+            let paper = self
+                .orders
+                .get_mut(order_id)
+                .expect("order looked up above");
+            let fill = Fill {
+                fill_id: format!("{order_id}-{}", paper.fills.len() + 1),
+                order_id: order_id.to_string(),
+                symbol: paper.order.symbol.clone(),
+                transaction_type: paper.order.transaction_type.clone(),
+                shares,
+                price,
+                filled_at,
+            };
+            paper.fills.push(fill);
+        }
+        Ok(self.orders[order_id].fills.clone())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::auth::Role;
+use crate::i18n::{self, Label};
+use crate::ledger::Transaction;
+use crate::period::Period;
+use crate::{
+    Portfolio, PortfolioError, PortfolioResult, PurchaseRecord, TransactionId, TransactionType,
+};
+use std::collections::BTreeSet;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExportFilter {
+    pub symbols: Vec<String>,
+    pub date_range: Option<Period>,
+    pub transaction_types: Vec<TransactionType>,
+    pub tags: Vec<String>,
+}
+
+impl ExportFilter {
+    fn matches(&self, symbol: &str, record: &PurchaseRecord, tags: &BTreeSet<String>) -> bool {
+        (self.symbols.is_empty() || self.symbols.iter().any(|wanted| wanted == symbol))
+            && self
+                .date_range
+                .is_none_or(|period| period.contains(record.trade_date()))
+            && (self.transaction_types.is_empty()
+                || self.transaction_types.contains(&record.transaction_type))
+            && (self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag)))
+    }
+}
+
+impl Portfolio {
+    pub fn tag_transaction(&mut self, id: TransactionId, tag: &str) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        if !self.journal().iter().any(|(_, record)| record.id == id) {
+            return Err(PortfolioError::UnknownTransaction(id));
+        }
+        self.commit_entry(Transaction::Tag {
+            transaction_id: id,
+            tag: tag.to_string(),
+        })
+    }
+
+    pub fn transaction_tags(&self, id: TransactionId) -> impl Iterator<Item = &str> {
+        self.transaction_tags
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    pub fn export_records(&self, filter: &ExportFilter) -> Vec<(&str, &PurchaseRecord)> {
+        let no_tags = BTreeSet::new();
+        self.journal()
+            .into_iter()
+            .filter(|(symbol, record)| {
+                let tags = self.transaction_tags.get(&record.id).unwrap_or(&no_tags);
+                filter.matches(symbol, record, tags)
+            })
+            .collect()
+    }
+
+    pub fn export_csv(&self, filter: &ExportFilter) -> String {
+        let locale = self.config.locale;
+        let header = [
+            Label::Date,
+            Label::Symbol,
+            Label::TransactionType,
+            Label::Shares,
+            Label::Price,
+        ]
+        .map(|label| i18n::label(label, locale))
+        .join(",");
+        let mut csv = header + "\n";
+        for (symbol, record) in self.export_records(filter) {
+            let transaction_type = match record.transaction_type {
+                TransactionType::Purchase => Label::Purchase,
+                TransactionType::Sell => Label::Sell,
+            };
+            let price = record
+                .price
+                .map(|price| price.amount.to_string())
+                .unwrap_or_default();
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                self.format_record_date(record),
+                symbol,
+                i18n::label(transaction_type, locale),
+                record.shares,
+                price
+            ));
+        }
+        csv
+    }
+}
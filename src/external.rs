@@ -0,0 +1,117 @@
+use crate::auth::Role;
+use crate::money::Money;
+use crate::position::Position;
+use crate::Quotes;
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ExternalPosition {
+    pub account: String,
+    pub symbol: String,
+    pub shares: u32,
+}
+
+impl Portfolio {
+    pub fn add_external_position(
+        &mut self,
+        account: &str,
+        symbol: &str,
+        shares: u32,
+    ) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        if shares == 0 {
+            return Err(PortfolioError::ZeroShares);
+        }
+        match self
+            .external_positions
+            .iter_mut()
+            .find(|position| position.account == account && position.symbol == symbol)
+        {
+            Some(position) => position.shares = shares,
+            None => self.external_positions.push(ExternalPosition {
+                account: account.to_string(),
+                symbol: symbol.to_string(),
+                shares,
+            }),
+        }
+        self.bump_version();
+        Ok(())
+    }
+
+    pub fn remove_external_position(
+        &mut self,
+        account: &str,
+        symbol: &str,
+    ) -> PortfolioResult<bool> {
+        self.authorize(Role::Trader)?;
+        let before = self.external_positions.len();
+        self.external_positions
+            .retain(|position| position.account != account || position.symbol != symbol);
+        let removed = self.external_positions.len() != before;
+        if removed {
+            self.bump_version();
+        }
+        Ok(removed)
+    }
+
+    pub fn external_positions(&self) -> &[ExternalPosition] {
+        &self.external_positions
+    }
+
+    fn consolidated_holdings(&self) -> BTreeMap<&str, i64> {
+        let mut holdings: BTreeMap<&str, i64> = BTreeMap::new();
+        for (symbol, position) in &self.holdings {
+            if *position != Position::Flat {
+                *holdings.entry(symbol).or_default() += position.signed_quantity();
+            }
+        }
+        for position in &self.external_positions {
+            *holdings.entry(&position.symbol).or_default() += i64::from(position.shares);
+        }
+        holdings
+    }
+
+    fn consolidated_values(&self, quotes: &Quotes) -> PortfolioResult<Vec<(&str, Money)>> {
+        self.consolidated_holdings()
+            .into_iter()
+            .map(|(symbol, shares)| {
+                let price = quotes
+                    .get(symbol)
+                    .ok_or_else(|| PortfolioError::MissingPrice(symbol.to_string()))?;
+                Ok((symbol, price.checked_mul(shares.into())?))
+            })
+            .collect()
+    }
+
+    pub fn consolidated_value(&self, quotes: &Quotes) -> PortfolioResult<Money> {
+        let values = self.consolidated_values(quotes)?;
+        Money::checked_sum(
+            self.config.base_currency,
+            values.iter().map(|(_, value)| value),
+        )
+    }
+
+    pub fn allocation(&self, quotes: &Quotes) -> PortfolioResult<Vec<(String, Decimal)>> {
+        let values = self.consolidated_values(quotes)?;
+        let total = Money::checked_sum(
+            self.config.base_currency,
+            values.iter().map(|(_, value)| value),
+        )?;
+        Ok(values
+            .into_iter()
+            .map(|(symbol, value)| {
+                let weight = if total.is_zero() {
+                    Decimal::ZERO
+                } else {
+                    value.amount / total.amount * Decimal::ONE_HUNDRED
+                };
+                (symbol.to_string(), weight)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,151 @@
+use crate::auth::Role;
+use crate::ledger::Trade;
+use crate::money::Money;
+use crate::{
+    Portfolio, PortfolioError, PortfolioResult, PurchaseRecord, TradeConfirmation, TransactionId,
+    TransactionType,
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TradeFx {
+    pub local_price: Money,
+    pub rate: Decimal,
+}
+
+impl TradeFx {
+    fn identity(price: Money) -> Self {
+        Self {
+            local_price: price,
+            rate: Decimal::ONE,
+        }
+    }
+
+    fn of(record: &PurchaseRecord) -> Option<Self> {
+        record.fx.or_else(|| record.price.map(Self::identity))
+    }
+
+    fn to_local(self, base: &Money) -> PortfolioResult<Money> {
+        base.amount
+            .checked_div(self.rate)
+            .map(|amount| Money::new(amount, self.local_price.currency))
+            .ok_or(PortfolioError::Overflow)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FxGain {
+    pub sale_id: TransactionId,
+    pub purchase_id: TransactionId,
+    pub shares: u32,
+    pub local_proceeds: Money,
+    pub local_cost: Money,
+    pub local_gain: Money,
+    pub base_proceeds: Money,
+    pub base_cost: Money,
+    pub base_gain: Money,
+    pub currency_gain: Money,
+}
+
+impl Portfolio {
+    pub fn transact_in_currency(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        transaction_type: TransactionType,
+        local_price: Money,
+        rate: Decimal,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        if rate <= Decimal::ZERO {
+            return Err(PortfolioError::InvalidFxRate(rate));
+        }
+        if local_price.is_negative() {
+            return Err(PortfolioError::NegativeAmount);
+        }
+        let base_price = Money::new(
+            local_price
+                .amount
+                .checked_mul(rate)
+                .ok_or(PortfolioError::Overflow)?,
+            self.config.base_currency,
+        );
+        let mut trade = Trade::new(symbol, shares, transaction_type, Some(base_price), date);
+        trade.record.fx = Some(TradeFx { local_price, rate });
+        self.record_trade(trade)
+    }
+
+    pub(crate) fn validate_local_currency(
+        &self,
+        symbol: &str,
+        record: &PurchaseRecord,
+    ) -> PortfolioResult<()> {
+        let Some(local) = TradeFx::of(record).map(|fx| fx.local_price.currency) else {
+            return Ok(());
+        };
+        let existing = self
+            .purchase_records
+            .get(symbol)
+            .into_iter()
+            .flatten()
+            .find_map(TradeFx::of);
+        match existing {
+            Some(fx) if fx.local_price.currency != local => Err(PortfolioError::CurrencyMismatch {
+                expected: fx.local_price.currency,
+                found: local,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn fx_gains(&self, symbol: &str) -> PortfolioResult<Vec<FxGain>> {
+        let purchases: HashMap<TransactionId, &PurchaseRecord> = self
+            .get_purchase_record(symbol)?
+            .iter()
+            .map(|record| (record.id, record))
+            .collect();
+        let mut gains = Vec::new();
+        for (record, confirmation) in self.replay_symbol(symbol)?.trades {
+            let Some(sale_fx) = TradeFx::of(&record) else {
+                continue;
+            };
+            for lot_gain in &confirmation.lot_gains {
+                let consumption = &lot_gain.consumption;
+                let purchase_fx = purchases
+                    .get(&consumption.sequence)
+                    .and_then(|purchase| TradeFx::of(purchase))
+                    .unwrap_or(TradeFx::identity(Money::zero(self.config.base_currency)));
+                let base_cost = lot_gain.proceeds.checked_sub(&lot_gain.gain)?;
+                let local_proceeds = sale_fx.local_price.checked_mul(consumption.shares.into())?;
+                let local_cost = purchase_fx.to_local(&base_cost)?;
+                let local_gain = local_proceeds.checked_sub(&local_cost)?;
+                let translated_gain = Money::new(
+                    local_gain
+                        .amount
+                        .checked_mul(sale_fx.rate)
+                        .ok_or(PortfolioError::Overflow)?,
+                    lot_gain.gain.currency,
+                );
+                gains.push(FxGain {
+                    sale_id: record.id,
+                    purchase_id: consumption.sequence,
+                    shares: consumption.shares,
+                    local_proceeds,
+                    local_cost,
+                    local_gain,
+                    base_proceeds: lot_gain.proceeds,
+                    base_cost,
+                    base_gain: lot_gain.gain,
+                    currency_gain: lot_gain.gain.checked_sub(&translated_gain)?,
+                });
+            }
+        }
+        Ok(gains)
+    }
+}
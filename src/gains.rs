@@ -0,0 +1,298 @@
+use crate::calendar;
+use crate::lots::{prorate, Acquisition, Lot, LotConsumption};
+use crate::money::{Currency, Money};
+use crate::{Portfolio, PortfolioError, PortfolioResult, TransactionType};
+use chrono::{DateTime, Months, NaiveDate, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum HoldingTerm {
+    ShortTerm,
+    LongTerm,
+}
+
+impl HoldingTerm {
+    pub fn classify(acquired: DateTime<Utc>, sold: DateTime<Utc>) -> Self {
+        match acquired.checked_add_months(Months::new(12)) {
+            Some(one_year) if sold > one_year => HoldingTerm::LongTerm,
+            _ => HoldingTerm::ShortTerm,
+        }
+    }
+
+    pub fn for_lot(lot: &Lot, as_of: DateTime<Utc>) -> Self {
+        match lot.acquisition {
+            Acquisition::Purchase | Acquisition::StockDividend => {
+                Self::classify(lot.acquired, as_of)
+            }
+            Acquisition::Gift { donor_acquired, .. } => Self::classify(donor_acquired, as_of),
+            Acquisition::Inheritance => HoldingTerm::LongTerm,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct GainLoss {
+    pub consumption: LotConsumption,
+    pub proceeds: Money,
+    pub gain: Money,
+    pub term: HoldingTerm,
+}
+
+pub(crate) fn realize(
+    consumed: &[LotConsumption],
+    price: &Money,
+    sold: DateTime<Utc>,
+) -> PortfolioResult<Vec<GainLoss>> {
+    consumed
+        .iter()
+        .map(|consumption| {
+            let proceeds = price.checked_mul(consumption.shares.into())?;
+            let (gain, term) = gain_and_term(consumption, &proceeds, sold)?;
+            Ok(GainLoss {
+                consumption: consumption.clone(),
+                gain,
+                proceeds,
+                term,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn realize_net(
+    consumed: &[LotConsumption],
+    net_proceeds: &Money,
+    shares: u32,
+    sold: DateTime<Utc>,
+) -> PortfolioResult<Vec<GainLoss>> {
+    let consumed_shares: u32 = consumed.iter().map(|consumption| consumption.shares).sum();
+    let mut unallocated = prorate(net_proceeds, consumed_shares.into(), shares.into())?;
+    let last = consumed.len().saturating_sub(1);
+    consumed
+        .iter()
+        .enumerate()
+        .map(|(index, consumption)| {
+            let proceeds = if index == last {
+                unallocated
+            } else {
+                prorate(net_proceeds, consumption.shares.into(), shares.into())?
+            };
+            unallocated = unallocated.checked_sub(&proceeds)?;
+            let (gain, term) = gain_and_term(consumption, &proceeds, sold)?;
+            Ok(GainLoss {
+                consumption: consumption.clone(),
+                gain,
+                proceeds,
+                term,
+            })
+        })
+        .collect()
+}
+
+fn gain_and_term(
+    consumption: &LotConsumption,
+    proceeds: &Money,
+    sold: DateTime<Utc>,
+) -> PortfolioResult<(Money, HoldingTerm)> {
+    let basis = &consumption.cost_basis;
+    match consumption.acquisition {
+        Acquisition::Purchase | Acquisition::StockDividend => Ok((
+            proceeds.checked_sub(basis)?,
+            HoldingTerm::classify(consumption.acquired, sold),
+        )),
+        Acquisition::Inheritance => Ok((proceeds.checked_sub(basis)?, HoldingTerm::LongTerm)),
+        Acquisition::Gift {
+            donor_acquired,
+            fair_market_value,
+        } => {
+            let carryover_term = HoldingTerm::classify(donor_acquired, sold);
+            let loss_basis = fair_market_value.checked_mul(consumption.shares.into())?;
+            if loss_basis.amount >= basis.amount || proceeds.amount > basis.amount {
+                Ok((proceeds.checked_sub(basis)?, carryover_term))
+            } else if proceeds.amount < loss_basis.amount {
+                Ok((
+                    proceeds.checked_sub(&loss_basis)?,
+                    HoldingTerm::classify(consumption.acquired, sold),
+                ))
+            } else {
+                Ok((Money::zero(proceeds.currency), carryover_term))
+            }
+        }
+    }
+}
+
+pub fn total_gain(
+    currency: Currency,
+    gains: &[GainLoss],
+    term: Option<HoldingTerm>,
+) -> PortfolioResult<Money> {
+    Money::checked_sum(
+        currency,
+        gains
+            .iter()
+            .filter(|gain| term.is_none_or(|term| gain.term == term))
+            .map(|gain| &gain.gain),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SellPreview {
+    pub symbol: String,
+    pub shares: u32,
+    pub price: Money,
+    pub proceeds: Money,
+    pub lots: Vec<GainLoss>,
+    pub short_term_gain: Money,
+    pub long_term_gain: Money,
+    pub realized_gain: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LotGain {
+    pub symbol: String,
+    pub date: DateTime<Utc>,
+    pub gain: GainLoss,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GainsReport {
+    pub lots: Vec<LotGain>,
+    pub short_term_gain: Money,
+    pub long_term_gain: Money,
+    pub total_gain: Money,
+}
+
+impl GainsReport {
+    fn new(currency: Currency, lots: Vec<LotGain>) -> PortfolioResult<Self> {
+        let gains: Vec<GainLoss> = lots.iter().map(|lot| lot.gain.clone()).collect();
+        Ok(GainsReport {
+            short_term_gain: total_gain(currency, &gains, Some(HoldingTerm::ShortTerm))?,
+            long_term_gain: total_gain(currency, &gains, Some(HoldingTerm::LongTerm))?,
+            total_gain: total_gain(currency, &gains, None)?,
+            lots,
+        })
+    }
+
+    pub fn by_symbol(&self) -> PortfolioResult<BTreeMap<String, Money>> {
+        let mut totals: BTreeMap<String, Money> = BTreeMap::new();
+        for lot in &self.lots {
+            let total = match totals.get(&lot.symbol) {
+                Some(total) => total.checked_add(&lot.gain.gain)?,
+                None => lot.gain.gain,
+            };
+            totals.insert(lot.symbol.clone(), total);
+        }
+        Ok(totals)
+    }
+}
+
+impl Portfolio {
+    pub fn long_term_date(&self, lot: &Lot) -> PortfolioResult<NaiveDate> {
+        let held_since = match lot.acquisition {
+            Acquisition::Purchase | Acquisition::StockDividend => lot.acquired,
+            Acquisition::Gift { donor_acquired, .. } => donor_acquired,
+            Acquisition::Inheritance => return Ok(lot.acquired.date_naive()),
+        };
+        let one_year = held_since
+            .checked_add_months(Months::new(12))
+            .ok_or(PortfolioError::Overflow)?;
+        calendar::next_trading_day(one_year.date_naive(), self.calendar.as_ref())
+    }
+
+    pub fn realized_gains(&self, symbol: &str) -> PortfolioResult<GainsReport> {
+        GainsReport::new(self.config.base_currency, self.realized_lot_gains(symbol)?)
+    }
+
+    pub fn realized_gains_total(&self) -> PortfolioResult<GainsReport> {
+        let mut lots = Vec::new();
+        for symbol in self.traded_symbols() {
+            lots.extend(self.realized_lot_gains(symbol)?);
+        }
+        GainsReport::new(self.config.base_currency, lots)
+    }
+
+    pub fn unrealized_gains(
+        &self,
+        symbol: &str,
+        current_price: Money,
+    ) -> PortfolioResult<GainsReport> {
+        let now = self.now();
+        let lots = self
+            .open_lots(symbol)
+            .iter()
+            .map(|lot| {
+                let consumption = LotConsumption {
+                    lot_id: lot.id,
+                    acquired: lot.acquired,
+                    sequence: lot.sequence,
+                    shares: lot.shares,
+                    cost_basis: lot.cost_basis,
+                    acquisition: lot.acquisition,
+                    covered: lot.covered,
+                    remainder_lot_id: None,
+                };
+                let market_value = current_price.checked_mul(lot.shares.into())?;
+                let (gain, term) = gain_and_term(&consumption, &market_value, now)?;
+                Ok(LotGain {
+                    symbol: symbol.to_string(),
+                    date: now,
+                    gain: GainLoss {
+                        consumption,
+                        proceeds: market_value,
+                        gain,
+                        term,
+                    },
+                })
+            })
+            .collect::<PortfolioResult<Vec<_>>>()?;
+        GainsReport::new(current_price.currency, lots)
+    }
+
+    fn realized_lot_gains(&self, symbol: &str) -> PortfolioResult<Vec<LotGain>> {
+        Ok(self
+            .replay_symbol(symbol)?
+            .trades
+            .into_iter()
+            .flat_map(|(record, confirmation)| {
+                confirmation.lot_gains.into_iter().map(move |gain| LotGain {
+                    symbol: symbol.to_string(),
+                    date: record.date,
+                    gain,
+                })
+            })
+            .collect())
+    }
+
+    pub fn preview_sell(
+        &self,
+        symbol: &str,
+        shares: u32,
+        price: Money,
+    ) -> PortfolioResult<SellPreview> {
+        let mut scratch = self.clone();
+        scratch.subscribers.clear();
+        let confirmation = scratch.transact_settled(
+            symbol,
+            shares,
+            TransactionType::Sell,
+            Some(price),
+            None,
+            self.now(),
+        )?;
+        let currency = price.currency;
+        let lots = confirmation.lot_gains;
+        Ok(SellPreview {
+            symbol: symbol.to_string(),
+            shares,
+            price,
+            proceeds: price.checked_mul(shares.into())?,
+            short_term_gain: total_gain(currency, &lots, Some(HoldingTerm::ShortTerm))?,
+            long_term_gain: total_gain(currency, &lots, Some(HoldingTerm::LongTerm))?,
+            realized_gain: total_gain(currency, &lots, None)?,
+            lots,
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use crate::auth::Role;
+use crate::money::Money;
+use crate::Quotes;
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Goal {
+    pub name: String,
+    pub target_value: Money,
+    pub target_date: NaiveDate,
+    pub monthly_contribution: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GoalProgress {
+    pub name: String,
+    pub current_value: Money,
+    pub funded_percent: Decimal,
+    pub months_remaining: u32,
+    pub required_monthly_contribution: Money,
+    pub on_track: bool,
+}
+
+fn months_between(from: NaiveDate, to: NaiveDate) -> u32 {
+    if to <= from {
+        return 0;
+    }
+    let months = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32;
+    let months = if to.day() < from.day() {
+        months - 1
+    } else {
+        months
+    };
+    months.max(0) as u32
+}
+
+impl Portfolio {
+    pub fn add_goal(&mut self, goal: Goal) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&goal.target_value)?;
+        self.validate_amount(&goal.monthly_contribution)?;
+        if goal.target_value.is_zero() {
+            return Err(PortfolioError::InvalidGoal);
+        }
+        self.goals.push(goal);
+        self.bump_version();
+        Ok(())
+    }
+
+    pub fn goals(&self) -> &[Goal] {
+        &self.goals
+    }
+
+    pub fn goal_progress(
+        &self,
+        quotes: &Quotes,
+        as_of: NaiveDate,
+    ) -> PortfolioResult<Vec<GoalProgress>> {
+        let current_value = self.market_value(quotes)?;
+        self.goals
+            .iter()
+            .map(|goal| {
+                let months_remaining = months_between(as_of, goal.target_date);
+                let shortfall = goal.target_value.checked_sub(&current_value)?;
+                let required = if shortfall.is_negative() {
+                    Money::zero(shortfall.currency)
+                } else if months_remaining == 0 {
+                    shortfall
+                } else {
+                    Money::new(
+                        shortfall.amount / Decimal::from(months_remaining),
+                        shortfall.currency,
+                    )
+                };
+                Ok(GoalProgress {
+                    name: goal.name.clone(),
+                    current_value,
+                    funded_percent: current_value.amount * Decimal::ONE_HUNDRED
+                        / goal.target_value.amount,
+                    months_remaining,
+                    on_track: goal.monthly_contribution.amount >= required.amount,
+                    required_monthly_contribution: required,
+                })
+            })
+            .collect()
+    }
+}
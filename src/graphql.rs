@@ -0,0 +1,168 @@
+use crate::export::ExportFilter;
+use crate::money::Money;
+use crate::shared::SharedPortfolio;
+use crate::{Order, TradeConfirmation, TransactionType};
+use async_graphql::{Context, EmptySubscription, Enum, Object, Result, Schema, SimpleObject};
+use rust_decimal::Decimal;
+
+pub type PortfolioSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+pub fn schema(portfolio: SharedPortfolio) -> PortfolioSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(portfolio)
+        .finish()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enum)]
+pub enum TradeSide {
+    Purchase,
+    Sell,
+}
+
+impl From<TradeSide> for TransactionType {
+    fn from(side: TradeSide) -> Self {
+        match side {
+            TradeSide::Purchase => TransactionType::Purchase,
+            TradeSide::Sell => TransactionType::Sell,
+        }
+    }
+}
+
+impl From<&TransactionType> for TradeSide {
+    fn from(transaction_type: &TransactionType) -> Self {
+        match transaction_type {
+            TransactionType::Purchase => TradeSide::Purchase,
+            TransactionType::Sell => TradeSide::Sell,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Holding {
+    pub symbol: String,
+    pub shares: i64,
+}
+
+#[derive(SimpleObject)]
+pub struct Transaction {
+    pub id: u64,
+    pub symbol: String,
+    pub date: String,
+    pub side: TradeSide,
+    pub shares: u32,
+    pub price: Option<Decimal>,
+}
+
+#[derive(SimpleObject)]
+pub struct TransactionPage {
+    pub total: usize,
+    pub items: Vec<Transaction>,
+}
+
+#[derive(SimpleObject)]
+pub struct Trade {
+    pub transaction_id: u64,
+    pub symbol: String,
+    pub side: TradeSide,
+    pub shares: u32,
+    pub resulting_shares: i64,
+    pub realized_gain: Option<Decimal>,
+}
+
+impl From<TradeConfirmation> for Trade {
+    fn from(confirmation: TradeConfirmation) -> Self {
+        Self {
+            transaction_id: confirmation.transaction_id,
+            side: TradeSide::from(&confirmation.transaction_type),
+            symbol: confirmation.symbol,
+            shares: confirmation.shares,
+            resulting_shares: confirmation.resulting_position.signed_quantity(),
+            realized_gain: confirmation.realized_gain.map(|gain| gain.amount),
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn holdings(&self, ctx: &Context<'_>) -> Vec<Holding> {
+        let portfolio = ctx.data_unchecked::<SharedPortfolio>().read();
+        portfolio
+            .traded_symbols()
+            .into_iter()
+            .map(|symbol| Holding {
+                symbol: symbol.to_string(),
+                shares: portfolio.get_signed_share_count(symbol),
+            })
+            .filter(|holding| holding.shares != 0)
+            .collect()
+    }
+
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        symbols: Option<Vec<String>>,
+        sides: Option<Vec<TradeSide>>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> TransactionPage {
+        let portfolio = ctx.data_unchecked::<SharedPortfolio>().read();
+        let filter = ExportFilter {
+            symbols: symbols.unwrap_or_default(),
+            transaction_types: sides
+                .unwrap_or_default()
+                .into_iter()
+                .map(TransactionType::from)
+                .collect(),
+            ..ExportFilter::default()
+        };
+        let records = portfolio.export_records(&filter);
+        let items = records
+            .iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|(symbol, record)| Transaction {
+                id: record.id,
+                symbol: symbol.to_string(),
+                date: portfolio.format_record_date(record),
+                side: TradeSide::from(&record.transaction_type),
+                shares: record.shares,
+                price: record.price.map(|price| price.amount),
+            })
+            .collect();
+        TransactionPage {
+            total: records.len(),
+            items,
+        }
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn trade(
+        &self,
+        ctx: &Context<'_>,
+        symbol: String,
+        side: TradeSide,
+        shares: u32,
+        price: Option<Decimal>,
+        idempotency_key: Option<String>,
+    ) -> Result<Trade> {
+        let mut portfolio = ctx.data_unchecked::<SharedPortfolio>().write();
+        let currency = portfolio.config().base_currency;
+        let order = Order {
+            symbol,
+            transaction_type: side.into(),
+            shares,
+            price: price.map(|amount| Money::new(amount, currency)),
+            idempotency_key,
+        };
+        portfolio
+            .submit(order)
+            .map(Trade::from)
+            .map_err(|error| portfolio.localize_error(&error).into())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::gains::HoldingTerm;
+use crate::lots::LotId;
+use crate::money::Money;
+use crate::period::Period;
+use crate::Quotes;
+use crate::{Portfolio, PortfolioError, PortfolioResult, TransactionType};
+use chrono::{Duration, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HarvestCandidate {
+    pub symbol: String,
+    pub lot_id: LotId,
+    pub shares: u32,
+    pub cost_basis: Money,
+    pub market_value: Money,
+    pub loss: Money,
+    pub term: HoldingTerm,
+    pub long_term_on: Option<NaiveDate>,
+    pub tax_savings: Money,
+    pub wash_sale_window: Period,
+    pub recent_purchase_in_window: bool,
+    pub replacement: Option<String>,
+}
+
+impl Portfolio {
+    pub fn harvest_candidates(
+        &self,
+        quotes: &Quotes,
+        min_loss: Money,
+    ) -> PortfolioResult<Vec<HarvestCandidate>> {
+        let now = self.now();
+        let today = now.date_naive();
+        let window_days = Duration::days(self.config.tax.wash_sale_days);
+        let wash_sale_window = Period::new(today - window_days, today + window_days);
+        let mut symbols: Vec<&String> = self.lots.keys().collect();
+        symbols.sort_unstable();
+
+        let mut candidates = Vec::new();
+        for symbol in symbols {
+            let lots = &self.lots[symbol];
+            if lots.is_empty() {
+                continue;
+            }
+            let price = quotes
+                .get(symbol)
+                .ok_or_else(|| PortfolioError::MissingPrice(symbol.clone()))?;
+            let recent_purchase_in_window = self.purchase_records[symbol].iter().any(|record| {
+                record.transaction_type == TransactionType::Purchase
+                    && wash_sale_window.contains(record.trade_date())
+                    && record.date <= now
+                    && !self.is_reversed(record.id)
+            });
+            for lot in lots {
+                let market_value = price.checked_mul(lot.shares.into())?;
+                let loss = lot.cost_basis.checked_sub(&market_value)?;
+                if loss.amount <= Decimal::ZERO || loss.amount < min_loss.amount {
+                    continue;
+                }
+                let term = HoldingTerm::for_lot(lot, now);
+                let (rate, long_term_on) = match term {
+                    HoldingTerm::ShortTerm => (
+                        self.config.tax.short_term_rate,
+                        Some(self.long_term_date(lot)?),
+                    ),
+                    HoldingTerm::LongTerm => (self.config.tax.long_term_rate, None),
+                };
+                candidates.push(HarvestCandidate {
+                    symbol: symbol.clone(),
+                    lot_id: lot.id,
+                    shares: lot.shares,
+                    cost_basis: lot.cost_basis,
+                    market_value,
+                    tax_savings: loss.checked_mul(rate)?,
+                    loss,
+                    term,
+                    long_term_on,
+                    wash_sale_window,
+                    recent_purchase_in_window,
+                    replacement: None,
+                });
+            }
+        }
+        Ok(candidates)
+    }
+}
+
+pub fn pair_replacements(
+    candidates: Vec<HarvestCandidate>,
+    replacements: &HashMap<String, String>,
+) -> Vec<HarvestCandidate> {
+    candidates
+        .into_iter()
+        .map(|candidate| HarvestCandidate {
+            replacement: replacements.get(&candidate.symbol).cloned(),
+            ..candidate
+        })
+        .collect()
+}
@@ -0,0 +1,509 @@
+use crate::PortfolioError;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    De,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Label {
+    Symbol,
+    Shares,
+    Price,
+    Date,
+    TransactionType,
+    Purchase,
+    Sell,
+}
+
+struct Catalog {
+    en: &'static str,
+    es: &'static str,
+    de: &'static str,
+}
+
+impl Catalog {
+    fn select(&self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => self.en,
+            Locale::Es => self.es,
+            Locale::De => self.de,
+        }
+    }
+}
+
+pub fn label(label: Label, locale: Locale) -> &'static str {
+    let catalog = match label {
+        Label::Symbol => Catalog {
+            en: "Symbol",
+            es: "Símbolo",
+            de: "Symbol",
+        },
+        Label::Shares => Catalog {
+            en: "Shares",
+            es: "Acciones",
+            de: "Anteile",
+        },
+        Label::Price => Catalog {
+            en: "Price",
+            es: "Precio",
+            de: "Preis",
+        },
+        Label::Date => Catalog {
+            en: "Date",
+            es: "Fecha",
+            de: "Datum",
+        },
+        Label::TransactionType => Catalog {
+            en: "Type",
+            es: "Tipo",
+            de: "Art",
+        },
+        Label::Purchase => Catalog {
+            en: "Purchase",
+            es: "Compra",
+            de: "Kauf",
+        },
+        Label::Sell => Catalog {
+            en: "Sell",
+            es: "Venta",
+            de: "Verkauf",
+        },
+    };
+    catalog.select(locale)
+}
+
+pub fn error_message(error: &PortfolioError, locale: Locale) -> String {
+    let (catalog, argument) = match error {
+        PortfolioError::ZeroShares => (
+            Catalog {
+                en: "Cannot perform transaction with zero shares",
+                es: "No se puede realizar una transacción con cero acciones",
+                de: "Transaktion mit null Anteilen nicht möglich",
+            },
+            None,
+        ),
+        PortfolioError::InvalidSell => (
+            Catalog {
+                en: "Cannot sell more shares than owned",
+                es: "No se pueden vender más acciones de las que se poseen",
+                de: "Es können nicht mehr Anteile verkauft werden als vorhanden",
+            },
+            None,
+        ),
+        PortfolioError::NoSymbolHistory => (
+            Catalog {
+                en: "No history for symbol",
+                es: "No hay historial para el símbolo",
+                de: "Kein Verlauf für das Symbol",
+            },
+            None,
+        ),
+        PortfolioError::InvalidPurchase => (
+            Catalog {
+                en: "Too many shares purchased",
+                es: "Se compraron demasiadas acciones",
+                de: "Zu viele Anteile gekauft",
+            },
+            None,
+        ),
+        PortfolioError::TradeLimitExceeded(limit) => (
+            Catalog {
+                en: "Trade exceeds the configured limit of {} shares",
+                es: "La operación supera el límite configurado de {} acciones",
+                de: "Der Handel überschreitet das konfigurierte Limit von {} Anteilen",
+            },
+            Some(limit.to_string()),
+        ),
+        PortfolioError::InvalidLotSize(lot_size) => (
+            Catalog {
+                en: "Trade must be a multiple of the {}-share lot size",
+                es: "La operación debe ser un múltiplo del lote de {} acciones",
+                de: "Der Handel muss ein Vielfaches der Lotgröße von {} Anteilen sein",
+            },
+            Some(lot_size.to_string()),
+        ),
+        PortfolioError::TradingHalted(symbol) => (
+            Catalog {
+                en: "Trading in {} is halted",
+                es: "La negociación de {} está suspendida",
+                de: "Der Handel mit {} ist ausgesetzt",
+            },
+            Some(symbol.clone()),
+        ),
+        PortfolioError::InvalidConfig(detail) => (
+            Catalog {
+                en: "Invalid configuration: {}",
+                es: "Configuración no válida: {}",
+                de: "Ungültige Konfiguration: {}",
+            },
+            Some(detail.clone()),
+        ),
+        PortfolioError::CurrencyMismatch { expected, .. } => (
+            Catalog {
+                en: "Currency mismatch: expected {}, found {found}",
+                es: "Divisas no coinciden: se esperaba {}, se encontró {found}",
+                de: "Währungskonflikt: erwartet {}, gefunden {found}",
+            },
+            Some(expected.to_string()),
+        ),
+        PortfolioError::Overflow => (
+            Catalog {
+                en: "Arithmetic overflow while aggregating portfolio values",
+                es: "Desbordamiento aritmético al agregar valores de la cartera",
+                de: "Arithmetischer Überlauf beim Aggregieren von Portfoliowerten",
+            },
+            None,
+        ),
+        PortfolioError::SharesOnLoan => (
+            Catalog {
+                en: "Shares on loan cannot be sold until recalled",
+                es: "Las acciones prestadas no se pueden vender hasta ser recuperadas",
+                de: "Verliehene Anteile können erst nach dem Rückruf verkauft werden",
+            },
+            None,
+        ),
+        PortfolioError::InvalidLend => (
+            Catalog {
+                en: "Cannot lend more shares than are available",
+                es: "No se pueden prestar más acciones de las disponibles",
+                de: "Es können nicht mehr Anteile verliehen werden als verfügbar",
+            },
+            None,
+        ),
+        PortfolioError::InsufficientSharesOnLoan => (
+            Catalog {
+                en: "Not enough shares on loan",
+                es: "No hay suficientes acciones prestadas",
+                de: "Nicht genügend Anteile verliehen",
+            },
+            None,
+        ),
+        PortfolioError::NegativeAmount => (
+            Catalog {
+                en: "Amount must not be negative",
+                es: "El importe no puede ser negativo",
+                de: "Der Betrag darf nicht negativ sein",
+            },
+            None,
+        ),
+        PortfolioError::NoOpenLots => (
+            Catalog {
+                en: "No open lots for symbol",
+                es: "No hay lotes abiertos para el símbolo",
+                de: "Keine offenen Posten für das Symbol",
+            },
+            None,
+        ),
+        PortfolioError::UnknownInstrument => (
+            Catalog {
+                en: "Instrument is not registered",
+                es: "El instrumento no está registrado",
+                de: "Das Instrument ist nicht registriert",
+            },
+            None,
+        ),
+        PortfolioError::MissingPrice(symbol) => (
+            Catalog {
+                en: "No price available for {}",
+                es: "No hay precio disponible para {}",
+                de: "Kein Preis verfügbar für {}",
+            },
+            Some(symbol.clone()),
+        ),
+        PortfolioError::StalePrice(symbol) => (
+            Catalog {
+                en: "Latest price for {} is older than the staleness threshold",
+                es: "El último precio de {} supera el umbral de antigüedad",
+                de: "Der letzte Preis für {} ist älter als der Aktualitätsschwellenwert",
+            },
+            Some(symbol.clone()),
+        ),
+        PortfolioError::InvalidGoal => (
+            Catalog {
+                en: "Goal target must be positive",
+                es: "El objetivo de la meta debe ser positivo",
+                de: "Das Sparziel muss positiv sein",
+            },
+            None,
+        ),
+        PortfolioError::InsufficientHistory => (
+            Catalog {
+                en: "Not enough value history to compute a return",
+                es: "No hay suficiente historial de valores para calcular un rendimiento",
+                de: "Nicht genügend Wertverlauf, um eine Rendite zu berechnen",
+            },
+            None,
+        ),
+        PortfolioError::IdempotencyKeyConflict(key) => (
+            Catalog {
+                en: "Idempotency key {} was already used for a different transaction",
+                es: "La clave de idempotencia {} ya se usó para otra transacción",
+                de:
+                    "Der Idempotenzschlüssel {} wurde bereits für eine andere Transaktion verwendet",
+            },
+            Some(key.clone()),
+        ),
+        PortfolioError::UnknownTransaction(id) => (
+            Catalog {
+                en: "No transaction with id {}",
+                es: "No existe ninguna transacción con el id {}",
+                de: "Keine Transaktion mit der ID {}",
+            },
+            Some(id.to_string()),
+        ),
+        PortfolioError::TransactionAlreadyReversed(id) => (
+            Catalog {
+                en: "Transaction {} has already been reversed",
+                es: "La transacción {} ya fue revertida",
+                de: "Die Transaktion {} wurde bereits storniert",
+            },
+            Some(id.to_string()),
+        ),
+        PortfolioError::AmbiguousLocalTime(time) => (
+            Catalog {
+                en: "Local time {} is ambiguous or does not exist in the given time zone",
+                es: "La hora local {} es ambigua o no existe en la zona horaria indicada",
+                de: "Die Ortszeit {} ist mehrdeutig oder existiert in der Zeitzone nicht",
+            },
+            Some(time.clone()),
+        ),
+        PortfolioError::FutureDated(date) => (
+            Catalog {
+                en: "Transaction dated {} is in the future",
+                es: "La transacción con fecha {} está en el futuro",
+                de: "Die Transaktion vom {} liegt in der Zukunft",
+            },
+            Some(date.to_string()),
+        ),
+        PortfolioError::InvalidWebhookUrl(url) => (
+            Catalog {
+                en: "Webhook endpoint {} must use https",
+                es: "El endpoint de webhook {} debe usar https",
+                de: "Der Webhook-Endpunkt {} muss https verwenden",
+            },
+            Some(url.clone()),
+        ),
+        PortfolioError::PublishFailed(reason) => (
+            Catalog {
+                en: "Failed to publish event: {}",
+                es: "No se pudo publicar el evento: {}",
+                de: "Ereignis konnte nicht veröffentlicht werden: {}",
+            },
+            Some(reason.clone()),
+        ),
+        PortfolioError::SyncFailed(reason) => (
+            Catalog {
+                en: "Account sync failed: {}",
+                es: "Falló la sincronización de la cuenta: {}",
+                de: "Kontosynchronisierung fehlgeschlagen: {}",
+            },
+            Some(reason.clone()),
+        ),
+        PortfolioError::BrokerError(reason) => (
+            Catalog {
+                en: "Broker request failed: {}",
+                es: "Falló la solicitud al bróker: {}",
+                de: "Broker-Anfrage fehlgeschlagen: {}",
+            },
+            Some(reason.clone()),
+        ),
+        PortfolioError::UnknownAlert(id) => (
+            Catalog {
+                en: "No alert with id {}",
+                es: "No existe ninguna alerta con el id {}",
+                de: "Kein Alarm mit der ID {}",
+            },
+            Some(id.to_string()),
+        ),
+        PortfolioError::NotificationFailed(reason) => (
+            Catalog {
+                en: "Failed to send notification: {}",
+                es: "No se pudo enviar la notificación: {}",
+                de: "Benachrichtigung konnte nicht gesendet werden: {}",
+            },
+            Some(reason.clone()),
+        ),
+        PortfolioError::PermissionDenied(actor) => (
+            Catalog {
+                en: "{} is not permitted to perform this action",
+                es: "{} no tiene permiso para realizar esta acción",
+                de: "{} ist nicht berechtigt, diese Aktion auszuführen",
+            },
+            Some(actor.clone()),
+        ),
+        PortfolioError::ActorRequired => (
+            Catalog {
+                en: "Portfolio has an owner; act as a member to change it",
+                es: "La cartera tiene un propietario; actúe como miembro para modificarla",
+                de: "Das Portfolio hat einen Eigentümer; handeln Sie als Mitglied, um es zu ändern",
+            },
+            None,
+        ),
+        PortfolioError::NoAccessControl => (
+            Catalog {
+                en: "Portfolio has no access control configured",
+                es: "La cartera no tiene control de acceso configurado",
+                de: "Für das Portfolio ist keine Zugriffskontrolle konfiguriert",
+            },
+            None,
+        ),
+        PortfolioError::SyncConflict(ids) => (
+            Catalog {
+                en: "Sync delta conflicts with local transactions {}",
+                es:
+                    "El delta de sincronización entra en conflicto con las transacciones locales {}",
+                de: "Das Synchronisierungsdelta kollidiert mit lokalen Transaktionen {}",
+            },
+            Some(format!("{ids:?}")),
+        ),
+        PortfolioError::InvalidSerializedPortfolio(detail) => (
+            Catalog {
+                en: "Invalid serialized portfolio: {}",
+                es: "Cartera serializada no válida: {}",
+                de: "Ungültiges serialisiertes Portfolio: {}",
+            },
+            Some(detail.clone()),
+        ),
+        PortfolioError::NotRetirementAccount => (
+            Catalog {
+                en: "Required minimum distributions only apply to retirement accounts",
+                es: "Las distribuciones mínimas obligatorias solo se aplican a cuentas de jubilación",
+                de: "Mindestausschüttungen gelten nur für Altersvorsorgekonten",
+            },
+            None,
+        ),
+        PortfolioError::UnknownManualAsset(id) => (
+            Catalog {
+                en: "No manual asset with id {}",
+                es: "No existe ningún activo manual con el id {}",
+                de: "Kein manueller Vermögenswert mit der ID {}",
+            },
+            Some(id.to_string()),
+        ),
+        PortfolioError::UnknownLiability(id) => (
+            Catalog {
+                en: "No liability with id {}",
+                es: "No existe ningún pasivo con el id {}",
+                de: "Keine Verbindlichkeit mit der ID {}",
+            },
+            Some(id.to_string()),
+        ),
+        PortfolioError::IneligibleDividend(ex_date) => (
+            Catalog {
+                en: "Dividend credited against shares bought on or after the {} ex-date",
+                es: "Dividendo abonado a acciones compradas en o después de la fecha ex-dividendo {}",
+                de: "Dividende für Anteile gutgeschrieben, die am oder nach dem Ex-Tag {} gekauft wurden",
+            },
+            Some(ex_date.to_string()),
+        ),
+        PortfolioError::InvalidBasisImport(detail) => (
+            Catalog {
+                en: "Invalid cost basis import: {}",
+                es: "Importación de base de costo no válida: {}",
+                de: "Ungültiger Import der Anschaffungskosten: {}",
+            },
+            Some(detail.clone()),
+        ),
+        PortfolioError::FeeAlreadyAccrued(start) => (
+            Catalog {
+                en: "Advisory fee already accrued for the month starting {}",
+                es: "La comisión de asesoría ya se devengó para el mes que comienza el {}",
+                de: "Die Beratungsgebühr wurde für den am {} beginnenden Monat bereits abgegrenzt",
+            },
+            Some(start.to_string()),
+        ),
+        PortfolioError::InvalidFxRate(rate) => (
+            Catalog {
+                en: "Exchange rate {} must be positive",
+                es: "El tipo de cambio {} debe ser positivo",
+                de: "Der Wechselkurs {} muss positiv sein",
+            },
+            Some(rate.to_string()),
+        ),
+        PortfolioError::InvalidCorporateAction(detail) => (
+            Catalog {
+                en: "Invalid corporate action: {}",
+                es: "Evento corporativo no válido: {}",
+                de: "Ungültige Kapitalmaßnahme: {}",
+            },
+            Some(detail.clone()),
+        ),
+        PortfolioError::UnknownLot(id) => (
+            Catalog {
+                en: "No open lot with id {}",
+                es: "No hay ningún lote abierto con id {}",
+                de: "Kein offener Posten mit der ID {}",
+            },
+            Some(id.to_string()),
+        ),
+        PortfolioError::InvalidLotSelection(id) => (
+            Catalog {
+                en: "Lot opened by transaction {} cannot cover the selected shares",
+                es: "El lote abierto por la transacción {} no cubre las acciones seleccionadas",
+                de: "Der durch Transaktion {} eröffnete Posten deckt die gewählten Anteile nicht ab",
+            },
+            Some(id.to_string()),
+        ),
+        PortfolioError::Io(detail) => (
+            Catalog {
+                en: "I/O error: {}",
+                es: "Error de entrada/salida: {}",
+                de: "Ein-/Ausgabefehler: {}",
+            },
+            Some(detail.clone()),
+        ),
+        PortfolioError::Corrupt(detail) => (
+            Catalog {
+                en: "Corrupt portfolio file: {}",
+                es: "Archivo de cartera dañado: {}",
+                de: "Beschädigte Portfoliodatei: {}",
+            },
+            Some(detail.clone()),
+        ),
+        PortfolioError::InvalidTransactionImport(detail) => (
+            Catalog {
+                en: "Invalid transaction import: {}",
+                es: "Importación de transacciones no válida: {}",
+                de: "Ungültiger Transaktionsimport: {}",
+            },
+            Some(detail.clone()),
+        ),
+        PortfolioError::NoTradingDay(date) => (
+            Catalog {
+                en: "No trading day follows {} on the holiday calendar",
+                es: "Ningún día hábil sigue al {} en el calendario de festivos",
+                de: "Auf den {} folgt im Feiertagskalender kein Handelstag",
+            },
+            Some(date.to_string()),
+        ),
+    };
+    let mut message = match argument {
+        Some(argument) => catalog.select(locale).replace("{}", &argument),
+        None => catalog.select(locale).to_string(),
+    };
+    for (placeholder, value) in named_arguments(error) {
+        message = message.replace(placeholder, &value);
+    }
+    message
+}
+
+fn named_arguments(error: &PortfolioError) -> Vec<(&'static str, String)> {
+    match error {
+        PortfolioError::CurrencyMismatch { found, .. } => vec![("{found}", found.to_string())],
+        _ => Vec::new(),
+    }
+}
+
+impl PortfolioError {
+    pub fn localized(&self, locale: Locale) -> String {
+        error_message(self, locale)
+    }
+}
@@ -0,0 +1,549 @@
+use crate::auth::Role;
+use crate::basis::BrokerBasis;
+use crate::config::FutureDatedPolicy;
+use crate::corporate_actions::CorporateAction;
+use crate::integrity::ImportedTransaction;
+use crate::ledger::Transaction;
+use crate::load::{LoadOptions, LoadReport};
+use crate::money::{Currency, Money};
+use crate::reconcile::Fingerprint;
+use crate::{
+    Portfolio, PortfolioError, PortfolioResult, PurchaseRecord, TradeConfirmation, TransactionId,
+    TransactionType,
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportOptions {
+    pub skip_duplicates: bool,
+    pub load: LoadOptions,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub skipped_duplicates: Vec<ImportedTransaction>,
+    pub future_dated: Vec<ImportedTransaction>,
+    pub queued: Vec<ImportedTransaction>,
+    pub load: LoadReport,
+}
+
+impl Portfolio {
+    fn existing_fingerprints(&self) -> HashMap<Fingerprint, usize> {
+        let mut fingerprints = HashMap::new();
+        for (symbol, records) in &self.purchase_records {
+            for record in records {
+                *fingerprints
+                    .entry(Fingerprint::of_record(symbol, record))
+                    .or_default() += 1;
+            }
+        }
+        fingerprints
+    }
+
+    pub fn import(
+        &mut self,
+        transactions: impl IntoIterator<Item = ImportedTransaction>,
+        options: &ImportOptions,
+    ) -> PortfolioResult<ImportReport> {
+        self.authorize(Role::Trader)?;
+        self.import_rows(transactions.into_iter().map(Ok), options, |_, error| error)
+    }
+
+    pub fn import_csv(
+        &mut self,
+        reader: impl Read,
+        options: &ImportOptions,
+    ) -> PortfolioResult<ImportReport> {
+        self.authorize(Role::Trader)?;
+        let currency = self.config.base_currency;
+        let (lines, rows): (Vec<usize>, Vec<_>) =
+            transaction_rows(reader, currency)?.into_iter().unzip();
+        self.import_rows(rows, options, |index, error| {
+            PortfolioError::InvalidTransactionImport(format!("line {}: {error}", lines[index]))
+        })
+    }
+
+    fn import_rows(
+        &mut self,
+        rows: impl IntoIterator<Item = PortfolioResult<ImportedTransaction>>,
+        options: &ImportOptions,
+        locate: impl Fn(usize, PortfolioError) -> PortfolioError,
+    ) -> PortfolioResult<ImportReport> {
+        let mut existing = if options.skip_duplicates {
+            self.existing_fingerprints()
+        } else {
+            HashMap::new()
+        };
+        let mut report = ImportReport::default();
+        for (index, row) in rows.into_iter().enumerate() {
+            let Some(transaction) = report.load.record(&options.load, index, row)? else {
+                continue;
+            };
+            if let Some(count) = existing
+                .get_mut(&Fingerprint::of_import(&transaction))
+                .filter(|count| **count > 0)
+            {
+                *count -= 1;
+                report.skipped_duplicates.push(transaction);
+                continue;
+            }
+            let future_dated = self.is_future_dated(transaction.date);
+            if future_dated && self.config.rules.future_dated == FutureDatedPolicy::Queue {
+                self.queued_transactions.push(transaction.clone());
+                report.queued.push(transaction);
+                continue;
+            }
+            let result = self
+                .transact_settled(
+                    &transaction.symbol,
+                    transaction.shares,
+                    transaction.transaction_type.clone(),
+                    transaction.price,
+                    transaction.net_amount,
+                    transaction.date,
+                )
+                .map_err(|error| locate(index, error));
+            if report.load.record(&options.load, index, result)?.is_some() {
+                report.imported += 1;
+                if future_dated {
+                    report.future_dated.push(transaction);
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    pub fn read_corporate_actions(
+        &self,
+        mut reader: impl Read,
+    ) -> PortfolioResult<Vec<CorporateAction>> {
+        let mut feed = String::new();
+        reader
+            .read_to_string(&mut feed)
+            .map_err(|error| PortfolioError::InvalidCorporateAction(error.to_string()))?;
+        if feed.trim_start().starts_with('[') {
+            corporate_actions_json(&feed)
+        } else {
+            corporate_actions_csv(&feed, self.config.base_currency)
+        }
+    }
+
+    pub fn queued_transactions(&self) -> &[ImportedTransaction] {
+        &self.queued_transactions
+    }
+
+    pub fn release_due_transactions(&mut self) -> PortfolioResult<Vec<TradeConfirmation>> {
+        self.authorize(Role::Trader)?;
+        let now = self.now();
+        let (mut due, pending): (Vec<_>, Vec<_>) = self
+            .queued_transactions
+            .iter()
+            .cloned()
+            .partition(|transaction| transaction.date <= now);
+        due.sort_by_key(|transaction| transaction.date);
+        let snapshot = self.clone();
+        self.queued_transactions = pending;
+        let mut confirmations = Vec::with_capacity(due.len());
+        for transaction in due {
+            let result = self.transact_settled(
+                &transaction.symbol,
+                transaction.shares,
+                transaction.transaction_type,
+                transaction.price,
+                transaction.net_amount,
+                transaction.date,
+            );
+            match result {
+                Ok(confirmation) => confirmations.push(confirmation),
+                Err(error) => {
+                    *self = snapshot;
+                    return Err(error);
+                }
+            }
+        }
+        Ok(confirmations)
+    }
+}
+
+const TRANSACTION_COLUMNS: [&str; 5] = ["date", "symbol", "type", "shares", "price"];
+
+fn parse_trade_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.to_utc())
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        })
+}
+
+fn parse_transaction_row(
+    line: usize,
+    fields: &[&str],
+    currency: Currency,
+) -> PortfolioResult<ImportedTransaction> {
+    let invalid =
+        |detail: &str| PortfolioError::InvalidTransactionImport(format!("line {line}: {detail}"));
+    let [date, symbol, transaction_type, shares, price] = fields else {
+        return Err(invalid("expected 5 columns"));
+    };
+    if symbol.is_empty() {
+        return Err(invalid("missing symbol"));
+    }
+    let price = match *price {
+        "" => None,
+        price => Some(Money::new(
+            price.parse().map_err(|_| invalid("invalid price"))?,
+            currency,
+        )),
+    };
+    Ok(ImportedTransaction {
+        symbol: symbol.to_string(),
+        date: parse_trade_date(date).ok_or_else(|| invalid("invalid date"))?,
+        transaction_type: match transaction_type.to_ascii_lowercase().as_str() {
+            "purchase" | "buy" => TransactionType::Purchase,
+            "sell" => TransactionType::Sell,
+            _ => return Err(invalid("invalid transaction type")),
+        },
+        shares: shares.parse().map_err(|_| invalid("invalid share count"))?,
+        price,
+        net_amount: None,
+    })
+}
+
+type TransactionRow = (usize, PortfolioResult<ImportedTransaction>);
+
+fn transaction_rows(reader: impl Read, currency: Currency) -> PortfolioResult<Vec<TransactionRow>> {
+    let read_error =
+        |error: std::io::Error| PortfolioError::InvalidTransactionImport(error.to_string());
+    let mut lines = BufReader::new(reader).lines();
+    let header = lines
+        .next()
+        .transpose()
+        .map_err(read_error)?
+        .unwrap_or_default();
+    let columns: Vec<String> = header
+        .split(',')
+        .map(|column| column.trim().to_ascii_lowercase())
+        .collect();
+    if columns != TRANSACTION_COLUMNS {
+        return Err(PortfolioError::InvalidTransactionImport(format!(
+            "expected header {}",
+            TRANSACTION_COLUMNS.join(",")
+        )));
+    }
+    let mut rows = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line.map_err(read_error)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        rows.push((
+            index + 2,
+            parse_transaction_row(index + 2, &fields, currency),
+        ));
+    }
+    Ok(rows)
+}
+
+const BASIS_COLUMNS: [&str; 5] = ["symbol", "acquired", "shares", "cost_basis", "covered"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BrokerLot {
+    pub symbol: String,
+    pub acquired: NaiveDate,
+    pub shares: u32,
+    pub cost_basis: Money,
+    pub covered: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BasisMode {
+    #[default]
+    Reconcile,
+    Override,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BasisStatus {
+    Matched,
+    Mismatched { computed: Money },
+    Missing,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BasisReconciliation {
+    pub lot: BrokerLot,
+    pub transaction_id: Option<TransactionId>,
+    pub status: BasisStatus,
+}
+
+fn parse_basis_row(line: usize, fields: &[&str], currency: Currency) -> PortfolioResult<BrokerLot> {
+    let invalid =
+        |detail: &str| PortfolioError::InvalidBasisImport(format!("line {line}: {detail}"));
+    let [symbol, acquired, shares, cost_basis, covered] = fields else {
+        return Err(invalid("expected 5 columns"));
+    };
+    Ok(BrokerLot {
+        symbol: symbol.to_string(),
+        acquired: NaiveDate::parse_from_str(acquired, "%Y-%m-%d")
+            .map_err(|_| invalid("invalid acquisition date"))?,
+        shares: shares.parse().map_err(|_| invalid("invalid share count"))?,
+        cost_basis: Money::new(
+            cost_basis
+                .parse()
+                .map_err(|_| invalid("invalid cost basis"))?,
+            currency,
+        ),
+        covered: match covered.to_ascii_lowercase().as_str() {
+            "covered" | "true" | "yes" => true,
+            "noncovered" | "false" | "no" => false,
+            _ => return Err(invalid("invalid covered flag")),
+        },
+    })
+}
+
+pub fn basis_1099b(reader: impl Read) -> PortfolioResult<Vec<BrokerLot>> {
+    let mut lines = BufReader::new(reader).lines();
+    let header = lines
+        .next()
+        .transpose()
+        .map_err(|error| PortfolioError::InvalidBasisImport(error.to_string()))?
+        .unwrap_or_default();
+    let columns: Vec<String> = header
+        .split(',')
+        .map(|column| column.trim().to_ascii_lowercase())
+        .collect();
+    if columns != BASIS_COLUMNS {
+        return Err(PortfolioError::InvalidBasisImport(format!(
+            "expected header {}",
+            BASIS_COLUMNS.join(",")
+        )));
+    }
+    let mut lots = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line.map_err(|error| PortfolioError::InvalidBasisImport(error.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        lots.push(parse_basis_row(index + 2, &fields, Currency::Usd)?);
+    }
+    Ok(lots)
+}
+
+const CORPORATE_ACTION_COLUMNS: [&str; 4] = ["date", "symbol", "action", "value"];
+
+fn parse_split_ratio(ratio: &str) -> Option<(u32, u32)> {
+    let (numerator, denominator) = ratio
+        .split_once(':')
+        .or_else(|| ratio.split_once("-for-"))?;
+    Some((
+        numerator.trim().parse().ok()?,
+        denominator.trim().parse().ok()?,
+    ))
+}
+
+fn parse_corporate_action_row(
+    line: usize,
+    fields: &[&str],
+    currency: Currency,
+) -> PortfolioResult<CorporateAction> {
+    let invalid =
+        |detail: &str| PortfolioError::InvalidCorporateAction(format!("line {line}: {detail}"));
+    let [date, symbol, action, value] = fields else {
+        return Err(invalid("expected 4 columns"));
+    };
+    let symbol = symbol.to_string();
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| invalid("invalid date"))?;
+    match action.to_ascii_lowercase().as_str() {
+        "split" => {
+            let (ratio, price) = match value.split_once('@') {
+                Some((ratio, price)) => (ratio, Some(price.trim())),
+                None => (*value, None),
+            };
+            let (numerator, denominator) =
+                parse_split_ratio(ratio).ok_or_else(|| invalid("invalid split ratio"))?;
+            let cash_in_lieu_price = price
+                .map(|price| {
+                    price
+                        .parse()
+                        .map(|amount| Money::new(amount, currency))
+                        .map_err(|_| invalid("invalid cash-in-lieu price"))
+                })
+                .transpose()?;
+            Ok(CorporateAction::Split {
+                symbol,
+                date,
+                numerator,
+                denominator,
+                cash_in_lieu_price,
+            })
+        }
+        "dividend" => Ok(CorporateAction::Dividend {
+            symbol,
+            date,
+            per_share: Money::new(
+                value
+                    .parse()
+                    .map_err(|_| invalid("invalid dividend amount"))?,
+                currency,
+            ),
+        }),
+        "rename" if !value.is_empty() => Ok(CorporateAction::Rename {
+            symbol,
+            date,
+            new_symbol: value.to_string(),
+        }),
+        "rename" => Err(invalid("missing new symbol")),
+        _ => Err(invalid("unknown action")),
+    }
+}
+
+fn corporate_actions_csv(feed: &str, currency: Currency) -> PortfolioResult<Vec<CorporateAction>> {
+    let mut lines = feed.lines();
+    let columns: Vec<String> = lines
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .map(|column| column.trim().to_ascii_lowercase())
+        .collect();
+    if columns != CORPORATE_ACTION_COLUMNS {
+        return Err(PortfolioError::InvalidCorporateAction(format!(
+            "expected header {}",
+            CORPORATE_ACTION_COLUMNS.join(",")
+        )));
+    }
+    let mut actions = Vec::new();
+    for (index, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        actions.push(parse_corporate_action_row(index + 2, &fields, currency)?);
+    }
+    Ok(actions)
+}
+
+fn corporate_actions_json(feed: &str) -> PortfolioResult<Vec<CorporateAction>> {
+    serde_json::from_str(feed)
+        .map_err(|error| PortfolioError::InvalidCorporateAction(error.to_string()))
+}
+
+impl Portfolio {
+    fn find_purchase_for(
+        &self,
+        lot: &BrokerLot,
+        claimed: &[TransactionId],
+    ) -> Option<&PurchaseRecord> {
+        self.get_purchase_record(&lot.symbol)
+            .unwrap_or_default()
+            .iter()
+            .find(|record| {
+                record.transaction_type == TransactionType::Purchase
+                    && record.trade_date() == lot.acquired
+                    && record.shares == lot.shares
+                    && !claimed.contains(&record.id)
+            })
+    }
+
+    fn computed_basis(&self, record: &PurchaseRecord) -> PortfolioResult<Money> {
+        if let Some(broker) = self.broker_basis.get(&record.id) {
+            return Ok(broker.cost_basis);
+        }
+        Ok(record
+            .settled_amount()?
+            .unwrap_or(Money::zero(self.config.base_currency)))
+    }
+
+    pub fn apply_broker_basis(
+        &mut self,
+        lots: &[BrokerLot],
+        mode: BasisMode,
+    ) -> PortfolioResult<Vec<BasisReconciliation>> {
+        self.authorize(Role::Trader)?;
+        let snapshot = self.clone();
+        let result = self.reconcile_broker_basis(lots, mode);
+        if result.is_err() {
+            *self = snapshot;
+        }
+        result
+    }
+
+    fn reconcile_broker_basis(
+        &mut self,
+        lots: &[BrokerLot],
+        mode: BasisMode,
+    ) -> PortfolioResult<Vec<BasisReconciliation>> {
+        let mut claimed = Vec::new();
+        let mut reconciliations = Vec::new();
+        let mut changed = false;
+        for lot in lots {
+            let broker = BrokerBasis {
+                cost_basis: lot.cost_basis,
+                covered: lot.covered,
+            };
+            let (transaction_id, status) = match self.find_purchase_for(lot, &claimed) {
+                Some(record) => {
+                    let computed = self.computed_basis(record)?;
+                    let status = if computed == lot.cost_basis {
+                        BasisStatus::Matched
+                    } else {
+                        BasisStatus::Mismatched { computed }
+                    };
+                    (Some(record.id), status)
+                }
+                None => (None, BasisStatus::Missing),
+            };
+            let transaction_id = match (mode, transaction_id, &status) {
+                (BasisMode::Override, Some(id), BasisStatus::Mismatched { .. }) => {
+                    self.record_broker_basis(id, broker);
+                    changed = true;
+                    Some(id)
+                }
+                (BasisMode::Override, None, _) => {
+                    let id = self.insert_backdated(ImportedTransaction {
+                        symbol: lot.symbol.clone(),
+                        date: lot.acquired.and_time(NaiveTime::MIN).and_utc(),
+                        transaction_type: TransactionType::Purchase,
+                        shares: lot.shares,
+                        price: lot
+                            .cost_basis
+                            .amount
+                            .checked_div(Decimal::from(lot.shares))
+                            .map(|price| Money::new(price, lot.cost_basis.currency)),
+                        net_amount: None,
+                    })?;
+                    self.record_broker_basis(id, broker);
+                    changed = true;
+                    Some(id)
+                }
+                (_, id, _) => id,
+            };
+            claimed.extend(transaction_id);
+            reconciliations.push(BasisReconciliation {
+                lot: lot.clone(),
+                transaction_id,
+                status,
+            });
+        }
+        if changed {
+            self.rebuild()?;
+        }
+        Ok(reconciliations)
+    }
+
+    fn record_broker_basis(&mut self, transaction_id: TransactionId, basis: BrokerBasis) {
+        self.broker_basis.insert(transaction_id, basis);
+        self.record_entry(Transaction::BrokerBasis {
+            transaction_id,
+            basis,
+        });
+    }
+}
@@ -0,0 +1,74 @@
+use crate::auth::Role;
+use crate::ledger::Transaction;
+use crate::money::Money;
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct CapitalGainDistribution {
+    pub date: DateTime<Utc>,
+    pub short_term: Money,
+    pub long_term: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapitalGainDistributionTotals {
+    pub short_term: Money,
+    pub long_term: Money,
+}
+
+impl Portfolio {
+    pub fn record_capital_gain_distribution(
+        &mut self,
+        symbol: &str,
+        short_term: Money,
+        long_term: Money,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&short_term)?;
+        self.validate_amount(&long_term)?;
+        if self.get_share_count(symbol) == 0 {
+            return Err(PortfolioError::NoOpenLots);
+        }
+        self.commit_entry(Transaction::CapitalGainDistribution {
+            symbol: symbol.to_string(),
+            distribution: CapitalGainDistribution {
+                date,
+                short_term,
+                long_term,
+            },
+        })
+    }
+
+    pub fn get_capital_gain_distributions(&self, symbol: &str) -> &[CapitalGainDistribution] {
+        self.capital_gain_distributions
+            .get(symbol)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn capital_gain_distribution_totals(
+        &self,
+        year: i32,
+    ) -> PortfolioResult<CapitalGainDistributionTotals> {
+        let currency = self.config.base_currency;
+        let mut totals = CapitalGainDistributionTotals {
+            short_term: Money::zero(currency),
+            long_term: Money::zero(currency),
+        };
+        for distribution in self
+            .capital_gain_distributions
+            .values()
+            .flatten()
+            .filter(|distribution| self.fiscal_year_of(distribution.date) == year)
+        {
+            totals.short_term = totals.short_term.checked_add(&distribution.short_term)?;
+            totals.long_term = totals.long_term.checked_add(&distribution.long_term)?;
+        }
+        Ok(totals)
+    }
+}
@@ -0,0 +1,166 @@
+use crate::calendar::{self, HolidayCalendar};
+use crate::config::CostBasisMethod;
+use crate::{PortfolioError, PortfolioResult};
+use chrono::{DateTime, Days, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum InstrumentKind {
+    Stock,
+    Etf,
+    MutualFund,
+    Bond,
+    Crypto,
+}
+
+impl InstrumentKind {
+    pub fn default_cost_basis_method(&self) -> Option<CostBasisMethod> {
+        match self {
+            InstrumentKind::MutualFund => Some(CostBasisMethod::AverageCost),
+            _ => None,
+        }
+    }
+
+    pub fn settlement_days(&self) -> u64 {
+        match self {
+            InstrumentKind::Stock | InstrumentKind::Etf | InstrumentKind::MutualFund => 1,
+            InstrumentKind::Bond => 2,
+            InstrumentKind::Crypto => 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Instrument {
+    pub kind: InstrumentKind,
+    pub expense_ratio: Option<Decimal>,
+    pub sector: Option<String>,
+    pub lot_size: Option<u32>,
+    pub halted: bool,
+}
+
+impl Instrument {
+    pub fn new(kind: InstrumentKind) -> Self {
+        Self {
+            kind,
+            expense_ratio: None,
+            sector: None,
+            lot_size: None,
+            halted: false,
+        }
+    }
+
+    pub fn settlement_date(
+        &self,
+        trade_date: DateTime<Utc>,
+        calendar: &(impl HolidayCalendar + ?Sized),
+    ) -> PortfolioResult<DateTime<Utc>> {
+        let days = self.kind.settlement_days();
+        if self.kind == InstrumentKind::Crypto {
+            return Ok(trade_date + Days::new(days));
+        }
+        let trade_day = trade_date.date_naive();
+        Ok(trade_date + (calendar::add_trading_days(trade_day, days, calendar)? - trade_day))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct InstrumentRegistry {
+    instruments: HashMap<String, Instrument>,
+}
+
+impl InstrumentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, symbol: &str, kind: InstrumentKind) {
+        self.instruments
+            .insert(symbol.to_string(), Instrument::new(kind));
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn entries(&self) -> impl Iterator<Item = (&String, &Instrument)> {
+        self.instruments.iter()
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn insert(&mut self, symbol: String, instrument: Instrument) {
+        self.instruments.insert(symbol, instrument);
+    }
+
+    pub(crate) fn rename(&mut self, from: &str, to: &str) {
+        if let Some(instrument) = self.instruments.remove(from) {
+            self.instruments.insert(to.to_string(), instrument);
+        }
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&Instrument> {
+        self.instruments.get(symbol)
+    }
+
+    fn get_mut(&mut self, symbol: &str) -> PortfolioResult<&mut Instrument> {
+        self.instruments
+            .get_mut(symbol)
+            .ok_or(PortfolioError::UnknownInstrument)
+    }
+
+    pub fn kind(&self, symbol: &str) -> Option<InstrumentKind> {
+        self.get(symbol).map(|instrument| instrument.kind)
+    }
+
+    pub fn set_expense_ratio(&mut self, symbol: &str, ratio: Decimal) -> PortfolioResult<()> {
+        if ratio.is_sign_negative() {
+            return Err(PortfolioError::NegativeAmount);
+        }
+        self.get_mut(symbol)?.expense_ratio = Some(ratio);
+        Ok(())
+    }
+
+    pub fn set_sector(&mut self, symbol: &str, sector: &str) -> PortfolioResult<()> {
+        self.get_mut(symbol)?.sector = Some(sector.to_string());
+        Ok(())
+    }
+
+    pub fn sector(&self, symbol: &str) -> Option<&str> {
+        self.get(symbol)?.sector.as_deref()
+    }
+
+    pub fn set_lot_size(&mut self, symbol: &str, lot_size: u32) -> PortfolioResult<()> {
+        if lot_size == 0 {
+            return Err(PortfolioError::ZeroShares);
+        }
+        self.get_mut(symbol)?.lot_size = Some(lot_size);
+        Ok(())
+    }
+
+    pub fn lot_size(&self, symbol: &str) -> Option<u32> {
+        self.get(symbol)?.lot_size
+    }
+
+    pub fn set_halted(&mut self, symbol: &str, halted: bool) -> PortfolioResult<()> {
+        self.get_mut(symbol)?.halted = halted;
+        Ok(())
+    }
+
+    pub fn is_halted(&self, symbol: &str) -> bool {
+        self.get(symbol).is_some_and(|instrument| instrument.halted)
+    }
+
+    pub fn expense_ratio(&self, symbol: &str) -> Option<Decimal> {
+        self.get(symbol)?.expense_ratio
+    }
+
+    pub fn symbols_with_expense_ratio(&self) -> impl Iterator<Item = (&str, Decimal)> {
+        self.instruments.iter().filter_map(|(symbol, instrument)| {
+            instrument
+                .expense_ratio
+                .map(|ratio| (symbol.as_str(), ratio))
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use crate::auth::Role;
+use crate::ledger::{Trade, Transaction};
+use crate::money::Money;
+use crate::{Portfolio, PortfolioResult, TransactionId, TransactionType};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ImportedTransaction {
+    pub symbol: String,
+    pub date: DateTime<Utc>,
+    pub transaction_type: TransactionType,
+    pub shares: u32,
+    pub price: Option<Money>,
+    pub net_amount: Option<Money>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    HoldingsDivergeFromRecords {
+        symbol: String,
+        holdings: i64,
+        records: i64,
+    },
+    LotsDivergeFromHoldings {
+        symbol: String,
+        holdings: u32,
+        lots: u64,
+    },
+}
+
+impl Portfolio {
+    fn shares_in_lots(&self, symbol: &str) -> u64 {
+        self.lots
+            .get(symbol)
+            .into_iter()
+            .flatten()
+            .map(|lot| u64::from(lot.shares))
+            .sum()
+    }
+
+    pub fn verify_integrity(&self) -> Vec<IntegrityIssue> {
+        let symbols: BTreeSet<&str> = self
+            .holdings
+            .keys()
+            .chain(self.purchase_records.keys())
+            .chain(self.lots.keys())
+            .map(|symbol| symbol.as_str())
+            .collect();
+
+        let mut issues = Vec::new();
+        for symbol in symbols {
+            let position = self.get_position(symbol);
+            let records = self.signed_shares_from_records(symbol, None);
+            if position.signed_quantity() != records {
+                issues.push(IntegrityIssue::HoldingsDivergeFromRecords {
+                    symbol: symbol.to_string(),
+                    holdings: position.signed_quantity(),
+                    records,
+                });
+            }
+            let lots = self.shares_in_lots(symbol);
+            if u64::from(position.long_quantity()) != lots {
+                issues.push(IntegrityIssue::LotsDivergeFromHoldings {
+                    symbol: symbol.to_string(),
+                    holdings: position.long_quantity(),
+                    lots,
+                });
+            }
+        }
+        issues
+    }
+
+    pub fn insert_backdated(
+        &mut self,
+        transaction: ImportedTransaction,
+    ) -> PortfolioResult<TransactionId> {
+        self.authorize(Role::Trader)?;
+        let mut trade = Trade::new(
+            &transaction.symbol,
+            transaction.shares,
+            transaction.transaction_type,
+            transaction.price,
+            transaction.date,
+        );
+        trade.record.net_amount = transaction.net_amount;
+        self.validate_trade(&trade)?;
+        let id = self.next_transaction_id;
+        trade.record.id = id;
+        trade.record.date = self.normalize_date(trade.record.date);
+        trade.record.day_sequence = self.records_on(trade.record.trade_date());
+        let snapshot = self.clone();
+        self.next_transaction_id += 1;
+        self.ledger.append(Transaction::Trade(trade.clone()));
+        if let Err(error) = self.replay_ledger() {
+            *self = snapshot;
+            return Err(error);
+        }
+        self.publish(trade.event());
+        Ok(id)
+    }
+}
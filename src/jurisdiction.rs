@@ -0,0 +1,318 @@
+use crate::config::Jurisdiction;
+use crate::lots::prorate;
+use crate::money::Money;
+use crate::period::Period;
+use crate::{Portfolio, PortfolioError, PortfolioResult, TransactionType};
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeMap;
+
+const MATCHING_WINDOW_DAYS: i64 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchRule {
+    Lot,
+    SameDay,
+    BedAndBreakfast,
+    Section104,
+    AdjustedCostBase,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShareMatch {
+    pub rule: MatchRule,
+    pub acquired: Option<NaiveDate>,
+    pub shares: u32,
+    pub cost: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Disposal {
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub shares: u32,
+    pub proceeds: Money,
+    pub allowable_cost: Money,
+    pub gain: Money,
+    pub denied_loss: Money,
+    pub matches: Vec<ShareMatch>,
+}
+
+pub trait DisposalRules {
+    fn disposals(&self, portfolio: &Portfolio, symbol: &str) -> PortfolioResult<Vec<Disposal>>;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UsLotMatching;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UkShareMatching;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CanadaSuperficialLoss;
+
+impl Jurisdiction {
+    pub fn rules(self) -> &'static dyn DisposalRules {
+        match self {
+            Jurisdiction::Us => &UsLotMatching,
+            Jurisdiction::Uk => &UkShareMatching,
+            Jurisdiction::Canada => &CanadaSuperficialLoss,
+        }
+    }
+}
+
+impl Portfolio {
+    pub fn disposals(&self, symbol: &str) -> PortfolioResult<Vec<Disposal>> {
+        self.config.jurisdiction.rules().disposals(self, symbol)
+    }
+}
+
+struct TradingDay {
+    date: NaiveDate,
+    acquired: u32,
+    cost: Money,
+    disposed: u32,
+    proceeds: Option<Money>,
+}
+
+fn trading_days(portfolio: &Portfolio, symbol: &str) -> PortfolioResult<Vec<TradingDay>> {
+    let currency = portfolio.config().base_currency;
+    let mut days: BTreeMap<NaiveDate, TradingDay> = BTreeMap::new();
+    for record in portfolio.get_purchase_record(symbol)? {
+        let date = record.trade_date();
+        let day = days.entry(date).or_insert_with(|| TradingDay {
+            date,
+            acquired: 0,
+            cost: Money::zero(currency),
+            disposed: 0,
+            proceeds: Some(Money::zero(currency)),
+        });
+        let value = record
+            .price
+            .map(|price| price.checked_mul(record.shares.into()))
+            .transpose()?;
+        match record.transaction_type {
+            TransactionType::Purchase => {
+                day.acquired += record.shares;
+                if let Some(value) = value {
+                    day.cost = day.cost.checked_add(&value)?;
+                }
+            }
+            TransactionType::Sell => {
+                day.disposed += record.shares;
+                day.proceeds = match (day.proceeds, value) {
+                    (Some(proceeds), Some(value)) => Some(proceeds.checked_add(&value)?),
+                    _ => None,
+                };
+            }
+        }
+    }
+    Ok(days.into_values().collect())
+}
+
+fn disposal(
+    symbol: &str,
+    day: &TradingDay,
+    matches: Vec<ShareMatch>,
+    denied_loss: Money,
+) -> PortfolioResult<Option<Disposal>> {
+    let Some(proceeds) = day.proceeds else {
+        return Ok(None);
+    };
+    let allowable_cost = Money::checked_sum(proceeds.currency, matches.iter().map(|m| &m.cost))?;
+    Ok(Some(Disposal {
+        symbol: symbol.to_string(),
+        date: day.date,
+        shares: day.disposed,
+        proceeds,
+        gain: proceeds
+            .checked_sub(&allowable_cost)?
+            .checked_add(&denied_loss)?,
+        allowable_cost,
+        denied_loss,
+        matches,
+    }))
+}
+
+impl DisposalRules for UsLotMatching {
+    fn disposals(&self, portfolio: &Portfolio, symbol: &str) -> PortfolioResult<Vec<Disposal>> {
+        let currency = portfolio.config().base_currency;
+        let mut disposals = Vec::new();
+        for (record, confirmation) in portfolio.replay_symbol(symbol)?.trades {
+            if confirmation.lot_gains.is_empty() {
+                continue;
+            }
+            let mut matches = Vec::with_capacity(confirmation.lot_gains.len());
+            for lot_gain in &confirmation.lot_gains {
+                matches.push(ShareMatch {
+                    rule: MatchRule::Lot,
+                    acquired: Some(lot_gain.consumption.acquired.date_naive()),
+                    shares: lot_gain.consumption.shares,
+                    cost: lot_gain.proceeds.checked_sub(&lot_gain.gain)?,
+                });
+            }
+            let proceeds = Money::checked_sum(
+                currency,
+                confirmation.lot_gains.iter().map(|gain| &gain.proceeds),
+            )?;
+            let gain = Money::checked_sum(
+                currency,
+                confirmation.lot_gains.iter().map(|gain| &gain.gain),
+            )?;
+            disposals.push(Disposal {
+                symbol: symbol.to_string(),
+                date: record.trade_date(),
+                shares: record.shares,
+                proceeds,
+                allowable_cost: proceeds.checked_sub(&gain)?,
+                gain,
+                denied_loss: Money::zero(currency),
+                matches,
+            });
+        }
+        Ok(disposals)
+    }
+}
+
+impl DisposalRules for UkShareMatching {
+    fn disposals(&self, portfolio: &Portfolio, symbol: &str) -> PortfolioResult<Vec<Disposal>> {
+        let currency = portfolio.config().base_currency;
+        let days = trading_days(portfolio, symbol)?;
+        let mut unmatched: Vec<u32> = days.iter().map(|day| day.acquired).collect();
+        let mut remaining: Vec<u32> = days.iter().map(|day| day.disposed).collect();
+        let mut matches: Vec<Vec<ShareMatch>> = days.iter().map(|_| Vec::new()).collect();
+        let mut match_acquisition =
+            |disposal: usize, acquisition: usize, rule: MatchRule| -> PortfolioResult<()> {
+                let shares = remaining[disposal].min(unmatched[acquisition]);
+                if shares == 0 {
+                    return Ok(());
+                }
+                let day = &days[acquisition];
+                matches[disposal].push(ShareMatch {
+                    rule,
+                    acquired: Some(day.date),
+                    shares,
+                    cost: prorate(&day.cost, shares.into(), day.acquired.into())?,
+                });
+                remaining[disposal] -= shares;
+                unmatched[acquisition] -= shares;
+                Ok(())
+            };
+
+        for index in 0..days.len() {
+            match_acquisition(index, index, MatchRule::SameDay)?;
+        }
+        for (index, day) in days.iter().enumerate() {
+            let window_end = day.date + Duration::days(MATCHING_WINDOW_DAYS);
+            let within_window = days[index + 1..]
+                .iter()
+                .take_while(|later| later.date <= window_end)
+                .count();
+            for later in index + 1..=index + within_window {
+                match_acquisition(index, later, MatchRule::BedAndBreakfast)?;
+            }
+        }
+
+        let mut pool_shares = 0u32;
+        let mut pool_cost = Money::zero(currency);
+        let mut disposals = Vec::new();
+        for (index, day) in days.iter().enumerate() {
+            if unmatched[index] > 0 {
+                pool_shares += unmatched[index];
+                pool_cost = pool_cost.checked_add(&prorate(
+                    &day.cost,
+                    unmatched[index].into(),
+                    day.acquired.into(),
+                )?)?;
+            }
+            let shares = remaining[index];
+            if shares > 0 {
+                if shares > pool_shares {
+                    return Err(PortfolioError::InvalidSell);
+                }
+                let cost = prorate(&pool_cost, shares.into(), pool_shares.into())?;
+                pool_cost = pool_cost.checked_sub(&cost)?;
+                pool_shares -= shares;
+                matches[index].push(ShareMatch {
+                    rule: MatchRule::Section104,
+                    acquired: None,
+                    shares,
+                    cost,
+                });
+            }
+            if day.disposed > 0 {
+                let day_matches = std::mem::take(&mut matches[index]);
+                disposals.extend(disposal(symbol, day, day_matches, Money::zero(currency))?);
+            }
+        }
+        Ok(disposals)
+    }
+}
+
+impl DisposalRules for CanadaSuperficialLoss {
+    fn disposals(&self, portfolio: &Portfolio, symbol: &str) -> PortfolioResult<Vec<Disposal>> {
+        let currency = portfolio.config().base_currency;
+        let days = trading_days(portfolio, symbol)?;
+        let mut pool_shares = 0u32;
+        let mut pool_cost = Money::zero(currency);
+        let mut carried_loss = Money::zero(currency);
+        let mut disposals = Vec::new();
+        for day in &days {
+            if day.acquired > 0 {
+                pool_shares += day.acquired;
+                pool_cost = pool_cost
+                    .checked_add(&day.cost)?
+                    .checked_add(&carried_loss)?;
+                carried_loss = Money::zero(currency);
+            }
+            if day.disposed == 0 {
+                continue;
+            }
+            if day.disposed > pool_shares {
+                return Err(PortfolioError::InvalidSell);
+            }
+            let cost = prorate(&pool_cost, day.disposed.into(), pool_shares.into())?;
+            pool_cost = pool_cost.checked_sub(&cost)?;
+            pool_shares -= day.disposed;
+
+            let mut denied_loss = Money::zero(currency);
+            if let Some(proceeds) = day.proceeds {
+                let loss = cost.checked_sub(&proceeds)?;
+                if !loss.is_negative() && !loss.is_zero() {
+                    let substituted = superficial_shares(&days, day);
+                    denied_loss = prorate(&loss, substituted.into(), day.disposed.into())?;
+                }
+            }
+            if pool_shares > 0 {
+                pool_cost = pool_cost.checked_add(&denied_loss)?;
+            } else {
+                carried_loss = carried_loss.checked_add(&denied_loss)?;
+            }
+            let matches = vec![ShareMatch {
+                rule: MatchRule::AdjustedCostBase,
+                acquired: None,
+                shares: day.disposed,
+                cost,
+            }];
+            disposals.extend(disposal(symbol, day, matches, denied_loss)?);
+        }
+        Ok(disposals)
+    }
+}
+
+fn superficial_shares(days: &[TradingDay], sale: &TradingDay) -> u32 {
+    let window = Duration::days(MATCHING_WINDOW_DAYS);
+    let period = Period::new(sale.date - window, sale.date + window);
+    let acquired: u32 = days
+        .iter()
+        .filter(|day| period.contains(day.date))
+        .map(|day| day.acquired)
+        .sum();
+    let held_at_end = days
+        .iter()
+        .take_while(|day| day.date <= period.end)
+        .fold(0i64, |held, day| {
+            held + i64::from(day.acquired) - i64::from(day.disposed)
+        });
+    let held_at_end = u32::try_from(held_at_end.max(0)).unwrap_or(u32::MAX);
+    sale.disposed.min(acquired).min(held_at_end)
+}
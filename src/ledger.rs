@@ -0,0 +1,619 @@
+use crate::advisory::AdvisoryFee;
+use crate::auth::Role;
+use crate::basis::{BrokerBasis, ReturnOfCapital};
+use crate::cash::CashTransfer;
+use crate::config::PortfolioConfig;
+use crate::corporate_actions::StockSplit;
+use crate::dividends::Dividend;
+use crate::equity::EquityAward;
+use crate::events::PortfolioEvent;
+use crate::income::CapitalGainDistribution;
+use crate::lots::{Acquisition, ConsolidationPolicy, SelectedLot};
+use crate::money::Money;
+use crate::reversal::Reversal;
+use crate::{
+    Portfolio, PortfolioError, PortfolioResult, PurchaseRecord, TradeConfirmation, TransactionId,
+    TransactionType,
+};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Trade {
+    pub symbol: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub record: PurchaseRecord,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub acquisition: Acquisition,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lot_selection: Option<Vec<SelectedLot>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub equity_award: Option<Box<EquityAward>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reverses: Option<TransactionId>,
+}
+
+impl Trade {
+    pub(crate) fn new(
+        symbol: &str,
+        shares: u32,
+        transaction_type: TransactionType,
+        price: Option<Money>,
+        date: DateTime<Utc>,
+    ) -> Self {
+        Self::from_record(
+            symbol,
+            PurchaseRecord {
+                id: 0,
+                date,
+                day_sequence: 0,
+                shares,
+                transaction_type,
+                price,
+                fx: None,
+                net_amount: None,
+            },
+        )
+    }
+
+    pub(crate) fn from_event(event: &PortfolioEvent) -> Option<Self> {
+        let PortfolioEvent::Transaction {
+            transaction_id,
+            symbol,
+            transaction_type,
+            shares,
+            price,
+            date,
+            fx,
+            net_amount,
+            acquisition,
+            lot_selection,
+            equity_award,
+            reverses,
+        } = event
+        else {
+            return None;
+        };
+        Some(Self {
+            symbol: symbol.clone(),
+            record: PurchaseRecord {
+                id: *transaction_id,
+                date: *date,
+                day_sequence: 0,
+                shares: *shares,
+                transaction_type: transaction_type.clone(),
+                price: *price,
+                fx: *fx,
+                net_amount: *net_amount,
+            },
+            acquisition: *acquisition,
+            lot_selection: lot_selection.clone(),
+            equity_award: equity_award.clone(),
+            reverses: *reverses,
+        })
+    }
+
+    pub(crate) fn event(&self) -> PortfolioEvent {
+        PortfolioEvent::Transaction {
+            transaction_id: self.record.id,
+            symbol: self.symbol.clone(),
+            transaction_type: self.record.transaction_type.clone(),
+            shares: self.record.shares,
+            price: self.record.price,
+            date: self.record.date,
+            fx: self.record.fx,
+            net_amount: self.record.net_amount,
+            acquisition: self.acquisition,
+            lot_selection: self.lot_selection.clone(),
+            equity_award: self.equity_award.clone(),
+            reverses: self.reverses,
+        }
+    }
+
+    pub(crate) fn from_record(symbol: &str, record: PurchaseRecord) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            record,
+            acquisition: Acquisition::default(),
+            lot_selection: None,
+            equity_award: None,
+            reverses: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum Transaction {
+    Trade(Trade),
+    ReturnOfCapital {
+        symbol: String,
+        sequence: TransactionId,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        adjustment: ReturnOfCapital,
+    },
+    Split {
+        symbol: String,
+        sequence: TransactionId,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        split: StockSplit,
+    },
+    Dividend {
+        symbol: String,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        dividend: Dividend,
+    },
+    BrokerBasis {
+        transaction_id: TransactionId,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        basis: BrokerBasis,
+    },
+    ConsolidateLots {
+        symbol: String,
+        date: DateTime<Utc>,
+        sequence: TransactionId,
+        policy: ConsolidationPolicy,
+    },
+    RenameSymbol {
+        from: String,
+        to: String,
+        date: DateTime<Utc>,
+        sequence: TransactionId,
+    },
+    CapitalGainDistribution {
+        symbol: String,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        distribution: CapitalGainDistribution,
+    },
+    Deposit {
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        transfer: CashTransfer,
+    },
+    Withdrawal {
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        transfer: CashTransfer,
+    },
+    Interest {
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        transfer: CashTransfer,
+    },
+    AdvisoryFee {
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        fee: AdvisoryFee,
+    },
+    LendShares {
+        symbol: String,
+        shares: u32,
+        date: DateTime<Utc>,
+        sequence: TransactionId,
+    },
+    RecallShares {
+        symbol: String,
+        shares: u32,
+        date: DateTime<Utc>,
+        sequence: TransactionId,
+    },
+    LendingIncome {
+        symbol: String,
+        income: Money,
+        date: DateTime<Utc>,
+        sequence: TransactionId,
+    },
+    Reversal {
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        reversal: Reversal,
+    },
+    Tag {
+        transaction_id: TransactionId,
+        tag: String,
+    },
+    Void(Trade),
+    Configure {
+        config: Box<PortfolioConfig>,
+    },
+}
+
+impl Transaction {
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            Transaction::Trade(Trade { symbol, .. })
+            | Transaction::ReturnOfCapital { symbol, .. }
+            | Transaction::Split { symbol, .. }
+            | Transaction::Dividend { symbol, .. }
+            | Transaction::ConsolidateLots { symbol, .. }
+            | Transaction::CapitalGainDistribution { symbol, .. }
+            | Transaction::LendShares { symbol, .. }
+            | Transaction::RecallShares { symbol, .. }
+            | Transaction::LendingIncome { symbol, .. } => Some(symbol),
+            Transaction::BrokerBasis { .. }
+            | Transaction::RenameSymbol { .. }
+            | Transaction::Deposit { .. }
+            | Transaction::Withdrawal { .. }
+            | Transaction::Interest { .. }
+            | Transaction::AdvisoryFee { .. }
+            | Transaction::Reversal { .. }
+            | Transaction::Tag { .. }
+            | Transaction::Void(_)
+            | Transaction::Configure { .. } => None,
+        }
+    }
+
+    pub(crate) fn from_event(event: &PortfolioEvent) -> Option<Self> {
+        match event {
+            PortfolioEvent::LedgerEntry { entry } => Some(entry.clone()),
+            other => Trade::from_event(other).map(Transaction::Trade),
+        }
+    }
+
+    pub(crate) fn event(&self) -> PortfolioEvent {
+        match self {
+            Transaction::Trade(trade) => trade.event(),
+            other => PortfolioEvent::LedgerEntry {
+                entry: other.clone(),
+            },
+        }
+    }
+
+    pub fn as_trade(&self) -> Option<&Trade> {
+        match self {
+            Transaction::Trade(trade) => Some(trade),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn date(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Transaction::Trade(trade) => Some(trade.record.date),
+            Transaction::ReturnOfCapital { adjustment, .. } => Some(adjustment.date),
+            Transaction::Split { split, .. } => Some(split.date),
+            Transaction::Dividend { dividend, .. } => Some(dividend.date),
+            Transaction::CapitalGainDistribution { distribution, .. } => Some(distribution.date),
+            Transaction::Deposit { transfer }
+            | Transaction::Withdrawal { transfer }
+            | Transaction::Interest { transfer } => Some(transfer.date),
+            Transaction::AdvisoryFee { fee } => Some(fee.date),
+            Transaction::ConsolidateLots { date, .. }
+            | Transaction::RenameSymbol { date, .. }
+            | Transaction::LendShares { date, .. }
+            | Transaction::RecallShares { date, .. }
+            | Transaction::LendingIncome { date, .. } => Some(*date),
+            Transaction::BrokerBasis { .. }
+            | Transaction::Reversal { .. }
+            | Transaction::Tag { .. }
+            | Transaction::Void(_)
+            | Transaction::Configure { .. } => None,
+        }
+    }
+
+    fn sequence(&self) -> TransactionId {
+        match self {
+            Transaction::Trade(trade) => trade.record.id,
+            Transaction::ReturnOfCapital { sequence, .. }
+            | Transaction::Split { sequence, .. }
+            | Transaction::ConsolidateLots { sequence, .. }
+            | Transaction::RenameSymbol { sequence, .. }
+            | Transaction::LendShares { sequence, .. }
+            | Transaction::RecallShares { sequence, .. }
+            | Transaction::LendingIncome { sequence, .. } => *sequence,
+            Transaction::Reversal { reversal } => reversal.contra,
+            Transaction::Tag { transaction_id, .. } => *transaction_id,
+            Transaction::Dividend { .. }
+            | Transaction::BrokerBasis { .. }
+            | Transaction::CapitalGainDistribution { .. }
+            | Transaction::Deposit { .. }
+            | Transaction::Withdrawal { .. }
+            | Transaction::Interest { .. }
+            | Transaction::AdvisoryFee { .. }
+            | Transaction::Void(_)
+            | Transaction::Configure { .. } => 0,
+        }
+    }
+
+    fn replay_key(&self) -> (DateTime<Utc>, TransactionId, bool) {
+        (
+            self.date().unwrap_or(DateTime::<Utc>::MIN_UTC),
+            self.sequence(),
+            matches!(self, Transaction::Trade(_)),
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ledger {
+    transactions: Vec<Transaction>,
+}
+
+impl Ledger {
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    pub fn trade(&self, id: TransactionId) -> Option<&Trade> {
+        self.transactions
+            .iter()
+            .filter_map(Transaction::as_trade)
+            .find(|trade| trade.record.id == id)
+    }
+
+    pub(crate) fn append(&mut self, transaction: Transaction) {
+        self.transactions.push(transaction);
+    }
+
+    pub(crate) fn voided(&self) -> BTreeSet<TransactionId> {
+        self.transactions
+            .iter()
+            .filter_map(|transaction| match transaction {
+                Transaction::Void(trade) => Some(trade.record.id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub(crate) fn next_transaction_id(&self) -> TransactionId {
+        self.transactions
+            .iter()
+            .filter_map(Transaction::as_trade)
+            .map(|trade| trade.record.id + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn latest_date(&self, symbol: &str) -> Option<DateTime<Utc>> {
+        self.transactions
+            .iter()
+            .filter(|transaction| match transaction {
+                Transaction::RenameSymbol { to, .. } => to == symbol,
+                other => other.symbol() == Some(symbol),
+            })
+            .filter_map(Transaction::date)
+            .max()
+    }
+
+    pub(crate) fn has_later_than(&self, date: DateTime<Utc>) -> bool {
+        self.transactions
+            .iter()
+            .filter_map(Transaction::date)
+            .any(|existing| existing > date)
+    }
+
+    fn in_replay_order(&self) -> Vec<&Transaction> {
+        let voided = self.voided();
+        let mut ordered: Vec<&Transaction> = self
+            .transactions
+            .iter()
+            .filter(|transaction| match transaction {
+                Transaction::Trade(trade) => !voided.contains(&trade.record.id),
+                Transaction::BrokerBasis { transaction_id, .. }
+                | Transaction::Tag { transaction_id, .. } => !voided.contains(transaction_id),
+                Transaction::Reversal { reversal } => {
+                    !voided.contains(&reversal.original) && !voided.contains(&reversal.contra)
+                }
+                _ => true,
+            })
+            .collect();
+        ordered.sort_by_key(|transaction| transaction.replay_key());
+        ordered
+    }
+
+    pub(crate) fn history_of(&self, symbol: &str) -> Vec<&Transaction> {
+        let mut names = HashSet::from([symbol]);
+        let mut history = Vec::new();
+        for transaction in self.in_replay_order().into_iter().rev() {
+            match transaction {
+                Transaction::RenameSymbol { from, to, .. } if names.contains(to.as_str()) => {
+                    names.insert(from);
+                    history.push(transaction);
+                }
+                Transaction::BrokerBasis { .. } => history.push(transaction),
+                other if other.symbol().is_some_and(|symbol| names.contains(symbol)) => {
+                    history.push(other)
+                }
+                _ => {}
+            }
+        }
+        history.reverse();
+        history
+    }
+}
+
+impl Portfolio {
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    pub fn events(&self) -> Vec<Transaction> {
+        let configure =
+            (self.config != PortfolioConfig::default()).then(|| Transaction::Configure {
+                config: Box::new(self.config.clone()),
+            });
+        configure
+            .into_iter()
+            .chain(self.ledger.transactions.iter().cloned())
+            .collect()
+    }
+
+    pub fn from_events(events: impl IntoIterator<Item = Transaction>) -> PortfolioResult<Self> {
+        let mut events = events.into_iter().peekable();
+        let config = match events.next_if(|event| matches!(event, Transaction::Configure { .. })) {
+            Some(Transaction::Configure { config }) => *config,
+            _ => PortfolioConfig::default(),
+        };
+        let mut portfolio = Portfolio::with_config(config);
+        portfolio.restore_events(events)?;
+        Ok(portfolio)
+    }
+
+    pub(crate) fn restore_events(
+        &mut self,
+        events: impl IntoIterator<Item = Transaction>,
+    ) -> PortfolioResult<()> {
+        let mut seen = BTreeSet::new();
+        for event in events {
+            if let Transaction::Configure { config } = event {
+                self.config = *config;
+                continue;
+            }
+            if let Transaction::Trade(trade) = &event {
+                let id = trade.record.id;
+                if !seen.insert(id) {
+                    return Err(PortfolioError::InvalidSerializedPortfolio(format!(
+                        "duplicate transaction id {id}"
+                    )));
+                }
+            }
+            self.ledger.append(event);
+        }
+        self.next_transaction_id = self.ledger.next_transaction_id();
+        self.replay_ledger()
+    }
+
+    pub(crate) fn record_entry(&mut self, entry: Transaction) {
+        let event = entry.event();
+        self.ledger.append(entry);
+        self.publish(event);
+    }
+
+    pub(crate) fn commit_entry(&mut self, entry: Transaction) -> PortfolioResult<()> {
+        self.apply(&entry)?;
+        self.record_entry(entry);
+        Ok(())
+    }
+
+    pub fn rebuild(&mut self) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        self.replay_ledger()?;
+        self.bump_version();
+        Ok(())
+    }
+
+    pub(crate) fn replay_ledger(&mut self) -> PortfolioResult<()> {
+        self.replay_confirming(None).map(|_| ())
+    }
+
+    pub(crate) fn replay_confirming(
+        &mut self,
+        id: Option<TransactionId>,
+    ) -> PortfolioResult<Option<TradeConfirmation>> {
+        let mut projection = Portfolio::with_config(self.config.clone());
+        projection.instruments = self.instruments.clone();
+        projection.calendar = self.calendar.clone();
+        let mut confirmation = None;
+        for transaction in self.ledger.in_replay_order() {
+            match transaction {
+                Transaction::Trade(trade) if Some(trade.record.id) == id => {
+                    confirmation = Some(projection.apply_trade(trade)?);
+                    projection.recall_excess_loans();
+                }
+                other => projection.apply(other)?,
+            }
+        }
+        self.holdings = projection.holdings;
+        self.purchase_records = projection.purchase_records;
+        self.lots = projection.lots;
+        self.acquisitions = projection.acquisitions;
+        self.lot_selections = projection.lot_selections;
+        self.next_lot_id = projection.next_lot_id;
+        self.lot_parents = projection.lot_parents;
+        self.lot_consolidations = projection.lot_consolidations;
+        self.return_of_capital = projection.return_of_capital;
+        self.dividends = projection.dividends;
+        self.broker_basis = projection.broker_basis;
+        self.equity_awards = projection.equity_awards;
+        self.lot_consumptions = projection.lot_consumptions;
+        self.capital_gain_distributions = projection.capital_gain_distributions;
+        self.deposits = projection.deposits;
+        self.interest = projection.interest;
+        self.withdrawals = projection.withdrawals;
+        self.advisory_fees = projection.advisory_fees;
+        self.shares_on_loan = projection.shares_on_loan;
+        self.lending_income = projection.lending_income;
+        self.splits = projection.splits;
+        self.reversals = projection.reversals;
+        self.transaction_tags = projection.transaction_tags;
+        Ok(confirmation)
+    }
+
+    pub(crate) fn apply(&mut self, transaction: &Transaction) -> PortfolioResult<()> {
+        match transaction {
+            Transaction::Trade(trade) => {
+                self.apply_trade(trade)?;
+                self.recall_excess_loans();
+            }
+            Transaction::ReturnOfCapital {
+                symbol, adjustment, ..
+            } => {
+                self.apply_adjustment(symbol, adjustment.per_share_amount, adjustment.date)?;
+            }
+            Transaction::Split { symbol, split, .. } => {
+                self.apply_split(symbol, split)?;
+            }
+            Transaction::Dividend { symbol, dividend } => self.apply_dividend(symbol, dividend),
+            Transaction::BrokerBasis {
+                transaction_id,
+                basis,
+            } => {
+                self.broker_basis.insert(*transaction_id, *basis);
+            }
+            Transaction::ConsolidateLots { symbol, policy, .. } => {
+                self.merge_lots(symbol, policy)?;
+            }
+            Transaction::RenameSymbol { from, to, .. } => self.rekey_projections(from, to),
+            Transaction::CapitalGainDistribution {
+                symbol,
+                distribution,
+            } => self
+                .capital_gain_distributions
+                .entry(symbol.clone())
+                .or_default()
+                .push(distribution.clone()),
+            Transaction::Deposit { transfer } => self.deposits.push(transfer.clone()),
+            Transaction::Withdrawal { transfer } => self.withdrawals.push(transfer.clone()),
+            Transaction::Interest { transfer } => self.interest.push(transfer.clone()),
+            Transaction::AdvisoryFee { fee } => self.advisory_fees.push(fee.clone()),
+            Transaction::LendShares { symbol, shares, .. } => {
+                *self.shares_on_loan.entry(symbol.clone()).or_default() += shares;
+            }
+            Transaction::RecallShares { symbol, shares, .. } => {
+                if let Some(on_loan) = self.shares_on_loan.get_mut(symbol) {
+                    *on_loan = on_loan.saturating_sub(*shares);
+                    if *on_loan == 0 {
+                        self.shares_on_loan.remove(symbol);
+                    }
+                }
+            }
+            Transaction::LendingIncome { symbol, income, .. } => {
+                let base_currency = self.config.base_currency;
+                let total = self
+                    .lending_income
+                    .entry(symbol.clone())
+                    .or_insert_with(|| Money::zero(base_currency));
+                *total = total.checked_add(income)?;
+            }
+            Transaction::Reversal { reversal } => self.reversals.push(reversal.clone()),
+            Transaction::Tag {
+                transaction_id,
+                tag,
+            } => {
+                self.transaction_tags
+                    .entry(*transaction_id)
+                    .or_default()
+                    .insert(tag.clone());
+            }
+            Transaction::Void(_) => {}
+            Transaction::Configure { config } => self.config = config.as_ref().clone(),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use crate::auth::Role;
+use crate::manual_assets::Valuation;
+use crate::money::Money;
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+use chrono::NaiveDate;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub type LiabilityId = u64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LiabilityKind {
+    Loan,
+    Margin,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Liability {
+    pub id: LiabilityId,
+    pub name: String,
+    pub kind: LiabilityKind,
+    pub balances: Vec<Valuation>,
+}
+
+impl Liability {
+    pub fn balance_on(&self, date: NaiveDate) -> Option<Money> {
+        self.balances
+            .iter()
+            .take_while(|balance| balance.date <= date)
+            .last()
+            .map(|balance| balance.value)
+    }
+}
+
+impl Portfolio {
+    pub fn add_liability(
+        &mut self,
+        name: &str,
+        kind: LiabilityKind,
+        balance: Money,
+        date: NaiveDate,
+    ) -> PortfolioResult<LiabilityId> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&balance)?;
+        let id = self
+            .liabilities
+            .last()
+            .map_or(0, |liability| liability.id + 1);
+        self.liabilities.push(Liability {
+            id,
+            name: name.to_string(),
+            kind,
+            balances: vec![Valuation {
+                date,
+                value: balance,
+            }],
+        });
+        self.bump_version();
+        Ok(id)
+    }
+
+    pub fn update_liability_balance(
+        &mut self,
+        id: LiabilityId,
+        balance: Money,
+        date: NaiveDate,
+    ) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&balance)?;
+        let liability = self
+            .liabilities
+            .iter_mut()
+            .find(|liability| liability.id == id)
+            .ok_or(PortfolioError::UnknownLiability(id))?;
+        match liability
+            .balances
+            .binary_search_by_key(&date, |balance| balance.date)
+        {
+            Ok(index) => liability.balances[index].value = balance,
+            Err(index) => liability.balances.insert(
+                index,
+                Valuation {
+                    date,
+                    value: balance,
+                },
+            ),
+        }
+        self.bump_version();
+        Ok(())
+    }
+
+    pub fn liabilities(&self) -> &[Liability] {
+        &self.liabilities
+    }
+
+    pub fn total_liabilities(&self, date: NaiveDate) -> PortfolioResult<Money> {
+        let balances: Vec<Money> = self
+            .liabilities
+            .iter()
+            .filter_map(|liability| liability.balance_on(date))
+            .collect();
+        Money::checked_sum(self.config.base_currency, &balances)
+    }
+}
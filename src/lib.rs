@@ -1,24 +1,279 @@
+pub mod advisory;
+pub mod alerts;
+pub mod auth;
+pub mod automation;
+pub mod basis;
+pub mod blotter;
+pub mod calendar;
+#[cfg(feature = "serde")]
+pub mod canonical;
+pub mod cash;
+pub mod clock;
+pub mod config;
+pub mod corporate_actions;
+pub mod dividends;
+pub mod equity;
+pub mod events;
+pub mod execution;
+pub mod export;
+pub mod external;
+pub mod fx;
+pub mod gains;
+pub mod goals;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod harvest;
+pub mod i18n;
+#[cfg(feature = "import")]
+pub mod import;
+pub mod income;
+pub mod instruments;
+pub mod integrity;
+pub mod jurisdiction;
+pub mod ledger;
+pub mod liabilities;
+pub mod liquidation;
+#[cfg(feature = "import")]
+pub mod load;
+pub mod lots;
+pub mod manager;
+pub mod manual_assets;
+pub mod money;
+pub mod net_worth;
+pub mod notifications;
+pub mod numeric;
+#[cfg(feature = "pricing")]
+pub mod performance;
+pub mod period;
+#[cfg(feature = "plaid")]
+pub mod plaid;
+pub mod position;
+#[cfg(feature = "pricing")]
+pub mod prices;
+pub mod publishing;
+pub mod reconcile;
+pub mod report;
+pub mod reversal;
+pub mod rmd;
+pub mod shared;
+pub mod snapshots;
+pub mod summary;
+pub mod sync;
+pub mod tax;
 mod tests;
-use chrono::NaiveDateTime;
-use std::collections::HashMap;
+pub mod timestamps;
+pub mod versions;
+pub mod view;
+#[cfg(feature = "webhooks")]
+pub mod webhooks;
+use advisory::AdvisoryFee;
+use alerts::{Alert, AlertId};
+use auth::{AccessControl, Actor, Role};
+use automation::Rule;
+use basis::{BrokerBasis, ReturnOfCapital};
+use calendar::{HolidayCalendar, WeekendsOnly};
+use cash::CashTransfer;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use clock::{Clock, SystemClock};
+use config::{CostBasisMethod, DateGranularity, FutureDatedPolicy, PortfolioConfig};
+use corporate_actions::StockSplit;
+use dividends::Dividend;
+use equity::EquityAward;
+use events::PortfolioEvent;
+use execution::{BrokerOrderId, PendingOrder};
+use external::ExternalPosition;
+use fx::TradeFx;
+use gains::GainLoss;
+use goals::Goal;
+use income::CapitalGainDistribution;
+use instruments::{Instrument, InstrumentKind, InstrumentRegistry};
+#[cfg(feature = "import")]
+use integrity::ImportedTransaction;
+use ledger::{Ledger, Trade, Transaction};
+use liabilities::{Liability, LiabilityId};
+use lots::{Acquisition, Lot, LotConsolidation, LotConsumption, LotId, SelectedLot};
+use manual_assets::{ManualAsset, ManualAssetId};
+use money::{Currency, Money};
+use numeric::MoneyAccumulator;
+use period::FiscalYear;
+use position::Position;
+use reversal::Reversal;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use snapshots::ValuationSnapshot;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use versions::{Version, VersionedEvent};
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum TransactionType {
     Purchase,
     Sell,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct PurchaseRecord {
-    pub date: NaiveDateTime,
+    id: TransactionId,
+    date: DateTime<Utc>,
+    day_sequence: u32,
+    shares: u32,
+    transaction_type: TransactionType,
+    price: Option<Money>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    fx: Option<TradeFx>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    net_amount: Option<Money>,
+}
+
+impl PurchaseRecord {
+    pub fn id(&self) -> TransactionId {
+        self.id
+    }
+
+    pub fn date(&self) -> DateTime<Utc> {
+        self.date
+    }
+
+    pub fn day_sequence(&self) -> u32 {
+        self.day_sequence
+    }
+
+    pub fn shares(&self) -> u32 {
+        self.shares
+    }
+
+    pub fn transaction_type(&self) -> &TransactionType {
+        &self.transaction_type
+    }
+
+    pub fn price(&self) -> Option<Money> {
+        self.price
+    }
+
+    pub fn fx(&self) -> Option<TradeFx> {
+        self.fx
+    }
+
+    pub fn net_amount(&self) -> Option<Money> {
+        self.net_amount
+    }
+
+    pub fn trade_date(&self) -> NaiveDate {
+        self.date.date_naive()
+    }
+
+    pub fn signed_shares(&self) -> i64 {
+        match self.transaction_type {
+            TransactionType::Purchase => i64::from(self.shares),
+            TransactionType::Sell => -i64::from(self.shares),
+        }
+    }
+
+    pub fn gross_amount(&self) -> PortfolioResult<Option<Money>> {
+        self.price
+            .map(|price| price.checked_mul(self.shares.into()))
+            .transpose()
+    }
+
+    pub fn settled_amount(&self) -> PortfolioResult<Option<Money>> {
+        match self.net_amount {
+            Some(net_amount) => Ok(Some(net_amount)),
+            None => self.gross_amount(),
+        }
+    }
+}
+
+pub type TransactionId = u64;
+
+pub type Quotes = HashMap<String, Money>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TradeConfirmation {
+    pub transaction_id: TransactionId,
+    pub symbol: String,
+    pub transaction_type: TransactionType,
     pub shares: u32,
+    pub price: Option<Money>,
+    pub fees: Money,
+    pub resulting_position: Position,
+    pub realized_gain: Option<Money>,
+    pub lot_gains: Vec<GainLoss>,
+    pub settlement_date: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Order {
+    pub symbol: String,
     pub transaction_type: TransactionType,
+    pub shares: u32,
+    pub price: Option<Money>,
+    pub idempotency_key: Option<String>,
 }
 
+impl Order {
+    fn matches(&self, confirmation: &TradeConfirmation) -> bool {
+        self.symbol == confirmation.symbol
+            && self.transaction_type == confirmation.transaction_type
+            && self.shares == confirmation.shares
+            && self.price == confirmation.price
+    }
+}
+
+#[derive(Clone)]
 pub struct Portfolio {
-    holdings: HashMap<String, u32>,
+    ledger: Ledger,
+    holdings: HashMap<String, Position>,
     purchase_records: HashMap<String, Vec<PurchaseRecord>>,
+    lots: HashMap<String, Vec<Lot>>,
+    acquisitions: HashMap<TransactionId, Acquisition>,
+    lot_selections: HashMap<TransactionId, Vec<SelectedLot>>,
+    next_lot_id: LotId,
+    lot_parents: HashMap<LotId, LotId>,
+    lot_consolidations: HashMap<String, Vec<LotConsolidation>>,
+    lot_consumptions: HashMap<TransactionId, Vec<LotConsumption>>,
+    next_transaction_id: TransactionId,
+    confirmations_by_key: HashMap<String, TradeConfirmation>,
+    pending_orders: HashMap<BrokerOrderId, PendingOrder>,
+    applied_fills: BTreeSet<String>,
+    reversals: Vec<Reversal>,
+    transaction_tags: HashMap<TransactionId, BTreeSet<String>>,
+    return_of_capital: HashMap<String, Vec<ReturnOfCapital>>,
+    capital_gain_distributions: HashMap<String, Vec<CapitalGainDistribution>>,
+    shares_on_loan: HashMap<String, u32>,
+    lending_income: HashMap<String, Money>,
+    splits: HashMap<String, Vec<StockSplit>>,
+    instruments: InstrumentRegistry,
+    goals: Vec<Goal>,
+    deposits: Vec<CashTransfer>,
+    withdrawals: Vec<CashTransfer>,
+    interest: Vec<CashTransfer>,
+    advisory_fees: Vec<AdvisoryFee>,
+    external_positions: Vec<ExternalPosition>,
+    manual_assets: Vec<ManualAsset>,
+    liabilities: Vec<Liability>,
+    dividends: HashMap<String, Vec<Dividend>>,
+    broker_basis: HashMap<TransactionId, BrokerBasis>,
+    automation_rules: Vec<Rule>,
+    alerts: Vec<Alert>,
+    equity_awards: BTreeMap<TransactionId, EquityAward>,
+    snapshots: BTreeMap<NaiveDate, ValuationSnapshot>,
+    #[cfg(feature = "import")]
+    queued_transactions: Vec<ImportedTransaction>,
+    subscribers: Vec<Sender<PortfolioEvent>>,
+    version: Version,
+    changes: Vec<VersionedEvent>,
+    access: Option<AccessControl>,
+    actor: Option<Actor>,
+    clock: Arc<dyn Clock>,
+    calendar: Arc<dyn HolidayCalendar>,
+    config: PortfolioConfig,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -32,8 +287,146 @@ pub enum PortfolioError {
     #[error("No history for symbol")]
     NoSymbolHistory,
 
-    #[error("Too many shares puchased")]
+    #[error("Too many shares purchased")]
     InvalidPurchase,
+
+    #[error("Trade exceeds the configured limit of {0} shares")]
+    TradeLimitExceeded(u32),
+
+    #[error("Trade must be a multiple of the {0}-share lot size")]
+    InvalidLotSize(u32),
+
+    #[error("Trading in {0} is halted")]
+    TradingHalted(String),
+
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("Currency mismatch: expected {expected}, found {found}")]
+    CurrencyMismatch { expected: Currency, found: Currency },
+
+    #[error("Arithmetic overflow while aggregating portfolio values")]
+    Overflow,
+
+    #[error("Shares on loan cannot be sold until recalled")]
+    SharesOnLoan,
+
+    #[error("Cannot lend more shares than are available")]
+    InvalidLend,
+
+    #[error("Not enough shares on loan")]
+    InsufficientSharesOnLoan,
+
+    #[error("Amount must not be negative")]
+    NegativeAmount,
+
+    #[error("No open lots for symbol")]
+    NoOpenLots,
+
+    #[error("Instrument is not registered")]
+    UnknownInstrument,
+
+    #[error("No price available for {0}")]
+    MissingPrice(String),
+
+    #[error("Latest price for {0} is older than the staleness threshold")]
+    StalePrice(String),
+
+    #[error("Goal target must be positive")]
+    InvalidGoal,
+
+    #[error("Not enough value history to compute a return")]
+    InsufficientHistory,
+
+    #[error("Idempotency key {0} was already used for a different transaction")]
+    IdempotencyKeyConflict(String),
+
+    #[error("No transaction with id {0}")]
+    UnknownTransaction(TransactionId),
+
+    #[error("Transaction {0} has already been reversed")]
+    TransactionAlreadyReversed(TransactionId),
+
+    #[error("Local time {0} is ambiguous or does not exist in the given time zone")]
+    AmbiguousLocalTime(String),
+
+    #[error("Transaction dated {0} is in the future")]
+    FutureDated(DateTime<Utc>),
+
+    #[error("Webhook endpoint {0} must use https")]
+    InvalidWebhookUrl(String),
+
+    #[error("Failed to publish event: {0}")]
+    PublishFailed(String),
+
+    #[error("Account sync failed: {0}")]
+    SyncFailed(String),
+
+    #[error("Broker request failed: {0}")]
+    BrokerError(String),
+
+    #[error("No alert with id {0}")]
+    UnknownAlert(AlertId),
+
+    #[error("Failed to send notification: {0}")]
+    NotificationFailed(String),
+
+    #[error("{0} is not permitted to perform this action")]
+    PermissionDenied(String),
+
+    #[error("Portfolio has an owner; act as a member to change it")]
+    ActorRequired,
+
+    #[error("Portfolio has no access control configured")]
+    NoAccessControl,
+
+    #[error("Sync delta conflicts with local transactions {0:?}")]
+    SyncConflict(Vec<TransactionId>),
+
+    #[error("Invalid serialized portfolio: {0}")]
+    InvalidSerializedPortfolio(String),
+
+    #[error("Required minimum distributions only apply to retirement accounts")]
+    NotRetirementAccount,
+
+    #[error("No manual asset with id {0}")]
+    UnknownManualAsset(ManualAssetId),
+
+    #[error("No liability with id {0}")]
+    UnknownLiability(LiabilityId),
+
+    #[error("Dividend credited against shares bought on or after the {0} ex-date")]
+    IneligibleDividend(NaiveDate),
+
+    #[error("Invalid cost basis import: {0}")]
+    InvalidBasisImport(String),
+
+    #[error("Advisory fee already accrued for the month starting {0}")]
+    FeeAlreadyAccrued(NaiveDate),
+
+    #[error("Exchange rate {0} must be positive")]
+    InvalidFxRate(Decimal),
+
+    #[error("Invalid corporate action: {0}")]
+    InvalidCorporateAction(String),
+
+    #[error("No open lot with id {0}")]
+    UnknownLot(LotId),
+
+    #[error("Lot opened by transaction {0} cannot cover the selected shares")]
+    InvalidLotSelection(TransactionId),
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    #[error("Corrupt portfolio file: {0}")]
+    Corrupt(String),
+
+    #[error("Invalid transaction import: {0}")]
+    InvalidTransactionImport(String),
+
+    #[error("No trading day follows {0} on the holiday calendar")]
+    NoTradingDay(NaiveDate),
 }
 
 pub type PortfolioResult<T> = Result<T, PortfolioError>;
@@ -44,23 +437,153 @@ impl Default for Portfolio {
     }
 }
 
-#[allow(dead_code)]
 impl Portfolio {
-    const FIXED_EPOCH_TIME_MS: i64 = 0;
-
-    const EMPTY_PURCHASE_RECORD: Vec<PurchaseRecord> = vec![];
-
-    pub fn fixed_date_time() -> NaiveDateTime {
-        NaiveDateTime::from_timestamp_millis(Self::FIXED_EPOCH_TIME_MS).unwrap()
+    pub fn fixed_date_time() -> DateTime<Utc> {
+        DateTime::UNIX_EPOCH
     }
 
     pub fn new() -> Self {
+        Self::with_config(PortfolioConfig::default())
+    }
+
+    pub fn with_config(config: PortfolioConfig) -> Self {
         Self {
+            ledger: Ledger::default(),
             holdings: HashMap::new(),
             purchase_records: HashMap::new(),
+            lots: HashMap::new(),
+            acquisitions: HashMap::new(),
+            lot_selections: HashMap::new(),
+            next_lot_id: 0,
+            lot_parents: HashMap::new(),
+            lot_consolidations: HashMap::new(),
+            lot_consumptions: HashMap::new(),
+            next_transaction_id: 0,
+            confirmations_by_key: HashMap::new(),
+            pending_orders: HashMap::new(),
+            applied_fills: BTreeSet::new(),
+            reversals: Vec::new(),
+            transaction_tags: HashMap::new(),
+            return_of_capital: HashMap::new(),
+            capital_gain_distributions: HashMap::new(),
+            shares_on_loan: HashMap::new(),
+            lending_income: HashMap::new(),
+            splits: HashMap::new(),
+            instruments: InstrumentRegistry::new(),
+            goals: Vec::new(),
+            deposits: Vec::new(),
+            withdrawals: Vec::new(),
+            interest: Vec::new(),
+            advisory_fees: Vec::new(),
+            external_positions: Vec::new(),
+            manual_assets: Vec::new(),
+            liabilities: Vec::new(),
+            dividends: HashMap::new(),
+            broker_basis: HashMap::new(),
+            automation_rules: Vec::new(),
+            alerts: Vec::new(),
+            equity_awards: BTreeMap::new(),
+            snapshots: BTreeMap::new(),
+            #[cfg(feature = "import")]
+            queued_transactions: Vec::new(),
+            subscribers: Vec::new(),
+            version: 0,
+            changes: Vec::new(),
+            access: None,
+            actor: None,
+            clock: Arc::new(SystemClock),
+            calendar: Arc::new(WeekendsOnly),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &PortfolioConfig {
+        &self.config
+    }
+
+    pub fn with_clock(clock: impl Clock + 'static) -> Self {
+        let mut portfolio = Self::new();
+        portfolio.set_clock(clock);
+        portfolio
+    }
+
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Arc::new(clock);
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    pub fn set_calendar(&mut self, calendar: impl HolidayCalendar + 'static) {
+        self.calendar = Arc::new(calendar);
+    }
+
+    pub fn settlement_date(
+        &self,
+        symbol: &str,
+        trade_date: DateTime<Utc>,
+    ) -> PortfolioResult<DateTime<Utc>> {
+        match self.instruments.get(symbol) {
+            Some(instrument) => instrument.settlement_date(trade_date, self.calendar.as_ref()),
+            None => Instrument::new(InstrumentKind::Stock)
+                .settlement_date(trade_date, self.calendar.as_ref()),
         }
     }
 
+    pub fn set_fiscal_year(&mut self, start_month: u32, start_day: u32) -> PortfolioResult<()> {
+        self.authorize(Role::Owner)?;
+        self.config.tax.fiscal_year = FiscalYear::new(start_month, start_day)?;
+        Ok(())
+    }
+
+    pub fn fiscal_year_of(&self, date: DateTime<Utc>) -> i32 {
+        self.config.tax.fiscal_year.year_of(date.date_naive())
+    }
+
+    pub fn is_future_dated(&self, date: DateTime<Utc>) -> bool {
+        date > self.now()
+    }
+
+    pub fn instruments(&self) -> &InstrumentRegistry {
+        &self.instruments
+    }
+
+    pub fn instruments_mut(&mut self) -> PortfolioResult<&mut InstrumentRegistry> {
+        self.authorize(Role::Trader)?;
+        Ok(&mut self.instruments)
+    }
+
+    pub fn cost_basis_method_for(&self, symbol: &str) -> CostBasisMethod {
+        self.instruments
+            .kind(symbol)
+            .and_then(|kind| kind.default_cost_basis_method())
+            .unwrap_or(self.config.cost_basis_method)
+    }
+
+    pub fn money_accumulator(&self) -> MoneyAccumulator {
+        MoneyAccumulator::new(
+            self.config.numeric_backend,
+            self.config.base_currency,
+            self.config.rounding.clone(),
+        )
+    }
+
+    pub(crate) fn money_total<'a>(
+        &self,
+        amounts: impl IntoIterator<Item = &'a Money>,
+    ) -> PortfolioResult<Money> {
+        let mut total = self.money_accumulator();
+        for amount in amounts {
+            total.add(amount)?;
+        }
+        total.total()
+    }
+
+    pub fn localize_error(&self, error: &PortfolioError) -> String {
+        error.localized(self.config.locale)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.holdings.is_empty()
     }
@@ -72,23 +595,339 @@ impl Portfolio {
         Ok(())
     }
 
-    pub fn purchase(&mut self, symbol: &str, shares: u32) -> PortfolioResult<()> {
-        self.transact(symbol, shares, TransactionType::Purchase)
+    fn validate_trade_limit(&self, shares: u32) -> PortfolioResult<()> {
+        match self.config.rules.max_shares_per_trade {
+            Some(limit) if shares > limit => Err(PortfolioError::TradeLimitExceeded(limit)),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_lot_size(
+        &self,
+        symbol: &str,
+        shares: u32,
+        transaction_type: &TransactionType,
+    ) -> PortfolioResult<()> {
+        let closes_position = match (transaction_type, self.get_position(symbol)) {
+            (TransactionType::Sell, Position::Long(held))
+            | (TransactionType::Purchase, Position::Short(held)) => shares == held,
+            _ => false,
+        };
+        match self.instruments.lot_size(symbol) {
+            Some(lot_size) if !closes_position && !shares.is_multiple_of(lot_size) => {
+                Err(PortfolioError::InvalidLotSize(lot_size))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_not_halted(&self, symbol: &str) -> PortfolioResult<()> {
+        if self.instruments.is_halted(symbol) {
+            return Err(PortfolioError::TradingHalted(symbol.to_string()));
+        }
+        Ok(())
+    }
+
+    fn validate_not_on_loan(&self, symbol: &str, shares: u32) -> PortfolioResult<()> {
+        let on_loan = self.get_shares_on_loan(symbol);
+        if on_loan > 0 && shares > self.get_available_shares(symbol) {
+            return Err(PortfolioError::SharesOnLoan);
+        }
+        Ok(())
+    }
+
+    fn validate_not_future_dated(&self, date: DateTime<Utc>) -> PortfolioResult<()> {
+        if self.config.rules.future_dated != FutureDatedPolicy::Warn && self.is_future_dated(date) {
+            return Err(PortfolioError::FutureDated(date));
+        }
+        Ok(())
+    }
+
+    fn validate_amount(&self, amount: &Money) -> PortfolioResult<()> {
+        Money::zero(self.config.base_currency).ensure_same_currency(amount)?;
+        if amount.is_negative() {
+            return Err(PortfolioError::NegativeAmount);
+        }
+        Ok(())
+    }
+
+    pub fn purchase(&mut self, symbol: &str, shares: u32) -> PortfolioResult<TradeConfirmation> {
+        self.transact(symbol, shares, TransactionType::Purchase, None, self.now())
+    }
+
+    pub fn purchase_at(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        price: Money,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.transact(
+            symbol,
+            shares,
+            TransactionType::Purchase,
+            Some(price),
+            self.now(),
+        )
+    }
+
+    pub fn sell(&mut self, symbol: &str, shares: u32) -> PortfolioResult<TradeConfirmation> {
+        self.transact(symbol, shares, TransactionType::Sell, None, self.now())
     }
 
-    pub fn sell(&mut self, symbol: &str, shares: u32) -> PortfolioResult<()> {
-        self.transact(symbol, shares, TransactionType::Sell)
+    pub fn sell_at(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        price: Money,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.transact(
+            symbol,
+            shares,
+            TransactionType::Sell,
+            Some(price),
+            self.now(),
+        )
     }
 
-    fn transact(
+    pub fn transact_with_net_amount(
         &mut self,
         symbol: &str,
         shares: u32,
         transaction_type: TransactionType,
-    ) -> PortfolioResult<()> {
-        Self::validate_share_count(shares)?;
-        self.update_holdings(symbol, shares, transaction_type.clone())?;
-        self.update_purchase_records(symbol, shares, transaction_type.clone())
+        price: Money,
+        net_amount: Money,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&net_amount)?;
+        self.transact_settled(
+            symbol,
+            shares,
+            transaction_type,
+            Some(price),
+            Some(net_amount),
+            date,
+        )
+    }
+
+    pub fn sell_all(&mut self, symbol: &str, price: Money) -> PortfolioResult<TradeConfirmation> {
+        self.sell_at(symbol, self.get_share_count(symbol), price)
+    }
+
+    pub fn close_position(
+        &mut self,
+        symbol: &str,
+        price: Money,
+    ) -> PortfolioResult<TradeConfirmation> {
+        match self.get_position(symbol) {
+            Position::Long(shares) => self.sell_at(symbol, shares, price),
+            Position::Short(shares) => self.purchase_at(symbol, shares, price),
+            Position::Flat => Err(PortfolioError::ZeroShares),
+        }
+    }
+
+    pub fn submit(&mut self, order: Order) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        let Some(key) = &order.idempotency_key else {
+            let confirmation = self.transact(
+                &order.symbol,
+                order.shares,
+                order.transaction_type,
+                order.price,
+                self.now(),
+            )?;
+            self.publish_fill(&confirmation, None);
+            return Ok(confirmation);
+        };
+        if let Some(confirmation) = self.confirmations_by_key.get(key) {
+            if !order.matches(confirmation) {
+                return Err(PortfolioError::IdempotencyKeyConflict(key.clone()));
+            }
+            return Ok(confirmation.clone());
+        }
+        let confirmation = self.transact(
+            &order.symbol,
+            order.shares,
+            order.transaction_type.clone(),
+            order.price,
+            self.now(),
+        )?;
+        self.confirmations_by_key
+            .insert(key.clone(), confirmation.clone());
+        self.publish_fill(&confirmation, Some(key.clone()));
+        Ok(confirmation)
+    }
+
+    fn publish_fill(&mut self, confirmation: &TradeConfirmation, idempotency_key: Option<String>) {
+        self.publish(PortfolioEvent::OrderFilled {
+            transaction_id: confirmation.transaction_id,
+            symbol: confirmation.symbol.clone(),
+            idempotency_key,
+        });
+    }
+
+    pub fn transact(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        transaction_type: TransactionType,
+        price: Option<Money>,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        self.transact_settled(symbol, shares, transaction_type, price, None, date)
+    }
+
+    pub(crate) fn transact_settled(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        transaction_type: TransactionType,
+        price: Option<Money>,
+        net_amount: Option<Money>,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<TradeConfirmation> {
+        let mut trade = Trade::new(symbol, shares, transaction_type, price, date);
+        trade.record.net_amount = net_amount;
+        self.record_trade(trade)
+    }
+
+    pub(crate) fn validate_synced_trade(&self, trade: &Trade) -> PortfolioResult<()> {
+        let Trade { symbol, record, .. } = trade;
+        Self::validate_share_count(record.shares)?;
+        self.validate_trade_limit(record.shares)?;
+        if let Some(price) = &record.price {
+            self.validate_amount(price)?;
+        }
+        self.validate_local_currency(symbol, record)?;
+        self.validate_not_future_dated(record.date)
+    }
+
+    fn validate_trade(&self, trade: &Trade) -> PortfolioResult<()> {
+        let Trade { symbol, record, .. } = trade;
+        self.validate_synced_trade(trade)?;
+        if trade.acquisition == Acquisition::Purchase {
+            self.validate_lot_size(symbol, record.shares, &record.transaction_type)?;
+            self.validate_not_halted(symbol)?;
+        }
+        if record.transaction_type == TransactionType::Sell {
+            self.validate_not_on_loan(symbol, record.shares)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn record_trade(&mut self, mut trade: Trade) -> PortfolioResult<TradeConfirmation> {
+        self.validate_trade(&trade)?;
+        trade.record.id = self.next_transaction_id;
+        trade.record.date = self.normalize_date(trade.record.date);
+        trade.record.day_sequence = self.records_on(trade.record.trade_date());
+        let event = trade.event();
+        let confirmation = if self.ledger.has_later_than(trade.record.date) {
+            let snapshot = self.clone();
+            let id = trade.record.id;
+            self.ledger.append(Transaction::Trade(trade));
+            match self.replay_confirming(Some(id)) {
+                Ok(Some(confirmation)) => confirmation,
+                Ok(None) => unreachable!("the appended trade is replayed"),
+                Err(error) => {
+                    *self = snapshot;
+                    return Err(error);
+                }
+            }
+        } else {
+            let confirmation = self.apply_trade(&trade)?;
+            self.ledger.append(Transaction::Trade(trade));
+            confirmation
+        };
+        self.next_transaction_id += 1;
+        self.publish(event);
+        Ok(confirmation)
+    }
+
+    pub(crate) fn apply_trade(&mut self, trade: &Trade) -> PortfolioResult<TradeConfirmation> {
+        let Trade { symbol, record, .. } = trade;
+        let previous_long = self.get_share_count(symbol);
+        self.update_holdings(symbol, record.shares, record.transaction_type.clone())?;
+        let consumed = self.update_lots(trade, previous_long)?;
+        if trade.acquisition != Acquisition::Purchase {
+            self.acquisitions.insert(record.id, trade.acquisition);
+        }
+        if let Some(selection) = &trade.lot_selection {
+            self.lot_selections.insert(record.id, selection.clone());
+        }
+        if let Some(award) = &trade.equity_award {
+            self.equity_awards.insert(record.id, award.as_ref().clone());
+        }
+        let fees = match (record.gross_amount()?, record.net_amount) {
+            (Some(gross), Some(net)) => match record.transaction_type {
+                TransactionType::Purchase => net.checked_sub(&gross)?,
+                TransactionType::Sell => gross.checked_sub(&net)?,
+            },
+            _ => Money::zero(self.config.base_currency),
+        };
+        self.update_purchase_records(symbol, record.clone())?;
+        let (realized_gain, lot_gains) =
+            match (&record.transaction_type, record.price, record.net_amount) {
+                (TransactionType::Sell, _, Some(net)) => {
+                    let lot_gains =
+                        gains::realize_net(&consumed, &net, record.shares, record.date)?;
+                    let total = gains::total_gain(net.currency, &lot_gains, None)?;
+                    (Some(total), lot_gains)
+                }
+                (TransactionType::Sell, Some(price), None) => {
+                    let lot_gains = gains::realize(&consumed, &price, record.date)?;
+                    let total = gains::total_gain(price.currency, &lot_gains, None)?;
+                    (Some(total), lot_gains)
+                }
+                _ => (None, Vec::new()),
+            };
+        Ok(TradeConfirmation {
+            transaction_id: record.id,
+            symbol: symbol.clone(),
+            transaction_type: record.transaction_type.clone(),
+            shares: record.shares,
+            price: record.price,
+            fees,
+            resulting_position: self.get_position(symbol),
+            realized_gain,
+            lot_gains,
+            settlement_date: self.settlement_date(symbol, record.date)?,
+        })
+    }
+
+    fn normalize_date(&self, date: DateTime<Utc>) -> DateTime<Utc> {
+        match self.config.date_granularity {
+            DateGranularity::Timestamp => date,
+            DateGranularity::Daily => date.date_naive().and_time(NaiveTime::MIN).and_utc(),
+        }
+    }
+
+    fn records_on(&self, date: NaiveDate) -> u32 {
+        let count = self
+            .purchase_records
+            .values()
+            .flatten()
+            .filter(|record| record.trade_date() == date)
+            .count();
+        u32::try_from(count).unwrap_or(u32::MAX)
+    }
+
+    pub fn format_record_date(&self, record: &PurchaseRecord) -> String {
+        match self.config.date_granularity {
+            DateGranularity::Timestamp => record.date.to_rfc3339(),
+            DateGranularity::Daily => record.trade_date().to_string(),
+        }
+    }
+
+    pub fn records_in_order(&self, symbol: &str) -> Vec<&PurchaseRecord> {
+        let mut records: Vec<&PurchaseRecord> = self
+            .purchase_records
+            .get(symbol)
+            .into_iter()
+            .flatten()
+            .collect();
+        records.sort_by_key(|record| (record.trade_date(), record.day_sequence));
+        records
     }
 
     fn update_holdings(
@@ -97,35 +936,322 @@ impl Portfolio {
         shares: u32,
         transaction_type: TransactionType,
     ) -> PortfolioResult<()> {
-        let count = self.holdings.entry(symbol.to_string()).or_default();
-        let new_shares = match transaction_type {
-            TransactionType::Purchase => count
-                .checked_add(shares)
+        let current = self.get_position(symbol).signed_quantity();
+        let new_position = match transaction_type {
+            TransactionType::Purchase => Position::from_signed(current + i64::from(shares))
                 .ok_or(PortfolioError::InvalidPurchase),
 
-            TransactionType::Sell => count.checked_sub(shares).ok_or(PortfolioError::InvalidSell),
+            TransactionType::Sell => Position::from_signed(current - i64::from(shares))
+                .filter(|position| !position.is_short() || self.config.rules.allow_short_selling)
+                .ok_or(PortfolioError::InvalidSell),
         }?;
-        *count = new_shares;
+        self.holdings.insert(symbol.to_string(), new_position);
+        Ok(())
+    }
+
+    fn update_lots(
+        &mut self,
+        trade: &Trade,
+        previous_long: u32,
+    ) -> PortfolioResult<Vec<LotConsumption>> {
+        let symbol = trade.symbol.as_str();
+        let PurchaseRecord {
+            id: sequence,
+            date,
+            price,
+            net_amount,
+            ..
+        } = trade.record;
+        let current_long = self.get_share_count(symbol);
+        if current_long > previous_long {
+            let shares = current_long - previous_long;
+            let cost_basis = match (self.broker_basis.get(&sequence), net_amount, price) {
+                (Some(broker), _, _) => broker.cost_basis,
+                (None, Some(net_amount), _) => net_amount,
+                (None, None, Some(price)) => price.checked_mul(shares.into())?,
+                (None, None, None) => Money::zero(self.config.base_currency),
+            };
+            if let Some(restored) = trade
+                .reverses
+                .and_then(|sale| self.lot_consumptions.get(&sale))
+                .cloned()
+            {
+                self.restore_lots(symbol, &restored)?;
+                return Ok(Vec::new());
+            }
+            let acquisition = trade.acquisition;
+            if let Some(lots) = self
+                .lots
+                .get_mut(symbol)
+                .filter(|lots| acquisition == Acquisition::StockDividend && !lots.is_empty())
+            {
+                lots::spread_shares(lots, shares);
+                return Ok(Vec::new());
+            }
+            let id = self.next_lot_id;
+            self.next_lot_id += 1;
+            self.lots.entry(symbol.to_string()).or_default().push(Lot {
+                id,
+                acquired: date,
+                sequence,
+                shares,
+                cost_basis,
+                acquisition,
+                covered: self
+                    .broker_basis
+                    .get(&sequence)
+                    .map_or(acquisition.reported_by_default(), |broker| broker.covered),
+            });
+        } else if current_long < previous_long {
+            let method = self.cost_basis_method_for(symbol);
+            let lots = self.lots.entry(symbol.to_string()).or_default();
+            let consumed = match &trade.lot_selection {
+                Some(selections) => {
+                    lots::consume_selected(lots, selections, &mut self.next_lot_id)?
+                }
+                None => lots::consume_lots(
+                    lots,
+                    previous_long - current_long,
+                    method,
+                    price.as_ref(),
+                    date,
+                    &mut self.next_lot_id,
+                )?,
+            };
+            for consumption in &consumed {
+                if let Some(remainder) = consumption.remainder_lot_id {
+                    self.lot_parents.insert(remainder, consumption.lot_id);
+                }
+            }
+            self.lot_consumptions.insert(sequence, consumed.clone());
+            return Ok(consumed);
+        }
+        Ok(Vec::new())
+    }
+
+    fn restore_lots(&mut self, symbol: &str, consumed: &[LotConsumption]) -> PortfolioResult<()> {
+        for consumption in consumed {
+            let lots = self.lots.entry(symbol.to_string()).or_default();
+            if let Some(remainder) = lots
+                .iter_mut()
+                .find(|lot| Some(lot.id) == consumption.remainder_lot_id)
+            {
+                remainder.shares += consumption.shares;
+                remainder.cost_basis = remainder.cost_basis.checked_add(&consumption.cost_basis)?;
+                continue;
+            }
+            let id = self.next_lot_id;
+            self.next_lot_id += 1;
+            self.lot_parents.insert(id, consumption.lot_id);
+            self.lots.entry(symbol.to_string()).or_default().push(Lot {
+                id,
+                acquired: consumption.acquired,
+                sequence: consumption.sequence,
+                shares: consumption.shares,
+                cost_basis: consumption.cost_basis,
+                acquisition: consumption.acquisition,
+                covered: consumption.covered,
+            });
+        }
         Ok(())
     }
 
     fn update_purchase_records(
         &mut self,
         symbol: &str,
-        shares: u32,
-        transaction_type: TransactionType,
+        record: PurchaseRecord,
     ) -> PortfolioResult<()> {
         let records = self.purchase_records.entry(symbol.to_string()).or_default();
-        records.push(PurchaseRecord {
-            date: Self::fixed_date_time(),
-            shares,
-            transaction_type,
-        });
+        records.push(record);
         Ok(())
     }
 
     pub fn get_share_count(&self, symbol: &str) -> u32 {
-        *self.holdings.get(symbol).unwrap_or(&0)
+        self.get_position(symbol).long_quantity()
+    }
+
+    pub fn get_share_count_as_of(&self, symbol: &str, date: NaiveDate) -> u32 {
+        Position::from_signed(self.signed_shares_from_records(symbol, Some(date)))
+            .unwrap_or_default()
+            .long_quantity()
+    }
+
+    pub(crate) fn signed_shares_from_records(&self, symbol: &str, as_of: Option<NaiveDate>) -> i64 {
+        let included = |date: DateTime<Utc>| as_of.is_none_or(|as_of| date.date_naive() <= as_of);
+        let mut records: Vec<&PurchaseRecord> = self
+            .purchase_records
+            .get(symbol)
+            .into_iter()
+            .flatten()
+            .filter(|record| included(record.date))
+            .collect();
+        records.sort_by_key(|record| record.date);
+        let mut splits = self
+            .get_splits(symbol)
+            .iter()
+            .filter(|split| included(split.date))
+            .peekable();
+        let mut shares = 0;
+        for record in records {
+            while let Some(split) = splits.next_if(|split| split.date <= record.date) {
+                shares = split.rescale(shares);
+            }
+            shares += record.signed_shares();
+        }
+        splits.fold(shares, |shares, split| split.rescale(shares))
+    }
+
+    pub fn get_signed_share_count(&self, symbol: &str) -> i64 {
+        self.get_position(symbol).signed_quantity()
+    }
+
+    pub fn get_position(&self, symbol: &str) -> Position {
+        self.holdings.get(symbol).copied().unwrap_or_default()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (&str, Position)> + '_ {
+        self.holdings
+            .iter()
+            .filter(|(_, position)| **position != Position::Flat)
+            .map(|(symbol, position)| (symbol.as_str(), *position))
+    }
+
+    pub fn total_share_count(&self) -> u128 {
+        self.holdings
+            .values()
+            .map(|position| u128::from(position.long_quantity()))
+            .sum()
+    }
+
+    pub fn lend_shares(&mut self, symbol: &str, shares: u32) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        Self::validate_share_count(shares)?;
+        if shares > self.get_available_shares(symbol) {
+            return Err(PortfolioError::InvalidLend);
+        }
+        self.commit_entry(Transaction::LendShares {
+            symbol: symbol.to_string(),
+            shares,
+            date: self.now(),
+            sequence: self.next_transaction_id,
+        })
+    }
+
+    pub fn recall_shares(&mut self, symbol: &str, shares: u32) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        Self::validate_share_count(shares)?;
+        if self.get_shares_on_loan(symbol) < shares {
+            return Err(PortfolioError::InsufficientSharesOnLoan);
+        }
+        self.commit_entry(Transaction::RecallShares {
+            symbol: symbol.to_string(),
+            shares,
+            date: self.now(),
+            sequence: self.next_transaction_id,
+        })
+    }
+
+    pub fn get_shares_on_loan(&self, symbol: &str) -> u32 {
+        *self.shares_on_loan.get(symbol).unwrap_or(&0)
+    }
+
+    pub fn get_available_shares(&self, symbol: &str) -> u32 {
+        self.get_share_count(symbol)
+            .saturating_sub(self.get_shares_on_loan(symbol))
+    }
+
+    pub(crate) fn recall_excess_loans(&mut self) {
+        let held: HashMap<String, u32> = self
+            .shares_on_loan
+            .keys()
+            .map(|symbol| (symbol.clone(), self.get_share_count(symbol)))
+            .collect();
+        self.shares_on_loan.retain(|symbol, on_loan| {
+            *on_loan = (*on_loan).min(held[symbol]);
+            *on_loan > 0
+        });
+    }
+
+    pub fn accrue_lending_income(&mut self, symbol: &str, income: Money) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        if self.get_shares_on_loan(symbol) == 0 {
+            return Err(PortfolioError::InsufficientSharesOnLoan);
+        }
+        self.get_lending_income(symbol).checked_add(&income)?;
+        self.commit_entry(Transaction::LendingIncome {
+            symbol: symbol.to_string(),
+            income,
+            date: self.now(),
+            sequence: self.next_transaction_id,
+        })
+    }
+
+    pub fn get_lending_income(&self, symbol: &str) -> Money {
+        self.lending_income
+            .get(symbol)
+            .copied()
+            .unwrap_or_else(|| Money::zero(self.config.base_currency))
+    }
+
+    pub(crate) fn lending_income_history(
+        &self,
+    ) -> impl Iterator<Item = (&str, DateTime<Utc>, &Money)> + '_ {
+        self.ledger
+            .transactions()
+            .iter()
+            .filter_map(|entry| match entry {
+                Transaction::LendingIncome {
+                    symbol,
+                    income,
+                    date,
+                    ..
+                } => Some((symbol.as_str(), *date, income)),
+                _ => None,
+            })
+    }
+
+    pub fn total_lending_income(&self) -> PortfolioResult<Money> {
+        self.money_total(self.lending_income.values())
+    }
+
+    pub fn market_value(&self, quotes: &Quotes) -> PortfolioResult<Money> {
+        let mut total = Money::zero(self.config.base_currency);
+        for (symbol, position) in &self.holdings {
+            if *position == Position::Flat {
+                continue;
+            }
+            let price = quotes
+                .get(symbol)
+                .ok_or_else(|| PortfolioError::MissingPrice(symbol.clone()))?;
+            total = total.checked_add(&price.checked_mul(position.signed_quantity().into())?)?;
+        }
+        Ok(total)
+    }
+
+    pub fn traded_symbols(&self) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self.purchase_records.keys().map(|s| s.as_str()).collect();
+        symbols.sort_unstable();
+        symbols
+    }
+
+    pub fn first_trade_date(&self) -> Option<NaiveDate> {
+        self.purchase_records
+            .values()
+            .flatten()
+            .map(|record| record.date.date_naive())
+            .min()
+    }
+
+    pub fn journal(&self) -> Vec<(&str, &PurchaseRecord)> {
+        let mut journal: Vec<(&str, &PurchaseRecord)> = self
+            .purchase_records
+            .iter()
+            .flat_map(|(symbol, records)| {
+                records.iter().map(move |record| (symbol.as_str(), record))
+            })
+            .collect();
+        journal.sort_by_key(|(_, record)| (record.date, record.id));
+        journal
     }
 
     pub fn get_purchase_record(&self, symbol: &str) -> PortfolioResult<&[PurchaseRecord]> {
@@ -0,0 +1,57 @@
+use crate::auth::Role;
+use crate::money::Money;
+use crate::position::Position;
+use crate::Quotes;
+use crate::{Portfolio, PortfolioError, PortfolioResult, TradeConfirmation, TransactionType};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Liquidation {
+    pub confirmations: Vec<TradeConfirmation>,
+    pub net_proceeds: Money,
+    pub realized_gain: Money,
+}
+
+impl Portfolio {
+    fn close_all_positions(&mut self, quotes: &Quotes) -> PortfolioResult<Liquidation> {
+        let currency = self.config.base_currency;
+        let mut symbols: Vec<String> = self
+            .holdings
+            .iter()
+            .filter(|(_, position)| **position != Position::Flat)
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        symbols.sort_unstable();
+
+        let mut liquidation = Liquidation {
+            confirmations: Vec::new(),
+            net_proceeds: Money::zero(currency),
+            realized_gain: Money::zero(currency),
+        };
+        for symbol in symbols {
+            let price = *quotes
+                .get(&symbol)
+                .ok_or_else(|| PortfolioError::MissingPrice(symbol.clone()))?;
+            let confirmation = self.close_position(&symbol, price)?;
+            let amount = price.checked_mul(confirmation.shares.into())?;
+            liquidation.net_proceeds = match confirmation.transaction_type {
+                TransactionType::Sell => liquidation.net_proceeds.checked_add(&amount)?,
+                TransactionType::Purchase => liquidation.net_proceeds.checked_sub(&amount)?,
+            };
+            if let Some(gain) = &confirmation.realized_gain {
+                liquidation.realized_gain = liquidation.realized_gain.checked_add(gain)?;
+            }
+            liquidation.confirmations.push(confirmation);
+        }
+        Ok(liquidation)
+    }
+
+    pub fn liquidate(&mut self, quotes: &Quotes) -> PortfolioResult<Liquidation> {
+        self.authorize(Role::Trader)?;
+        let snapshot = self.clone();
+        let liquidation = self.close_all_positions(quotes);
+        if liquidation.is_err() {
+            *self = snapshot;
+        }
+        liquidation
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{PortfolioError, PortfolioResult};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LoadMode {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    pub mode: LoadMode,
+}
+
+#[derive(Debug)]
+pub struct SkippedEntry {
+    pub index: usize,
+    pub error: PortfolioError,
+}
+
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    pub skipped: Vec<SkippedEntry>,
+}
+
+impl LoadReport {
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty()
+    }
+
+    pub(crate) fn record<T>(
+        &mut self,
+        options: &LoadOptions,
+        index: usize,
+        result: PortfolioResult<T>,
+    ) -> PortfolioResult<Option<T>> {
+        match (result, options.mode) {
+            (Ok(value), _) => Ok(Some(value)),
+            (Err(error), LoadMode::Strict) => Err(error),
+            (Err(error), LoadMode::Lenient) => {
+                self.skipped.push(SkippedEntry { index, error });
+                Ok(None)
+            }
+        }
+    }
+}
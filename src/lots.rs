@@ -0,0 +1,516 @@
+use crate::auth::Role;
+use crate::config::CostBasisMethod;
+use crate::gains::HoldingTerm;
+use crate::ledger::{Trade, Transaction};
+use crate::money::Money;
+use crate::{
+    Portfolio, PortfolioError, PortfolioResult, TradeConfirmation, TransactionId, TransactionType,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+pub type LotId = u64;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum Acquisition {
+    #[default]
+    Purchase,
+    Gift {
+        donor_acquired: DateTime<Utc>,
+        fair_market_value: Money,
+    },
+    Inheritance,
+    StockDividend,
+}
+
+impl Acquisition {
+    pub fn reported_by_default(&self) -> bool {
+        matches!(self, Acquisition::Purchase)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lot {
+    pub id: LotId,
+    pub acquired: DateTime<Utc>,
+    pub sequence: TransactionId,
+    pub shares: u32,
+    pub cost_basis: Money,
+    pub acquisition: Acquisition,
+    pub covered: bool,
+}
+
+impl Lot {
+    pub fn basis_per_share(&self) -> Decimal {
+        if self.shares == 0 {
+            return Decimal::ZERO;
+        }
+        self.cost_basis.amount / Decimal::from(self.shares)
+    }
+
+    fn basis_for(&self, shares: u32) -> PortfolioResult<Money> {
+        if shares == self.shares {
+            return Ok(self.cost_basis);
+        }
+        prorate(&self.cost_basis, u64::from(shares), u64::from(self.shares))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct LotConsumption {
+    pub lot_id: LotId,
+    pub acquired: DateTime<Utc>,
+    pub sequence: TransactionId,
+    pub shares: u32,
+    pub cost_basis: Money,
+    pub acquisition: Acquisition,
+    pub covered: bool,
+    pub remainder_lot_id: Option<LotId>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LotSelection {
+    pub lot_id: LotId,
+    pub shares: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SelectedLot {
+    pub sequence: TransactionId,
+    pub shares: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ConsolidationPolicy {
+    pub date_tolerance_days: i64,
+    pub price_tolerance: Decimal,
+}
+
+impl ConsolidationPolicy {
+    fn can_merge(&self, anchor: &Lot, lot: &Lot) -> bool {
+        lot.acquisition == anchor.acquisition
+            && lot.covered == anchor.covered
+            && (lot.acquired - anchor.acquired).num_days().abs() <= self.date_tolerance_days
+            && (lot.basis_per_share() - anchor.basis_per_share()).abs() <= self.price_tolerance
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LotConsolidation {
+    pub merged: Vec<LotId>,
+    pub into: LotId,
+}
+
+fn merge(group: Vec<Lot>, id: LotId) -> PortfolioResult<Lot> {
+    let first = &group[0];
+    let currency = first.cost_basis.currency;
+    Ok(Lot {
+        id,
+        acquired: first.acquired,
+        sequence: first.sequence,
+        shares: group.iter().map(|lot| lot.shares).sum(),
+        cost_basis: Money::checked_sum(currency, group.iter().map(|lot| &lot.cost_basis))?,
+        acquisition: first.acquisition,
+        covered: first.covered,
+    })
+}
+
+impl Portfolio {
+    pub fn receive_gift(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        donor_basis: Money,
+        donor_acquired: DateTime<Utc>,
+        fair_market_value: Money,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&fair_market_value)?;
+        let acquisition = Acquisition::Gift {
+            donor_acquired,
+            fair_market_value,
+        };
+        self.acquire(symbol, shares, donor_basis, date, acquisition)
+    }
+
+    pub fn inherit(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        fair_market_value: Money,
+        date_of_death: DateTime<Utc>,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        self.acquire(
+            symbol,
+            shares,
+            fair_market_value,
+            date_of_death,
+            Acquisition::Inheritance,
+        )
+    }
+
+    fn acquire(
+        &mut self,
+        symbol: &str,
+        shares: u32,
+        basis: Money,
+        date: DateTime<Utc>,
+        acquisition: Acquisition,
+    ) -> PortfolioResult<TradeConfirmation> {
+        let mut trade = Trade::new(symbol, shares, TransactionType::Purchase, Some(basis), date);
+        trade.acquisition = acquisition;
+        self.record_trade(trade)
+    }
+
+    pub fn record_stock_dividend(
+        &mut self,
+        symbol: &str,
+        additional_shares: u32,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        if self.lots.get(symbol).is_none_or(|lots| lots.is_empty()) {
+            return Err(PortfolioError::NoOpenLots);
+        }
+        self.acquire(
+            symbol,
+            additional_shares,
+            Money::zero(self.config.base_currency),
+            date,
+            Acquisition::StockDividend,
+        )
+    }
+
+    pub fn sell_lots(
+        &mut self,
+        symbol: &str,
+        selections: &[LotSelection],
+        price: Money,
+        date: DateTime<Utc>,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        let open = self.open_lots(symbol);
+        let mut selected: Vec<SelectedLot> = Vec::with_capacity(selections.len());
+        for selection in selections {
+            Self::validate_share_count(selection.shares)?;
+            let lot = open
+                .iter()
+                .find(|lot| lot.id == selection.lot_id)
+                .ok_or(PortfolioError::UnknownLot(selection.lot_id))?;
+            let already = selected
+                .iter()
+                .filter(|chosen| chosen.sequence == lot.sequence)
+                .map(|chosen| chosen.shares)
+                .sum::<u32>();
+            if already + selection.shares > lot.shares {
+                return Err(PortfolioError::InvalidSell);
+            }
+            selected.push(SelectedLot {
+                sequence: lot.sequence,
+                shares: selection.shares,
+            });
+        }
+        let shares = selected.iter().map(|chosen| chosen.shares).sum();
+        let mut trade = Trade::new(symbol, shares, TransactionType::Sell, Some(price), date);
+        trade.lot_selection = Some(selected);
+        self.record_trade(trade)
+    }
+
+    pub fn lot_selection_of(&self, id: TransactionId) -> Option<&[SelectedLot]> {
+        self.lot_selections.get(&id).map(Vec::as_slice)
+    }
+
+    pub fn open_lots(&self, symbol: &str) -> &[Lot] {
+        self.lots
+            .get(symbol)
+            .map(|lots| lots.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn acquisition_of(&self, id: TransactionId) -> Acquisition {
+        self.acquisitions.get(&id).copied().unwrap_or_default()
+    }
+
+    pub fn consolidate_lots(
+        &mut self,
+        symbol: &str,
+        policy: &ConsolidationPolicy,
+    ) -> PortfolioResult<Vec<LotConsolidation>> {
+        self.authorize(Role::Trader)?;
+        if self.lots.get(symbol).is_none_or(|lots| lots.is_empty()) {
+            return Err(PortfolioError::NoOpenLots);
+        }
+        let consolidations = self.merge_lots(symbol, policy)?;
+        if !consolidations.is_empty() {
+            self.record_entry(Transaction::ConsolidateLots {
+                symbol: symbol.to_string(),
+                date: self
+                    .ledger
+                    .latest_date(symbol)
+                    .unwrap_or_else(|| self.now()),
+                sequence: self.next_transaction_id,
+                policy: policy.clone(),
+            });
+        }
+        Ok(consolidations)
+    }
+
+    pub(crate) fn merge_lots(
+        &mut self,
+        symbol: &str,
+        policy: &ConsolidationPolicy,
+    ) -> PortfolioResult<Vec<LotConsolidation>> {
+        let Some(lots) = self.lots.get_mut(symbol) else {
+            return Ok(Vec::new());
+        };
+
+        let mut groups: Vec<Vec<Lot>> = Vec::new();
+        for lot in lots.drain(..) {
+            match groups.last_mut() {
+                Some(group) if policy.can_merge(&group[0], &lot) => group.push(lot),
+                _ => groups.push(vec![lot]),
+            }
+        }
+
+        let mut consolidations = Vec::new();
+        for group in groups {
+            if group.len() == 1 {
+                lots.extend(group);
+                continue;
+            }
+            let merged = group.iter().map(|lot| lot.id).collect();
+            let lot = merge(group, self.next_lot_id)?;
+            self.next_lot_id += 1;
+            consolidations.push(LotConsolidation {
+                merged,
+                into: lot.id,
+            });
+            lots.push(lot);
+        }
+        self.lot_consolidations
+            .entry(symbol.to_string())
+            .or_default()
+            .extend(consolidations.iter().cloned());
+        Ok(consolidations)
+    }
+
+    pub fn get_lot_consolidations(&self, symbol: &str) -> &[LotConsolidation] {
+        self.lot_consolidations
+            .get(symbol)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn lot_lineage(&self, lot_id: LotId) -> Vec<LotId> {
+        let mut lineage = vec![lot_id];
+        while let Some(parent) = self.lot_parents.get(lineage.last().unwrap()) {
+            lineage.push(*parent);
+        }
+        lineage
+    }
+
+    pub fn cost_basis(&self, symbol: &str) -> PortfolioResult<Money> {
+        Money::checked_sum(
+            self.config.base_currency,
+            self.lots
+                .get(symbol)
+                .into_iter()
+                .flatten()
+                .map(|lot| &lot.cost_basis),
+        )
+    }
+
+    pub fn average_price(&self, symbol: &str) -> PortfolioResult<Option<Money>> {
+        let shares: u32 = self
+            .lots
+            .get(symbol)
+            .into_iter()
+            .flatten()
+            .map(|lot| lot.shares)
+            .sum();
+        if shares == 0 {
+            return Ok(None);
+        }
+        let cost_basis = self.cost_basis(symbol)?;
+        Ok(Some(Money::new(
+            cost_basis.amount / Decimal::from(shares),
+            cost_basis.currency,
+        )))
+    }
+
+    pub fn iter_lots(
+        &self,
+        symbol: &str,
+    ) -> impl Iterator<Item = (LotId, NaiveDate, u32, Decimal)> + '_ {
+        self.lots.get(symbol).into_iter().flatten().map(|lot| {
+            (
+                lot.id,
+                lot.acquired.date_naive(),
+                lot.shares,
+                lot.basis_per_share(),
+            )
+        })
+    }
+}
+
+fn tax_rank(lot: &Lot, price: &Money, sold: DateTime<Utc>) -> (u8, Decimal) {
+    let gain = price.amount - lot.basis_per_share();
+    let long_term = HoldingTerm::for_lot(lot, sold) == HoldingTerm::LongTerm;
+    let rank = match (gain < Decimal::ZERO, long_term) {
+        (true, true) => 0,
+        (true, false) => 1,
+        (false, true) => 2,
+        (false, false) => 3,
+    };
+    (rank, gain)
+}
+
+pub(crate) fn consume_lots(
+    lots: &mut Vec<Lot>,
+    shares: u32,
+    method: CostBasisMethod,
+    price: Option<&Money>,
+    sold: DateTime<Utc>,
+    next_lot_id: &mut LotId,
+) -> PortfolioResult<Vec<LotConsumption>> {
+    if method == CostBasisMethod::AverageCost {
+        pool_basis(lots)?;
+    }
+    let mut remaining = shares;
+    let mut consumed = Vec::new();
+    while remaining > 0 {
+        let order = |(_, lot): &(usize, &Lot)| (lot.acquired, lot.sequence);
+        let candidates = lots.iter().enumerate();
+        let index = match (method, price) {
+            (CostBasisMethod::Lifo, _) => candidates.max_by_key(order),
+            (CostBasisMethod::Fifo | CostBasisMethod::AverageCost, _) => {
+                candidates.min_by_key(order)
+            }
+            (CostBasisMethod::HighestCost, _) | (CostBasisMethod::MinimizeTax, None) => candidates
+                .min_by_key(|candidate| (Reverse(candidate.1.basis_per_share()), order(candidate))),
+            (CostBasisMethod::MinimizeTax, Some(price)) => candidates
+                .min_by_key(|candidate| (tax_rank(candidate.1, price, sold), order(candidate))),
+        }
+        .map(|(index, _)| index);
+        let Some(index) = index else {
+            break;
+        };
+        let consumption = take_from_lot(lots, index, remaining, next_lot_id)?;
+        remaining -= consumption.shares;
+        consumed.push(consumption);
+    }
+    Ok(consumed)
+}
+
+pub(crate) fn consume_selected(
+    lots: &mut Vec<Lot>,
+    selections: &[SelectedLot],
+    next_lot_id: &mut LotId,
+) -> PortfolioResult<Vec<LotConsumption>> {
+    let mut consumed = Vec::with_capacity(selections.len());
+    for selection in selections {
+        let index = lots
+            .iter()
+            .position(|lot| lot.sequence == selection.sequence && lot.shares >= selection.shares)
+            .ok_or(PortfolioError::InvalidLotSelection(selection.sequence))?;
+        consumed.push(take_from_lot(lots, index, selection.shares, next_lot_id)?);
+    }
+    Ok(consumed)
+}
+
+fn take_from_lot(
+    lots: &mut Vec<Lot>,
+    index: usize,
+    shares: u32,
+    next_lot_id: &mut LotId,
+) -> PortfolioResult<LotConsumption> {
+    let lot = &mut lots[index];
+    let acquired = lot.acquired;
+    let sequence = lot.sequence;
+    let acquisition = lot.acquisition;
+    let covered = lot.covered;
+    let taken = shares.min(lot.shares);
+    let basis = lot.basis_for(taken)?;
+    let lot_id = lot.id;
+    lot.shares -= taken;
+    lot.cost_basis = lot.cost_basis.checked_sub(&basis)?;
+    let remainder_lot_id = if lot.shares == 0 {
+        lots.remove(index);
+        None
+    } else {
+        lot.id = *next_lot_id;
+        *next_lot_id += 1;
+        Some(lot.id)
+    };
+    Ok(LotConsumption {
+        lot_id,
+        acquired,
+        sequence,
+        shares: taken,
+        cost_basis: basis,
+        acquisition,
+        covered,
+        remainder_lot_id,
+    })
+}
+
+pub(crate) fn spread_shares(lots: &mut [Lot], additional: u32) {
+    let total: u64 = lots.iter().map(|lot| u64::from(lot.shares)).sum();
+    if total == 0 {
+        return;
+    }
+    let mut allocated = 0u32;
+    let mut remainders = Vec::with_capacity(lots.len());
+    for (index, lot) in lots.iter_mut().enumerate() {
+        let exact = u64::from(lot.shares) * u64::from(additional);
+        let whole = u32::try_from(exact / total).unwrap_or(additional);
+        remainders.push((exact % total, index));
+        lot.shares += whole;
+        allocated += whole;
+    }
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, index) in remainders
+        .into_iter()
+        .take((additional - allocated) as usize)
+    {
+        lots[index].shares += 1;
+    }
+}
+
+fn pool_basis(lots: &mut [Lot]) -> PortfolioResult<()> {
+    let Some(currency) = lots.first().map(|lot| lot.cost_basis.currency) else {
+        return Ok(());
+    };
+    let total_basis = Money::checked_sum(currency, lots.iter().map(|lot| &lot.cost_basis))?;
+    let total_shares: u64 = lots.iter().map(|lot| u64::from(lot.shares)).sum();
+    let mut unallocated = total_basis;
+    let last = lots.len() - 1;
+    for (index, lot) in lots.iter_mut().enumerate() {
+        lot.cost_basis = if index == last {
+            unallocated
+        } else {
+            prorate(&total_basis, u64::from(lot.shares), total_shares)?
+        };
+        unallocated = unallocated.checked_sub(&lot.cost_basis)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn prorate(money: &Money, numerator: u64, denominator: u64) -> PortfolioResult<Money> {
+    money
+        .amount
+        .checked_mul(Decimal::from(numerator))
+        .and_then(|amount| amount.checked_div(Decimal::from(denominator)))
+        .map(|amount| Money::new(amount, money.currency))
+        .ok_or(PortfolioError::Overflow)
+}
@@ -0,0 +1,44 @@
+use crate::money::Currency;
+use crate::Portfolio;
+use std::collections::BTreeMap;
+
+#[derive(Clone)]
+pub struct PortfolioManager {
+    base_currency: Currency,
+    accounts: BTreeMap<String, Portfolio>,
+}
+
+impl PortfolioManager {
+    pub fn new(base_currency: Currency) -> Self {
+        Self {
+            base_currency,
+            accounts: BTreeMap::new(),
+        }
+    }
+
+    pub fn base_currency(&self) -> Currency {
+        self.base_currency
+    }
+
+    pub fn add_account(&mut self, name: &str, portfolio: Portfolio) -> Option<Portfolio> {
+        self.accounts.insert(name.to_string(), portfolio)
+    }
+
+    pub fn remove_account(&mut self, name: &str) -> Option<Portfolio> {
+        self.accounts.remove(name)
+    }
+
+    pub fn account(&self, name: &str) -> Option<&Portfolio> {
+        self.accounts.get(name)
+    }
+
+    pub fn account_mut(&mut self, name: &str) -> Option<&mut Portfolio> {
+        self.accounts.get_mut(name)
+    }
+
+    pub fn accounts(&self) -> impl Iterator<Item = (&str, &Portfolio)> {
+        self.accounts
+            .iter()
+            .map(|(name, portfolio)| (name.as_str(), portfolio))
+    }
+}
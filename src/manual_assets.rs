@@ -0,0 +1,101 @@
+use crate::auth::Role;
+use crate::money::Money;
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+use chrono::NaiveDate;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub type ManualAssetId = u64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Valuation {
+    pub date: NaiveDate,
+    pub value: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ManualAsset {
+    pub id: ManualAssetId,
+    pub name: String,
+    pub valuations: Vec<Valuation>,
+}
+
+impl ManualAsset {
+    pub fn value_on(&self, date: NaiveDate) -> Option<Money> {
+        self.valuations
+            .iter()
+            .take_while(|valuation| valuation.date <= date)
+            .last()
+            .map(|valuation| valuation.value)
+    }
+}
+
+impl Portfolio {
+    pub fn add_manual_asset(
+        &mut self,
+        name: &str,
+        value: Money,
+        date: NaiveDate,
+    ) -> PortfolioResult<ManualAssetId> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&value)?;
+        let id = self.manual_assets.last().map_or(0, |asset| asset.id + 1);
+        self.manual_assets.push(ManualAsset {
+            id,
+            name: name.to_string(),
+            valuations: vec![Valuation { date, value }],
+        });
+        self.bump_version();
+        Ok(id)
+    }
+
+    pub fn update_valuation(
+        &mut self,
+        id: ManualAssetId,
+        value: Money,
+        date: NaiveDate,
+    ) -> PortfolioResult<()> {
+        self.authorize(Role::Trader)?;
+        self.validate_amount(&value)?;
+        let asset = self
+            .manual_assets
+            .iter_mut()
+            .find(|asset| asset.id == id)
+            .ok_or(PortfolioError::UnknownManualAsset(id))?;
+        match asset
+            .valuations
+            .binary_search_by_key(&date, |valuation| valuation.date)
+        {
+            Ok(index) => asset.valuations[index].value = value,
+            Err(index) => asset.valuations.insert(index, Valuation { date, value }),
+        }
+        self.bump_version();
+        Ok(())
+    }
+
+    pub fn remove_manual_asset(&mut self, id: ManualAssetId) -> PortfolioResult<ManualAsset> {
+        self.authorize(Role::Trader)?;
+        let index = self
+            .manual_assets
+            .iter()
+            .position(|asset| asset.id == id)
+            .ok_or(PortfolioError::UnknownManualAsset(id))?;
+        self.bump_version();
+        Ok(self.manual_assets.remove(index))
+    }
+
+    pub fn manual_assets(&self) -> &[ManualAsset] {
+        &self.manual_assets
+    }
+
+    pub fn manual_asset_value(&self, date: NaiveDate) -> PortfolioResult<Money> {
+        let values: Vec<Money> = self
+            .manual_assets
+            .iter()
+            .filter_map(|asset| asset.value_on(date))
+            .collect();
+        Money::checked_sum(self.config.base_currency, &values)
+    }
+}
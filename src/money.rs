@@ -0,0 +1,205 @@
+use crate::config::{RoundingMode, RoundingPolicy};
+use crate::i18n::Locale;
+use crate::{PortfolioError, PortfolioResult};
+use rust_decimal::{Decimal, RoundingStrategy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+pub enum Currency {
+    #[default]
+    Usd,
+    Eur,
+    Gbp,
+    Jpy,
+    Cad,
+    Chf,
+}
+
+impl Currency {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "USD" => Some(Currency::Usd),
+            "EUR" => Some(Currency::Eur),
+            "GBP" => Some(Currency::Gbp),
+            "JPY" => Some(Currency::Jpy),
+            "CAD" => Some(Currency::Cad),
+            "CHF" => Some(Currency::Chf),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+            Currency::Jpy => "JPY",
+            Currency::Cad => "CAD",
+            Currency::Chf => "CHF",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Usd => "$",
+            Currency::Eur => "€",
+            Currency::Gbp => "£",
+            Currency::Jpy => "¥",
+            Currency::Cad => "CA$",
+            Currency::Chf => "CHF",
+        }
+    }
+
+    pub fn minor_units(&self) -> u32 {
+        match self {
+            Currency::Jpy => 0,
+            _ => 2,
+        }
+    }
+
+    fn native_locale(&self) -> Locale {
+        match self {
+            Currency::Eur => Locale::De,
+            _ => Locale::En,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Money {
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount.is_sign_negative() && !self.amount.is_zero()
+    }
+
+    pub fn negated(&self) -> Money {
+        Money::new(-self.amount, self.currency)
+    }
+
+    pub(crate) fn ensure_same_currency(&self, other: &Money) -> PortfolioResult<()> {
+        if self.currency != other.currency {
+            return Err(PortfolioError::CurrencyMismatch {
+                expected: self.currency,
+                found: other.currency,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn checked_add(&self, other: &Money) -> PortfolioResult<Money> {
+        self.ensure_same_currency(other)?;
+        self.amount
+            .checked_add(other.amount)
+            .map(|amount| Money::new(amount, self.currency))
+            .ok_or(PortfolioError::Overflow)
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> PortfolioResult<Money> {
+        self.ensure_same_currency(other)?;
+        self.amount
+            .checked_sub(other.amount)
+            .map(|amount| Money::new(amount, self.currency))
+            .ok_or(PortfolioError::Overflow)
+    }
+
+    pub fn checked_mul(&self, factor: Decimal) -> PortfolioResult<Money> {
+        self.amount
+            .checked_mul(factor)
+            .map(|amount| Money::new(amount, self.currency))
+            .ok_or(PortfolioError::Overflow)
+    }
+
+    pub fn checked_sum<'a>(
+        currency: Currency,
+        amounts: impl IntoIterator<Item = &'a Money>,
+    ) -> PortfolioResult<Money> {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(currency), |total, money| {
+                total.checked_add(money)
+            })
+    }
+
+    pub fn rounded(&self, policy: &RoundingPolicy) -> Money {
+        let strategy = match policy.mode {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::Truncate => RoundingStrategy::ToZero,
+        };
+        Money::new(
+            self.amount
+                .round_dp_with_strategy(policy.decimal_places, strategy),
+            self.currency,
+        )
+    }
+
+    pub fn format(&self, locale: Locale) -> String {
+        let (group_separator, decimal_separator, symbol_first) = match locale {
+            Locale::En => (',', '.', true),
+            Locale::Es | Locale::De => ('.', ',', false),
+        };
+        let places = self.currency.minor_units();
+        let rounded = self
+            .amount
+            .abs()
+            .round_dp_with_strategy(places, RoundingStrategy::MidpointNearestEven);
+        let digits = format!("{:.*}", places as usize, rounded);
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+
+        let mut grouped = String::new();
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.push(group_separator);
+            }
+            grouped.push(digit);
+        }
+        if !fraction.is_empty() {
+            grouped.push(decimal_separator);
+            grouped.push_str(fraction);
+        }
+
+        let sign = if self.is_negative() && !rounded.is_zero() {
+            "-"
+        } else {
+            ""
+        };
+        if symbol_first {
+            format!("{sign}{}{grouped}", self.currency.symbol())
+        } else {
+            format!("{sign}{grouped} {}", self.currency.symbol())
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(self.currency.native_locale()))
+    }
+}
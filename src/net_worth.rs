@@ -0,0 +1,69 @@
+use crate::manager::PortfolioManager;
+use crate::money::{Currency, Money};
+use crate::Quotes;
+use crate::{Portfolio, PortfolioResult};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NetWorth {
+    pub investments: Money,
+    pub manual_assets: Money,
+    pub liabilities: Money,
+    pub total: Money,
+}
+
+impl NetWorth {
+    fn zero(currency: Currency) -> Self {
+        Self {
+            investments: Money::zero(currency),
+            manual_assets: Money::zero(currency),
+            liabilities: Money::zero(currency),
+            total: Money::zero(currency),
+        }
+    }
+
+    fn checked_add(&self, other: &NetWorth) -> PortfolioResult<NetWorth> {
+        Ok(NetWorth {
+            investments: self.investments.checked_add(&other.investments)?,
+            manual_assets: self.manual_assets.checked_add(&other.manual_assets)?,
+            liabilities: self.liabilities.checked_add(&other.liabilities)?,
+            total: self.total.checked_add(&other.total)?,
+        })
+    }
+}
+
+impl Portfolio {
+    pub(crate) fn net_worth(&self, quotes: &Quotes, date: NaiveDate) -> PortfolioResult<NetWorth> {
+        let investments = self.consolidated_value(quotes)?;
+        let manual_assets = self.manual_asset_value(date)?;
+        let liabilities = self.total_liabilities(date)?;
+        Ok(NetWorth {
+            investments,
+            manual_assets,
+            liabilities,
+            total: investments
+                .checked_add(&manual_assets)?
+                .checked_sub(&liabilities)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetWorthReport {
+    pub accounts: BTreeMap<String, NetWorth>,
+    pub total: NetWorth,
+}
+
+impl PortfolioManager {
+    pub fn net_worth(&self, quotes: &Quotes, date: NaiveDate) -> PortfolioResult<NetWorthReport> {
+        let mut accounts = BTreeMap::new();
+        let mut total = NetWorth::zero(self.base_currency());
+        for (name, portfolio) in self.accounts() {
+            let net_worth = portfolio.net_worth(quotes, date)?;
+            total = total.checked_add(&net_worth)?;
+            accounts.insert(name.to_string(), net_worth);
+        }
+        Ok(NetWorthReport { accounts, total })
+    }
+}
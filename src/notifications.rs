@@ -0,0 +1,87 @@
+use crate::alerts::Alert;
+#[cfg(feature = "pricing")]
+use crate::alerts::AlertId;
+use crate::auth::Role;
+use crate::automation::{Action, ActionStatus, AutomationOutcome};
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+use crate::Quotes;
+use crate::{Portfolio, PortfolioResult};
+#[cfg(feature = "pricing")]
+use chrono::NaiveDate;
+
+#[cfg(feature = "desktop")]
+pub mod desktop;
+#[cfg(feature = "smtp")]
+pub mod smtp;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    pub fn from_alert(alert: &Alert) -> Self {
+        Self {
+            title: format!("Alert triggered: {}", alert.name),
+            body: match alert.condition.symbol() {
+                Some(symbol) => format!("{} triggered for {symbol}", alert.name),
+                None => format!("{} triggered", alert.name),
+            },
+        }
+    }
+
+    pub fn from_outcome(outcome: &AutomationOutcome) -> Self {
+        let action = match &outcome.action {
+            Action::Buy { symbol, shares } => format!("buy {shares} {symbol}"),
+            Action::Sell { symbol, shares } => format!("sell {shares} {symbol}"),
+            Action::Alert { symbol, message } => format!("{symbol}: {message}"),
+        };
+        let body = match &outcome.status {
+            ActionStatus::Proposed => format!("Proposed: {action}"),
+            ActionStatus::Executed(_) => format!("Executed: {action}"),
+            ActionStatus::Failed(reason) => format!("Failed to {action}: {reason}"),
+        };
+        Self {
+            title: format!("Automation rule: {}", outcome.rule),
+            body,
+        }
+    }
+}
+
+pub trait NotificationSink {
+    fn notify(&self, notification: &Notification) -> PortfolioResult<()>;
+}
+
+impl Portfolio {
+    #[cfg(feature = "pricing")]
+    pub fn check_alerts_and_notify(
+        &mut self,
+        prices: &PriceHistory,
+        date: NaiveDate,
+        sink: &impl NotificationSink,
+    ) -> PortfolioResult<Vec<AlertId>> {
+        self.authorize(Role::Trader)?;
+        let triggered = self.check_alerts(prices, date)?;
+        for alert in self.alerts() {
+            if triggered.contains(&alert.id) {
+                sink.notify(&Notification::from_alert(alert))?;
+            }
+        }
+        Ok(triggered)
+    }
+
+    pub fn run_automation_and_notify(
+        &mut self,
+        quotes: &Quotes,
+        sink: &impl NotificationSink,
+    ) -> PortfolioResult<Vec<AutomationOutcome>> {
+        self.authorize(Role::Trader)?;
+        let outcomes = self.run_automation(quotes)?;
+        for outcome in &outcomes {
+            sink.notify(&Notification::from_outcome(outcome))?;
+        }
+        Ok(outcomes)
+    }
+}
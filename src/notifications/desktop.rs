@@ -0,0 +1,35 @@
+use crate::notifications::{Notification, NotificationSink};
+use crate::{PortfolioError, PortfolioResult};
+
+#[derive(Clone, Debug, Default)]
+pub struct DesktopSink {
+    app_name: Option<String>,
+}
+
+impl DesktopSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_app_name(app_name: &str) -> Self {
+        Self {
+            app_name: Some(app_name.to_string()),
+        }
+    }
+}
+
+impl NotificationSink for DesktopSink {
+    fn notify(&self, notification: &Notification) -> PortfolioResult<()> {
+        let mut desktop = notify_rust::Notification::new();
+        desktop
+            .summary(&notification.title)
+            .body(&notification.body);
+        if let Some(app_name) = &self.app_name {
+            desktop.appname(app_name);
+        }
+        desktop
+            .show()
+            .map(|_| ())
+            .map_err(|e| PortfolioError::NotificationFailed(e.to_string()))
+    }
+}
@@ -0,0 +1,55 @@
+use crate::notifications::{Notification, NotificationSink};
+use crate::{PortfolioError, PortfolioResult};
+use mail_builder::MessageBuilder;
+use mail_send::SmtpClientBuilder;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub implicit_tls: bool,
+    pub username: String,
+    pub password: String,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+pub struct SmtpSink {
+    settings: SmtpSettings,
+}
+
+fn notification_error(error: impl ToString) -> PortfolioError {
+    PortfolioError::NotificationFailed(error.to_string())
+}
+
+impl SmtpSink {
+    pub fn new(settings: SmtpSettings) -> Self {
+        Self { settings }
+    }
+}
+
+impl NotificationSink for SmtpSink {
+    fn notify(&self, notification: &Notification) -> PortfolioResult<()> {
+        let settings = &self.settings;
+        let message = MessageBuilder::new()
+            .from(settings.from.as_str())
+            .to(settings.to.iter().map(String::as_str).collect::<Vec<_>>())
+            .subject(notification.title.as_str())
+            .text_body(notification.body.as_str());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(notification_error)?;
+        runtime.block_on(async {
+            SmtpClientBuilder::new(settings.host.as_str(), settings.port)
+                .implicit_tls(settings.implicit_tls)
+                .credentials((settings.username.as_str(), settings.password.as_str()))
+                .connect()
+                .await
+                .map_err(notification_error)?
+                .send(message)
+                .await
+                .map_err(notification_error)
+        })
+    }
+}
@@ -0,0 +1,88 @@
+use crate::config::RoundingPolicy;
+use crate::money::{Currency, Money};
+use crate::{PortfolioError, PortfolioResult};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const CENTS_SCALE: u32 = 2;
+const MICRO_UNITS_SCALE: u32 = 6;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NumericBackend {
+    #[default]
+    Decimal,
+    Cents,
+    MicroUnits,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Total {
+    Decimal(Decimal),
+    Cents(i64),
+    MicroUnits(i128),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoneyAccumulator {
+    currency: Currency,
+    rounding: RoundingPolicy,
+    total: Total,
+}
+
+impl MoneyAccumulator {
+    pub fn new(backend: NumericBackend, currency: Currency, rounding: RoundingPolicy) -> Self {
+        let total = match backend {
+            NumericBackend::Decimal => Total::Decimal(Decimal::ZERO),
+            NumericBackend::Cents => Total::Cents(0),
+            NumericBackend::MicroUnits => Total::MicroUnits(0),
+        };
+        Self {
+            currency,
+            rounding,
+            total,
+        }
+    }
+
+    pub fn add(&mut self, money: &Money) -> PortfolioResult<()> {
+        Money::zero(self.currency).ensure_same_currency(money)?;
+        self.total = match self.total {
+            Total::Decimal(total) => total.checked_add(money.amount).map(Total::Decimal),
+            Total::Cents(total) => i64::try_from(self.to_units(money, CENTS_SCALE)?)
+                .ok()
+                .and_then(|units| total.checked_add(units))
+                .map(Total::Cents),
+            Total::MicroUnits(total) => total
+                .checked_add(self.to_units(money, MICRO_UNITS_SCALE)?)
+                .map(Total::MicroUnits),
+        }
+        .ok_or(PortfolioError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn total(&self) -> PortfolioResult<Money> {
+        let amount = match self.total {
+            Total::Decimal(total) => total,
+            Total::Cents(total) => Decimal::new(total, CENTS_SCALE),
+            Total::MicroUnits(total) => Decimal::try_from_i128_with_scale(total, MICRO_UNITS_SCALE)
+                .map_err(|_| PortfolioError::Overflow)?,
+        };
+        Ok(Money::new(amount, self.currency))
+    }
+
+    fn to_units(&self, money: &Money, scale: u32) -> PortfolioResult<i128> {
+        let policy = RoundingPolicy {
+            decimal_places: scale,
+            ..self.rounding.clone()
+        };
+        money
+            .rounded(&policy)
+            .amount
+            .checked_mul(Decimal::from(10i64.pow(scale)))
+            .and_then(|units| units.to_i128())
+            .ok_or(PortfolioError::Overflow)
+    }
+}
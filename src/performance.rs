@@ -0,0 +1,557 @@
+use crate::dividends::Dividend;
+use crate::instruments::InstrumentKind;
+use crate::money::Money;
+use crate::period::{DayCountConvention, Period};
+use crate::prices::PriceHistory;
+use crate::{Portfolio, PortfolioError, PortfolioResult, TransactionType};
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::{Decimal, MathematicalOps};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrowthAttribution {
+    pub period: Period,
+    pub start_value: Money,
+    pub end_value: Money,
+    pub net_contributions: Money,
+    pub market_growth: Money,
+    pub income: Money,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributionGrouping {
+    Sector,
+    AssetClass,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AttributionGroup {
+    Sector(String),
+    AssetClass(InstrumentKind),
+    Unclassified,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupAttribution {
+    pub group: AttributionGroup,
+    pub start_value: Money,
+    pub end_value: Money,
+    pub net_contributions: Money,
+    pub gain: Money,
+    pub contribution_percent: Decimal,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DividendGrowth {
+    pub year: i32,
+    pub per_share: Money,
+    pub growth_percent: Option<Decimal>,
+}
+
+pub type ValueSeries = BTreeMap<NaiveDate, Money>;
+
+pub type CpiSeries = BTreeMap<NaiveDate, Decimal>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReturnWindow {
+    Months(u32),
+    Years(u32),
+}
+
+impl ReturnWindow {
+    pub const STANDARD: [ReturnWindow; 4] = [
+        ReturnWindow::Months(1),
+        ReturnWindow::Months(3),
+        ReturnWindow::Years(1),
+        ReturnWindow::Years(3),
+    ];
+
+    pub fn months(&self) -> u32 {
+        match self {
+            ReturnWindow::Months(months) => *months,
+            ReturnWindow::Years(years) => years * 12,
+        }
+    }
+
+    pub fn start_date(&self, end: NaiveDate) -> Option<NaiveDate> {
+        end.checked_sub_months(Months::new(self.months()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrailingReturn {
+    pub window: ReturnWindow,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub total_percent: Decimal,
+    pub annualized_percent: Decimal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RealReturn {
+    pub nominal_percent: Decimal,
+    pub inflation_percent: Decimal,
+    pub real_percent: Decimal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CaptureRatios {
+    pub upside_percent: Option<Decimal>,
+    pub downside_percent: Option<Decimal>,
+    pub up_periods: usize,
+    pub down_periods: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RelativeDrawdown {
+    pub peak_date: NaiveDate,
+    pub trough_date: NaiveDate,
+    pub percent: Decimal,
+}
+
+fn symbol_value_as_of(
+    portfolio: &Portfolio,
+    prices: &PriceHistory,
+    symbol: &str,
+    date: NaiveDate,
+) -> PortfolioResult<Money> {
+    let shares = portfolio.get_share_count_as_of(symbol, date);
+    if shares == 0 {
+        return Ok(Money::zero(portfolio.config().base_currency));
+    }
+    prices.price_on(symbol, date)?.checked_mul(shares.into())
+}
+
+fn symbol_net_contributions(
+    portfolio: &Portfolio,
+    prices: &PriceHistory,
+    symbol: &str,
+    period: &Period,
+) -> PortfolioResult<Money> {
+    let mut net_contributions = Money::zero(portfolio.config().base_currency);
+    for record in portfolio.get_purchase_record(symbol)? {
+        let date = record.date.date_naive();
+        if !period.contains(date) {
+            continue;
+        }
+        let price = match record.price {
+            Some(price) => price,
+            None => prices.price_on(symbol, date)?,
+        };
+        let amount = price.checked_mul(record.shares.into())?;
+        net_contributions = match record.transaction_type {
+            TransactionType::Purchase => net_contributions.checked_add(&amount)?,
+            TransactionType::Sell => net_contributions.checked_sub(&amount)?,
+        };
+    }
+    Ok(net_contributions)
+}
+
+fn symbol_income(portfolio: &Portfolio, symbol: &str, period: &Period) -> PortfolioResult<Money> {
+    let dividends = portfolio
+        .get_dividends(symbol)
+        .iter()
+        .filter(|dividend| period.contains(dividend.date.date_naive()))
+        .map(Dividend::amount)
+        .collect::<PortfolioResult<Vec<_>>>()?;
+    let lending_income = portfolio
+        .lending_income_history()
+        .filter(|(lent, date, _)| *lent == symbol && period.contains(date.date_naive()))
+        .map(|(_, _, income)| income);
+    portfolio.money_total(
+        portfolio
+            .get_capital_gain_distributions(symbol)
+            .iter()
+            .filter(|distribution| period.contains(distribution.date.date_naive()))
+            .flat_map(|distribution| [&distribution.short_term, &distribution.long_term])
+            .chain(&dividends)
+            .chain(lending_income),
+    )
+}
+
+fn opening_date(period: &Period) -> NaiveDate {
+    period.start.pred_opt().unwrap_or(period.start)
+}
+
+pub fn value_as_of(
+    portfolio: &Portfolio,
+    prices: &PriceHistory,
+    date: NaiveDate,
+) -> PortfolioResult<Money> {
+    let mut total = Money::zero(portfolio.config().base_currency);
+    for symbol in portfolio.traded_symbols() {
+        total = total.checked_add(&symbol_value_as_of(portfolio, prices, symbol, date)?)?;
+    }
+    Ok(total)
+}
+
+pub fn growth_attribution(
+    portfolio: &Portfolio,
+    prices: &PriceHistory,
+    period: &Period,
+) -> PortfolioResult<GrowthAttribution> {
+    let currency = portfolio.config().base_currency;
+    let start_value = value_as_of(portfolio, prices, opening_date(period))?;
+    let end_value = value_as_of(portfolio, prices, period.end)?;
+
+    let mut net_contributions = Money::zero(currency);
+    let mut income = Money::zero(currency);
+    for symbol in portfolio.traded_symbols() {
+        net_contributions = net_contributions.checked_add(&symbol_net_contributions(
+            portfolio, prices, symbol, period,
+        )?)?;
+        income = income.checked_add(&symbol_income(portfolio, symbol, period)?)?;
+    }
+
+    let market_growth = end_value
+        .checked_sub(&start_value)?
+        .checked_sub(&net_contributions)?;
+    Ok(GrowthAttribution {
+        period: *period,
+        start_value,
+        end_value,
+        net_contributions,
+        market_growth,
+        income,
+    })
+}
+
+fn group_of(
+    portfolio: &Portfolio,
+    symbol: &str,
+    grouping: AttributionGrouping,
+) -> AttributionGroup {
+    let instruments = portfolio.instruments();
+    let group = match grouping {
+        AttributionGrouping::Sector => instruments
+            .sector(symbol)
+            .map(|sector| AttributionGroup::Sector(sector.to_string())),
+        AttributionGrouping::AssetClass => {
+            instruments.kind(symbol).map(AttributionGroup::AssetClass)
+        }
+    };
+    group.unwrap_or(AttributionGroup::Unclassified)
+}
+
+pub fn attribution(
+    portfolio: &Portfolio,
+    prices: &PriceHistory,
+    period: &Period,
+    grouping: AttributionGrouping,
+) -> PortfolioResult<Vec<GroupAttribution>> {
+    let currency = portfolio.config().base_currency;
+    let opening_date = opening_date(period);
+    let mut groups: BTreeMap<AttributionGroup, GroupAttribution> = BTreeMap::new();
+    for symbol in portfolio.traded_symbols() {
+        let start_value = symbol_value_as_of(portfolio, prices, symbol, opening_date)?;
+        let end_value = symbol_value_as_of(portfolio, prices, symbol, period.end)?;
+        let net_contributions = symbol_net_contributions(portfolio, prices, symbol, period)?;
+        let gain = end_value
+            .checked_sub(&start_value)?
+            .checked_sub(&net_contributions)?
+            .checked_add(&symbol_income(portfolio, symbol, period)?)?;
+
+        let group = group_of(portfolio, symbol, grouping);
+        let entry = groups
+            .entry(group.clone())
+            .or_insert_with(|| GroupAttribution {
+                group,
+                start_value: Money::zero(currency),
+                end_value: Money::zero(currency),
+                net_contributions: Money::zero(currency),
+                gain: Money::zero(currency),
+                contribution_percent: Decimal::ZERO,
+            });
+        entry.start_value = entry.start_value.checked_add(&start_value)?;
+        entry.end_value = entry.end_value.checked_add(&end_value)?;
+        entry.net_contributions = entry.net_contributions.checked_add(&net_contributions)?;
+        entry.gain = entry.gain.checked_add(&gain)?;
+    }
+
+    let total_start_value = Money::checked_sum(
+        currency,
+        groups.values().map(|attribution| &attribution.start_value),
+    )?;
+    let mut attributions: Vec<GroupAttribution> = groups.into_values().collect();
+    if !total_start_value.is_zero() {
+        for attribution in &mut attributions {
+            attribution.contribution_percent =
+                attribution.gain.amount * Decimal::ONE_HUNDRED / total_start_value.amount;
+        }
+    }
+    Ok(attributions)
+}
+
+pub fn value_series(
+    portfolio: &Portfolio,
+    prices: &PriceHistory,
+    period: &Period,
+) -> PortfolioResult<ValueSeries> {
+    period
+        .iter_days()
+        .map(|date| Ok((date, value_as_of(portfolio, prices, date)?)))
+        .collect()
+}
+
+fn growth_factor(start_value: &Money, end_value: &Money) -> PortfolioResult<Decimal> {
+    start_value.ensure_same_currency(end_value)?;
+    if start_value.is_zero() {
+        return Err(PortfolioError::InsufficientHistory);
+    }
+    end_value
+        .amount
+        .checked_div(start_value.amount)
+        .ok_or(PortfolioError::Overflow)
+}
+
+fn annualize(growth_factor: Decimal, years: Decimal) -> PortfolioResult<Decimal> {
+    if years <= Decimal::ZERO {
+        return Err(PortfolioError::InsufficientHistory);
+    }
+    let annual_factor = growth_factor
+        .checked_powd(Decimal::ONE / years)
+        .ok_or(PortfolioError::Overflow)?;
+    Ok((annual_factor - Decimal::ONE) * Decimal::ONE_HUNDRED)
+}
+
+pub fn annualized_return(series: &ValueSeries) -> PortfolioResult<Decimal> {
+    let ((start_date, start_value), (end_date, end_value)) = series
+        .first_key_value()
+        .zip(series.last_key_value())
+        .ok_or(PortfolioError::InsufficientHistory)?;
+    cagr(start_value, end_value, *start_date, *end_date)
+}
+
+pub fn cagr(
+    start_value: &Money,
+    end_value: &Money,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> PortfolioResult<Decimal> {
+    cagr_with_day_count(
+        start_value,
+        end_value,
+        start_date,
+        end_date,
+        DayCountConvention::default(),
+    )
+}
+
+pub fn cagr_with_day_count(
+    start_value: &Money,
+    end_value: &Money,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    day_count: DayCountConvention,
+) -> PortfolioResult<Decimal> {
+    annualize(
+        growth_factor(start_value, end_value)?,
+        day_count.year_fraction(start_date, end_date),
+    )
+}
+
+pub fn portfolio_cagr(
+    portfolio: &Portfolio,
+    prices: &PriceHistory,
+    end_date: NaiveDate,
+) -> PortfolioResult<Decimal> {
+    let inception = portfolio
+        .first_trade_date()
+        .ok_or(PortfolioError::InsufficientHistory)?;
+    cagr_with_day_count(
+        &value_as_of(portfolio, prices, inception)?,
+        &value_as_of(portfolio, prices, end_date)?,
+        inception,
+        end_date,
+        portfolio.config().day_count,
+    )
+}
+
+pub fn rolling_returns(
+    series: &ValueSeries,
+    windows: &[ReturnWindow],
+) -> PortfolioResult<Vec<TrailingReturn>> {
+    let Some((&end_date, end_value)) = series.last_key_value() else {
+        return Ok(Vec::new());
+    };
+    let mut returns = Vec::new();
+    for window in windows {
+        let Some((&start_date, start_value)) = window
+            .start_date(end_date)
+            .and_then(|target| series.range(..=target).next_back())
+        else {
+            continue;
+        };
+        let factor = growth_factor(start_value, end_value)?;
+        let total_percent = (factor - Decimal::ONE) * Decimal::ONE_HUNDRED;
+        let annualized_percent = if window.months() > 12 {
+            let years = DayCountConvention::default().year_fraction(start_date, end_date);
+            annualize(factor, years)?
+        } else {
+            total_percent
+        };
+        returns.push(TrailingReturn {
+            window: *window,
+            start_date,
+            end_date,
+            total_percent,
+            annualized_percent,
+        });
+    }
+    Ok(returns)
+}
+
+fn cpi_as_of(cpi_series: &CpiSeries, date: NaiveDate) -> PortfolioResult<Decimal> {
+    cpi_series
+        .range(..=date)
+        .next_back()
+        .map(|(_, index)| *index)
+        .filter(|index| !index.is_zero())
+        .ok_or(PortfolioError::InsufficientHistory)
+}
+
+pub fn real_return(
+    nominal_series: &ValueSeries,
+    cpi_series: &CpiSeries,
+) -> PortfolioResult<RealReturn> {
+    let ((start_date, start_value), (end_date, end_value)) = nominal_series
+        .first_key_value()
+        .zip(nominal_series.last_key_value())
+        .ok_or(PortfolioError::InsufficientHistory)?;
+    let nominal_factor = growth_factor(start_value, end_value)?;
+    let inflation_factor = cpi_as_of(cpi_series, *end_date)? / cpi_as_of(cpi_series, *start_date)?;
+    let real_factor = nominal_factor
+        .checked_div(inflation_factor)
+        .ok_or(PortfolioError::Overflow)?;
+    Ok(RealReturn {
+        nominal_percent: (nominal_factor - Decimal::ONE) * Decimal::ONE_HUNDRED,
+        inflation_percent: (inflation_factor - Decimal::ONE) * Decimal::ONE_HUNDRED,
+        real_percent: (real_factor - Decimal::ONE) * Decimal::ONE_HUNDRED,
+    })
+}
+
+fn aligned_factors(
+    series: &ValueSeries,
+    benchmark: &ValueSeries,
+) -> PortfolioResult<Vec<(NaiveDate, Decimal, Decimal)>> {
+    let common: Vec<(NaiveDate, &Money, &Money)> = series
+        .iter()
+        .filter_map(|(date, value)| benchmark.get(date).map(|bench| (*date, value, bench)))
+        .collect();
+    if common.len() < 2 {
+        return Err(PortfolioError::InsufficientHistory);
+    }
+    common
+        .windows(2)
+        .map(|pair| {
+            let (_, start_value, start_bench) = pair[0];
+            let (date, end_value, end_bench) = pair[1];
+            Ok((
+                date,
+                growth_factor(start_value, end_value)?,
+                growth_factor(start_bench, end_bench)?,
+            ))
+        })
+        .collect()
+}
+
+fn capture(periods: &[(Decimal, Decimal)]) -> Option<Decimal> {
+    let (total, bench_total) = periods.iter().fold(
+        (Decimal::ZERO, Decimal::ZERO),
+        |(total, bench_total), (r, b)| (total + r, bench_total + b),
+    );
+    (!bench_total.is_zero()).then(|| total / bench_total * Decimal::ONE_HUNDRED)
+}
+
+pub fn capture_ratios(
+    series: &ValueSeries,
+    benchmark: &ValueSeries,
+) -> PortfolioResult<CaptureRatios> {
+    let mut up = Vec::new();
+    let mut down = Vec::new();
+    for (_, factor, bench_factor) in aligned_factors(series, benchmark)? {
+        let returns = (factor - Decimal::ONE, bench_factor - Decimal::ONE);
+        if returns.1 > Decimal::ZERO {
+            up.push(returns);
+        } else if returns.1 < Decimal::ZERO {
+            down.push(returns);
+        }
+    }
+    Ok(CaptureRatios {
+        upside_percent: capture(&up),
+        downside_percent: capture(&down),
+        up_periods: up.len(),
+        down_periods: down.len(),
+    })
+}
+
+pub fn relative_drawdown(
+    series: &ValueSeries,
+    benchmark: &ValueSeries,
+) -> PortfolioResult<Option<RelativeDrawdown>> {
+    let factors = aligned_factors(series, benchmark)?;
+    let first_date = *series
+        .keys()
+        .find(|date| benchmark.contains_key(date))
+        .ok_or(PortfolioError::InsufficientHistory)?;
+    let mut relative = Decimal::ONE;
+    let mut peak = (first_date, Decimal::ONE);
+    let mut worst: Option<RelativeDrawdown> = None;
+    for (date, factor, bench_factor) in factors {
+        relative = relative
+            .checked_mul(factor)
+            .and_then(|value| value.checked_div(bench_factor))
+            .ok_or(PortfolioError::Overflow)?;
+        if relative > peak.1 {
+            peak = (date, relative);
+            continue;
+        }
+        let percent = (peak.1 - relative) / peak.1 * Decimal::ONE_HUNDRED;
+        if percent > Decimal::ZERO && worst.is_none_or(|worst| percent > worst.percent) {
+            worst = Some(RelativeDrawdown {
+                peak_date: peak.0,
+                trough_date: date,
+                percent,
+            });
+        }
+    }
+    Ok(worst)
+}
+
+fn received_per_share(portfolio: &Portfolio, symbol: &str, year: i32) -> PortfolioResult<Money> {
+    let mut total = Money::zero(portfolio.config().base_currency);
+    for dividend in portfolio.get_dividends(symbol) {
+        if dividend.date.year() != year || dividend.shares == 0 {
+            continue;
+        }
+        let eligible = dividend.shares.saturating_sub(dividend.ineligible_shares);
+        let received = dividend
+            .per_share
+            .checked_mul(Decimal::from(eligible) / Decimal::from(dividend.shares))?;
+        total = total.checked_add(&received)?;
+    }
+    Ok(total)
+}
+
+pub fn dividend_growth(
+    portfolio: &Portfolio,
+    symbol: &str,
+    years: u32,
+) -> PortfolioResult<Vec<DividendGrowth>> {
+    let last_year = portfolio.now().year() - 1;
+    let first_year = last_year - i32::try_from(years).map_err(|_| PortfolioError::Overflow)? + 1;
+    let mut previous = received_per_share(portfolio, symbol, first_year - 1)?;
+    let mut growth = Vec::new();
+    for year in first_year..=last_year {
+        let per_share = received_per_share(portfolio, symbol, year)?;
+        let growth_percent = (!previous.amount.is_zero())
+            .then(|| (per_share.amount - previous.amount) / previous.amount * Decimal::ONE_HUNDRED);
+        growth.push(DividendGrowth {
+            year,
+            per_share,
+            growth_percent,
+        });
+        previous = per_share;
+    }
+    Ok(growth)
+}
@@ -0,0 +1,157 @@
+use crate::{PortfolioError, PortfolioResult};
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DayCountConvention {
+    #[default]
+    Actual365Fixed,
+    Actual360,
+    ActualActual,
+}
+
+fn days_in_year(year: i32) -> i64 {
+    if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+        366
+    } else {
+        365
+    }
+}
+
+impl DayCountConvention {
+    pub fn year_fraction(&self, start: NaiveDate, end: NaiveDate) -> Decimal {
+        let days = (end - start).num_days();
+        match self {
+            DayCountConvention::Actual365Fixed => Decimal::from(days) / Decimal::from(365),
+            DayCountConvention::Actual360 => Decimal::from(days) / Decimal::from(360),
+            DayCountConvention::ActualActual => {
+                if end < start {
+                    return -self.year_fraction(end, start);
+                }
+                let mut fraction = Decimal::ZERO;
+                let mut from = start;
+                for year in start.year()..=end.year() {
+                    let to = NaiveDate::from_ymd_opt(year + 1, 1, 1)
+                        .map_or(end, |next_year| next_year.min(end));
+                    fraction +=
+                        Decimal::from((to - from).num_days()) / Decimal::from(days_in_year(year));
+                    from = to;
+                }
+                fraction
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Period {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Period {
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+        Self { start, end }
+    }
+
+    pub fn month(year: i32, month: u32) -> Option<Self> {
+        let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let end = start.checked_add_months(Months::new(1))?.pred_opt()?;
+        Some(Self::new(start, end))
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+
+    pub fn iter_days(&self) -> impl Iterator<Item = NaiveDate> {
+        let end = self.end;
+        self.start.iter_days().take_while(move |date| *date <= end)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+struct FiscalYearStart {
+    start_month: u32,
+    start_day: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "FiscalYearStart"))]
+pub struct FiscalYear {
+    start_month: u32,
+    start_day: u32,
+}
+
+impl Default for FiscalYear {
+    fn default() -> Self {
+        Self {
+            start_month: 1,
+            start_day: 1,
+        }
+    }
+}
+
+impl TryFrom<FiscalYearStart> for FiscalYear {
+    type Error = PortfolioError;
+
+    fn try_from(start: FiscalYearStart) -> PortfolioResult<Self> {
+        Self::new(start.start_month, start.start_day)
+    }
+}
+
+impl FiscalYear {
+    pub fn new(start_month: u32, start_day: u32) -> PortfolioResult<Self> {
+        if NaiveDate::from_ymd_opt(2023, start_month, start_day).is_none() {
+            return Err(PortfolioError::InvalidConfig(format!(
+                "invalid fiscal year start {start_month:02}-{start_day:02}"
+            )));
+        }
+        Ok(Self {
+            start_month,
+            start_day,
+        })
+    }
+
+    pub fn start_month(&self) -> u32 {
+        self.start_month
+    }
+
+    pub fn start_day(&self) -> u32 {
+        self.start_day
+    }
+
+    fn start_in(&self, year: i32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, self.start_month, self.start_day)
+            .expect("fiscal year start is validated on construction")
+    }
+
+    pub fn year_of(&self, date: NaiveDate) -> i32 {
+        if date < self.start_in(date.year()) {
+            date.year() - 1
+        } else {
+            date.year()
+        }
+    }
+
+    pub fn period(&self, year: i32) -> Period {
+        let start = self.start_in(year);
+        let end = start
+            .checked_add_months(Months::new(12))
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(NaiveDate::MAX);
+        Period::new(start, end)
+    }
+}
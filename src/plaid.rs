@@ -0,0 +1,275 @@
+use crate::auth::Role;
+use crate::import::{ImportOptions, ImportReport};
+use crate::integrity::ImportedTransaction;
+use crate::load::{LoadMode, LoadOptions};
+use crate::money::{Currency, Money};
+use crate::reconcile::Discrepancy;
+use crate::{Portfolio, PortfolioError, PortfolioResult, TransactionType};
+use chrono::{Months, NaiveDate};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+const PAGE_SIZE: usize = 500;
+const HISTORY_MONTHS: u32 = 24;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PlaidSecurity {
+    pub security_id: String,
+    pub ticker_symbol: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PlaidInvestmentTransaction {
+    pub investment_transaction_id: String,
+    pub security_id: Option<String>,
+    pub date: NaiveDate,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    #[serde(rename = "type")]
+    pub transaction_type: String,
+    pub iso_currency_code: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PlaidTransactionsPage {
+    pub investment_transactions: Vec<PlaidInvestmentTransaction>,
+    pub securities: Vec<PlaidSecurity>,
+    pub total_investment_transactions: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PlaidHolding {
+    pub security_id: String,
+    pub quantity: Decimal,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PlaidHoldings {
+    pub holdings: Vec<PlaidHolding>,
+    pub securities: Vec<PlaidSecurity>,
+}
+
+pub trait PlaidApi {
+    fn investment_transactions(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        offset: usize,
+        count: usize,
+    ) -> PortfolioResult<PlaidTransactionsPage>;
+
+    fn holdings(&self) -> PortfolioResult<PlaidHoldings>;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlaidCursor {
+    pub synced_through: Option<NaiveDate>,
+    pub seen: BTreeSet<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct PlaidSyncReport {
+    pub import: ImportReport,
+    pub already_synced: usize,
+    pub unsupported: Vec<String>,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+fn ticker_map(securities: &[PlaidSecurity]) -> HashMap<&str, &str> {
+    securities
+        .iter()
+        .filter_map(|security| {
+            let ticker = security.ticker_symbol.as_deref()?;
+            Some((security.security_id.as_str(), ticker))
+        })
+        .collect()
+}
+
+fn whole_shares(quantity: Decimal) -> Option<u32> {
+    let quantity = quantity.abs();
+    if quantity.fract() != Decimal::ZERO {
+        return None;
+    }
+    u32::try_from(quantity).ok()
+}
+
+fn to_imported(
+    transaction: &PlaidInvestmentTransaction,
+    tickers: &HashMap<&str, &str>,
+    base_currency: Currency,
+) -> Option<ImportedTransaction> {
+    let symbol = tickers.get(transaction.security_id.as_deref()?)?;
+    let transaction_type = match transaction.transaction_type.as_str() {
+        "buy" => TransactionType::Purchase,
+        "sell" => TransactionType::Sell,
+        _ => return None,
+    };
+    let currency = match &transaction.iso_currency_code {
+        Some(code) => Currency::from_code(code)?,
+        None => base_currency,
+    };
+    Some(ImportedTransaction {
+        symbol: symbol.to_string(),
+        date: transaction.date.and_hms_opt(0, 0, 0)?.and_utc(),
+        transaction_type,
+        shares: whole_shares(transaction.quantity)?,
+        price: Some(Money::new(transaction.price, currency)),
+        net_amount: None,
+    })
+}
+
+impl Portfolio {
+    pub fn sync_plaid(
+        &mut self,
+        api: &impl PlaidApi,
+        cursor: &mut PlaidCursor,
+    ) -> PortfolioResult<PlaidSyncReport> {
+        self.authorize(Role::Trader)?;
+        let end = self.now().date_naive();
+        let start = cursor
+            .synced_through
+            .or_else(|| end.checked_sub_months(Months::new(HISTORY_MONTHS)))
+            .unwrap_or(end)
+            .min(end);
+        let mut fetched = Vec::new();
+        let mut securities = Vec::new();
+        loop {
+            let page = api.investment_transactions(start, end, fetched.len(), PAGE_SIZE)?;
+            let done = page.investment_transactions.is_empty()
+                || fetched.len() + page.investment_transactions.len()
+                    >= page.total_investment_transactions;
+            fetched.extend(page.investment_transactions);
+            securities.extend(page.securities);
+            if done {
+                break;
+            }
+        }
+        fetched.sort_by(|a, b| {
+            (a.date, &a.investment_transaction_id).cmp(&(b.date, &b.investment_transaction_id))
+        });
+
+        let mut report = PlaidSyncReport::default();
+        let tickers = ticker_map(&securities);
+        let mut transactions = Vec::new();
+        let mut sources = Vec::new();
+        for transaction in &fetched {
+            if cursor.seen.contains(&transaction.investment_transaction_id) {
+                report.already_synced += 1;
+                continue;
+            }
+            match to_imported(transaction, &tickers, self.config.base_currency) {
+                Some(imported) => {
+                    transactions.push(imported);
+                    sources.push(transaction);
+                }
+                None => report
+                    .unsupported
+                    .push(transaction.investment_transaction_id.clone()),
+            }
+        }
+        let options = ImportOptions {
+            skip_duplicates: true,
+            load: LoadOptions {
+                mode: LoadMode::Lenient,
+            },
+        };
+        report.import = self.import(transactions, &options)?;
+        let skipped: HashSet<usize> = report
+            .import
+            .load
+            .skipped
+            .iter()
+            .map(|entry| entry.index)
+            .collect();
+        let mut synced_through = end;
+        for (index, transaction) in sources.into_iter().enumerate() {
+            if skipped.contains(&index) {
+                synced_through = synced_through.min(transaction.date);
+            } else {
+                cursor
+                    .seen
+                    .insert(transaction.investment_transaction_id.clone());
+            }
+        }
+        cursor.synced_through = Some(synced_through);
+
+        let holdings = api.holdings()?;
+        let tickers = ticker_map(&holdings.securities);
+        for holding in &holdings.holdings {
+            let Some(symbol) = tickers.get(holding.security_id.as_str()) else {
+                continue;
+            };
+            let statement = whole_shares(holding.quantity).ok_or_else(|| {
+                PortfolioError::SyncFailed(format!("fractional holding of {symbol}"))
+            })?;
+            let portfolio = self.get_share_count(symbol);
+            if statement != portfolio {
+                report.discrepancies.push(Discrepancy::QuantityMismatch {
+                    symbol: symbol.to_string(),
+                    statement,
+                    portfolio,
+                });
+            }
+        }
+        Ok(report)
+    }
+}
+
+pub struct PlaidHttpClient {
+    agent: ureq::Agent,
+    base_url: String,
+    client_id: String,
+    secret: String,
+    access_token: String,
+}
+
+impl PlaidHttpClient {
+    pub fn new(base_url: &str, client_id: &str, secret: &str, access_token: &str) -> Self {
+        Self {
+            agent: ureq::Agent::new_with_defaults(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client_id: client_id.to_string(),
+            secret: secret.to_string(),
+            access_token: access_token.to_string(),
+        }
+    }
+
+    fn post<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        mut body: serde_json::Value,
+    ) -> PortfolioResult<T> {
+        body["client_id"] = self.client_id.clone().into();
+        body["secret"] = self.secret.clone().into();
+        body["access_token"] = self.access_token.clone().into();
+        self.agent
+            .post(format!("{}{path}", self.base_url))
+            .send_json(body)
+            .and_then(|mut response| response.body_mut().read_json())
+            .map_err(|e| PortfolioError::SyncFailed(e.to_string()))
+    }
+}
+
+impl PlaidApi for PlaidHttpClient {
+    fn investment_transactions(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        offset: usize,
+        count: usize,
+    ) -> PortfolioResult<PlaidTransactionsPage> {
+        self.post(
+            "/investments/transactions/get",
+            serde_json::json!({
+                "start_date": start.to_string(),
+                "end_date": end.to_string(),
+                "options": { "offset": offset, "count": count },
+            }),
+        )
+    }
+
+    fn holdings(&self) -> PortfolioResult<PlaidHoldings> {
+        self.post("/investments/holdings/get", serde_json::json!({}))
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Position {
+    #[default]
+    Flat,
+    Long(u32),
+    Short(u32),
+}
+
+impl Position {
+    pub fn from_signed(quantity: i64) -> Option<Self> {
+        let magnitude = u32::try_from(quantity.unsigned_abs()).ok()?;
+        Some(match quantity.signum() {
+            1 => Position::Long(magnitude),
+            -1 => Position::Short(magnitude),
+            _ => Position::Flat,
+        })
+    }
+
+    pub fn signed_quantity(&self) -> i64 {
+        match *self {
+            Position::Flat => 0,
+            Position::Long(shares) => i64::from(shares),
+            Position::Short(shares) => -i64::from(shares),
+        }
+    }
+
+    pub fn long_quantity(&self) -> u32 {
+        match *self {
+            Position::Long(shares) => shares,
+            Position::Flat | Position::Short(_) => 0,
+        }
+    }
+
+    pub fn is_short(&self) -> bool {
+        matches!(self, Position::Short(_))
+    }
+}
@@ -0,0 +1,184 @@
+use crate::corporate_actions::CorporateAction;
+use crate::money::Money;
+use crate::{PortfolioError, PortfolioResult};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+const SPLIT_FACTORS: [(u32, u32); 12] = [
+    (2, 1),
+    (3, 1),
+    (3, 2),
+    (4, 1),
+    (5, 1),
+    (10, 1),
+    (20, 1),
+    (1, 2),
+    (1, 3),
+    (1, 5),
+    (1, 10),
+    (1, 20),
+];
+const SPLIT_TOLERANCE: Decimal = Decimal::from_parts(5, 0, 0, false, 2);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingPricePolicy {
+    #[default]
+    CarryForward,
+    Interpolate,
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PricePolicy {
+    pub missing: MissingPricePolicy,
+    pub max_staleness_days: Option<i64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuspectedSplit {
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub previous_close: Money,
+    pub close: Money,
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl SuspectedSplit {
+    pub fn action(&self) -> CorporateAction {
+        CorporateAction::Split {
+            symbol: self.symbol.clone(),
+            date: self.date,
+            numerator: self.numerator,
+            denominator: self.denominator,
+            cash_in_lieu_price: None,
+        }
+    }
+}
+
+fn matching_split_factor(previous: &Money, close: &Money) -> Option<(u32, u32)> {
+    if previous.currency != close.currency || close.amount <= Decimal::ZERO {
+        return None;
+    }
+    let ratio = previous.amount.checked_div(close.amount)?;
+    SPLIT_FACTORS
+        .into_iter()
+        .map(|(numerator, denominator)| {
+            let factor = Decimal::from(numerator) / Decimal::from(denominator);
+            (
+                (ratio / factor - Decimal::ONE).abs(),
+                (numerator, denominator),
+            )
+        })
+        .filter(|(deviation, _)| *deviation <= SPLIT_TOLERANCE)
+        .min_by_key(|(deviation, _)| *deviation)
+        .map(|(_, factor)| factor)
+}
+
+pub fn detect_splits(history: &PriceHistory) -> Vec<SuspectedSplit> {
+    let mut splits = Vec::new();
+    for (symbol, closes) in &history.closes {
+        for ((_, previous), (date, close)) in closes.iter().zip(closes.iter().skip(1)) {
+            if let Some((numerator, denominator)) = matching_split_factor(previous, close) {
+                splits.push(SuspectedSplit {
+                    symbol: symbol.clone(),
+                    date: *date,
+                    previous_close: *previous,
+                    close: *close,
+                    numerator,
+                    denominator,
+                });
+            }
+        }
+    }
+    splits.sort_by(|a, b| (&a.symbol, a.date).cmp(&(&b.symbol, b.date)));
+    splits
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PriceHistory {
+    closes: HashMap<String, BTreeMap<NaiveDate, Money>>,
+    policy: PricePolicy,
+}
+
+impl PriceHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_policy(policy: PricePolicy) -> Self {
+        Self {
+            closes: HashMap::new(),
+            policy,
+        }
+    }
+
+    pub fn policy(&self) -> PricePolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: PricePolicy) {
+        self.policy = policy;
+    }
+
+    pub fn insert(&mut self, symbol: &str, date: NaiveDate, close: Money) {
+        self.closes
+            .entry(symbol.to_string())
+            .or_default()
+            .insert(date, close);
+    }
+
+    pub fn close_on(&self, symbol: &str, date: NaiveDate) -> Option<Money> {
+        self.closes.get(symbol)?.get(&date).copied()
+    }
+
+    pub fn latest_on_or_before(&self, symbol: &str, date: NaiveDate) -> Option<(NaiveDate, Money)> {
+        self.closes
+            .get(symbol)?
+            .range(..=date)
+            .next_back()
+            .map(|(date, close)| (*date, *close))
+    }
+
+    pub fn price_on(&self, symbol: &str, date: NaiveDate) -> PortfolioResult<Money> {
+        let missing = || PortfolioError::MissingPrice(symbol.to_string());
+        if let Some(close) = self.close_on(symbol, date) {
+            return Ok(close);
+        }
+        if self.policy.missing == MissingPricePolicy::Error {
+            return Err(missing());
+        }
+        let (previous_date, previous) =
+            self.latest_on_or_before(symbol, date).ok_or_else(missing)?;
+        if self
+            .policy
+            .max_staleness_days
+            .is_some_and(|max_days| (date - previous_date).num_days() > max_days)
+        {
+            return Err(PortfolioError::StalePrice(symbol.to_string()));
+        }
+        let next = self
+            .closes
+            .get(symbol)
+            .and_then(|closes| closes.range(date..).next());
+        match (self.policy.missing, next) {
+            (MissingPricePolicy::Interpolate, Some((next_date, next))) => {
+                previous.ensure_same_currency(next)?;
+                let elapsed = Decimal::from((date - previous_date).num_days());
+                let span = Decimal::from((*next_date - previous_date).num_days());
+                let amount = previous.amount + (next.amount - previous.amount) * elapsed / span;
+                Ok(Money::new(amount, previous.currency))
+            }
+            _ => Ok(previous),
+        }
+    }
+
+    pub fn series(&self, symbol: &str) -> impl Iterator<Item = (NaiveDate, Money)> + '_ {
+        self.closes
+            .get(symbol)
+            .into_iter()
+            .flatten()
+            .map(|(date, close)| (*date, *close))
+    }
+}
@@ -0,0 +1,43 @@
+use crate::events::PortfolioEvent;
+use crate::PortfolioResult;
+use std::collections::VecDeque;
+use std::sync::mpsc::Receiver;
+
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+
+pub trait EventPublisher {
+    fn publish(&self, event: &PortfolioEvent) -> PortfolioResult<()>;
+}
+
+#[derive(Debug, Default)]
+pub struct EventForwarder {
+    pending: VecDeque<PortfolioEvent>,
+}
+
+impl EventForwarder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pending(&self) -> impl Iterator<Item = &PortfolioEvent> {
+        self.pending.iter()
+    }
+
+    pub fn forward(
+        &mut self,
+        events: &Receiver<PortfolioEvent>,
+        publisher: &impl EventPublisher,
+    ) -> PortfolioResult<usize> {
+        self.pending.extend(events.try_iter());
+        let mut published = 0;
+        while let Some(event) = self.pending.front() {
+            publisher.publish(event)?;
+            self.pending.pop_front();
+            published += 1;
+        }
+        Ok(published)
+    }
+}
@@ -0,0 +1,44 @@
+use crate::events::PortfolioEvent;
+use crate::publishing::EventPublisher;
+use crate::{PortfolioError, PortfolioResult};
+use rdkafka::producer::{BaseProducer, BaseRecord, Producer};
+use rdkafka::ClientConfig;
+use std::time::Duration;
+
+pub struct KafkaPublisher {
+    producer: BaseProducer,
+    topic: String,
+}
+
+impl KafkaPublisher {
+    pub fn new(brokers: &str, topic: &str) -> PortfolioResult<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create()
+            .map_err(|e| PortfolioError::PublishFailed(e.to_string()))?;
+        Ok(Self {
+            producer,
+            topic: topic.to_string(),
+        })
+    }
+
+    pub fn flush(&self, timeout: Duration) -> PortfolioResult<()> {
+        self.producer
+            .flush(timeout)
+            .map_err(|e| PortfolioError::PublishFailed(e.to_string()))
+    }
+}
+
+impl EventPublisher for KafkaPublisher {
+    fn publish(&self, event: &PortfolioEvent) -> PortfolioResult<()> {
+        let payload = serde_json::to_vec(event).expect("portfolio events serialize to JSON");
+        let record = BaseRecord::to(&self.topic)
+            .key(event.symbol())
+            .payload(&payload);
+        self.producer
+            .send(record)
+            .map_err(|(e, _)| PortfolioError::PublishFailed(e.to_string()))?;
+        self.producer.poll(Duration::ZERO);
+        Ok(())
+    }
+}
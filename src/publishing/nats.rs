@@ -0,0 +1,46 @@
+use crate::events::PortfolioEvent;
+use crate::publishing::EventPublisher;
+use crate::{PortfolioError, PortfolioResult};
+use tokio::runtime::Runtime;
+
+pub struct NatsPublisher {
+    runtime: Runtime,
+    client: async_nats::Client,
+    subject_prefix: String,
+}
+
+impl NatsPublisher {
+    pub fn connect(url: &str, subject_prefix: &str) -> PortfolioResult<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .map_err(|e| PortfolioError::PublishFailed(e.to_string()))?;
+        let client = runtime
+            .block_on(async_nats::connect(url))
+            .map_err(|e| PortfolioError::PublishFailed(e.to_string()))?;
+        Ok(Self {
+            runtime,
+            client,
+            subject_prefix: subject_prefix.to_string(),
+        })
+    }
+
+    pub fn subject(&self, event: &PortfolioEvent) -> String {
+        format!(
+            "{}.{}.{}",
+            self.subject_prefix,
+            event.kind(),
+            event.symbol()
+        )
+    }
+}
+
+impl EventPublisher for NatsPublisher {
+    fn publish(&self, event: &PortfolioEvent) -> PortfolioResult<()> {
+        let payload = serde_json::to_vec(event).expect("portfolio events serialize to JSON");
+        self.runtime
+            .block_on(self.client.publish(self.subject(event), payload.into()))
+            .map_err(|e| PortfolioError::PublishFailed(e.to_string()))
+    }
+}
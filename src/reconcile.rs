@@ -0,0 +1,159 @@
+use crate::integrity::ImportedTransaction;
+use crate::money::Money;
+use crate::period::Period;
+use crate::{Portfolio, PortfolioResult, PurchaseRecord, TransactionType};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Fingerprint {
+    symbol: String,
+    date: DateTime<Utc>,
+    transaction_type: TransactionType,
+    shares: u32,
+    price: Option<Money>,
+}
+
+impl Fingerprint {
+    #[cfg(feature = "import")]
+    pub(crate) fn of_record(symbol: &str, record: &PurchaseRecord) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            date: record.date,
+            transaction_type: record.transaction_type.clone(),
+            shares: record.shares,
+            price: record.price,
+        }
+    }
+
+    pub(crate) fn of_import(transaction: &ImportedTransaction) -> Self {
+        Self {
+            symbol: transaction.symbol.clone(),
+            date: transaction.date,
+            transaction_type: transaction.transaction_type.clone(),
+            shares: transaction.shares,
+            price: transaction.price,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BrokerStatement {
+    pub period: Period,
+    pub transactions: Vec<ImportedTransaction>,
+    pub positions: HashMap<String, u32>,
+    pub net_trade_cash: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Discrepancy {
+    MissingFromPortfolio(ImportedTransaction),
+    MissingFromStatement(ImportedTransaction),
+    QuantityMismatch {
+        symbol: String,
+        statement: u32,
+        portfolio: u32,
+    },
+    CashMismatch {
+        statement: Money,
+        portfolio: Money,
+    },
+}
+
+fn as_imported(symbol: &str, record: &PurchaseRecord) -> ImportedTransaction {
+    ImportedTransaction {
+        symbol: symbol.to_string(),
+        date: record.date,
+        transaction_type: record.transaction_type.clone(),
+        shares: record.shares,
+        price: record.price,
+        net_amount: record.net_amount,
+    }
+}
+
+impl Portfolio {
+    fn transactions_in(&self, period: &Period) -> Vec<ImportedTransaction> {
+        let mut transactions = Vec::new();
+        for symbol in self.traded_symbols() {
+            for record in &self.purchase_records[symbol] {
+                if period.contains(record.date.date_naive()) {
+                    transactions.push(as_imported(symbol, record));
+                }
+            }
+        }
+        transactions
+    }
+
+    fn net_trade_cash(&self, transactions: &[ImportedTransaction]) -> PortfolioResult<Money> {
+        let mut cash = Money::zero(self.config.base_currency);
+        for transaction in transactions {
+            let amount = match (transaction.net_amount, transaction.price) {
+                (Some(net_amount), _) => net_amount,
+                (None, Some(price)) => price.checked_mul(transaction.shares.into())?,
+                (None, None) => continue,
+            };
+            cash = match transaction.transaction_type {
+                TransactionType::Purchase => cash.checked_sub(&amount)?,
+                TransactionType::Sell => cash.checked_add(&amount)?,
+            };
+        }
+        Ok(cash)
+    }
+
+    pub fn reconcile(&self, statement: &BrokerStatement) -> PortfolioResult<Vec<Discrepancy>> {
+        let recorded = self.transactions_in(&statement.period);
+        let mut unmatched: HashMap<Fingerprint, usize> = HashMap::new();
+        for transaction in &recorded {
+            *unmatched
+                .entry(Fingerprint::of_import(transaction))
+                .or_default() += 1;
+        }
+
+        let mut discrepancies = Vec::new();
+        for transaction in &statement.transactions {
+            match unmatched
+                .get_mut(&Fingerprint::of_import(transaction))
+                .filter(|count| **count > 0)
+            {
+                Some(count) => *count -= 1,
+                None => discrepancies.push(Discrepancy::MissingFromPortfolio(transaction.clone())),
+            }
+        }
+        for transaction in &recorded {
+            if let Some(count) = unmatched
+                .get_mut(&Fingerprint::of_import(transaction))
+                .filter(|count| **count > 0)
+            {
+                *count -= 1;
+                discrepancies.push(Discrepancy::MissingFromStatement(transaction.clone()));
+            }
+        }
+
+        let symbols: BTreeSet<&str> = statement
+            .positions
+            .keys()
+            .map(|symbol| symbol.as_str())
+            .chain(self.traded_symbols())
+            .collect();
+        for symbol in symbols {
+            let expected = statement.positions.get(symbol).copied().unwrap_or(0);
+            let actual = self.get_share_count_as_of(symbol, statement.period.end);
+            if expected != actual {
+                discrepancies.push(Discrepancy::QuantityMismatch {
+                    symbol: symbol.to_string(),
+                    statement: expected,
+                    portfolio: actual,
+                });
+            }
+        }
+
+        let cash = self.net_trade_cash(&recorded)?;
+        if cash != statement.net_trade_cash {
+            discrepancies.push(Discrepancy::CashMismatch {
+                statement: statement.net_trade_cash,
+                portfolio: cash,
+            });
+        }
+        Ok(discrepancies)
+    }
+}
@@ -0,0 +1,420 @@
+use crate::config::RoundingPolicy;
+use crate::lots::Acquisition;
+use crate::money::Money;
+#[cfg(feature = "pricing")]
+use crate::performance::value_as_of;
+use crate::period::Period;
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+#[cfg(feature = "pricing")]
+use crate::PortfolioError;
+use crate::{Portfolio, PortfolioResult, TransactionId, TransactionType};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+#[cfg(feature = "pricing")]
+const DAYS_PER_YEAR: i64 = 365;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FundFeeDrag {
+    pub symbol: String,
+    pub expense_ratio: Decimal,
+    pub average_value: Money,
+    pub estimated_fees: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeDragReport {
+    pub period: Period,
+    pub funds: Vec<FundFeeDrag>,
+    pub total_fees: Money,
+}
+
+#[cfg(feature = "pricing")]
+pub fn fee_drag(
+    portfolio: &Portfolio,
+    prices: &PriceHistory,
+    period: &Period,
+) -> PortfolioResult<FeeDragReport> {
+    let currency = portfolio.config().base_currency;
+    let mut funds = Vec::new();
+    for (symbol, expense_ratio) in portfolio.instruments().symbols_with_expense_ratio() {
+        let mut value_days = Money::zero(currency);
+        for date in period.iter_days() {
+            let shares = portfolio.get_share_count_as_of(symbol, date);
+            let Ok(close) = prices.price_on(symbol, date) else {
+                continue;
+            };
+            value_days = value_days.checked_add(&close.checked_mul(shares.into())?)?;
+        }
+        let annualized_fees = value_days.checked_mul(expense_ratio)?;
+        funds.push(FundFeeDrag {
+            symbol: symbol.to_string(),
+            expense_ratio,
+            average_value: Money::new(value_days.amount / Decimal::from(period.days()), currency),
+            estimated_fees: Money::new(
+                annualized_fees.amount / Decimal::from(DAYS_PER_YEAR),
+                currency,
+            ),
+        });
+    }
+    funds.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    let total_fees = portfolio.money_total(funds.iter().map(|fund| &fund.estimated_fees))?;
+    Ok(FeeDragReport {
+        period: *period,
+        funds,
+        total_fees,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CashFlowKind {
+    Deposit,
+    Withdrawal,
+    Buy,
+    Sell,
+    Dividend,
+    ReturnOfCapital,
+    Interest,
+    LendingIncome,
+    Fee,
+}
+
+impl CashFlowKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            CashFlowKind::Deposit => "deposit",
+            CashFlowKind::Withdrawal => "withdrawal",
+            CashFlowKind::Buy => "buy",
+            CashFlowKind::Sell => "sell",
+            CashFlowKind::Dividend => "dividend",
+            CashFlowKind::ReturnOfCapital => "return_of_capital",
+            CashFlowKind::Interest => "interest",
+            CashFlowKind::LendingIncome => "lending_income",
+            CashFlowKind::Fee => "fee",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CashFlowEntry {
+    pub date: DateTime<Utc>,
+    pub kind: CashFlowKind,
+    pub symbol: Option<String>,
+    pub amount: Money,
+    pub balance: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CashFlowStatement {
+    pub period: Period,
+    pub opening_balance: Money,
+    pub entries: Vec<CashFlowEntry>,
+    pub closing_balance: Money,
+}
+
+impl CashFlowStatement {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("date,kind,symbol,amount,balance\n");
+        for entry in &self.entries {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                entry.date.date_naive(),
+                entry.kind.as_str(),
+                entry.symbol.as_deref().unwrap_or_default(),
+                entry.amount.amount,
+                entry.balance.amount
+            ));
+        }
+        csv
+    }
+}
+
+impl CashFlowEntry {
+    fn new(date: DateTime<Utc>, kind: CashFlowKind, symbol: Option<&str>, amount: Money) -> Self {
+        Self {
+            date,
+            kind,
+            symbol: symbol.map(str::to_string),
+            balance: Money::zero(amount.currency),
+            amount,
+        }
+    }
+}
+
+fn cash_movements(portfolio: &Portfolio) -> PortfolioResult<Vec<CashFlowEntry>> {
+    let mut movements = Vec::new();
+    for transfer in portfolio.deposits() {
+        movements.push(CashFlowEntry::new(
+            transfer.date,
+            CashFlowKind::Deposit,
+            None,
+            transfer.amount,
+        ));
+    }
+    for transfer in portfolio.withdrawals() {
+        movements.push(CashFlowEntry::new(
+            transfer.date,
+            CashFlowKind::Withdrawal,
+            None,
+            transfer.amount.negated(),
+        ));
+    }
+    for transfer in portfolio.interest() {
+        movements.push(CashFlowEntry::new(
+            transfer.date,
+            CashFlowKind::Interest,
+            None,
+            transfer.amount,
+        ));
+    }
+    for (symbol, date, income) in portfolio.lending_income_history() {
+        movements.push(CashFlowEntry::new(
+            date,
+            CashFlowKind::LendingIncome,
+            Some(symbol),
+            *income,
+        ));
+    }
+    for fee in portfolio.advisory_fees() {
+        movements.push(CashFlowEntry::new(
+            fee.date,
+            CashFlowKind::Fee,
+            None,
+            fee.amount.negated(),
+        ));
+    }
+    for symbol in portfolio.traded_symbols() {
+        let replay = portfolio.replay_symbol(symbol)?;
+        for cash in &replay.cash_in_lieu {
+            movements.push(CashFlowEntry::new(
+                cash.date,
+                CashFlowKind::Sell,
+                Some(symbol),
+                cash.proceeds,
+            ));
+        }
+        for (record, confirmation) in replay.trades {
+            if portfolio.acquisition_of(record.id) == Acquisition::StockDividend {
+                continue;
+            }
+            if let Some(price) = record.price {
+                let value = price.checked_mul(record.shares.into())?;
+                let (kind, amount) = match record.transaction_type {
+                    TransactionType::Purchase => (CashFlowKind::Buy, value.negated()),
+                    TransactionType::Sell => (CashFlowKind::Sell, value),
+                };
+                movements.push(CashFlowEntry::new(record.date, kind, Some(symbol), amount));
+            }
+            if !confirmation.fees.is_zero() {
+                movements.push(CashFlowEntry::new(
+                    record.date,
+                    CashFlowKind::Fee,
+                    Some(symbol),
+                    confirmation.fees.negated(),
+                ));
+            }
+        }
+        for distribution in portfolio.get_capital_gain_distributions(symbol) {
+            movements.push(CashFlowEntry::new(
+                distribution.date,
+                CashFlowKind::Dividend,
+                Some(symbol),
+                distribution
+                    .short_term
+                    .checked_add(&distribution.long_term)?,
+            ));
+        }
+        for dividend in portfolio.get_dividends(symbol) {
+            movements.push(CashFlowEntry::new(
+                dividend.date,
+                CashFlowKind::Dividend,
+                Some(symbol),
+                dividend.amount()?,
+            ));
+        }
+        for adjustment in portfolio.get_return_of_capital_history(symbol) {
+            movements.push(CashFlowEntry::new(
+                adjustment.date,
+                CashFlowKind::ReturnOfCapital,
+                Some(symbol),
+                adjustment
+                    .basis_reduction
+                    .checked_add(&adjustment.realized_gain)?,
+            ));
+        }
+    }
+    movements.sort_by(|a, b| (a.date, a.kind, &a.symbol).cmp(&(b.date, b.kind, &b.symbol)));
+    Ok(movements)
+}
+
+pub fn cash_flows(portfolio: &Portfolio, period: &Period) -> PortfolioResult<CashFlowStatement> {
+    let mut balance = Money::zero(portfolio.config().base_currency);
+    let mut opening_balance = balance;
+    let mut entries = Vec::new();
+    for mut entry in cash_movements(portfolio)? {
+        let day = entry.date.date_naive();
+        if day > period.end {
+            break;
+        }
+        balance = balance.checked_add(&entry.amount)?;
+        if day < period.start {
+            opening_balance = balance;
+            continue;
+        }
+        entry.balance = balance;
+        entries.push(entry);
+    }
+    Ok(CashFlowStatement {
+        period: *period,
+        opening_balance,
+        entries,
+        closing_balance: balance,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MonthlyStatement {
+    pub period: Period,
+    pub opening_value: Money,
+    pub closing_value: Money,
+    pub activity: Vec<CashFlowEntry>,
+    pub income: Money,
+    pub realized_gain: Money,
+    pub fees: Money,
+}
+
+#[cfg(feature = "pricing")]
+fn realized_gain_in(portfolio: &Portfolio, period: &Period) -> PortfolioResult<Money> {
+    let mut realized_gain = Money::zero(portfolio.config().base_currency);
+    for symbol in portfolio.traded_symbols() {
+        let replay = portfolio.replay_symbol(symbol)?;
+        for (_, confirmation) in replay
+            .trades
+            .iter()
+            .filter(|(record, _)| period.contains(record.trade_date()))
+        {
+            if let Some(gain) = &confirmation.realized_gain {
+                realized_gain = realized_gain.checked_add(gain)?;
+            }
+        }
+        for applied in &replay.adjustments {
+            let adjustment = &applied.adjustment;
+            if period.contains(adjustment.date.date_naive()) {
+                realized_gain = realized_gain.checked_add(&adjustment.realized_gain)?;
+            }
+        }
+        for cash in &replay.cash_in_lieu {
+            if period.contains(cash.date.date_naive()) {
+                realized_gain = realized_gain.checked_add(&cash.gain)?;
+            }
+        }
+    }
+    Ok(realized_gain)
+}
+
+#[cfg(feature = "pricing")]
+pub fn monthly_statement(
+    portfolio: &Portfolio,
+    year: i32,
+    month: u32,
+    prices: &PriceHistory,
+) -> PortfolioResult<MonthlyStatement> {
+    let invalid_month = || PortfolioError::InvalidConfig(format!("invalid month {year}-{month}"));
+    let period = Period::month(year, month).ok_or_else(invalid_month)?;
+    let opening_date = period.start.pred_opt().ok_or_else(invalid_month)?;
+
+    let activity = cash_flows(portfolio, &period)?.entries;
+    let total_of = |kind: CashFlowKind| {
+        portfolio.money_total(
+            activity
+                .iter()
+                .filter(|entry| entry.kind == kind)
+                .map(|entry| &entry.amount),
+        )
+    };
+    let income = total_of(CashFlowKind::Dividend)?
+        .checked_add(&total_of(CashFlowKind::Interest)?)?
+        .checked_add(&total_of(CashFlowKind::LendingIncome)?)?;
+    let fees = total_of(CashFlowKind::Fee)?.negated();
+    Ok(MonthlyStatement {
+        period,
+        opening_value: value_as_of(portfolio, prices, opening_date)?,
+        closing_value: value_as_of(portfolio, prices, period.end)?,
+        realized_gain: realized_gain_in(portfolio, &period)?,
+        activity,
+        income,
+        fees,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingSource {
+    TradeValue,
+    LotBasis,
+    Dividend,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoundingResidue {
+    pub source: RoundingSource,
+    pub symbol: String,
+    pub transaction_id: Option<TransactionId>,
+    pub exact: Money,
+    pub settled: Money,
+    pub residue: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoundingAudit {
+    pub entries: Vec<RoundingResidue>,
+    pub total_residue: Money,
+}
+
+pub fn rounding_audit(portfolio: &Portfolio) -> PortfolioResult<RoundingAudit> {
+    let currency = portfolio.config().base_currency;
+    let policy = RoundingPolicy {
+        mode: portfolio.config().rounding.mode,
+        decimal_places: currency.minor_units(),
+    };
+    let mut entries = Vec::new();
+    let mut audit = |source, symbol: &str, transaction_id, exact: Money| -> PortfolioResult<()> {
+        let settled = exact.rounded(&policy);
+        let residue = exact.checked_sub(&settled)?;
+        if !residue.is_zero() {
+            entries.push(RoundingResidue {
+                source,
+                symbol: symbol.to_string(),
+                transaction_id,
+                exact,
+                settled,
+                residue,
+            });
+        }
+        Ok(())
+    };
+    for (symbol, record) in portfolio.journal() {
+        if let Some(price) = record.price {
+            let value = price.checked_mul(record.shares.into())?;
+            audit(RoundingSource::TradeValue, symbol, Some(record.id), value)?;
+        }
+    }
+    for symbol in portfolio.traded_symbols() {
+        for lot in portfolio.lots.get(symbol).into_iter().flatten() {
+            audit(
+                RoundingSource::LotBasis,
+                symbol,
+                Some(lot.sequence),
+                lot.cost_basis,
+            )?;
+        }
+        for dividend in portfolio.get_dividends(symbol) {
+            audit(RoundingSource::Dividend, symbol, None, dividend.amount()?)?;
+        }
+    }
+    let total_residue = Money::checked_sum(currency, entries.iter().map(|entry| &entry.residue))?;
+    Ok(RoundingAudit {
+        entries,
+        total_residue,
+    })
+}
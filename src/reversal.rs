@@ -0,0 +1,91 @@
+use crate::auth::Role;
+use crate::ledger::{Trade, Transaction};
+use crate::lots::SelectedLot;
+use crate::{
+    Portfolio, PortfolioError, PortfolioResult, TradeConfirmation, TransactionId, TransactionType,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Reversal {
+    pub original: TransactionId,
+    pub contra: TransactionId,
+    pub reason: String,
+}
+
+impl Portfolio {
+    pub fn reverse_transaction(
+        &mut self,
+        id: TransactionId,
+        reason: &str,
+    ) -> PortfolioResult<TradeConfirmation> {
+        self.authorize(Role::Trader)?;
+        if self.reversal_of(id).is_some() {
+            return Err(PortfolioError::TransactionAlreadyReversed(id));
+        }
+        let (symbol, record) = self
+            .purchase_records
+            .iter()
+            .find_map(|(symbol, records)| {
+                records
+                    .iter()
+                    .find(|record| record.id == id)
+                    .map(|record| (symbol.clone(), record.clone()))
+            })
+            .ok_or(PortfolioError::UnknownTransaction(id))?;
+        let contra_type = match record.transaction_type {
+            TransactionType::Purchase => TransactionType::Sell,
+            TransactionType::Sell => TransactionType::Purchase,
+        };
+        let mut contra = Trade::new(
+            &symbol,
+            record.shares,
+            contra_type,
+            record.price,
+            self.now().max(record.date),
+        );
+        contra.record.fx = record.fx;
+        contra.record.net_amount = record.net_amount;
+        contra.reverses = Some(id);
+        if record.transaction_type == TransactionType::Purchase {
+            let intact = self
+                .open_lots(&symbol)
+                .iter()
+                .any(|lot| lot.sequence == id && lot.shares >= record.shares);
+            if !intact {
+                return Err(PortfolioError::InvalidLotSelection(id));
+            }
+            contra.lot_selection = Some(vec![SelectedLot {
+                sequence: id,
+                shares: record.shares,
+            }]);
+        }
+        let confirmation = self.record_trade(contra)?;
+        self.commit_entry(Transaction::Reversal {
+            reversal: Reversal {
+                original: id,
+                contra: confirmation.transaction_id,
+                reason: reason.to_string(),
+            },
+        })?;
+        Ok(confirmation)
+    }
+
+    pub fn reversal_of(&self, id: TransactionId) -> Option<&Reversal> {
+        self.reversals
+            .iter()
+            .find(|reversal| reversal.original == id)
+    }
+
+    pub fn reversals(&self) -> &[Reversal] {
+        &self.reversals
+    }
+
+    pub(crate) fn is_reversed(&self, id: TransactionId) -> bool {
+        self.reversals
+            .iter()
+            .any(|reversal| reversal.original == id || reversal.contra == id)
+    }
+}
@@ -0,0 +1,194 @@
+#[cfg(feature = "pricing")]
+use crate::config::AccountType;
+#[cfg(feature = "pricing")]
+use crate::manager::PortfolioManager;
+use crate::money::Money;
+#[cfg(feature = "pricing")]
+use crate::performance::value_as_of;
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+#[cfg(feature = "pricing")]
+use crate::{Portfolio, PortfolioError, PortfolioResult};
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+const UNIFORM_LIFETIME_TABLE: [(i32, i64); 48] = [
+    (72, 274),
+    (73, 265),
+    (74, 255),
+    (75, 246),
+    (76, 237),
+    (77, 229),
+    (78, 220),
+    (79, 211),
+    (80, 202),
+    (81, 194),
+    (82, 185),
+    (83, 177),
+    (84, 168),
+    (85, 160),
+    (86, 152),
+    (87, 144),
+    (88, 137),
+    (89, 129),
+    (90, 122),
+    (91, 115),
+    (92, 108),
+    (93, 101),
+    (94, 95),
+    (95, 89),
+    (96, 84),
+    (97, 78),
+    (98, 73),
+    (99, 68),
+    (100, 64),
+    (101, 60),
+    (102, 56),
+    (103, 52),
+    (104, 49),
+    (105, 46),
+    (106, 43),
+    (107, 41),
+    (108, 39),
+    (109, 37),
+    (110, 35),
+    (111, 34),
+    (112, 33),
+    (113, 31),
+    (114, 30),
+    (115, 29),
+    (116, 28),
+    (117, 27),
+    (118, 25),
+    (119, 23),
+];
+
+const FINAL_DISTRIBUTION_PERIOD: i64 = 20;
+
+pub fn required_beginning_age(birthdate: NaiveDate) -> i32 {
+    match birthdate.year() {
+        ..=1950 => 72,
+        1951..=1959 => 73,
+        _ => 75,
+    }
+}
+
+pub fn distribution_period(age: i32) -> Option<Decimal> {
+    if age < UNIFORM_LIFETIME_TABLE[0].0 {
+        return None;
+    }
+    let tenths = UNIFORM_LIFETIME_TABLE
+        .iter()
+        .find(|(table_age, _)| *table_age == age)
+        .map_or(FINAL_DISTRIBUTION_PERIOD, |(_, period)| *period);
+    Some(Decimal::new(tenths, 1))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RmdReport {
+    pub year: i32,
+    pub age: i32,
+    pub prior_year_end_balance: Money,
+    pub distribution_period: Option<Decimal>,
+    pub required: Money,
+    pub withdrawn: Money,
+    pub remaining: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RmdSummary {
+    pub year: i32,
+    pub accounts: BTreeMap<String, RmdReport>,
+    pub required: Money,
+    pub withdrawn: Money,
+    pub remaining: Money,
+}
+
+#[cfg(feature = "pricing")]
+impl Portfolio {
+    pub(crate) fn rmd_for_year(
+        &self,
+        year: i32,
+        birthdate: NaiveDate,
+        prices: &PriceHistory,
+    ) -> PortfolioResult<RmdReport> {
+        let currency = self.config.base_currency;
+        let age = year - birthdate.year();
+        let distribution_period = match self.config.account_type {
+            AccountType::Taxable => return Err(PortfolioError::NotRetirementAccount),
+            AccountType::RothIra => None,
+            AccountType::TraditionalIra if age < required_beginning_age(birthdate) => None,
+            AccountType::TraditionalIra => distribution_period(age),
+        };
+        let prior_year_end = NaiveDate::from_ymd_opt(year - 1, 12, 31)
+            .ok_or_else(|| PortfolioError::InvalidConfig(format!("invalid year {year}")))?;
+        let prior_year_end_balance = value_as_of(self, prices, prior_year_end)?;
+        let required = match distribution_period {
+            Some(period) => Money::new(
+                (prior_year_end_balance.amount / period).round_dp(2),
+                currency,
+            ),
+            None => Money::zero(currency),
+        };
+        let withdrawn = Money::checked_sum(
+            currency,
+            self.withdrawals
+                .iter()
+                .filter(|withdrawal| withdrawal.date.year() == year)
+                .map(|withdrawal| &withdrawal.amount),
+        )?;
+        let remaining = Money::new(
+            (required.amount - withdrawn.amount).max(Decimal::ZERO),
+            currency,
+        );
+        Ok(RmdReport {
+            year,
+            age,
+            prior_year_end_balance,
+            distribution_period,
+            required,
+            withdrawn,
+            remaining,
+        })
+    }
+}
+
+#[cfg(feature = "pricing")]
+impl PortfolioManager {
+    pub fn rmd_for_year(
+        &self,
+        year: i32,
+        birthdate: NaiveDate,
+        prices: &PriceHistory,
+    ) -> PortfolioResult<RmdSummary> {
+        let currency = self.base_currency();
+        let mut accounts = BTreeMap::new();
+        let mut required = Money::zero(currency);
+        let mut withdrawn = Money::zero(currency);
+        for (name, portfolio) in self.accounts() {
+            if portfolio.config().account_type == AccountType::Taxable {
+                continue;
+            }
+            let report = portfolio.rmd_for_year(year, birthdate, prices)?;
+            if report.distribution_period.is_some() {
+                required = required.checked_add(&report.required)?;
+                withdrawn = withdrawn.checked_add(&report.withdrawn)?;
+            }
+            accounts.insert(name.to_string(), report);
+        }
+        if accounts.is_empty() {
+            return Err(PortfolioError::NotRetirementAccount);
+        }
+        Ok(RmdSummary {
+            year,
+            accounts,
+            required,
+            withdrawn,
+            remaining: Money::new(
+                (required.amount - withdrawn.amount).max(Decimal::ZERO),
+                currency,
+            ),
+        })
+    }
+}
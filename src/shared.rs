@@ -0,0 +1,23 @@
+use crate::Portfolio;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[derive(Clone, Default)]
+pub struct SharedPortfolio {
+    inner: Arc<RwLock<Portfolio>>,
+}
+
+impl SharedPortfolio {
+    pub fn new(portfolio: Portfolio) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(portfolio)),
+        }
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, Portfolio> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, Portfolio> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,81 @@
+use crate::auth::Role;
+use crate::money::Money;
+#[cfg(feature = "pricing")]
+use crate::performance::{self, ValueSeries};
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+use crate::{Portfolio, PortfolioResult};
+use chrono::{Datelike, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ValuationSnapshot {
+    pub date: NaiveDate,
+    pub market_value: Money,
+    pub shares: BTreeMap<String, u32>,
+}
+
+impl Portfolio {
+    #[cfg(feature = "pricing")]
+    pub fn record_eod_snapshot(
+        &mut self,
+        date: NaiveDate,
+        prices: &PriceHistory,
+    ) -> PortfolioResult<&ValuationSnapshot> {
+        self.authorize(Role::Trader)?;
+        let market_value = performance::value_as_of(self, prices, date)?;
+        let shares = self
+            .traded_symbols()
+            .into_iter()
+            .map(|symbol| (symbol.to_string(), self.get_share_count_as_of(symbol, date)))
+            .filter(|(_, shares)| *shares > 0)
+            .collect();
+        let snapshot = ValuationSnapshot {
+            date,
+            market_value,
+            shares,
+        };
+        self.snapshots.insert(date, snapshot);
+        Ok(&self.snapshots[&date])
+    }
+
+    pub fn compact_snapshots(&mut self) -> PortfolioResult<usize> {
+        self.authorize(Role::Trader)?;
+        let retention = &self.config.storage.snapshot_retention;
+        let today = self.now().date_naive();
+        let mut kept: BTreeMap<(u8, i32, u32), NaiveDate> = BTreeMap::new();
+        for date in self.snapshots.keys().copied() {
+            let age = (today - date).num_days();
+            let bucket = if age <= retention.daily_days {
+                (0, date.year(), date.ordinal())
+            } else if age <= retention.weekly_days {
+                let week = date.iso_week();
+                (1, week.year(), week.week())
+            } else if retention.monthly_days.is_none_or(|days| age <= days) {
+                (2, date.year(), date.month())
+            } else {
+                continue;
+            };
+            kept.insert(bucket, date);
+        }
+        let before = self.snapshots.len();
+        let kept: Vec<NaiveDate> = kept.into_values().collect();
+        self.snapshots.retain(|date, _| kept.contains(date));
+        Ok(before - self.snapshots.len())
+    }
+
+    pub fn snapshots(&self) -> impl Iterator<Item = &ValuationSnapshot> {
+        self.snapshots.values()
+    }
+
+    #[cfg(feature = "pricing")]
+    pub fn snapshot_series(&self) -> ValueSeries {
+        self.snapshots
+            .values()
+            .map(|snapshot| (snapshot.date, snapshot.market_value))
+            .collect()
+    }
+}
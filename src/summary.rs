@@ -0,0 +1,262 @@
+use crate::basis::AppliedAdjustment;
+use crate::corporate_actions::CashInLieu;
+use crate::dividends::Dividend;
+use crate::ledger::Transaction;
+use crate::money::Money;
+use crate::position::Position;
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+use crate::Quotes;
+use crate::{Portfolio, PortfolioError, PortfolioResult, PurchaseRecord, TradeConfirmation};
+#[cfg(feature = "pricing")]
+use chrono::Duration;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+#[derive(Default)]
+pub(crate) struct SymbolReplay {
+    pub trades: Vec<(PurchaseRecord, TradeConfirmation)>,
+    pub adjustments: Vec<AppliedAdjustment>,
+    pub cash_in_lieu: Vec<CashInLieu>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PositionSummary {
+    pub symbol: String,
+    pub shares: u32,
+    pub cost_basis: Money,
+    pub market_value: Money,
+    pub unrealized_gain: Money,
+    pub realized_gain: Money,
+    pub income: Money,
+    pub weight: Option<Decimal>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PositionDrawdown {
+    pub symbol: String,
+    pub shares: u32,
+    pub average_cost: Money,
+    pub price: Money,
+    pub cost_basis: Money,
+    pub market_value: Money,
+    pub decline_pct: Decimal,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PositionContext {
+    pub symbol: String,
+    pub as_of: NaiveDate,
+    pub price: Money,
+    pub high: Money,
+    pub high_date: NaiveDate,
+    pub low: Money,
+    pub low_date: NaiveDate,
+    pub from_high_pct: Decimal,
+    pub from_low_pct: Decimal,
+}
+
+#[cfg(feature = "pricing")]
+fn percent_from(price: &Money, reference: &Money) -> Decimal {
+    if reference.amount.is_zero() {
+        return Decimal::ZERO;
+    }
+    (price.amount - reference.amount) / reference.amount * Decimal::ONE_HUNDRED
+}
+
+impl Portfolio {
+    #[cfg(feature = "pricing")]
+    pub fn position_context(
+        &self,
+        symbol: &str,
+        history: &PriceHistory,
+    ) -> PortfolioResult<PositionContext> {
+        self.get_purchase_record(symbol)?;
+        let today = self.now().date_naive();
+        let window_start = today - Duration::weeks(52);
+        let closes: Vec<(NaiveDate, Money)> = history
+            .series(symbol)
+            .filter(|(date, _)| *date > window_start && *date <= today)
+            .collect();
+        let missing = || PortfolioError::MissingPrice(symbol.to_string());
+        let (as_of, price) = *closes.last().ok_or_else(missing)?;
+        let (high_date, high) = *closes
+            .iter()
+            .max_by(|a, b| a.1.amount.cmp(&b.1.amount).then(b.0.cmp(&a.0)))
+            .ok_or_else(missing)?;
+        let (low_date, low) = *closes
+            .iter()
+            .min_by(|a, b| a.1.amount.cmp(&b.1.amount).then(a.0.cmp(&b.0)))
+            .ok_or_else(missing)?;
+        Ok(PositionContext {
+            symbol: symbol.to_string(),
+            as_of,
+            price,
+            high,
+            high_date,
+            low,
+            low_date,
+            from_high_pct: percent_from(&price, &high),
+            from_low_pct: percent_from(&price, &low),
+        })
+    }
+
+    pub fn positions_below_basis(
+        &self,
+        quotes: &Quotes,
+        threshold_pct: Decimal,
+    ) -> PortfolioResult<Vec<PositionDrawdown>> {
+        let mut drawdowns = Vec::new();
+        for (symbol, lots) in &self.lots {
+            let shares: u32 = lots.iter().map(|lot| lot.shares).sum();
+            if shares == 0 {
+                continue;
+            }
+            let cost_basis = Money::checked_sum(
+                self.config.base_currency,
+                lots.iter().map(|lot| &lot.cost_basis),
+            )?;
+            if cost_basis.amount <= Decimal::ZERO {
+                continue;
+            }
+            let price = quotes
+                .get(symbol)
+                .ok_or_else(|| PortfolioError::MissingPrice(symbol.clone()))?;
+            let market_value = price.checked_mul(shares.into())?;
+            let decline_pct = (cost_basis.amount - market_value.amount) / cost_basis.amount
+                * Decimal::ONE_HUNDRED;
+            if decline_pct <= threshold_pct {
+                continue;
+            }
+            drawdowns.push(PositionDrawdown {
+                symbol: symbol.clone(),
+                shares,
+                average_cost: Money::new(
+                    cost_basis.amount / Decimal::from(shares),
+                    cost_basis.currency,
+                ),
+                price: *price,
+                cost_basis,
+                market_value,
+                decline_pct,
+            });
+        }
+        drawdowns.sort_by(|a, b| {
+            b.decline_pct
+                .cmp(&a.decline_pct)
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+        Ok(drawdowns)
+    }
+
+    pub fn position_summary(
+        &self,
+        symbol: &str,
+        price: Money,
+        quotes: &Quotes,
+    ) -> PortfolioResult<PositionSummary> {
+        self.get_purchase_record(symbol)?;
+        let cost_basis = self.cost_basis(symbol)?;
+        let market_value = price.checked_mul(self.get_position(symbol).signed_quantity().into())?;
+        let lending_income = self.get_lending_income(symbol);
+        let dividends = self
+            .get_dividends(symbol)
+            .iter()
+            .map(Dividend::amount)
+            .collect::<PortfolioResult<Vec<_>>>()?;
+        let income = self.money_total(
+            std::iter::once(&lending_income).chain(&dividends).chain(
+                self.get_capital_gain_distributions(symbol)
+                    .iter()
+                    .flat_map(|distribution| [&distribution.short_term, &distribution.long_term]),
+            ),
+        )?;
+        Ok(PositionSummary {
+            symbol: symbol.to_string(),
+            shares: self.get_share_count(symbol),
+            cost_basis,
+            unrealized_gain: market_value.checked_sub(&cost_basis)?,
+            market_value,
+            realized_gain: self.realized_gain_to_date(symbol)?,
+            income,
+            weight: self.weight_of(symbol, &market_value, quotes)?,
+        })
+    }
+
+    fn weight_of(
+        &self,
+        symbol: &str,
+        market_value: &Money,
+        quotes: &Quotes,
+    ) -> PortfolioResult<Option<Decimal>> {
+        let mut total = *market_value;
+        for (held, position) in &self.holdings {
+            if held == symbol || *position == Position::Flat {
+                continue;
+            }
+            let Some(price) = quotes.get(held) else {
+                return Ok(None);
+            };
+            total = total.checked_add(&price.checked_mul(position.signed_quantity().into())?)?;
+        }
+        if total.is_zero() {
+            return Ok(Some(Decimal::ZERO));
+        }
+        Ok(Some(
+            market_value.amount / total.amount * Decimal::ONE_HUNDRED,
+        ))
+    }
+
+    fn realized_gain_to_date(&self, symbol: &str) -> PortfolioResult<Money> {
+        let replay = self.replay_symbol(symbol)?;
+        let trade_gains = replay
+            .trades
+            .iter()
+            .filter_map(|(_, confirmation)| confirmation.realized_gain.as_ref());
+        let adjustment_gains = replay
+            .adjustments
+            .iter()
+            .map(|applied| &applied.adjustment.realized_gain);
+        let cash_in_lieu_gains = replay.cash_in_lieu.iter().map(|cash| &cash.gain);
+        Money::checked_sum(
+            self.config.base_currency,
+            trade_gains
+                .chain(adjustment_gains)
+                .chain(cash_in_lieu_gains),
+        )
+    }
+
+    pub(crate) fn replay_symbol(&self, symbol: &str) -> PortfolioResult<SymbolReplay> {
+        self.get_purchase_record(symbol)?;
+        let mut replay = Portfolio::with_config(self.config.clone());
+        replay.instruments = self.instruments.clone();
+        replay.calendar = self.calendar.clone();
+        let mut result = SymbolReplay::default();
+        for transaction in self.ledger.history_of(symbol) {
+            match transaction {
+                Transaction::Trade(trade) => {
+                    let confirmation = replay.apply_trade(trade)?;
+                    if !self.is_reversed(trade.record.id) {
+                        result.trades.push((trade.record.clone(), confirmation));
+                    }
+                }
+                Transaction::ReturnOfCapital {
+                    symbol, adjustment, ..
+                } => {
+                    result.adjustments.push(replay.apply_adjustment(
+                        symbol,
+                        adjustment.per_share_amount,
+                        adjustment.date,
+                    )?);
+                }
+                Transaction::Split { symbol, split, .. } => {
+                    result
+                        .cash_in_lieu
+                        .extend(replay.apply_split(symbol, split)?);
+                }
+                other => replay.apply(other)?,
+            }
+        }
+        Ok(result)
+    }
+}
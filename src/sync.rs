@@ -0,0 +1,271 @@
+use crate::auth::Role;
+use crate::ledger::{Trade, Transaction};
+use crate::reversal::Reversal;
+use crate::versions::Version;
+use crate::{
+    Portfolio, PortfolioError, PortfolioResult, PurchaseRecord, TransactionId, TransactionType,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Delta {
+    pub since: Version,
+    pub version: Version,
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeltaReport {
+    pub applied: Vec<TransactionId>,
+    pub duplicates: Vec<TransactionId>,
+    pub applied_entries: usize,
+    pub duplicate_entries: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub symbol: String,
+    pub record: PurchaseRecord,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    pub merged: Vec<TransactionId>,
+    pub duplicates: usize,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+fn same_trade(entry: &Trade, symbol: &str, record: &PurchaseRecord) -> bool {
+    entry.symbol == symbol
+        && entry.record.date == record.date
+        && entry.record.shares == record.shares
+        && entry.record.transaction_type == record.transaction_type
+        && entry.record.price == record.price
+}
+
+fn merge_key(entry: &Trade) -> (DateTime<Utc>, &str, bool, u32, Option<Decimal>) {
+    (
+        entry.record.date,
+        &entry.symbol,
+        entry.record.transaction_type == TransactionType::Sell,
+        entry.record.shares,
+        entry.record.price.map(|price| price.amount),
+    )
+}
+
+impl Portfolio {
+    pub(crate) fn find_record(&self, id: TransactionId) -> Option<(&str, &PurchaseRecord)> {
+        self.purchase_records.iter().find_map(|(symbol, records)| {
+            records
+                .iter()
+                .find(|record| record.id == id)
+                .map(|record| (symbol.as_str(), record))
+        })
+    }
+
+    fn insert_journal_entries(&mut self, entries: Vec<Transaction>) -> PortfolioResult<()> {
+        let snapshot = self.clone();
+        let mut pending: HashMap<NaiveDate, u32> = HashMap::new();
+        let mut inserted = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let entry = match entry {
+                Transaction::Trade(mut entry) => {
+                    let earlier = pending.entry(entry.record.trade_date()).or_default();
+                    entry.record.day_sequence =
+                        self.records_on(entry.record.trade_date()) + *earlier;
+                    *earlier += 1;
+                    self.next_transaction_id = self.next_transaction_id.max(entry.record.id + 1);
+                    Transaction::Trade(entry)
+                }
+                other => other,
+            };
+            self.ledger.append(entry.clone());
+            inserted.push(entry);
+        }
+        if let Err(error) = self.replay_ledger() {
+            *self = snapshot;
+            return Err(error);
+        }
+        for entry in inserted {
+            self.publish(entry.event());
+        }
+        Ok(())
+    }
+}
+
+pub fn export_delta(portfolio: &Portfolio, since: Version) -> Delta {
+    Delta {
+        since,
+        version: portfolio.version(),
+        transactions: portfolio
+            .changes_since(since)
+            .iter()
+            .filter_map(|change| Transaction::from_event(&change.event))
+            .collect(),
+    }
+}
+
+pub fn apply_delta(portfolio: &mut Portfolio, delta: &Delta) -> PortfolioResult<DeltaReport> {
+    portfolio.authorize(Role::Trader)?;
+    let mut report = DeltaReport::default();
+    let mut conflicts = Vec::new();
+    let mut entries = Vec::new();
+    for transaction in &delta.transactions {
+        if let Transaction::Void(voided) = transaction {
+            let known = portfolio.ledger.trade(voided.record.id);
+            if !known.is_some_and(|trade| same_trade(voided, &trade.symbol, &trade.record)) {
+                conflicts.push(voided.record.id);
+                continue;
+            }
+        }
+        let Transaction::Trade(entry) = transaction else {
+            if portfolio.ledger.transactions().contains(transaction) {
+                report.duplicate_entries += 1;
+            } else {
+                report.applied_entries += 1;
+                entries.push(transaction.clone());
+            }
+            continue;
+        };
+        match portfolio.find_record(entry.record.id) {
+            Some((symbol, record)) if same_trade(entry, symbol, record) => {
+                report.duplicates.push(entry.record.id)
+            }
+            Some(_) => conflicts.push(entry.record.id),
+            None => match portfolio.ledger.trade(entry.record.id) {
+                Some(voided) if same_trade(entry, &voided.symbol, &voided.record) => {
+                    report.duplicates.push(entry.record.id)
+                }
+                Some(_) => conflicts.push(entry.record.id),
+                None => {
+                    portfolio.validate_synced_trade(entry)?;
+                    report.applied.push(entry.record.id);
+                    entries.push(transaction.clone());
+                }
+            },
+        }
+    }
+    if !conflicts.is_empty() {
+        return Err(PortfolioError::SyncConflict(conflicts));
+    }
+    portfolio.insert_journal_entries(entries)?;
+    Ok(report)
+}
+
+fn divergent_entries(portfolio: &Portfolio, other: &Portfolio) -> Vec<Trade> {
+    let voided = portfolio.ledger.voided();
+    let mut entries: Vec<Trade> = portfolio
+        .ledger
+        .transactions()
+        .iter()
+        .filter_map(Transaction::as_trade)
+        .filter(|entry| !voided.contains(&entry.record.id))
+        .filter(|entry| {
+            !matches!(
+                other.find_record(entry.record.id),
+                Some((symbol, record)) if same_trade(entry, symbol, record)
+            )
+        })
+        .cloned()
+        .collect();
+    entries.sort_by_key(|entry| (entry.record.date, entry.record.id));
+    entries
+}
+
+pub fn merge(local: &mut Portfolio, remote: &Portfolio) -> PortfolioResult<MergeReport> {
+    local.authorize(Role::Trader)?;
+    let mut report = MergeReport::default();
+    let local_only = divergent_entries(local, remote);
+    let mut remote_only = divergent_entries(remote, local);
+    let mut combined: Vec<(Trade, bool)> = Vec::new();
+    for entry in &local_only {
+        if let Some(index) = remote_only
+            .iter()
+            .position(|other| same_trade(other, &entry.symbol, &entry.record))
+        {
+            remote_only.remove(index);
+            report.duplicates += 1;
+        }
+        combined.push((entry.clone(), true));
+    }
+    combined.extend(remote_only.into_iter().map(|entry| (entry, false)));
+    combined.sort_by(|(a, _), (b, _)| merge_key(a).cmp(&merge_key(b)));
+
+    let snapshot = local.clone();
+    local.insert_journal_entries(local_only.into_iter().map(Transaction::Void).collect())?;
+
+    let mut next_id = local
+        .ledger
+        .next_transaction_id()
+        .max(remote.ledger.next_transaction_id());
+    let mut renumbered = HashMap::new();
+    let mut remote_renumbered = HashMap::new();
+    for (entry, from_local) in combined {
+        let (source, ids) = if from_local {
+            (&snapshot, &mut renumbered)
+        } else {
+            (remote, &mut remote_renumbered)
+        };
+        let original_id = entry.record.id;
+        let mut renamed = entry.clone();
+        renamed.record.id = next_id;
+        for lot in renamed.lot_selection.iter_mut().flatten() {
+            if let Some(id) = ids.get(&lot.sequence) {
+                lot.sequence = *id;
+            }
+        }
+        if let Some(reversed) = renamed.reverses.as_mut() {
+            if let Some(id) = ids.get(reversed) {
+                *reversed = *id;
+            }
+        }
+        let mut inserted = Vec::new();
+        if let Some(basis) = source.broker_basis_of(original_id) {
+            inserted.push(Transaction::BrokerBasis {
+                transaction_id: next_id,
+                basis,
+            });
+        }
+        inserted.push(Transaction::Trade(renamed));
+        inserted.extend(
+            source
+                .transaction_tags(original_id)
+                .map(|tag| Transaction::Tag {
+                    transaction_id: next_id,
+                    tag: tag.to_string(),
+                }),
+        );
+        if let Some(reversal) = source
+            .reversals()
+            .iter()
+            .find(|reversal| reversal.contra == original_id)
+        {
+            inserted.push(Transaction::Reversal {
+                reversal: Reversal {
+                    original: ids
+                        .get(&reversal.original)
+                        .copied()
+                        .unwrap_or(reversal.original),
+                    contra: next_id,
+                    reason: reversal.reason.clone(),
+                },
+            });
+        }
+        if local.insert_journal_entries(inserted).is_err() {
+            report.conflicts.push(MergeConflict {
+                symbol: entry.symbol,
+                record: entry.record,
+            });
+            continue;
+        }
+        ids.insert(original_id, next_id);
+        report.merged.push(next_id);
+        next_id += 1;
+    }
+    Ok(report)
+}
@@ -0,0 +1,348 @@
+use crate::config::TaxSettings;
+use crate::dividends::Dividend;
+use crate::equity::{EquityAward, EsppSale};
+use crate::gains::{GainLoss, HoldingTerm};
+use crate::money::Money;
+use crate::summary::SymbolReplay;
+use crate::{Portfolio, PortfolioResult, TransactionId};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const QUALIFIED_DIVIDEND_HOLDING_DAYS: i64 = 61;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct TaxBracket {
+    pub floor: Decimal,
+    pub rate: Decimal,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct TaxProfile {
+    pub ordinary_brackets: Vec<TaxBracket>,
+    pub long_term_brackets: Vec<TaxBracket>,
+    pub other_ordinary_income: Decimal,
+}
+
+impl TaxProfile {
+    pub fn flat(short_term_rate: Decimal, long_term_rate: Decimal) -> Self {
+        Self {
+            ordinary_brackets: vec![TaxBracket {
+                floor: Decimal::ZERO,
+                rate: short_term_rate,
+            }],
+            long_term_brackets: vec![TaxBracket {
+                floor: Decimal::ZERO,
+                rate: long_term_rate,
+            }],
+            other_ordinary_income: Decimal::ZERO,
+        }
+    }
+}
+
+impl From<&TaxSettings> for TaxProfile {
+    fn from(settings: &TaxSettings) -> Self {
+        Self::flat(settings.short_term_rate, settings.long_term_rate)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaxEstimate {
+    pub year: i32,
+    pub short_term_gain: Money,
+    pub long_term_gain: Money,
+    pub ordinary_income: Money,
+    pub qualified_dividends: Money,
+    pub ordinary_tax: Money,
+    pub long_term_tax: Money,
+    pub total: Money,
+}
+
+fn tax_on(brackets: &[TaxBracket], stacked_on: Decimal, amount: Decimal) -> Decimal {
+    let mut sorted: Vec<&TaxBracket> = brackets.iter().collect();
+    sorted.sort_by_key(|bracket| bracket.floor);
+    let top = stacked_on + amount;
+    sorted
+        .iter()
+        .enumerate()
+        .map(|(index, bracket)| {
+            let ceiling = sorted
+                .get(index + 1)
+                .map_or(top, |next| next.floor.min(top));
+            let taxed = ceiling - bracket.floor.max(stacked_on);
+            taxed.max(Decimal::ZERO) * bracket.rate
+        })
+        .sum()
+}
+
+fn net_losses(short_term: Decimal, long_term: Decimal) -> (Decimal, Decimal) {
+    if short_term < Decimal::ZERO && long_term > Decimal::ZERO {
+        (Decimal::ZERO, (long_term + short_term).max(Decimal::ZERO))
+    } else if long_term < Decimal::ZERO && short_term > Decimal::ZERO {
+        ((short_term + long_term).max(Decimal::ZERO), Decimal::ZERO)
+    } else {
+        (short_term.max(Decimal::ZERO), long_term.max(Decimal::ZERO))
+    }
+}
+
+struct RealizedLot {
+    symbol: String,
+    sold: DateTime<Utc>,
+    gain: GainLoss,
+    espp_ordinary_income: Option<Money>,
+}
+
+impl RealizedLot {
+    fn capital_gain(&self) -> PortfolioResult<Money> {
+        match &self.espp_ordinary_income {
+            Some(income) => self.gain.gain.checked_sub(income),
+            None => Ok(self.gain.gain),
+        }
+    }
+}
+
+fn realized_lots(
+    portfolio: &Portfolio,
+    symbol: &str,
+    replay: &SymbolReplay,
+    espp_sales: &HashMap<(TransactionId, TransactionId), EsppSale>,
+    year: i32,
+) -> Vec<RealizedLot> {
+    replay
+        .trades
+        .iter()
+        .filter(|(record, _)| portfolio.fiscal_year_of(record.date) == year)
+        .flat_map(|(record, confirmation)| {
+            confirmation.lot_gains.iter().map(|gain| RealizedLot {
+                symbol: symbol.to_string(),
+                sold: record.date,
+                gain: gain.clone(),
+                espp_ordinary_income: espp_sales
+                    .get(&(record.id, gain.consumption.sequence))
+                    .map(|sale| sale.ordinary_income),
+            })
+        })
+        .collect()
+}
+
+fn espp_sales_by_lot(
+    portfolio: &Portfolio,
+) -> PortfolioResult<HashMap<(TransactionId, TransactionId), EsppSale>> {
+    Ok(portfolio
+        .espp_sales()?
+        .into_iter()
+        .map(|sale| ((sale.sale_id, sale.purchase_id), sale))
+        .collect())
+}
+
+fn qualified_shares(portfolio: &Portfolio, symbol: &str, dividend: &Dividend) -> u32 {
+    let ex_date = dividend.ex_date.unwrap_or(dividend.date.date_naive());
+    let held_since = ex_date - Duration::days(QUALIFIED_DIVIDEND_HOLDING_DAYS);
+    portfolio
+        .get_share_count_as_of(symbol, held_since)
+        .min(dividend.shares)
+}
+
+pub fn estimate_liability(
+    portfolio: &Portfolio,
+    year: i32,
+    profile: &TaxProfile,
+) -> PortfolioResult<TaxEstimate> {
+    let currency = portfolio.config().base_currency;
+    let mut short_term_gain = Money::zero(currency);
+    let mut long_term_gain = Money::zero(currency);
+    let mut ordinary_income = Money::zero(currency);
+    let mut qualified_dividends = Money::zero(currency);
+
+    let espp_sales = espp_sales_by_lot(portfolio)?;
+    for symbol in portfolio.traded_symbols() {
+        let replay = portfolio.replay_symbol(symbol)?;
+        for realized in realized_lots(portfolio, symbol, &replay, &espp_sales, year) {
+            if let Some(income) = &realized.espp_ordinary_income {
+                ordinary_income = ordinary_income.checked_add(income)?;
+            }
+            let capital_gain = realized.capital_gain()?;
+            match realized.gain.term {
+                HoldingTerm::ShortTerm => {
+                    short_term_gain = short_term_gain.checked_add(&capital_gain)?
+                }
+                HoldingTerm::LongTerm => {
+                    long_term_gain = long_term_gain.checked_add(&capital_gain)?
+                }
+            }
+        }
+        for applied in replay
+            .adjustments
+            .iter()
+            .filter(|applied| portfolio.fiscal_year_of(applied.adjustment.date) == year)
+        {
+            let short_term = applied
+                .adjustment
+                .realized_gain
+                .checked_sub(&applied.long_term_gain)?;
+            short_term_gain = short_term_gain.checked_add(&short_term)?;
+            long_term_gain = long_term_gain.checked_add(&applied.long_term_gain)?;
+        }
+        for cash in replay
+            .cash_in_lieu
+            .iter()
+            .filter(|cash| portfolio.fiscal_year_of(cash.date) == year)
+        {
+            match cash.term {
+                HoldingTerm::ShortTerm => {
+                    short_term_gain = short_term_gain.checked_add(&cash.gain)?
+                }
+                HoldingTerm::LongTerm => long_term_gain = long_term_gain.checked_add(&cash.gain)?,
+            }
+        }
+        for dividend in portfolio
+            .get_dividends(symbol)
+            .iter()
+            .filter(|dividend| portfolio.fiscal_year_of(dividend.date) == year)
+        {
+            let qualified = dividend
+                .per_share
+                .checked_mul(qualified_shares(portfolio, symbol, dividend).into())?;
+            qualified_dividends = qualified_dividends.checked_add(&qualified)?;
+            ordinary_income =
+                ordinary_income.checked_add(&dividend.amount()?.checked_sub(&qualified)?)?;
+        }
+    }
+    for (_, date, income) in portfolio.lending_income_history() {
+        if portfolio.fiscal_year_of(date) == year {
+            ordinary_income = ordinary_income.checked_add(income)?;
+        }
+    }
+
+    let distributions = portfolio.capital_gain_distribution_totals(year)?;
+    short_term_gain = short_term_gain.checked_add(&distributions.short_term)?;
+    long_term_gain = long_term_gain.checked_add(&distributions.long_term)?;
+    for (_, award) in portfolio.equity_awards() {
+        if let EquityAward::RsuVest {
+            shares, fmv, date, ..
+        } = award
+        {
+            if portfolio.fiscal_year_of(*date) == year {
+                ordinary_income =
+                    ordinary_income.checked_add(&fmv.checked_mul((*shares).into())?)?;
+            }
+        }
+    }
+
+    let (taxable_short_term, taxable_long_term) =
+        net_losses(short_term_gain.amount, long_term_gain.amount);
+    let ordinary_taxable = taxable_short_term + ordinary_income.amount;
+    let ordinary_tax = tax_on(
+        &profile.ordinary_brackets,
+        profile.other_ordinary_income,
+        ordinary_taxable,
+    );
+    let long_term_tax = tax_on(
+        &profile.long_term_brackets,
+        profile.other_ordinary_income + ordinary_taxable,
+        taxable_long_term + qualified_dividends.amount,
+    );
+    let ordinary_tax = Money::new(ordinary_tax.round_dp(2), currency);
+    let long_term_tax = Money::new(long_term_tax.round_dp(2), currency);
+    Ok(TaxEstimate {
+        year,
+        short_term_gain,
+        long_term_gain,
+        ordinary_income,
+        qualified_dividends,
+        total: ordinary_tax.checked_add(&long_term_tax)?,
+        ordinary_tax,
+        long_term_tax,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Form8949Box {
+    A,
+    B,
+    D,
+    E,
+}
+
+impl Form8949Box {
+    fn for_lot(term: HoldingTerm, covered: bool) -> Self {
+        match (term, covered) {
+            (HoldingTerm::ShortTerm, true) => Form8949Box::A,
+            (HoldingTerm::ShortTerm, false) => Form8949Box::B,
+            (HoldingTerm::LongTerm, true) => Form8949Box::D,
+            (HoldingTerm::LongTerm, false) => Form8949Box::E,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Form8949Row {
+    pub form_box: Form8949Box,
+    pub symbol: String,
+    pub shares: u32,
+    pub acquired: NaiveDate,
+    pub sold: NaiveDate,
+    pub proceeds: Money,
+    pub cost_basis: Money,
+    pub gain: Money,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Form8949 {
+    pub year: i32,
+    pub rows: Vec<Form8949Row>,
+}
+
+impl Form8949 {
+    pub fn rows_in(&self, form_box: Form8949Box) -> impl Iterator<Item = &Form8949Row> {
+        self.rows.iter().filter(move |row| row.form_box == form_box)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("box,description,date_acquired,date_sold,proceeds,cost_basis,gain\n");
+        for row in &self.rows {
+            csv.push_str(&format!(
+                "{:?},{} sh {},{},{},{},{},{}\n",
+                row.form_box,
+                row.shares,
+                row.symbol,
+                row.acquired,
+                row.sold,
+                row.proceeds.amount,
+                row.cost_basis.amount,
+                row.gain.amount
+            ));
+        }
+        csv
+    }
+}
+
+pub fn form_8949(portfolio: &Portfolio, year: i32) -> PortfolioResult<Form8949> {
+    let espp_sales = espp_sales_by_lot(portfolio)?;
+    let mut rows = Vec::new();
+    for symbol in portfolio.traded_symbols() {
+        let replay = portfolio.replay_symbol(symbol)?;
+        for realized in realized_lots(portfolio, symbol, &replay, &espp_sales, year) {
+            let gain = realized.capital_gain()?;
+            let consumption = &realized.gain.consumption;
+            rows.push(Form8949Row {
+                form_box: Form8949Box::for_lot(realized.gain.term, consumption.covered),
+                symbol: realized.symbol,
+                shares: consumption.shares,
+                acquired: consumption.acquired.date_naive(),
+                sold: realized.sold.date_naive(),
+                proceeds: realized.gain.proceeds,
+                cost_basis: realized.gain.proceeds.checked_sub(&gain)?,
+                gain,
+            });
+        }
+    }
+    rows.sort_by(|a, b| (a.form_box, a.sold, &a.symbol).cmp(&(b.form_box, b.sold, &b.symbol)));
+    Ok(Form8949 { year, rows })
+}
@@ -0,0 +1,92 @@
+use crate::advisory::*;
+use crate::period::Period;
+use crate::prices::PriceHistory;
+use crate::report::{cash_flows, monthly_statement, CashFlowKind};
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn one_percent() -> AumFee {
+    AumFee {
+        annual_rate: Decimal::new(1, 2),
+    }
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .transact(
+            IBM,
+            10,
+            TransactionType::Purchase,
+            Some(usd(100)),
+            noon(2024, 1, 2),
+        )
+        .unwrap();
+    portfolio
+}
+
+#[fixture]
+fn prices() -> PriceHistory {
+    let mut prices = PriceHistory::new();
+    prices.insert(IBM, date(2024, 1, 2), usd(100));
+    prices.insert(IBM, date(2024, 4, 16), usd(130));
+    prices
+}
+
+#[rstest]
+fn average_balance_weights_each_day_equally(portfolio: Portfolio, prices: PriceHistory) {
+    let april = Period::month(2024, 4).unwrap();
+    assert_eq!(
+        portfolio.average_balance(&april, &prices).unwrap(),
+        usd(1_150)
+    );
+}
+
+#[rstest]
+fn monthly_fee_is_booked_as_cash_outflow(
+    mut portfolio: Portfolio,
+    prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let fee = portfolio.accrue_advisory_fee(&one_percent(), 2024, 4, &prices)?;
+    assert_eq!(fee.average_balance, usd(1_150));
+    assert_eq!(fee.amount, cents(95));
+    assert_eq!(portfolio.advisory_fees(), [fee]);
+
+    let april = Period::month(2024, 4).unwrap();
+    let statement = cash_flows(&portfolio, &april)?;
+    assert_eq!(statement.entries.len(), 1);
+    assert_eq!(statement.entries[0].kind, CashFlowKind::Fee);
+    assert_eq!(statement.entries[0].amount, cents(-95));
+    assert_eq!(
+        monthly_statement(&portfolio, 2024, 4, &prices)?.fees,
+        cents(95)
+    );
+    Ok(())
+}
+
+#[rstest]
+fn month_cannot_be_accrued_twice(mut portfolio: Portfolio, prices: PriceHistory) {
+    portfolio
+        .accrue_advisory_fee(&one_percent(), 2024, 4, &prices)
+        .unwrap();
+    assert!(matches!(
+        portfolio.accrue_advisory_fee(&one_percent(), 2024, 4, &prices),
+        Err(PortfolioError::FeeAlreadyAccrued(start)) if start == date(2024, 4, 1)
+    ));
+    assert_eq!(portfolio.advisory_fees().len(), 1);
+}
+
+#[rstest]
+fn negative_fee_is_rejected(mut portfolio: Portfolio, prices: PriceHistory) {
+    let rebate = AumFee {
+        annual_rate: Decimal::new(-1, 2),
+    };
+    assert!(matches!(
+        portfolio.accrue_advisory_fee(&rebate, 2024, 4, &prices),
+        Err(PortfolioError::NegativeAmount)
+    ));
+    assert!(portfolio.advisory_fees().is_empty());
+}
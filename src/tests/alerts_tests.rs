@@ -0,0 +1,141 @@
+use crate::alerts::*;
+use crate::clock::FixedClock;
+use crate::prices::PriceHistory;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(VTI, 10, usd(100)).unwrap();
+    p
+}
+
+#[fixture]
+fn prices() -> PriceHistory {
+    let mut h = PriceHistory::new();
+    h.insert(IBM, date(2024, 1, 2), usd(120));
+    h.insert(IBM, date(2024, 1, 3), usd(102));
+    h.insert(IBM, date(2024, 1, 4), usd(85));
+    h.insert(VTI, date(2024, 1, 2), usd(100));
+    h
+}
+
+fn drawdown() -> AlertCondition {
+    AlertCondition::DrawdownFromHigh {
+        symbol: IBM.to_string(),
+        percent: Decimal::from(15),
+    }
+}
+
+#[rstest]
+fn triggers_once_and_does_not_refire(
+    mut portfolio: Portfolio,
+    prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let id = portfolio.add_alert("IBM drawdown", drawdown())?;
+    let events = portfolio.subscribe();
+    assert!(portfolio
+        .check_alerts(&prices, date(2024, 1, 2))?
+        .is_empty());
+    assert_eq!(portfolio.check_alerts(&prices, date(2024, 1, 3))?, vec![id]);
+    assert!(portfolio
+        .check_alerts(&prices, date(2024, 1, 4))?
+        .is_empty());
+    assert_eq!(portfolio.alerts()[0].state, AlertState::Triggered);
+    assert_eq!(events.try_iter().count(), 1);
+    Ok(())
+}
+
+#[rstest]
+fn acknowledged_alert_rearms_once_condition_clears(
+    mut portfolio: Portfolio,
+    mut prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let id = portfolio.add_alert("IBM drawdown", drawdown())?;
+    portfolio.check_alerts(&prices, date(2024, 1, 3))?;
+    portfolio.acknowledge_alert(id)?;
+    assert!(portfolio
+        .check_alerts(&prices, date(2024, 1, 4))?
+        .is_empty());
+    assert_eq!(portfolio.alerts()[0].state, AlertState::Acknowledged);
+
+    prices.insert(IBM, date(2024, 1, 5), usd(119));
+    portfolio.check_alerts(&prices, date(2024, 1, 5))?;
+    assert_eq!(portfolio.alerts()[0].state, AlertState::Armed);
+    prices.insert(IBM, date(2024, 1, 6), usd(90));
+    assert_eq!(portfolio.check_alerts(&prices, date(2024, 1, 6))?, vec![id]);
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    AlertCondition::PercentBelowBasis { symbol: IBM.to_string(), percent: Decimal::from(10) },
+    vec![false, false, true]
+)]
+#[case(
+    AlertCondition::AllocationDrift {
+        symbol: IBM.to_string(),
+        target_percent: Decimal::from(50),
+        tolerance_percent: Decimal::from(4),
+    },
+    vec![true, false, true]
+)]
+#[case(
+    AlertCondition::All(vec![
+        AlertCondition::PriceBelow { symbol: IBM.to_string(), price: Decimal::from(110) },
+        AlertCondition::PriceAbove { symbol: VTI.to_string(), price: Decimal::from(90) },
+    ]),
+    vec![false, true, true]
+)]
+fn evaluates_composite_conditions(
+    portfolio: Portfolio,
+    prices: PriceHistory,
+    #[case] condition: AlertCondition,
+    #[case] expected: Vec<bool>,
+) {
+    let triggered: Vec<bool> = [date(2024, 1, 2), date(2024, 1, 3), date(2024, 1, 4)]
+        .into_iter()
+        .map(|date| {
+            let mut fresh = portfolio.clone();
+            fresh.add_alert("alert", condition.clone()).unwrap();
+            !fresh.check_alerts(&prices, date).unwrap().is_empty()
+        })
+        .collect();
+    assert_eq!(triggered, expected);
+}
+
+#[rstest]
+fn triggers_on_distribution_received(
+    mut portfolio: Portfolio,
+    prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let id = portfolio.add_alert(
+        "VTI paid out",
+        AlertCondition::DistributionReceived {
+            symbol: VTI.to_string(),
+        },
+    )?;
+    portfolio.record_capital_gain_distribution(
+        VTI,
+        usd(5),
+        usd(0),
+        date(2024, 1, 3).and_hms_opt(0, 0, 0).unwrap().and_utc(),
+    )?;
+    assert!(portfolio
+        .check_alerts(&prices, date(2024, 1, 2))?
+        .is_empty());
+    assert_eq!(portfolio.check_alerts(&prices, date(2024, 1, 3))?, vec![id]);
+    Ok(())
+}
+
+#[rstest]
+fn error_when_acknowledging_unknown_alert(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.acknowledge_alert(7),
+        Err(PortfolioError::UnknownAlert(7))
+    ));
+}
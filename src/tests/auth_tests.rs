@@ -0,0 +1,161 @@
+use crate::auth::*;
+use crate::goals::Goal;
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+
+fn owner() -> Actor {
+    Actor::new("alice")
+}
+
+fn advisor() -> Actor {
+    Actor::new("bob")
+}
+
+fn spouse() -> Actor {
+    Actor::new("carol")
+}
+
+#[fixture]
+fn household() -> Portfolio {
+    let mut p = Portfolio::with_owner(owner());
+    {
+        let mut acting = p.acting_as(&owner()).unwrap();
+        acting.grant(advisor(), Role::Trader).unwrap();
+        acting.grant(spouse(), Role::Viewer).unwrap();
+    }
+    p
+}
+
+#[rstest]
+fn resolves_roles(household: Portfolio) {
+    assert_eq!(household.role_of(&owner()), Some(Role::Owner));
+    assert_eq!(household.role_of(&advisor()), Some(Role::Trader));
+    assert_eq!(household.role_of(&spouse()), Some(Role::Viewer));
+    assert_eq!(household.role_of(&Actor::new("mallory")), None);
+}
+
+#[rstest]
+fn owner_and_trader_can_trade(mut household: Portfolio) -> PortfolioResult<()> {
+    household
+        .acting_as(&owner())?
+        .purchase_at(IBM, 10, usd(100))?;
+    household.acting_as(&advisor())?.sell_at(IBM, 4, usd(110))?;
+    assert_eq!(household.get_share_count(IBM), 6);
+    Ok(())
+}
+
+#[rstest]
+fn viewer_cannot_trade(mut household: Portfolio) -> PortfolioResult<()> {
+    let mut acting = household.acting_as(&spouse())?;
+    assert_eq!(acting.view().get_share_count(IBM), 0);
+    assert!(matches!(
+        acting.purchase(IBM, 1),
+        Err(PortfolioError::PermissionDenied(actor)) if actor == "carol"
+    ));
+    drop(acting);
+    assert!(household.is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn viewer_can_preview_sells(mut household: Portfolio) -> PortfolioResult<()> {
+    household
+        .acting_as(&advisor())?
+        .purchase_at(IBM, 10, usd(100))?;
+    let acting = household.acting_as(&spouse())?;
+    let preview = acting.preview_sell(IBM, 4, usd(110))?;
+    assert_eq!(preview.realized_gain, usd(40));
+    drop(acting);
+    assert_eq!(household.get_share_count(IBM), 10);
+    Ok(())
+}
+
+#[rstest]
+fn viewer_cannot_change_the_ledger(mut household: Portfolio) -> PortfolioResult<()> {
+    let id = household
+        .acting_as(&advisor())?
+        .purchase_at(IBM, 10, usd(100))?
+        .transaction_id;
+    let mut acting = household.acting_as(&spouse())?;
+    let denied = |result: PortfolioResult<()>| matches!(result, Err(PortfolioError::PermissionDenied(actor)) if actor == "carol");
+    assert!(denied(acting.reverse_transaction(id, "typo").map(|_| ())));
+    assert!(denied(
+        acting
+            .record_dividend(IBM, usd(1), noon(2024, 3, 1), None)
+            .map(|_| ())
+    ));
+    assert!(denied(
+        acting
+            .liquidate(&Quotes::from([(IBM.to_string(), usd(110))]))
+            .map(|_| ())
+    ));
+    assert!(denied(acting.record_deposit(usd(500), noon(2024, 3, 1))));
+    assert!(denied(acting.instruments_mut().map(|_| ())));
+    drop(acting);
+    assert_eq!(household.get_share_count(IBM), 10);
+    Ok(())
+}
+
+#[rstest]
+fn owned_portfolio_rejects_changes_without_an_actor(mut household: Portfolio) {
+    assert!(matches!(
+        household.purchase(IBM, 1),
+        Err(PortfolioError::ActorRequired)
+    ));
+    assert!(matches!(
+        household.add_goal(Goal {
+            name: "House".to_string(),
+            target_value: usd(100_000),
+            target_date: date(2030, 1, 1),
+            monthly_contribution: usd(1_000),
+        }),
+        Err(PortfolioError::ActorRequired)
+    ));
+    assert!(household.is_empty());
+}
+
+#[rstest]
+fn acting_ends_when_the_handle_is_dropped(mut household: Portfolio) -> PortfolioResult<()> {
+    household.acting_as(&owner())?.purchase(IBM, 1)?;
+    assert!(matches!(
+        household.sell(IBM, 1),
+        Err(PortfolioError::ActorRequired)
+    ));
+    Ok(())
+}
+
+#[rstest]
+fn unknown_actor_cannot_act(mut household: Portfolio) {
+    assert!(matches!(
+        household.acting_as(&Actor::new("mallory")),
+        Err(PortfolioError::PermissionDenied(_))
+    ));
+}
+
+#[rstest]
+fn only_owner_manages_members(mut household: Portfolio) -> PortfolioResult<()> {
+    assert!(matches!(
+        household
+            .acting_as(&advisor())?
+            .grant(Actor::new("dave"), Role::Trader),
+        Err(PortfolioError::PermissionDenied(_))
+    ));
+    household.acting_as(&owner())?.revoke(&advisor())?;
+    assert_eq!(household.role_of(&advisor()), None);
+    Ok(())
+}
+
+#[rstest]
+fn unowned_portfolio_has_no_members() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    assert_eq!(portfolio.role_of(&advisor()), None);
+    assert!(matches!(
+        portfolio.acting_as(&advisor()),
+        Err(PortfolioError::NoAccessControl)
+    ));
+    portfolio.purchase(IBM, 1)?;
+    assert_eq!(portfolio.get_share_count(IBM), 1);
+    Ok(())
+}
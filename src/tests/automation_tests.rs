@@ -0,0 +1,153 @@
+use crate::automation::*;
+use crate::events::PortfolioEvent;
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+const RULES: &str = r#"
+[[rules]]
+name = "trim IBM"
+mode = "execute"
+
+[rules.when]
+kind = "weight_above"
+symbol = "IBM"
+percent = "25"
+
+[rules.then]
+kind = "sell"
+symbol = "IBM"
+shares = 2
+
+[[rules]]
+name = "buy the dip"
+
+[rules.when]
+kind = "all"
+conditions = [
+    { kind = "price_below", symbol = "VTI", price = "200" },
+    { kind = "shares_below", symbol = "VTI", shares = 100 },
+]
+
+[rules.then]
+kind = "buy"
+symbol = "VTI"
+shares = 5
+
+[[rules]]
+name = "IBM rally"
+mode = "execute"
+
+[rules.when]
+kind = "price_above"
+symbol = "IBM"
+price = "500"
+
+[rules.then]
+kind = "alert"
+symbol = "IBM"
+message = "IBM above 500"
+"#;
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(VTI, 10, usd(200)).unwrap();
+    for rule in RuleSet::from_toml_str(RULES).unwrap().rules {
+        p.add_automation_rule(rule).unwrap();
+    }
+    p
+}
+
+fn quotes(ibm: i64, vti: i64) -> Quotes {
+    Quotes::from([(IBM.to_string(), usd(ibm)), (VTI.to_string(), usd(vti))])
+}
+
+#[rstest]
+fn parses_serialized_rules(portfolio: Portfolio) {
+    let rules = portfolio.automation_rules();
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[1].mode, RuleMode::Propose);
+    assert_eq!(
+        rules[0].when,
+        Condition::WeightAbove {
+            symbol: IBM.to_string(),
+            percent: Decimal::from(25),
+        }
+    );
+}
+
+#[rstest]
+fn executes_and_proposes_matching_rules(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let outcomes = portfolio.run_automation(&quotes(100, 150))?;
+    let summary: Vec<(&str, &ActionStatus)> = outcomes
+        .iter()
+        .map(|outcome| (outcome.rule.as_str(), &outcome.status))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("trim IBM", &ActionStatus::Executed(Some(2))),
+            ("buy the dip", &ActionStatus::Proposed),
+        ]
+    );
+    assert_eq!(portfolio.get_share_count(IBM), 8);
+    assert_eq!(portfolio.get_share_count(VTI), 10);
+    Ok(())
+}
+
+#[rstest]
+fn no_actions_when_conditions_do_not_hold(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    assert!(portfolio.run_automation(&quotes(50, 250))?.is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn alert_actions_publish_events(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let events = portfolio.subscribe();
+    let outcomes = portfolio.run_automation(&quotes(600, 250))?;
+    assert_eq!(
+        outcomes.last().unwrap().status,
+        ActionStatus::Executed(None)
+    );
+    assert!(events.try_iter().any(|event| event
+        == PortfolioEvent::Alert {
+            symbol: IBM.to_string(),
+            message: "IBM above 500".to_string(),
+        }));
+    Ok(())
+}
+
+#[rstest]
+fn failed_actions_are_reported_not_raised(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    portfolio.add_automation_rule(Rule {
+        name: "oversell".to_string(),
+        when: Condition::SharesBelow {
+            symbol: IBM.to_string(),
+            shares: 100,
+        },
+        then: Action::Sell {
+            symbol: IBM.to_string(),
+            shares: 50,
+        },
+        mode: RuleMode::Execute,
+    })?;
+    let outcomes = portfolio.run_automation(&quotes(50, 250))?;
+    assert_eq!(
+        outcomes[0].status,
+        ActionStatus::Failed("Cannot sell more shares than owned".to_string())
+    );
+    Ok(())
+}
+
+#[rstest]
+fn error_when_rule_references_unquoted_symbol(mut portfolio: Portfolio) {
+    let quotes = Quotes::from([(IBM.to_string(), usd(100))]);
+    assert!(matches!(
+        portfolio.run_automation(&quotes),
+        Err(PortfolioError::MissingPrice(symbol)) if symbol == VTI
+    ));
+}
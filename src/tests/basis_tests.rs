@@ -0,0 +1,70 @@
+use crate::money::Money;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+const REIT: &str = "O";
+
+#[fixture]
+fn portfolio_with_reit() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(REIT, 10, usd(5)).unwrap();
+    p.purchase_at(REIT, 10, usd(2)).unwrap();
+    p
+}
+
+#[rstest]
+fn return_of_capital_reduces_lot_basis(mut portfolio_with_reit: Portfolio) -> PortfolioResult<()> {
+    let adjustment =
+        portfolio_with_reit.apply_return_of_capital(REIT, usd(1), Portfolio::fixed_date_time())?;
+    assert_eq!(adjustment.basis_reduction, usd(20));
+    assert_eq!(adjustment.realized_gain, usd(0));
+    let bases: Vec<Money> = portfolio_with_reit.lots[REIT]
+        .iter()
+        .map(|lot| lot.cost_basis)
+        .collect();
+    assert_eq!(bases, vec![usd(40), usd(10)]);
+    Ok(())
+}
+
+#[rstest]
+fn return_of_capital_beyond_basis_is_realized_gain(
+    mut portfolio_with_reit: Portfolio,
+) -> PortfolioResult<()> {
+    let adjustment =
+        portfolio_with_reit.apply_return_of_capital(REIT, usd(3), Portfolio::fixed_date_time())?;
+    assert_eq!(adjustment.basis_reduction, usd(50));
+    assert_eq!(adjustment.realized_gain, usd(10));
+    let bases: Vec<Money> = portfolio_with_reit.lots[REIT]
+        .iter()
+        .map(|lot| lot.cost_basis)
+        .collect();
+    assert_eq!(bases, vec![usd(20), usd(0)]);
+    Ok(())
+}
+
+#[rstest]
+fn records_return_of_capital_history(mut portfolio_with_reit: Portfolio) -> PortfolioResult<()> {
+    let first =
+        portfolio_with_reit.apply_return_of_capital(REIT, usd(1), Portfolio::fixed_date_time())?;
+    assert_eq!(
+        portfolio_with_reit.get_return_of_capital_history(REIT),
+        [first]
+    );
+    assert!(portfolio_with_reit
+        .get_return_of_capital_history("IBM")
+        .is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn error_on_return_of_capital_without_open_lots(mut portfolio_with_reit: Portfolio) {
+    assert!(matches!(
+        portfolio_with_reit.apply_return_of_capital("IBM", usd(1), Portfolio::fixed_date_time()),
+        Err(PortfolioError::NoOpenLots)
+    ));
+    assert!(matches!(
+        portfolio_with_reit.apply_return_of_capital(REIT, usd(-1), Portfolio::fixed_date_time()),
+        Err(PortfolioError::NegativeAmount)
+    ));
+}
@@ -0,0 +1,108 @@
+use crate::blotter::*;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{DateTime, Utc};
+use rstest::*;
+
+fn at_hour(day: u32, hour: u32) -> DateTime<Utc> {
+    date(2024, 3, day)
+        .and_hms_opt(hour, 0, 0)
+        .unwrap()
+        .and_utc()
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.set_clock(|| at_hour(31, 0));
+    p.record_deposit(usd(10_000), at_hour(4, 9)).unwrap();
+    p.transact(
+        IBM,
+        20,
+        TransactionType::Purchase,
+        Some(usd(100)),
+        at_hour(4, 10),
+    )
+    .unwrap();
+    p.transact(
+        VTI,
+        10,
+        TransactionType::Purchase,
+        Some(usd(200)),
+        at_hour(5, 11),
+    )
+    .unwrap();
+    p.transact(
+        IBM,
+        5,
+        TransactionType::Sell,
+        Some(usd(110)),
+        at_hour(5, 10),
+    )
+    .unwrap();
+    p.record_dividend(IBM, usd(1), at_hour(5, 16), None)
+        .unwrap();
+    p.record_withdrawal(usd(300), at_hour(5, 17)).unwrap();
+    p
+}
+
+#[rstest]
+fn lists_day_activity_in_execution_order(portfolio: Portfolio) -> PortfolioResult<()> {
+    let blotter = portfolio.blotter(at_hour(5, 0).date_naive())?;
+    let activity: Vec<(Option<&str>, &BlotterActivity)> = blotter
+        .entries
+        .iter()
+        .map(|entry| (entry.symbol.as_deref(), &entry.activity))
+        .collect();
+    assert_eq!(
+        activity,
+        vec![
+            (
+                Some(IBM),
+                &BlotterActivity::Trade {
+                    transaction_type: TransactionType::Sell,
+                    shares: 5,
+                    price: Some(usd(110)),
+                }
+            ),
+            (
+                Some(VTI),
+                &BlotterActivity::Trade {
+                    transaction_type: TransactionType::Purchase,
+                    shares: 10,
+                    price: Some(usd(200)),
+                }
+            ),
+            (Some(IBM), &BlotterActivity::Dividend { amount: usd(15) }),
+            (None, &BlotterActivity::Withdrawal { amount: usd(300) }),
+        ]
+    );
+    assert_eq!(blotter.entries[0].transaction_id, Some(2));
+    Ok(())
+}
+
+#[rstest]
+fn summarizes_day_totals(portfolio: Portfolio) -> PortfolioResult<()> {
+    let summary = portfolio.blotter(at_hour(5, 0).date_naive())?.summary;
+    assert_eq!(
+        summary,
+        BlotterSummary {
+            trades: 2,
+            shares_bought: 10,
+            shares_sold: 5,
+            bought: usd(2_000),
+            sold: usd(550),
+            income: usd(15),
+            net_cash: usd(-1_735),
+        }
+    );
+    Ok(())
+}
+
+#[rstest]
+fn quiet_day_has_empty_blotter(portfolio: Portfolio) -> PortfolioResult<()> {
+    let blotter = portfolio.blotter(at_hour(6, 0).date_naive())?;
+    assert!(blotter.entries.is_empty());
+    assert!(blotter.summary.net_cash.is_zero());
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use crate::calendar::*;
+use crate::instruments::{Instrument, InstrumentKind};
+use crate::tests::helpers::*;
+use crate::{PortfolioError, PortfolioResult};
+use chrono::NaiveDate;
+use rstest::*;
+
+#[fixture]
+fn july_fourth() -> FixedHolidays {
+    FixedHolidays::new([date(2024, 7, 4)])
+}
+
+#[rstest]
+#[case(date(2024, 7, 1), date(2024, 7, 2))]
+#[case(date(2024, 7, 3), date(2024, 7, 5))]
+#[case(date(2024, 7, 5), date(2024, 7, 8))]
+#[case(date(2024, 7, 6), date(2024, 7, 8))]
+fn next_trading_day_skips_weekends_and_holidays(
+    july_fourth: FixedHolidays,
+    #[case] from: NaiveDate,
+    #[case] expected: NaiveDate,
+) -> PortfolioResult<()> {
+    assert_eq!(next_trading_day(from, &july_fourth)?, expected);
+    Ok(())
+}
+
+#[rstest]
+fn next_trading_day_reports_an_exhausted_calendar() {
+    assert!(matches!(
+        next_trading_day(NaiveDate::MAX, &WeekendsOnly),
+        Err(PortfolioError::NoTradingDay(date)) if date == NaiveDate::MAX
+    ));
+}
+
+#[rstest]
+#[case(date(2024, 7, 3), date(2024, 7, 3))]
+#[case(date(2024, 7, 4), date(2024, 7, 5))]
+#[case(date(2024, 7, 6), date(2024, 7, 8))]
+fn trading_session_rolls_forward_from_closed_days(
+    july_fourth: FixedHolidays,
+    #[case] from: NaiveDate,
+    #[case] expected: NaiveDate,
+) -> PortfolioResult<()> {
+    assert_eq!(trading_session(from, &july_fourth)?, expected);
+    Ok(())
+}
+
+#[rstest]
+fn counts_trading_days_after_start_through_end(july_fourth: FixedHolidays) {
+    assert_eq!(
+        trading_days_between(date(2024, 7, 1), date(2024, 7, 8), &july_fourth),
+        4
+    );
+    assert_eq!(
+        trading_days_between(date(2024, 7, 1), date(2024, 7, 8), &WeekendsOnly),
+        5
+    );
+    assert_eq!(
+        trading_days_between(date(2024, 7, 8), date(2024, 7, 1), &july_fourth),
+        0
+    );
+}
+
+#[rstest]
+fn adds_trading_days(july_fourth: FixedHolidays) -> PortfolioResult<()> {
+    assert_eq!(
+        add_trading_days(date(2024, 7, 3), 0, &july_fourth)?,
+        date(2024, 7, 3)
+    );
+    assert_eq!(
+        add_trading_days(date(2024, 7, 3), 2, &july_fourth)?,
+        date(2024, 7, 8)
+    );
+    Ok(())
+}
+
+#[rstest]
+fn settlement_rolls_over_weekends_and_holidays(july_fourth: FixedHolidays) -> PortfolioResult<()> {
+    let trade_date = date(2024, 7, 3).and_hms_opt(15, 30, 0).unwrap().and_utc();
+    let stock = Instrument::new(InstrumentKind::Stock);
+    let bond = Instrument::new(InstrumentKind::Bond);
+    let crypto = Instrument::new(InstrumentKind::Crypto);
+    assert_eq!(
+        stock
+            .settlement_date(trade_date, &july_fourth)?
+            .date_naive(),
+        date(2024, 7, 5)
+    );
+    assert_eq!(
+        bond.settlement_date(trade_date, &july_fourth)?.date_naive(),
+        date(2024, 7, 8)
+    );
+    assert_eq!(
+        crypto.settlement_date(trade_date, &july_fourth)?,
+        trade_date
+    );
+    Ok(())
+}
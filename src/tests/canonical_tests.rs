@@ -0,0 +1,442 @@
+use crate::advisory::AumFee;
+use crate::alerts::AlertCondition;
+use crate::auth::{AccessControl, Actor, Role};
+use crate::automation::{Action, Condition, Rule, RuleMode};
+use crate::canonical::SCHEMA_VERSION;
+use crate::clock::FixedClock;
+use crate::config::{PortfolioConfig, RuleSettings};
+use crate::execution::paper::PaperBroker;
+use crate::goals::Goal;
+use crate::instruments::InstrumentKind;
+use crate::integrity::ImportedTransaction;
+use crate::liabilities::LiabilityKind;
+use crate::lots::{ConsolidationPolicy, LotSelection};
+use crate::position::Position;
+use crate::prices::PriceHistory;
+use crate::sync::{apply_delta, export_delta};
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+use std::path::PathBuf;
+
+fn build() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(VTI, 20, usd(200)).unwrap();
+    p.purchase(IBM, 5).unwrap();
+    let sell = p.sell_at(VTI, 4, usd(210)).unwrap();
+    p.reverse_transaction(sell.transaction_id, "fat finger")
+        .unwrap();
+    p.tag_transaction(0, "core").unwrap();
+    p.tag_transaction(1, "index").unwrap();
+    p.apply_return_of_capital(VTI, usd(1), at(2024, 3, 1))
+        .unwrap();
+    p.record_capital_gain_distribution(VTI, usd(5), usd(7), at(2024, 6, 1))
+        .unwrap();
+    p.inherit(IBM, 2, usd(90), at(2024, 2, 1)).unwrap();
+    let unpriced = p.open_lots(IBM)[1].id;
+    let selection = LotSelection {
+        lot_id: unpriced,
+        shares: 2,
+    };
+    p.sell_lots(IBM, &[selection], usd(120), at(2024, 9, 1))
+        .unwrap();
+    p.lend_shares(IBM, 3).unwrap();
+    p.accrue_lending_income(IBM, usd(2)).unwrap();
+    p.record_deposit(usd(500), at(2024, 1, 2)).unwrap();
+    p.record_withdrawal(usd(50), at(2024, 7, 1)).unwrap();
+    let fee = AumFee {
+        annual_rate: Decimal::new(1, 2),
+    };
+    let mut prices = PriceHistory::new();
+    prices.insert(IBM, at(2024, 1, 1).date_naive(), usd(100));
+    prices.insert(VTI, at(2024, 1, 1).date_naive(), usd(200));
+    p.accrue_advisory_fee(&fee, 2024, 1, &prices).unwrap();
+    p.record_dividend(IBM, usd(1), at(2024, 8, 1), None)
+        .unwrap();
+    p.add_external_position("401k", VTI, 12).unwrap();
+    p.add_manual_asset("House", usd(300_000), at(2024, 1, 1).date_naive())
+        .unwrap();
+    p.add_liability(
+        "Mortgage",
+        LiabilityKind::Loan,
+        usd(200_000),
+        at(2024, 1, 1).date_naive(),
+    )
+    .unwrap();
+    p
+}
+
+#[rstest]
+fn round_trips_through_canonical_bytes() -> PortfolioResult<()> {
+    let portfolio = build();
+    let bytes = portfolio.canonical_bytes()?;
+    let loaded = Portfolio::from_canonical_bytes(&bytes, PortfolioConfig::default())?;
+    assert_eq!(loaded.canonical_bytes()?, bytes);
+    assert_eq!(loaded.journal(), portfolio.journal());
+    assert_eq!(loaded.lots, portfolio.lots);
+    let selected_sale = portfolio.journal().last().unwrap().1.id;
+    assert!(loaded.lot_selection_of(selected_sale).is_some());
+    assert_eq!(
+        loaded.lot_selection_of(selected_sale),
+        portfolio.lot_selection_of(selected_sale)
+    );
+    assert_eq!(loaded.get_shares_on_loan(IBM), 3);
+    assert_eq!(loaded.reversals(), portfolio.reversals());
+    assert_eq!(loaded.deposits(), portfolio.deposits());
+    assert_eq!(loaded.withdrawals(), portfolio.withdrawals());
+    assert_eq!(loaded.advisory_fees(), portfolio.advisory_fees());
+    assert_eq!(loaded.external_positions(), portfolio.external_positions());
+    assert_eq!(loaded.manual_assets(), portfolio.manual_assets());
+    assert_eq!(loaded.liabilities(), portfolio.liabilities());
+    assert_eq!(loaded.get_dividends(IBM), portfolio.get_dividends(IBM));
+    assert_eq!(
+        loaded.transaction_tags(1).collect::<Vec<_>>(),
+        vec!["index"]
+    );
+    assert!(loaded.verify_integrity().is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn bytes_are_stable_across_instances() -> PortfolioResult<()> {
+    assert_eq!(build().canonical_bytes()?, build().canonical_bytes()?);
+    Ok(())
+}
+
+#[rstest]
+fn loaded_portfolio_continues_transaction_ids() -> PortfolioResult<()> {
+    let bytes = build().canonical_bytes()?;
+    let mut loaded = Portfolio::from_canonical_bytes(&bytes, PortfolioConfig::default())?;
+    assert_eq!(loaded.purchase(IBM, 1)?.transaction_id, 7);
+    Ok(())
+}
+
+#[rstest]
+fn loaded_portfolio_keeps_its_version_for_sync() -> PortfolioResult<()> {
+    let mut server = build();
+    let mut client = Portfolio::new();
+    apply_delta(&mut client, &export_delta(&server, 0))?;
+    let synced = server.version();
+    let bytes = server.canonical_bytes()?;
+    server = Portfolio::from_canonical_bytes(&bytes, PortfolioConfig::default())?;
+    assert_eq!(server.version(), synced);
+    server.purchase_at(IBM, 1, usd(100))?;
+    apply_delta(&mut client, &export_delta(&server, synced))?;
+    assert_eq!(client.get_share_count(IBM), server.get_share_count(IBM));
+    Ok(())
+}
+
+#[rstest]
+fn rejects_malformed_bytes() {
+    assert!(matches!(
+        Portfolio::from_canonical_bytes(b"transactions = 3", PortfolioConfig::default()),
+        Err(PortfolioError::InvalidSerializedPortfolio(_))
+    ));
+}
+
+fn build_fully_populated() -> Portfolio {
+    let mut p = build();
+    p.instruments_mut()
+        .unwrap()
+        .register(IBM, InstrumentKind::Stock);
+    p.instruments_mut()
+        .unwrap()
+        .set_sector(IBM, "Technology")
+        .unwrap();
+    p.add_goal(Goal {
+        name: "Retire".to_string(),
+        target_value: usd(1_000_000),
+        target_date: date(2040, 1, 1),
+        monthly_contribution: usd(1_000),
+    })
+    .unwrap();
+    p.add_alert(
+        "IBM high",
+        AlertCondition::PriceAbove {
+            symbol: IBM.to_string(),
+            price: Decimal::new(150, 0),
+        },
+    )
+    .unwrap();
+    p.add_automation_rule(Rule {
+        name: "Rebuy".to_string(),
+        when: Condition::SharesBelow {
+            symbol: VTI.to_string(),
+            shares: 5,
+        },
+        then: Action::Buy {
+            symbol: VTI.to_string(),
+            shares: 5,
+        },
+        mode: RuleMode::Propose,
+    })
+    .unwrap();
+    let mut prices = PriceHistory::new();
+    prices.insert(IBM, date(2024, 1, 2), usd(100));
+    prices.insert(VTI, date(2024, 1, 2), usd(200));
+    p.record_eod_snapshot(date(2024, 1, 2), &prices).unwrap();
+    p.record_rsu_vest(AAPL, 4, usd(150), at(2024, 5, 1))
+        .unwrap();
+    p.consolidate_lots(IBM, &ConsolidationPolicy::default())
+        .unwrap();
+    p.submit(Order {
+        symbol: VTI.to_string(),
+        transaction_type: TransactionType::Purchase,
+        shares: 1,
+        price: Some(usd(200)),
+        idempotency_key: Some("order-1".to_string()),
+    })
+    .unwrap();
+    let quotes = Quotes::from([(VTI.to_string(), usd(200))]);
+    let mut broker = PaperBroker::new(quotes)
+        .with_partial_fills(1)
+        .with_clock(Portfolio::fixed_date_time);
+    let order_id = p
+        .route_order(
+            &mut broker,
+            Order {
+                symbol: VTI.to_string(),
+                transaction_type: TransactionType::Purchase,
+                shares: 3,
+                price: None,
+                idempotency_key: None,
+            },
+        )
+        .unwrap();
+    p.collect_fills(&mut broker, &order_id).unwrap();
+    p.queued_transactions.push(ImportedTransaction {
+        symbol: IBM.to_string(),
+        date: at(2030, 1, 1),
+        transaction_type: TransactionType::Purchase,
+        shares: 1,
+        price: Some(usd(100)),
+        net_amount: None,
+    });
+    p.access = Some(AccessControl::new(Actor::new("owner")));
+    p.acting_as(&Actor::new("owner"))
+        .unwrap()
+        .grant(Actor::new("viewer"), Role::Viewer)
+        .unwrap();
+    p
+}
+
+#[rstest]
+fn round_trips_a_fully_populated_portfolio() -> PortfolioResult<()> {
+    let portfolio = build_fully_populated();
+    let bytes = portfolio.canonical_bytes()?;
+    let loaded = Portfolio::from_canonical_bytes(&bytes, PortfolioConfig::default())?;
+    assert_eq!(loaded.canonical_bytes()?, bytes);
+    assert_eq!(loaded.lots, portfolio.lots);
+    assert!(!loaded.lot_parents.is_empty());
+    assert_eq!(loaded.lot_parents, portfolio.lot_parents);
+    assert_eq!(
+        loaded.get_lot_consolidations(IBM),
+        portfolio.get_lot_consolidations(IBM)
+    );
+    assert_eq!(loaded.equity_awards, portfolio.equity_awards);
+    assert_eq!(loaded.instruments().sector(IBM), Some("Technology"));
+    assert_eq!(loaded.goals(), portfolio.goals());
+    assert_eq!(loaded.alerts(), portfolio.alerts());
+    assert_eq!(loaded.automation_rules(), portfolio.automation_rules());
+    assert!(loaded.snapshots().eq(portfolio.snapshots()));
+    assert_eq!(
+        loaded.queued_transactions(),
+        portfolio.queued_transactions()
+    );
+    assert_eq!(loaded.confirmations_by_key, portfolio.confirmations_by_key);
+    assert_eq!(loaded.pending_orders, portfolio.pending_orders);
+    assert_eq!(loaded.pending_orders.len(), 1);
+    assert_eq!(loaded.applied_fills, portfolio.applied_fills);
+    assert_eq!(loaded.access_control(), portfolio.access_control());
+    Ok(())
+}
+
+#[rstest]
+fn round_trips_through_serde() -> PortfolioResult<()> {
+    let portfolio = build();
+    let serialized = toml::to_string(&portfolio).unwrap();
+    let loaded: Portfolio = toml::from_str(&serialized).unwrap();
+    assert_eq!(loaded.canonical_bytes()?, portfolio.canonical_bytes()?);
+    assert_eq!(loaded.get_share_count(IBM), portfolio.get_share_count(IBM));
+    assert_eq!(loaded.get_share_count(VTI), portfolio.get_share_count(VTI));
+    for symbol in [IBM, VTI] {
+        assert_eq!(
+            loaded.get_purchase_record(symbol)?,
+            portfolio.get_purchase_record(symbol)?
+        );
+    }
+    Ok(())
+}
+
+#[rstest]
+fn round_trips_purchase_records_through_serde() -> PortfolioResult<()> {
+    let portfolio = build();
+    for record in portfolio.get_purchase_record(IBM)? {
+        let serialized = toml::to_string(record).unwrap();
+        let loaded: PurchaseRecord = toml::from_str(&serialized).unwrap();
+        assert_eq!(&loaded, record);
+    }
+    Ok(())
+}
+
+#[rstest]
+fn deserializing_rejects_inconsistent_history() {
+    let sell_before_buy = r#"
+        [[transactions]]
+        event = "trade"
+        symbol = "IBM"
+        id = 0
+        date = "2024-01-01T00:00:00Z"
+        day_sequence = 0
+        shares = 5
+        transaction_type = "sell"
+    "#;
+    let error = toml::from_str::<Portfolio>(sell_before_buy).err().unwrap();
+    assert!(error
+        .to_string()
+        .contains(&PortfolioError::InvalidSell.to_string()));
+}
+
+fn portfolio_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("portfolio_{name}_{}.toml", std::process::id()))
+}
+
+#[rstest]
+fn saves_and_loads_from_disk() -> PortfolioResult<()> {
+    let path = portfolio_path("save");
+    let portfolio = build();
+    portfolio.save_to(&path)?;
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let loaded = Portfolio::load_from(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(contents.starts_with(&format!("schema_version = {SCHEMA_VERSION}")));
+    assert_eq!(loaded?.canonical_bytes()?, portfolio.canonical_bytes()?);
+    Ok(())
+}
+
+#[rstest]
+fn saving_leaves_neighbouring_tmp_files_alone() -> PortfolioResult<()> {
+    let path = portfolio_path("staging");
+    let neighbour = path.with_extension("tmp");
+    std::fs::write(&neighbour, "keep").unwrap();
+    build().save_to(&path)?;
+    let neighbour_contents = std::fs::read_to_string(&neighbour).unwrap();
+    let staged = path
+        .with_file_name(format!(
+            ".{}.{}.tmp",
+            path.file_name().unwrap().to_string_lossy(),
+            std::process::id()
+        ))
+        .exists();
+    std::fs::remove_file(&neighbour).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(neighbour_contents, "keep");
+    assert!(!staged);
+    Ok(())
+}
+
+fn short_seller() -> Portfolio {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        rules: RuleSettings {
+            allow_short_selling: true,
+            ..RuleSettings::default()
+        },
+        ..PortfolioConfig::default()
+    });
+    portfolio.sell_at(IBM, 5, usd(100)).unwrap();
+    portfolio
+}
+
+#[rstest]
+fn saves_and_loads_the_config() -> PortfolioResult<()> {
+    let path = portfolio_path("config");
+    let portfolio = short_seller();
+    portfolio.save_to(&path)?;
+    let loaded = Portfolio::load_from(&path);
+    std::fs::remove_file(&path).unwrap();
+    let loaded = loaded?;
+    assert_eq!(loaded.config(), portfolio.config());
+    assert_eq!(loaded.get_position(IBM), Position::Short(5));
+    Ok(())
+}
+
+#[rstest]
+fn serde_round_trip_keeps_the_config() {
+    let portfolio = short_seller();
+    let serialized = toml::to_string(&portfolio).unwrap();
+    let loaded: Portfolio = toml::from_str(&serialized).unwrap();
+    assert_eq!(loaded.config(), portfolio.config());
+    assert_eq!(loaded.get_position(IBM), Position::Short(5));
+}
+
+#[rstest]
+fn saves_and_loads_consolidated_lots() -> PortfolioResult<()> {
+    let path = portfolio_path("consolidated");
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    portfolio.purchase_at(IBM, 10, usd(100))?;
+    portfolio.purchase_at(IBM, 10, usd(100))?;
+    portfolio.consolidate_lots(IBM, &ConsolidationPolicy::default())?;
+    let merged = LotSelection {
+        lot_id: portfolio.open_lots(IBM)[0].id,
+        shares: 15,
+    };
+    portfolio.sell_lots(IBM, &[merged], usd(120), Portfolio::fixed_date_time())?;
+    portfolio.save_to(&path)?;
+    let loaded = Portfolio::load_from(&path);
+    std::fs::remove_file(&path).unwrap();
+    let loaded = loaded?;
+    assert_eq!(loaded.lots, portfolio.lots);
+    assert_eq!(
+        loaded.get_lot_consolidations(IBM),
+        portfolio.get_lot_consolidations(IBM)
+    );
+    assert_eq!(loaded.canonical_bytes()?, portfolio.canonical_bytes()?);
+    Ok(())
+}
+
+#[rstest]
+fn migrates_side_state_into_the_ledger() -> PortfolioResult<()> {
+    let path = portfolio_path("v2");
+    let contents = "schema_version = 2\n\
+         [portfolio.shares_on_loan]\nIBM = 4\n\
+         [[portfolio.transactions]]\nevent = \"trade\"\nsymbol = \"IBM\"\nid = 0\n\
+         date = \"2024-01-01T00:00:00Z\"\nday_sequence = 0\nshares = 10\n\
+         transaction_type = \"purchase\"\n\
+         [[portfolio.tags]]\ntransaction_id = 0\ntags = [\"core\"]\n";
+    std::fs::write(&path, contents).unwrap();
+    let loaded = Portfolio::load_from(&path);
+    std::fs::remove_file(&path).unwrap();
+    let loaded = loaded?;
+    assert_eq!(loaded.get_shares_on_loan(IBM), 4);
+    assert_eq!(loaded.transaction_tags(0).collect::<Vec<_>>(), vec!["core"]);
+    assert_eq!(loaded.ledger().len(), 3);
+    Ok(())
+}
+
+#[rstest]
+fn loading_a_missing_file_is_an_io_error() {
+    assert!(matches!(
+        Portfolio::load_from(portfolio_path("missing")),
+        Err(PortfolioError::Io(_))
+    ));
+}
+
+#[rstest]
+#[case::not_an_envelope("envelope", "records = 3")]
+#[case::future_schema("schema", "schema_version = 99\n[portfolio]\n")]
+#[case::sell_before_buy(
+    "history",
+    "schema_version = 1\n[[portfolio.records]]\nsymbol = \"IBM\"\nid = 0\n\
+     date = \"2024-01-01T00:00:00Z\"\nday_sequence = 0\nshares = 5\n\
+     transaction_type = \"sell\"\n"
+)]
+fn rejects_corrupt_files(#[case] name: &str, #[case] contents: &str) {
+    let path = portfolio_path(name);
+    std::fs::write(&path, contents).unwrap();
+    let loaded = Portfolio::load_from(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(loaded, Err(PortfolioError::Corrupt(_))));
+}
@@ -0,0 +1,40 @@
+use crate::clock::*;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{DateTime, Duration, Utc};
+use rstest::*;
+
+fn at_hour(hour: u32) -> DateTime<Utc> {
+    date(2024, 3, 1).and_hms_opt(hour, 0, 0).unwrap().and_utc()
+}
+
+#[rstest]
+fn step_clock_advances_on_each_reading() {
+    let clock = StepClock::new(at_hour(9), Duration::hours(1));
+    assert_eq!(clock.now(), at_hour(9));
+    assert_eq!(clock.now(), at_hour(10));
+    assert_eq!(FixedClock(at_hour(9)).now(), at_hour(9));
+}
+
+#[rstest]
+fn trades_are_stamped_by_the_injected_clock() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_clock(StepClock::new(at_hour(9), Duration::hours(1)));
+    portfolio.purchase_at(IBM, 10, usd(100))?;
+    portfolio.sell(IBM, 4)?;
+    let dates: Vec<DateTime<Utc>> = portfolio
+        .get_purchase_record(IBM)?
+        .iter()
+        .map(|record| record.date)
+        .collect();
+    assert_eq!(dates, vec![at_hour(9), at_hour(10)]);
+    Ok(())
+}
+
+#[rstest]
+fn system_clock_is_the_default() -> PortfolioResult<()> {
+    let before = Utc::now();
+    let mut portfolio = Portfolio::new();
+    portfolio.purchase(IBM, 1)?;
+    assert!(portfolio.get_purchase_record(IBM)?[0].date >= before);
+    Ok(())
+}
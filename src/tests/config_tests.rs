@@ -0,0 +1,149 @@
+use crate::config::*;
+use crate::i18n::Locale;
+use crate::money::Currency;
+use crate::numeric::NumericBackend;
+use crate::period::{DayCountConvention, FiscalYear};
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+use std::path::PathBuf;
+
+#[fixture]
+fn config_path() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("portfolio_config_{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"
+account_type = "traditional_ira"
+jurisdiction = "uk"
+cost_basis_method = "lifo"
+numeric_backend = "cents"
+base_currency = "EUR"
+day_count = "actual_actual"
+date_granularity = "daily"
+
+[rounding]
+mode = "half_up"
+decimal_places = 4
+
+[rules]
+max_shares_per_trade = 100
+allow_short_selling = true
+future_dated = "queue"
+ex_dividend = "reject"
+
+[storage]
+path = "/var/lib/portfolio/data.json"
+
+[storage.snapshot_retention]
+daily_days = 30
+monthly_days = 3650
+
+[tax]
+short_term_rate = "0.32"
+wash_sale_days = 31
+fiscal_year = { start_month = 4, start_day = 6 }
+"#,
+    )
+    .unwrap();
+    path
+}
+
+#[rstest]
+fn loads_all_sections_from_path(config_path: PathBuf) -> PortfolioResult<()> {
+    let config = PortfolioConfig::from_path(&config_path)?;
+    std::fs::remove_file(config_path).unwrap();
+    assert_eq!(
+        config,
+        PortfolioConfig {
+            account_type: AccountType::TraditionalIra,
+            jurisdiction: Jurisdiction::Uk,
+            cost_basis_method: CostBasisMethod::Lifo,
+            rounding: RoundingPolicy {
+                mode: RoundingMode::HalfUp,
+                decimal_places: 4,
+            },
+            numeric_backend: NumericBackend::Cents,
+            base_currency: Currency::Eur,
+            locale: Locale::En,
+            day_count: DayCountConvention::ActualActual,
+            date_granularity: DateGranularity::Daily,
+            rules: RuleSettings {
+                max_shares_per_trade: Some(100),
+                allow_short_selling: true,
+                future_dated: FutureDatedPolicy::Queue,
+                ex_dividend: ExDividendPolicy::Reject,
+            },
+            storage: StorageSettings {
+                path: Some(PathBuf::from("/var/lib/portfolio/data.json")),
+                snapshot_retention: SnapshotRetention {
+                    daily_days: 30,
+                    weekly_days: 730,
+                    monthly_days: Some(3650),
+                },
+            },
+            tax: TaxSettings {
+                short_term_rate: Decimal::new(32, 2),
+                long_term_rate: Decimal::new(15, 2),
+                wash_sale_days: 31,
+                fiscal_year: FiscalYear::new(4, 6)?,
+            },
+        }
+    );
+    Ok(())
+}
+
+#[rstest]
+fn missing_keys_fall_back_to_defaults() -> PortfolioResult<()> {
+    let config = PortfolioConfig::from_toml_str("base_currency = \"GBP\"")?;
+    assert_eq!(config.cost_basis_method, CostBasisMethod::Fifo);
+    assert_eq!(config.rounding, RoundingPolicy::default());
+    assert_eq!(config.base_currency, Currency::Gbp);
+    Ok(())
+}
+
+#[rstest]
+fn error_on_unknown_currency() {
+    assert!(matches!(
+        PortfolioConfig::from_toml_str("base_currency = \"XYZ\""),
+        Err(PortfolioError::InvalidConfig(_))
+    ));
+}
+
+#[rstest]
+fn error_on_unknown_key() {
+    assert!(matches!(
+        PortfolioConfig::from_toml_str("cost_basis = \"fifo\""),
+        Err(PortfolioError::InvalidConfig(_))
+    ));
+}
+
+#[rstest]
+fn error_on_missing_file() {
+    assert!(matches!(
+        PortfolioConfig::from_path("/nonexistent/portfolio.toml"),
+        Err(PortfolioError::InvalidConfig(_))
+    ));
+}
+
+#[rstest]
+fn portfolio_enforces_configured_trade_limit() -> PortfolioResult<()> {
+    let config = PortfolioConfig::from_toml_str("[rules]\nmax_shares_per_trade = 10")?;
+    let mut portfolio = Portfolio::with_config(config);
+    portfolio.purchase(IBM, 10)?;
+    assert!(matches!(
+        portfolio.purchase(IBM, 11),
+        Err(PortfolioError::TradeLimitExceeded(10))
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+    Ok(())
+}
+
+#[rstest]
+fn error_on_invalid_fiscal_year_start() {
+    let result = PortfolioConfig::from_toml_str(
+        "[tax]\nfiscal_year = { start_month = 2, start_day = 30 }\n",
+    );
+    assert!(matches!(result, Err(PortfolioError::InvalidConfig(_))));
+}
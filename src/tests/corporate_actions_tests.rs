@@ -0,0 +1,359 @@
+use crate::clock::FixedClock;
+use crate::config::{FutureDatedPolicy, PortfolioConfig, RuleSettings, TaxSettings};
+use crate::corporate_actions::*;
+use crate::gains::HoldingTerm;
+use crate::money::{Currency, Money};
+#[cfg(feature = "pricing")]
+use crate::prices::{PriceHistory, SuspectedSplit};
+use crate::tax::{estimate_liability, TaxProfile};
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+const FB: &str = "FB";
+const META: &str = "META";
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .transact(
+            IBM,
+            10,
+            TransactionType::Purchase,
+            Some(usd(100)),
+            noon(2024, 1, 2),
+        )
+        .unwrap();
+    portfolio
+        .transact(
+            IBM,
+            20,
+            TransactionType::Purchase,
+            Some(usd(130)),
+            noon(2024, 2, 1),
+        )
+        .unwrap();
+    portfolio
+        .transact(
+            FB,
+            5,
+            TransactionType::Purchase,
+            Some(usd(300)),
+            noon(2024, 1, 2),
+        )
+        .unwrap();
+    portfolio
+}
+
+#[cfg(feature = "import")]
+#[rstest]
+fn parses_csv_feed() -> PortfolioResult<()> {
+    let feed = "date,symbol,action,value\n\
+                2024-03-01,IBM,split,2:1\n\
+                2024-04-01,IBM,dividend,0.50\n\
+                \n\
+                2024-06-09,FB,rename,META\n";
+    assert_eq!(
+        Portfolio::new().read_corporate_actions(feed.as_bytes())?,
+        vec![
+            CorporateAction::Split {
+                symbol: IBM.to_string(),
+                date: date(2024, 3, 1),
+                numerator: 2,
+                denominator: 1,
+                cash_in_lieu_price: None,
+            },
+            CorporateAction::Dividend {
+                symbol: IBM.to_string(),
+                date: date(2024, 4, 1),
+                per_share: Money::new(Decimal::new(50, 2), Currency::Usd),
+            },
+            CorporateAction::Rename {
+                symbol: FB.to_string(),
+                date: date(2024, 6, 9),
+                new_symbol: META.to_string(),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "import")]
+#[rstest]
+fn parses_csv_amounts_in_the_base_currency() -> PortfolioResult<()> {
+    let portfolio = Portfolio::with_config(PortfolioConfig {
+        base_currency: Currency::Eur,
+        ..PortfolioConfig::default()
+    });
+    let feed = "date,symbol,action,value\n\
+                2024-03-01,FB,split,1:2@700.00\n\
+                2024-04-01,IBM,dividend,0.50\n";
+    assert_eq!(
+        portfolio.read_corporate_actions(feed.as_bytes())?,
+        vec![
+            CorporateAction::Split {
+                symbol: FB.to_string(),
+                date: date(2024, 3, 1),
+                numerator: 1,
+                denominator: 2,
+                cash_in_lieu_price: Some(Money::new(Decimal::new(700, 0), Currency::Eur)),
+            },
+            CorporateAction::Dividend {
+                symbol: IBM.to_string(),
+                date: date(2024, 4, 1),
+                per_share: Money::new(Decimal::new(50, 2), Currency::Eur),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "import")]
+#[rstest]
+#[case("symbol,date,action,value\n")]
+#[case("date,symbol,action,value\n2024-03-01,IBM,merger,XYZ\n")]
+#[case("date,symbol,action,value\n2024-03-01,IBM,split,two\n")]
+#[case("date,symbol,action,value\n2024-03-01,IBM,split,1:2@abc\n")]
+#[case("date,symbol,action,value\n03/01/2024,IBM,dividend,0.5\n")]
+fn rejects_malformed_csv_feed(#[case] feed: &str) {
+    assert!(matches!(
+        Portfolio::new().read_corporate_actions(feed.as_bytes()),
+        Err(PortfolioError::InvalidCorporateAction(_))
+    ));
+}
+
+#[cfg(feature = "import")]
+#[rstest]
+fn parses_json_feed() -> PortfolioResult<()> {
+    let feed = r#"[
+        {"action": "split", "symbol": "IBM", "date": "2024-03-01", "numerator": 3, "denominator": 2},
+        {"action": "rename", "symbol": "FB", "date": "2024-06-09", "new_symbol": "META"}
+    ]"#;
+    let actions = Portfolio::new().read_corporate_actions(feed.as_bytes())?;
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[1].symbol(), FB);
+    Ok(())
+}
+
+#[rstest]
+fn applies_actions_in_date_order(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let actions = vec![
+        CorporateAction::Dividend {
+            symbol: IBM.to_string(),
+            date: date(2024, 4, 1),
+            per_share: Money::new(Decimal::new(50, 2), Currency::Usd),
+        },
+        CorporateAction::Split {
+            symbol: IBM.to_string(),
+            date: date(2024, 3, 1),
+            numerator: 2,
+            denominator: 1,
+            cash_in_lieu_price: None,
+        },
+        CorporateAction::Rename {
+            symbol: FB.to_string(),
+            date: date(2024, 6, 9),
+            new_symbol: META.to_string(),
+        },
+    ];
+    let report = portfolio.apply_corporate_actions(actions)?;
+    assert_eq!(report.applied.len(), 3);
+    assert_eq!(report.applied[0].date(), date(2024, 3, 1));
+
+    assert_eq!(portfolio.get_share_count(IBM), 60);
+    let basis: Vec<(u32, Money)> = portfolio.lots[IBM]
+        .iter()
+        .map(|lot| (lot.shares, lot.cost_basis))
+        .collect();
+    assert_eq!(basis, vec![(20, usd(1_000)), (40, usd(2_600))]);
+    assert_eq!(portfolio.get_dividends(IBM)[0].shares, 60);
+
+    assert_eq!(portfolio.get_share_count(FB), 0);
+    assert_eq!(portfolio.get_share_count(META), 5);
+    assert_eq!(portfolio.get_purchase_record(META)?.len(), 1);
+    Ok(())
+}
+
+#[rstest]
+fn skips_actions_for_symbols_not_held(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let split = CorporateAction::Split {
+        symbol: IBM.to_string(),
+        date: date(2024, 1, 1),
+        numerator: 4,
+        denominator: 1,
+        cash_in_lieu_price: None,
+    };
+    let rename = CorporateAction::Rename {
+        symbol: "TWTR".to_string(),
+        date: date(2024, 7, 1),
+        new_symbol: "X".to_string(),
+    };
+    let report = portfolio.apply_corporate_actions(vec![split.clone(), rename.clone()])?;
+    assert_eq!(report.skipped, vec![split, rename]);
+    assert_eq!(portfolio.get_share_count(IBM), 30);
+    Ok(())
+}
+
+#[rstest]
+#[case(1, 10)]
+#[case(3, 2)]
+fn rolls_back_splits_with_unpriced_fractional_shares(
+    mut portfolio: Portfolio,
+    #[case] numerator: u32,
+    #[case] denominator: u32,
+) {
+    let actions = vec![
+        CorporateAction::Dividend {
+            symbol: FB.to_string(),
+            date: date(2024, 3, 1),
+            per_share: usd(1),
+        },
+        CorporateAction::Split {
+            symbol: FB.to_string(),
+            date: date(2024, 4, 1),
+            numerator,
+            denominator,
+            cash_in_lieu_price: None,
+        },
+    ];
+    assert!(matches!(
+        portfolio.apply_corporate_actions(actions),
+        Err(PortfolioError::InvalidCorporateAction(_))
+    ));
+    assert!(portfolio.get_dividends(FB).is_empty());
+    assert_eq!(portfolio.get_share_count(FB), 5);
+}
+
+#[rstest]
+fn reverse_split_pays_cash_in_lieu_of_fractional_shares(
+    mut portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    let cash = portfolio.record_split(
+        FB,
+        StockSplit {
+            date: at(2024, 4, 1),
+            numerator: 1,
+            denominator: 2,
+            cash_in_lieu_price: Some(usd(700)),
+        },
+    )?;
+    assert_eq!(cash.len(), 1);
+    assert_eq!(cash[0].proceeds, usd(350));
+    assert_eq!(cash[0].gain, usd(50));
+    assert_eq!(cash[0].term, HoldingTerm::ShortTerm);
+
+    assert_eq!(portfolio.get_share_count(FB), 2);
+    assert_eq!(portfolio.open_lots(FB)[0].cost_basis, usd(1_200));
+    assert_eq!(portfolio.get_share_count_as_of(FB, date(2024, 3, 31)), 5);
+    assert_eq!(portfolio.get_share_count_as_of(FB, date(2024, 4, 1)), 2);
+
+    let estimate =
+        estimate_liability(&portfolio, 2024, &TaxProfile::from(&TaxSettings::default()))?;
+    assert_eq!(estimate.short_term_gain, usd(50));
+    Ok(())
+}
+
+#[rstest]
+fn reverse_split_rounds_up_the_largest_fractions_first(
+    mut portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    let cash = portfolio.record_split(
+        IBM,
+        StockSplit {
+            date: at(2024, 4, 1),
+            numerator: 2,
+            denominator: 3,
+            cash_in_lieu_price: None,
+        },
+    )?;
+    assert!(cash.is_empty());
+    let shares: Vec<u32> = portfolio
+        .open_lots(IBM)
+        .iter()
+        .map(|lot| lot.shares)
+        .collect();
+    assert_eq!(shares, vec![7, 13]);
+    assert_eq!(portfolio.get_share_count(IBM), 20);
+    Ok(())
+}
+
+#[rstest]
+fn splits_bypass_trade_rules() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        rules: RuleSettings {
+            max_shares_per_trade: Some(10),
+            future_dated: FutureDatedPolicy::Reject,
+            ..RuleSettings::default()
+        },
+        ..PortfolioConfig::default()
+    });
+    portfolio.set_clock(FixedClock(noon(2024, 2, 1)));
+    portfolio.transact(
+        IBM,
+        10,
+        TransactionType::Purchase,
+        Some(usd(100)),
+        noon(2024, 1, 2),
+    )?;
+    portfolio.apply_corporate_actions(vec![CorporateAction::Split {
+        symbol: IBM.to_string(),
+        date: date(2024, 3, 1),
+        numerator: 3,
+        denominator: 1,
+        cash_in_lieu_price: None,
+    }])?;
+    assert_eq!(portfolio.get_share_count(IBM), 30);
+    assert_eq!(portfolio.open_lots(IBM)[0].cost_basis, usd(1_000));
+    Ok(())
+}
+
+#[rstest]
+fn splits_survive_replay(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    portfolio.record_split(
+        FB,
+        StockSplit {
+            date: at(2024, 4, 1),
+            numerator: 1,
+            denominator: 2,
+            cash_in_lieu_price: Some(usd(700)),
+        },
+    )?;
+    let restored = Portfolio::from_events(portfolio.events())?;
+    assert_eq!(restored.lots, portfolio.lots);
+    assert_eq!(restored.get_share_count(FB), 2);
+    assert_eq!(restored.get_splits(FB), portfolio.get_splits(FB));
+    assert!(restored.verify_integrity().is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn rename_rejects_symbol_with_history(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.rename_symbol(FB, IBM),
+        Err(PortfolioError::InvalidCorporateAction(_))
+    ));
+    assert!(matches!(
+        portfolio.rename_symbol("TWTR", "X"),
+        Err(PortfolioError::NoSymbolHistory)
+    ));
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn flags_suspected_splits_for_held_symbols(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let mut history = PriceHistory::new();
+    history.insert(IBM, date(2024, 3, 1), usd(140));
+    history.insert(IBM, date(2024, 3, 4), usd(70));
+    history.insert("AAPL", date(2024, 3, 1), usd(400));
+    history.insert("AAPL", date(2024, 3, 4), usd(100));
+    let suspected = portfolio.suspected_splits(&history);
+    assert_eq!(suspected.len(), 1);
+    assert_eq!(suspected[0].symbol, IBM);
+
+    let actions = suspected.iter().map(SuspectedSplit::action).collect();
+    portfolio.apply_corporate_actions(actions)?;
+    assert_eq!(portfolio.get_share_count(IBM), 60);
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use crate::config::{ExDividendPolicy, PortfolioConfig, RuleSettings};
+use crate::dividends::*;
+use crate::period::Period;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+const KO: &str = "KO";
+const O: &str = "O";
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.set_clock(|| at(2024, 12, 31));
+    p.transact(
+        KO,
+        100,
+        TransactionType::Purchase,
+        Some(cents(6_000)),
+        at(2023, 1, 3),
+    )
+    .unwrap();
+    for (month, amount) in [(4, 46), (7, 46), (10, 46), (1, 48)] {
+        let year = if month == 1 { 2024 } else { 2023 };
+        p.record_dividend(KO, cents(amount), at(year, month, 1), None)
+            .unwrap();
+    }
+    p
+}
+
+#[rstest]
+fn records_dividends_against_shares_held(portfolio: Portfolio) {
+    let dividends = portfolio.get_dividends(KO);
+    assert_eq!(dividends.len(), 4);
+    assert_eq!(dividends[3].shares, 100);
+    assert_eq!(dividends[3].amount().unwrap(), cents(4_800));
+}
+
+#[rstest]
+fn infers_quarterly_rate_from_history(portfolio: Portfolio) {
+    let rate = portfolio.infer_dividend_rate(KO).unwrap().unwrap();
+    assert_eq!(rate.frequency, DividendFrequency::Quarterly);
+    assert_eq!(rate.per_payment, cents(48));
+    assert_eq!(rate.trailing_annual, cents(186));
+    assert_eq!(rate.forward_annual().unwrap(), cents(192));
+    assert_eq!(rate.last_paid, date(2024, 1, 1));
+}
+
+#[rstest]
+fn infers_monthly_rate(mut portfolio: Portfolio) {
+    portfolio
+        .transact(
+            O,
+            10,
+            TransactionType::Purchase,
+            Some(cents(5_000)),
+            at(2024, 1, 2),
+        )
+        .unwrap();
+    for month in 2..=4 {
+        portfolio
+            .record_dividend(O, cents(26), at(2024, month, 15), None)
+            .unwrap();
+    }
+    let rate = portfolio.infer_dividend_rate(O).unwrap().unwrap();
+    assert_eq!(rate.frequency, DividendFrequency::Monthly);
+}
+
+#[rstest]
+fn needs_two_payments_to_infer_a_rate(mut portfolio: Portfolio) {
+    portfolio
+        .transact(
+            O,
+            10,
+            TransactionType::Purchase,
+            Some(cents(5_000)),
+            at(2024, 1, 2),
+        )
+        .unwrap();
+    portfolio
+        .record_dividend(O, cents(26), at(2024, 2, 15), None)
+        .unwrap();
+    assert_eq!(portfolio.infer_dividend_rate(O).unwrap(), None);
+}
+
+#[rstest]
+fn projects_future_payments_from_the_inferred_rate(portfolio: Portfolio) {
+    let period = Period::new(date(2024, 2, 1), date(2024, 9, 30));
+    let projected = portfolio.projected_dividends(&period).unwrap();
+    assert_eq!(
+        projected,
+        vec![
+            ProjectedDividend {
+                symbol: KO.to_string(),
+                date: date(2024, 4, 1),
+                amount: cents(4_800),
+            },
+            ProjectedDividend {
+                symbol: KO.to_string(),
+                date: date(2024, 7, 1),
+                amount: cents(4_800),
+            },
+        ]
+    );
+}
+
+#[rstest]
+fn rejects_dividends_without_shares(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.record_dividend(O, cents(10), at(2024, 1, 1), None),
+        Err(PortfolioError::NoOpenLots)
+    ));
+}
+
+#[rstest]
+fn flags_shares_bought_on_or_after_the_ex_date(mut portfolio: Portfolio) {
+    portfolio
+        .transact(
+            KO,
+            20,
+            TransactionType::Purchase,
+            Some(cents(6_200)),
+            at(2024, 3, 14),
+        )
+        .unwrap();
+    let dividend = portfolio
+        .record_dividend(KO, cents(48), at(2024, 4, 1), Some(date(2024, 3, 14)))
+        .unwrap();
+    assert_eq!(dividend.shares, 120);
+    assert_eq!(dividend.ineligible_shares, 20);
+}
+
+#[rstest]
+fn shares_held_before_the_ex_date_are_eligible(mut portfolio: Portfolio) {
+    let dividend = portfolio
+        .record_dividend(KO, cents(48), at(2024, 4, 1), Some(date(2024, 3, 14)))
+        .unwrap();
+    assert_eq!(dividend.ineligible_shares, 0);
+}
+
+#[rstest]
+fn rejects_ineligible_dividends_when_configured() {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        rules: RuleSettings {
+            ex_dividend: ExDividendPolicy::Reject,
+            ..RuleSettings::default()
+        },
+        ..PortfolioConfig::default()
+    });
+    portfolio.set_clock(|| at(2024, 12, 31));
+    portfolio
+        .transact(
+            KO,
+            10,
+            TransactionType::Purchase,
+            Some(cents(6_000)),
+            at(2024, 3, 20),
+        )
+        .unwrap();
+    assert!(matches!(
+        portfolio.record_dividend(KO, cents(48), at(2024, 4, 1), Some(date(2024, 3, 14))),
+        Err(PortfolioError::IneligibleDividend(ex_date)) if ex_date == date(2024, 3, 14)
+    ));
+    assert!(portfolio.get_dividends(KO).is_empty());
+}
+
+#[rstest]
+fn yield_on_cost_divides_trailing_dividends_by_average_cost(mut portfolio: Portfolio) {
+    portfolio.set_clock(|| at(2024, 3, 1));
+    assert_eq!(
+        portfolio.yield_on_cost(KO).unwrap(),
+        Some(Decimal::new(31, 1))
+    );
+}
+
+#[rstest]
+fn yield_on_cost_uses_cost_not_current_price(mut portfolio: Portfolio) {
+    portfolio.set_clock(|| at(2024, 3, 1));
+    portfolio
+        .transact(
+            KO,
+            100,
+            TransactionType::Purchase,
+            Some(cents(18_000)),
+            at(2024, 2, 1),
+        )
+        .unwrap();
+    assert_eq!(
+        portfolio.yield_on_cost(KO).unwrap(),
+        Some(Decimal::new(155, 2))
+    );
+}
+
+#[rstest]
+fn yield_on_cost_ignores_dividends_older_than_a_year(portfolio: Portfolio) {
+    assert_eq!(portfolio.yield_on_cost(KO).unwrap(), Some(Decimal::ZERO));
+}
+
+#[rstest]
+fn yield_on_cost_is_undefined_without_a_position(portfolio: Portfolio) {
+    assert_eq!(portfolio.yield_on_cost(O).unwrap(), None);
+}
@@ -0,0 +1,94 @@
+use crate::equity::*;
+use crate::money::Money;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{DateTime, Utc};
+use rstest::*;
+use rust_decimal::Decimal;
+
+const ACME: &str = "ACME";
+
+fn purchase() -> EsppPurchase {
+    EsppPurchase {
+        offering_date: at(2022, 1, 1),
+        offering_fmv: usd(100),
+        purchase_date: at(2022, 6, 30),
+        purchase_fmv: usd(120),
+        discount_percent: Decimal::from(15),
+    }
+}
+
+#[rstest]
+fn rsu_vest_opens_lot_at_fair_market_value() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.record_rsu_vest(ACME, 10, usd(50), at(2023, 3, 15))?;
+    portfolio.record_rsu_vest(ACME, 10, usd(60), at(2023, 6, 15))?;
+    assert_eq!(portfolio.get_share_count(ACME), 20);
+    assert_eq!(portfolio.lots[ACME][1].cost_basis, usd(600));
+    assert_eq!(portfolio.rsu_vest_income()?, usd(1100));
+    assert_eq!(portfolio.equity_awards().count(), 2);
+    Ok(())
+}
+
+#[rstest]
+fn espp_purchase_price_uses_lookback_discount() -> PortfolioResult<()> {
+    assert_eq!(purchase().purchase_price()?, usd(85));
+    let mut portfolio = Portfolio::new();
+    let confirmation = portfolio.record_espp_purchase(ACME, 10, purchase())?;
+    assert_eq!(confirmation.price, Some(usd(85)));
+    assert_eq!(portfolio.lots[ACME][0].cost_basis, usd(850));
+    Ok(())
+}
+
+#[rstest]
+#[case(at(2023, 6, 30), EsppDisposition::Disqualifying)]
+#[case(at(2024, 1, 1), EsppDisposition::Disqualifying)]
+#[case(at(2024, 1, 2), EsppDisposition::Qualifying)]
+fn classifies_disposition_by_holding_period(
+    #[case] sold: DateTime<Utc>,
+    #[case] expected: EsppDisposition,
+) {
+    assert_eq!(purchase().disposition(sold), expected);
+}
+
+#[rstest]
+#[case(
+    at(2023, 3, 1),
+    EsppDisposition::Disqualifying,
+    usd(350),
+    usd(1200),
+    usd(300)
+)]
+#[case(
+    at(2024, 3, 1),
+    EsppDisposition::Qualifying,
+    usd(150),
+    usd(1000),
+    usd(500)
+)]
+fn reports_ordinary_income_and_adjusted_basis_on_sale(
+    #[case] sold: DateTime<Utc>,
+    #[case] disposition: EsppDisposition,
+    #[case] ordinary_income: Money,
+    #[case] adjusted_basis: Money,
+    #[case] capital_gain: Money,
+) -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.record_rsu_vest(ACME, 5, usd(90), at(2022, 2, 1))?;
+    let espp = portfolio.record_espp_purchase(ACME, 10, purchase())?;
+    portfolio.purchase_at("VTI", 1, usd(200))?;
+    let sell = portfolio.transact(ACME, 15, TransactionType::Sell, Some(usd(150)), sold)?;
+    let sales = portfolio.espp_sales()?;
+    assert_eq!(sales.len(), 1);
+    let sale = &sales[0];
+    assert_eq!(
+        (sale.sale_id, sale.purchase_id),
+        (sell.transaction_id, espp.transaction_id)
+    );
+    assert_eq!(sale.shares, 10);
+    assert_eq!(sale.disposition, disposition);
+    assert_eq!(sale.ordinary_income, ordinary_income);
+    assert_eq!(sale.adjusted_basis, adjusted_basis);
+    assert_eq!(sale.capital_gain, capital_gain);
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::clock::FixedClock;
+use crate::events::*;
+use crate::lots::Acquisition;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+#[rstest]
+fn subscribers_receive_transaction_and_fill_events() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    let events = portfolio.subscribe();
+    portfolio.purchase_at(IBM, 10, usd(100))?;
+    portfolio.submit(Order {
+        symbol: IBM.to_string(),
+        transaction_type: TransactionType::Sell,
+        shares: 4,
+        price: None,
+        idempotency_key: Some("order-1".to_string()),
+    })?;
+    let received: Vec<PortfolioEvent> = events.try_iter().collect();
+    assert_eq!(
+        received,
+        vec![
+            PortfolioEvent::Transaction {
+                transaction_id: 0,
+                symbol: IBM.to_string(),
+                transaction_type: TransactionType::Purchase,
+                shares: 10,
+                price: Some(usd(100)),
+                date: Portfolio::fixed_date_time(),
+                fx: None,
+                net_amount: None,
+                acquisition: Acquisition::Purchase,
+                lot_selection: None,
+                equity_award: None,
+                reverses: None,
+            },
+            PortfolioEvent::Transaction {
+                transaction_id: 1,
+                symbol: IBM.to_string(),
+                transaction_type: TransactionType::Sell,
+                shares: 4,
+                price: None,
+                date: Portfolio::fixed_date_time(),
+                fx: None,
+                net_amount: None,
+                acquisition: Acquisition::Purchase,
+                lot_selection: None,
+                equity_award: None,
+                reverses: None,
+            },
+            PortfolioEvent::OrderFilled {
+                transaction_id: 1,
+                symbol: IBM.to_string(),
+                idempotency_key: Some("order-1".to_string()),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn failed_trades_publish_nothing_and_dropped_subscribers_are_pruned() {
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    let events = portfolio.subscribe();
+    drop(portfolio.subscribe());
+    assert!(portfolio.sell(IBM, 1).is_err());
+    portfolio.publish(PortfolioEvent::Alert {
+        symbol: IBM.to_string(),
+        message: "check".to_string(),
+    });
+    assert_eq!(events.try_iter().count(), 1);
+    assert_eq!(portfolio.subscribers.len(), 1);
+}
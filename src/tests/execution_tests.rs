@@ -0,0 +1,150 @@
+use crate::execution::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+#[derive(Default)]
+struct ScriptedBroker {
+    placed: Vec<Order>,
+    fills: Vec<Fill>,
+}
+
+impl ScriptedBroker {
+    fn fill(&mut self, fill_id: &str, shares: u32, price: i64) {
+        self.fills.push(Fill {
+            fill_id: fill_id.to_string(),
+            order_id: "order-1".to_string(),
+            symbol: IBM.to_string(),
+            transaction_type: TransactionType::Purchase,
+            shares,
+            price: usd(price),
+            filled_at: Portfolio::fixed_date_time(),
+        });
+    }
+}
+
+impl Broker for ScriptedBroker {
+    fn place_order(&mut self, order: &Order) -> PortfolioResult<BrokerOrderId> {
+        self.placed.push(order.clone());
+        Ok(format!("order-{}", self.placed.len()))
+    }
+
+    fn fills(&mut self, order_id: &str) -> PortfolioResult<Vec<Fill>> {
+        Ok(self
+            .fills
+            .iter()
+            .filter(|fill| fill.order_id == order_id)
+            .cloned()
+            .collect())
+    }
+}
+
+fn buy_ten() -> Order {
+    Order {
+        symbol: IBM.to_string(),
+        transaction_type: TransactionType::Purchase,
+        shares: 10,
+        price: None,
+        idempotency_key: None,
+    }
+}
+
+#[rstest]
+fn routed_orders_wait_for_fills() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let mut broker = ScriptedBroker::default();
+    let order_id = portfolio.route_order(&mut broker, buy_ten())?;
+    assert_eq!(broker.placed, vec![buy_ten()]);
+    assert_eq!(portfolio.get_share_count(IBM), 0);
+    assert_eq!(
+        portfolio
+            .pending_order(&order_id)
+            .unwrap()
+            .remaining_shares(),
+        10
+    );
+    Ok(())
+}
+
+#[rstest]
+fn partial_fills_become_transactions_once() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let mut broker = ScriptedBroker::default();
+    let order_id = portfolio.route_order(&mut broker, buy_ten())?;
+
+    broker.fill("fill-1", 4, 100);
+    let confirmations = portfolio.collect_fills(&mut broker, &order_id)?;
+    assert_eq!(confirmations.len(), 1);
+    assert_eq!(confirmations[0].price, Some(usd(100)));
+    assert_eq!(
+        portfolio
+            .pending_order(&order_id)
+            .unwrap()
+            .remaining_shares(),
+        6
+    );
+
+    broker.fill("fill-2", 6, 101);
+    let confirmations = portfolio.collect_fills(&mut broker, &order_id)?;
+    assert_eq!(confirmations.len(), 1);
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+    assert!(portfolio.pending_order(&order_id).is_none());
+    assert!(portfolio.collect_fills(&mut broker, &order_id)?.is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn rejects_fills_beyond_the_order_size() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let mut broker = ScriptedBroker::default();
+    let order_id = portfolio.route_order(&mut broker, buy_ten())?;
+
+    broker.fill("fill-1", 7, 100);
+    portfolio.collect_fills(&mut broker, &order_id)?;
+    broker.fill("fill-2", 5, 100);
+    assert!(matches!(
+        portfolio.collect_fills(&mut broker, &order_id),
+        Err(PortfolioError::BrokerError(_))
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 7);
+    assert_eq!(
+        portfolio
+            .pending_order(&order_id)
+            .unwrap()
+            .remaining_shares(),
+        3
+    );
+    Ok(())
+}
+
+#[rstest]
+fn rejects_invalid_orders_before_routing() {
+    let mut portfolio = Portfolio::new();
+    let mut broker = ScriptedBroker::default();
+    let order = Order {
+        shares: 0,
+        ..buy_ten()
+    };
+    assert!(matches!(
+        portfolio.route_order(&mut broker, order),
+        Err(PortfolioError::ZeroShares)
+    ));
+    assert!(broker.placed.is_empty());
+}
+
+#[rstest]
+fn rejects_orders_for_halted_symbols() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(IBM, instruments::InstrumentKind::Stock);
+    portfolio.instruments_mut().unwrap().set_halted(IBM, true)?;
+    let mut broker = ScriptedBroker::default();
+    assert!(matches!(
+        portfolio.route_order(&mut broker, buy_ten()),
+        Err(PortfolioError::TradingHalted(_))
+    ));
+    assert!(broker.placed.is_empty());
+    Ok(())
+}
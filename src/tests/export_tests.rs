@@ -0,0 +1,118 @@
+use crate::config::{DateGranularity, PortfolioConfig};
+use crate::export::*;
+use crate::i18n::Locale;
+use crate::import::ImportOptions;
+use crate::integrity::ImportedTransaction;
+use crate::money::{Currency, Money};
+use crate::period::Period;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::NaiveDate;
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn trade(
+    symbol: &str,
+    on: NaiveDate,
+    transaction_type: TransactionType,
+    shares: u32,
+) -> ImportedTransaction {
+    ImportedTransaction {
+        symbol: symbol.to_string(),
+        date: on.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+        transaction_type,
+        shares,
+        price: Some(Money::new(Decimal::from(100), Currency::Usd)),
+        net_amount: None,
+    }
+}
+
+fn portfolio_with_locale(locale: Locale) -> Portfolio {
+    let mut p = Portfolio::with_config(PortfolioConfig {
+        date_granularity: DateGranularity::Daily,
+        locale,
+        ..PortfolioConfig::default()
+    });
+    p.import(
+        vec![
+            trade(AAPL, date(2023, 6, 1), TransactionType::Purchase, 10),
+            trade(AAPL, date(2023, 12, 1), TransactionType::Sell, 2),
+            trade(IBM, date(2024, 1, 5), TransactionType::Purchase, 5),
+            trade(AAPL, date(2024, 3, 1), TransactionType::Sell, 3),
+            trade(AAPL, date(2024, 9, 1), TransactionType::Sell, 1),
+        ],
+        &ImportOptions::default(),
+    )
+    .unwrap();
+    p
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    portfolio_with_locale(Locale::En)
+}
+
+fn aapl_sells_in_2024() -> ExportFilter {
+    ExportFilter {
+        symbols: vec![AAPL.to_string()],
+        date_range: Some(Period::new(date(2024, 1, 1), date(2024, 12, 31))),
+        transaction_types: vec![TransactionType::Sell],
+        ..ExportFilter::default()
+    }
+}
+
+#[rstest]
+fn default_filter_exports_full_journal(portfolio: Portfolio) {
+    assert_eq!(portfolio.export_records(&ExportFilter::default()).len(), 5);
+}
+
+#[rstest]
+fn exports_filtered_transactions_as_csv(portfolio: Portfolio) {
+    assert_eq!(
+        portfolio.export_csv(&aapl_sells_in_2024()),
+        "Date,Symbol,Type,Shares,Price\n\
+         2024-03-01,AAPL,Sell,3,100\n\
+         2024-09-01,AAPL,Sell,1,100\n"
+    );
+}
+
+#[rstest]
+fn csv_headers_follow_locale() {
+    let portfolio = portfolio_with_locale(Locale::Es);
+    let csv = portfolio.export_csv(&aapl_sells_in_2024());
+    assert!(csv.starts_with("Fecha,Símbolo,Tipo,Acciones,Precio\n2024-03-01,AAPL,Venta,3,100\n"));
+}
+
+#[rstest]
+fn filters_by_tag(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let ids: Vec<TransactionId> = portfolio
+        .journal()
+        .iter()
+        .map(|(_, record)| record.id)
+        .collect();
+    portfolio.tag_transaction(ids[1], "rebalance")?;
+    portfolio.tag_transaction(ids[2], "gift")?;
+    let filter = ExportFilter {
+        tags: vec!["rebalance".to_string(), "gift".to_string()],
+        ..ExportFilter::default()
+    };
+    let exported: Vec<TransactionId> = portfolio
+        .export_records(&filter)
+        .iter()
+        .map(|(_, record)| record.id)
+        .collect();
+    assert_eq!(exported, vec![ids[1], ids[2]]);
+    assert_eq!(
+        portfolio.transaction_tags(ids[1]).collect::<Vec<_>>(),
+        vec!["rebalance"]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn error_when_tagging_unknown_transaction(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.tag_transaction(99, "gift"),
+        Err(PortfolioError::UnknownTransaction(99))
+    ));
+}
@@ -0,0 +1,76 @@
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+const FUND: &str = "FXAIX";
+const PLAN: &str = "401k";
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.add_external_position(PLAN, FUND, 30).unwrap();
+    p
+}
+
+#[fixture]
+fn quotes() -> Quotes {
+    Quotes::from([(IBM.to_string(), usd(150)), (FUND.to_string(), usd(50))])
+}
+
+#[rstest]
+fn external_positions_count_toward_consolidated_value(portfolio: Portfolio, quotes: Quotes) {
+    assert_eq!(portfolio.market_value(&quotes).unwrap(), usd(1_500));
+    assert_eq!(portfolio.consolidated_value(&quotes).unwrap(), usd(3_000));
+}
+
+#[rstest]
+fn allocation_includes_external_positions(portfolio: Portfolio, quotes: Quotes) {
+    assert_eq!(
+        portfolio.allocation(&quotes).unwrap(),
+        vec![
+            (FUND.to_string(), Decimal::from(50)),
+            (IBM.to_string(), Decimal::from(50)),
+        ]
+    );
+}
+
+#[rstest]
+fn external_positions_merge_with_held_symbols(mut portfolio: Portfolio, quotes: Quotes) {
+    portfolio.add_external_position(PLAN, IBM, 10).unwrap();
+    assert_eq!(portfolio.consolidated_value(&quotes).unwrap(), usd(4_500));
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+}
+
+#[rstest]
+fn external_positions_are_excluded_from_trading_and_lots(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.sell(FUND, 1),
+        Err(PortfolioError::InvalidSell)
+    ));
+    assert_eq!(portfolio.iter_lots(FUND).count(), 0);
+}
+
+#[rstest]
+fn adding_again_replaces_the_share_count(mut portfolio: Portfolio) {
+    portfolio.add_external_position(PLAN, FUND, 40).unwrap();
+    assert_eq!(portfolio.external_positions().len(), 1);
+    assert_eq!(portfolio.external_positions()[0].shares, 40);
+}
+
+#[rstest]
+fn removes_external_positions(mut portfolio: Portfolio) {
+    assert!(portfolio.remove_external_position(PLAN, FUND).unwrap());
+    assert!(!portfolio.remove_external_position(PLAN, FUND).unwrap());
+    assert!(portfolio.external_positions().is_empty());
+}
+
+#[rstest]
+fn rejects_zero_shares(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.add_external_position(PLAN, FUND, 0),
+        Err(PortfolioError::ZeroShares)
+    ));
+}
@@ -0,0 +1,167 @@
+#[cfg(feature = "toml")]
+use crate::config::PortfolioConfig;
+use crate::events::PortfolioEvent;
+use crate::fx::*;
+use crate::money::{Currency, Money};
+use crate::sync::{apply_delta, export_delta};
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+const SAP: &str = "SAP";
+
+fn rate(value: i64) -> Decimal {
+    Decimal::new(value, 2)
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .transact_in_currency(
+            SAP,
+            10,
+            TransactionType::Purchase,
+            eur(100),
+            rate(110),
+            noon(2024, 1, 2),
+        )
+        .unwrap();
+    portfolio
+        .transact_in_currency(
+            SAP,
+            10,
+            TransactionType::Sell,
+            eur(120),
+            rate(105),
+            noon(2024, 6, 3),
+        )
+        .unwrap();
+    portfolio
+}
+
+#[rstest]
+fn records_keep_trade_time_rate_and_base_price(portfolio: Portfolio) -> PortfolioResult<()> {
+    let purchase = &portfolio.get_purchase_record(SAP)?[0];
+    assert_eq!(purchase.price, Some(usd(110)));
+    assert_eq!(
+        purchase.fx,
+        Some(TradeFx {
+            local_price: eur(100),
+            rate: rate(110),
+        })
+    );
+    Ok(())
+}
+
+#[rstest]
+fn gains_are_reported_in_local_and_base_currency(portfolio: Portfolio) -> PortfolioResult<()> {
+    let gains = portfolio.fx_gains(SAP)?;
+    assert_eq!(gains.len(), 1);
+    let gain = &gains[0];
+    assert_eq!(gain.local_proceeds, eur(1_200));
+    assert_eq!(gain.local_cost, eur(1_000));
+    assert_eq!(gain.local_gain, eur(200));
+    assert_eq!(gain.base_proceeds, usd(1_260));
+    assert_eq!(gain.base_cost, usd(1_100));
+    assert_eq!(gain.base_gain, usd(160));
+    assert_eq!(gain.currency_gain, usd(-50));
+    Ok(())
+}
+
+#[rstest]
+fn base_currency_trades_have_no_currency_gain() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.purchase_at(SAP, 10, usd(100))?;
+    portfolio.sell_at(SAP, 4, usd(150))?;
+    let gains = portfolio.fx_gains(SAP)?;
+    assert_eq!(gains[0].local_gain, usd(200));
+    assert_eq!(gains[0].base_gain, usd(200));
+    assert!(gains[0].currency_gain.is_zero());
+    Ok(())
+}
+
+#[rstest]
+#[case(Decimal::ZERO)]
+#[case(Decimal::NEGATIVE_ONE)]
+fn non_positive_rate_is_rejected(#[case] fx_rate: Decimal) {
+    let mut portfolio = Portfolio::new();
+    assert!(matches!(
+        portfolio.transact_in_currency(
+            SAP,
+            10,
+            TransactionType::Purchase,
+            eur(100),
+            fx_rate,
+            noon(2024, 1, 2),
+        ),
+        Err(PortfolioError::InvalidFxRate(_))
+    ));
+    assert_eq!(portfolio.get_share_count(SAP), 0);
+}
+
+#[cfg(feature = "toml")]
+#[rstest]
+fn rates_survive_canonical_round_trip(portfolio: Portfolio) -> PortfolioResult<()> {
+    let loaded =
+        Portfolio::from_canonical_bytes(&portfolio.canonical_bytes()?, PortfolioConfig::default())?;
+    assert_eq!(loaded.journal(), portfolio.journal());
+    assert_eq!(loaded.fx_gains(SAP)?, portfolio.fx_gains(SAP)?);
+    Ok(())
+}
+
+#[rstest]
+fn events_and_deltas_carry_the_trade_rate(portfolio: Portfolio) -> PortfolioResult<()> {
+    let purchase_fx = portfolio.get_purchase_record(SAP)?[0].fx;
+    let fx: Vec<Option<TradeFx>> = portfolio
+        .changes_since(0)
+        .iter()
+        .filter_map(|change| match &change.event {
+            PortfolioEvent::Transaction { fx, .. } => Some(*fx),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(fx[0], purchase_fx);
+    let mut replica = Portfolio::new();
+    apply_delta(&mut replica, &export_delta(&portfolio, 0))?;
+    assert_eq!(replica.journal(), portfolio.journal());
+    assert_eq!(replica.fx_gains(SAP)?, portfolio.fx_gains(SAP)?);
+    Ok(())
+}
+
+#[rstest]
+#[case::other_local_currency(TransactionType::Sell, Money::new(Decimal::from(90), Currency::Gbp))]
+#[case::base_currency_price(TransactionType::Purchase, usd(100))]
+fn rejects_trades_in_a_different_local_currency(
+    #[case] transaction_type: TransactionType,
+    #[case] local_price: Money,
+) {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .transact_in_currency(
+            SAP,
+            10,
+            TransactionType::Purchase,
+            eur(100),
+            rate(110),
+            noon(2024, 1, 2),
+        )
+        .unwrap();
+    assert!(matches!(
+        portfolio.transact_in_currency(
+            SAP,
+            5,
+            transaction_type,
+            local_price,
+            rate(120),
+            noon(2024, 2, 1),
+        ),
+        Err(PortfolioError::CurrencyMismatch { .. })
+    ));
+    assert!(matches!(
+        portfolio.sell_at(SAP, 5, usd(120)),
+        Err(PortfolioError::CurrencyMismatch { .. })
+    ));
+    assert_eq!(portfolio.get_share_count(SAP), 10);
+}
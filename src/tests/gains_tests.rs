@@ -0,0 +1,334 @@
+use crate::clock::FixedClock;
+use crate::gains::*;
+#[cfg(feature = "import")]
+use crate::import::{BasisMode, BrokerLot};
+use crate::money::{Currency, Money};
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{DateTime, Utc};
+use rstest::*;
+use rust_decimal::Decimal;
+
+#[rstest]
+#[case(at(2023, 1, 15), HoldingTerm::ShortTerm)]
+#[case(at(2024, 1, 15), HoldingTerm::ShortTerm)]
+#[case(at(2024, 1, 16), HoldingTerm::LongTerm)]
+fn classifies_holding_term_after_more_than_one_year(
+    #[case] sold: DateTime<Utc>,
+    #[case] expected: HoldingTerm,
+) {
+    assert_eq!(HoldingTerm::classify(at(2023, 1, 15), sold), expected);
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(IBM, 10, usd(120)).unwrap();
+    p.lots.get_mut(IBM).unwrap()[0].acquired = at(1968, 6, 1);
+    p
+}
+
+#[rstest]
+fn priced_sell_returns_per_lot_gain_breakdown(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let confirmation = portfolio.sell_at(IBM, 14, usd(130))?;
+    let breakdown: Vec<(u32, Money, Money, HoldingTerm)> = confirmation
+        .lot_gains
+        .iter()
+        .map(|g| (g.consumption.shares, g.proceeds, g.gain, g.term))
+        .collect();
+    assert_eq!(
+        breakdown,
+        vec![
+            (10, usd(1300), usd(300), HoldingTerm::LongTerm),
+            (4, usd(520), usd(40), HoldingTerm::ShortTerm),
+        ]
+    );
+    assert_eq!(confirmation.realized_gain, Some(usd(340)));
+    assert_eq!(
+        total_gain(
+            Currency::Usd,
+            &confirmation.lot_gains,
+            Some(HoldingTerm::LongTerm)
+        )?,
+        usd(300)
+    );
+    Ok(())
+}
+
+#[rstest]
+fn unpriced_sell_has_no_gain_breakdown(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    assert!(portfolio.sell(IBM, 5)?.lot_gains.is_empty());
+    Ok(())
+}
+
+fn sell_gifted(fair_market_value: i64, price: i64) -> PortfolioResult<GainLoss> {
+    let mut portfolio = Portfolio::new();
+    portfolio.receive_gift(
+        IBM,
+        10,
+        usd(100),
+        at(2010, 1, 1),
+        usd(fair_market_value),
+        at(2024, 1, 1),
+    )?;
+    let confirmation = portfolio.transact(
+        IBM,
+        10,
+        TransactionType::Sell,
+        Some(usd(price)),
+        at(2024, 3, 1),
+    )?;
+    Ok(confirmation.lot_gains[0].clone())
+}
+
+#[rstest]
+#[case(120, 150, usd(500), HoldingTerm::LongTerm)]
+#[case(60, 150, usd(500), HoldingTerm::LongTerm)]
+#[case(60, 50, usd(-100), HoldingTerm::ShortTerm)]
+#[case(60, 80, usd(0), HoldingTerm::LongTerm)]
+fn gifted_shares_apply_dual_basis_rules(
+    #[case] fair_market_value: i64,
+    #[case] price: i64,
+    #[case] gain: Money,
+    #[case] term: HoldingTerm,
+) -> PortfolioResult<()> {
+    let realized = sell_gifted(fair_market_value, price)?;
+    assert_eq!(realized.gain, gain);
+    assert_eq!(realized.term, term);
+    Ok(())
+}
+
+#[rstest]
+fn inherited_shares_use_stepped_up_basis_and_are_long_term() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.inherit(IBM, 10, usd(200), at(2024, 1, 1))?;
+    let confirmation = portfolio.transact(
+        IBM,
+        10,
+        TransactionType::Sell,
+        Some(usd(210)),
+        at(2024, 2, 1),
+    )?;
+    assert_eq!(confirmation.realized_gain, Some(usd(100)));
+    assert_eq!(confirmation.lot_gains[0].term, HoldingTerm::LongTerm);
+    Ok(())
+}
+
+#[rstest]
+fn acquisition_type_survives_rebuild() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let confirmation = portfolio.inherit(IBM, 10, usd(200), at(2024, 1, 1))?;
+    portfolio.rebuild()?;
+    assert_eq!(
+        portfolio.lots[IBM][0].acquisition,
+        lots::Acquisition::Inheritance
+    );
+    assert_eq!(
+        portfolio.acquisition_of(confirmation.transaction_id),
+        lots::Acquisition::Inheritance
+    );
+    Ok(())
+}
+
+#[cfg(feature = "import")]
+#[rstest]
+fn lots_record_whether_basis_is_broker_reported() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.transact(
+        IBM,
+        10,
+        TransactionType::Purchase,
+        Some(usd(100)),
+        at(2024, 1, 2),
+    )?;
+    portfolio.inherit(IBM, 5, usd(200), at(2024, 1, 3))?;
+    let covered: Vec<bool> = portfolio.lots[IBM].iter().map(|lot| lot.covered).collect();
+    assert_eq!(covered, vec![true, false]);
+
+    let noncovered = BrokerLot {
+        symbol: IBM.to_string(),
+        acquired: at(2024, 1, 2).date_naive(),
+        shares: 10,
+        cost_basis: usd(900),
+        covered: false,
+    };
+    portfolio.apply_broker_basis(&[noncovered], BasisMode::Override)?;
+    assert!(!portfolio.lots[IBM][0].covered);
+    Ok(())
+}
+
+#[rstest]
+fn preview_sell_reports_gains_without_executing(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    portfolio.set_clock(FixedClock(portfolio.now()));
+    let version = portfolio.version();
+    let preview = portfolio.preview_sell(IBM, 14, usd(130))?;
+    assert_eq!(preview.proceeds, usd(1820));
+    assert_eq!(preview.long_term_gain, usd(300));
+    assert_eq!(preview.short_term_gain, usd(40));
+    assert_eq!(preview.realized_gain, usd(340));
+    assert_eq!(
+        preview
+            .lots
+            .iter()
+            .map(|lot| (lot.consumption.shares, lot.term))
+            .collect::<Vec<_>>(),
+        vec![(10, HoldingTerm::LongTerm), (4, HoldingTerm::ShortTerm)]
+    );
+    assert_eq!(portfolio.get_share_count(IBM), 20);
+    assert_eq!(portfolio.lots[IBM].len(), 2);
+    assert_eq!(portfolio.version(), version);
+    Ok(())
+}
+
+#[rstest]
+fn preview_sell_matches_executed_sell(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    portfolio.set_clock(FixedClock(portfolio.now()));
+    let preview = portfolio.preview_sell(IBM, 14, usd(130))?;
+    let confirmation = portfolio.sell_at(IBM, 14, usd(130))?;
+    assert_eq!(preview.lots, confirmation.lot_gains);
+    Ok(())
+}
+
+#[rstest]
+fn preview_sell_rejects_oversized_sell(portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.preview_sell(IBM, 25, usd(130)),
+        Err(PortfolioError::InvalidSell)
+    ));
+}
+
+#[rstest]
+fn net_amounts_from_confirms_drive_basis_and_proceeds() -> PortfolioResult<()> {
+    let cents = |amount: i64| Money::new(Decimal::new(amount, 2), Currency::Usd);
+    let mut portfolio = Portfolio::new();
+    let purchase = portfolio.transact_with_net_amount(
+        IBM,
+        10,
+        TransactionType::Purchase,
+        usd(100),
+        cents(100_007),
+        at(2024, 1, 2),
+    )?;
+    assert_eq!(purchase.fees, cents(7));
+    assert_eq!(portfolio.lots[IBM][0].cost_basis, cents(100_007));
+
+    let sale = portfolio.transact_with_net_amount(
+        IBM,
+        4,
+        TransactionType::Sell,
+        usd(110),
+        cents(43_995),
+        at(2024, 3, 1),
+    )?;
+    assert_eq!(sale.fees, cents(5));
+    assert_eq!(sale.lot_gains[0].proceeds, cents(43_995));
+    assert_eq!(
+        sale.realized_gain,
+        Some(Money::new(Decimal::new(399_220, 4), Currency::Usd))
+    );
+
+    let record = &portfolio.get_purchase_record(IBM)?[1];
+    assert_eq!(record.gross_amount()?, Some(usd(440)));
+    assert_eq!(record.settled_amount()?, Some(cents(43_995)));
+    let replayed = portfolio.replay_symbol(IBM)?;
+    assert_eq!(
+        replayed.trades[1].1.realized_gain,
+        Some(Money::new(Decimal::new(399_220, 4), Currency::Usd))
+    );
+    Ok(())
+}
+
+#[rstest]
+fn net_amount_must_match_base_currency() {
+    let mut portfolio = Portfolio::new();
+    assert!(matches!(
+        portfolio.transact_with_net_amount(
+            IBM,
+            10,
+            TransactionType::Purchase,
+            usd(100),
+            Money::new(Decimal::from(1_000), Currency::Eur),
+            at(2024, 1, 2),
+        ),
+        Err(PortfolioError::CurrencyMismatch { .. })
+    ));
+}
+
+fn trading_history() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(at(2024, 6, 1)));
+    for (symbol, shares, kind, price, on) in [
+        (IBM, 10, TransactionType::Purchase, 100, at(2022, 1, 3)),
+        (IBM, 10, TransactionType::Purchase, 120, at(2024, 1, 3)),
+        (IBM, 14, TransactionType::Sell, 130, at(2024, 3, 1)),
+        (VTI, 5, TransactionType::Purchase, 200, at(2024, 2, 1)),
+        (VTI, 5, TransactionType::Sell, 180, at(2024, 4, 1)),
+    ] {
+        p.transact(symbol, shares, kind, Some(usd(price)), on)
+            .unwrap();
+    }
+    p
+}
+
+#[rstest]
+fn realized_gains_break_down_sales_by_lot() -> PortfolioResult<()> {
+    let report = trading_history().realized_gains(IBM)?;
+    assert_eq!(
+        report
+            .lots
+            .iter()
+            .map(|lot| (lot.gain.consumption.shares, lot.gain.gain, lot.gain.term))
+            .collect::<Vec<_>>(),
+        vec![
+            (10, usd(300), HoldingTerm::LongTerm),
+            (4, usd(40), HoldingTerm::ShortTerm),
+        ]
+    );
+    assert!(report.lots.iter().all(|lot| lot.date == at(2024, 3, 1)));
+    assert_eq!(report.long_term_gain, usd(300));
+    assert_eq!(report.short_term_gain, usd(40));
+    assert_eq!(report.total_gain, usd(340));
+    Ok(())
+}
+
+#[rstest]
+fn realized_gains_total_covers_every_symbol() -> PortfolioResult<()> {
+    let report = trading_history().realized_gains_total()?;
+    assert_eq!(report.lots.len(), 3);
+    assert_eq!(report.total_gain, usd(240));
+    assert_eq!(
+        report.by_symbol()?.into_iter().collect::<Vec<_>>(),
+        vec![(IBM.to_string(), usd(340)), (VTI.to_string(), usd(-100))]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn unrealized_gains_value_open_lots_at_current_price() -> PortfolioResult<()> {
+    let report = trading_history().unrealized_gains(IBM, usd(110))?;
+    assert_eq!(report.lots.len(), 1);
+    let lot = &report.lots[0];
+    assert_eq!(lot.date, at(2024, 6, 1));
+    assert_eq!(lot.gain.consumption.shares, 6);
+    assert_eq!(lot.gain.proceeds, usd(660));
+    assert_eq!(lot.gain.gain, usd(-60));
+    assert_eq!(report.short_term_gain, usd(-60));
+    assert_eq!(report.long_term_gain, usd(0));
+    Ok(())
+}
+
+#[rstest]
+fn unrealized_gains_are_empty_without_open_lots() -> PortfolioResult<()> {
+    let report = trading_history().unrealized_gains(VTI, usd(190))?;
+    assert!(report.lots.is_empty());
+    assert_eq!(report.total_gain, usd(0));
+    Ok(())
+}
+
+#[rstest]
+fn realized_gains_require_symbol_history() {
+    assert!(matches!(
+        trading_history().realized_gains("MSFT"),
+        Err(PortfolioError::NoSymbolHistory)
+    ));
+}
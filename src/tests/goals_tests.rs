@@ -0,0 +1,95 @@
+use crate::goals::*;
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn house_goal(monthly_contribution: i64) -> Goal {
+    Goal {
+        name: "House".to_string(),
+        target_value: usd(50_000),
+        target_date: date(2026, 1, 1),
+        monthly_contribution: usd(monthly_contribution),
+    }
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase(VTI, 100).unwrap();
+    p
+}
+
+#[fixture]
+fn quotes() -> Quotes {
+    Quotes::from([(VTI.to_string(), usd(200))])
+}
+
+#[rstest]
+fn reports_funded_percentage_and_required_contribution(
+    mut portfolio: Portfolio,
+    quotes: Quotes,
+) -> PortfolioResult<()> {
+    portfolio.add_goal(house_goal(1_000))?;
+    let progress = portfolio.goal_progress(&quotes, date(2024, 1, 1))?;
+    assert_eq!(
+        progress,
+        vec![GoalProgress {
+            name: "House".to_string(),
+            current_value: usd(20_000),
+            funded_percent: Decimal::from(40),
+            months_remaining: 24,
+            required_monthly_contribution: usd(1_250),
+            on_track: false,
+        }]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn on_track_when_contribution_covers_shortfall(
+    mut portfolio: Portfolio,
+    quotes: Quotes,
+) -> PortfolioResult<()> {
+    portfolio.add_goal(house_goal(1_500))?;
+    let progress = portfolio.goal_progress(&quotes, date(2024, 1, 1))?;
+    assert!(progress[0].on_track);
+    Ok(())
+}
+
+#[rstest]
+fn funded_goal_requires_no_contribution(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    portfolio.add_goal(house_goal(0))?;
+    let quotes = Quotes::from([(VTI.to_string(), usd(600))]);
+    let progress = portfolio.goal_progress(&quotes, date(2024, 1, 1))?;
+    assert_eq!(progress[0].required_monthly_contribution, usd(0));
+    assert_eq!(progress[0].funded_percent, Decimal::from(120));
+    assert!(progress[0].on_track);
+    Ok(())
+}
+
+#[rstest]
+fn past_due_goal_requires_entire_shortfall(
+    mut portfolio: Portfolio,
+    quotes: Quotes,
+) -> PortfolioResult<()> {
+    portfolio.add_goal(house_goal(1_000))?;
+    let progress = portfolio.goal_progress(&quotes, date(2026, 6, 1))?;
+    assert_eq!(progress[0].months_remaining, 0);
+    assert_eq!(progress[0].required_monthly_contribution, usd(30_000));
+    Ok(())
+}
+
+#[rstest]
+fn rejects_goal_without_positive_target(mut portfolio: Portfolio) {
+    let goal = Goal {
+        target_value: usd(0),
+        ..house_goal(100)
+    };
+    assert!(matches!(
+        portfolio.add_goal(goal),
+        Err(PortfolioError::InvalidGoal)
+    ));
+    assert!(portfolio.goals().is_empty());
+}
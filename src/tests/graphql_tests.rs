@@ -0,0 +1,83 @@
+use crate::graphql::*;
+use crate::shared::SharedPortfolio;
+use crate::tests::helpers::*;
+use crate::*;
+use async_graphql::{value, Value};
+use rstest::*;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[fixture]
+fn shared() -> SharedPortfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(AAPL, 5, usd(150)).unwrap();
+    p.sell_at(IBM, 4, usd(120)).unwrap();
+    SharedPortfolio::new(p)
+}
+
+fn execute(schema: &PortfolioSchema, query: &str) -> Value {
+    let response = block_on(schema.execute(query));
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    response.data
+}
+
+#[rstest]
+fn queries_holdings(shared: SharedPortfolio) {
+    let data = execute(&schema(shared), "{ holdings { symbol shares } }");
+    assert_eq!(
+        data,
+        value!({
+            "holdings": [
+                { "symbol": "AAPL", "shares": 5 },
+                { "symbol": "IBM", "shares": 6 },
+            ]
+        })
+    );
+}
+
+#[rstest]
+fn filters_and_paginates_transactions(shared: SharedPortfolio) {
+    let data = execute(
+        &schema(shared),
+        r#"{ transactions(symbols: ["IBM"], offset: 1, limit: 5) { total items { id side shares } } }"#,
+    );
+    assert_eq!(
+        data,
+        value!({
+            "transactions": {
+                "total": 2,
+                "items": [{ "id": 2, "side": "SELL", "shares": 4 }],
+            }
+        })
+    );
+}
+
+#[rstest]
+fn trade_mutation_updates_shared_portfolio(shared: SharedPortfolio) {
+    let schema = schema(shared.clone());
+    execute(
+        &schema,
+        r#"mutation { trade(symbol: "AAPL", side: SELL, shares: 2, price: "160") { resultingShares } }"#,
+    );
+    assert_eq!(shared.read().get_share_count(AAPL), 3);
+
+    let response = block_on(schema.execute(
+        r#"mutation { trade(symbol: "AAPL", side: SELL, shares: 50) { resultingShares } }"#,
+    ));
+    assert_eq!(
+        response.errors[0].message,
+        "Cannot sell more shares than owned"
+    );
+}
@@ -0,0 +1,113 @@
+use crate::calendar::FixedHolidays;
+use crate::gains::HoldingTerm;
+use crate::harvest::*;
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use chrono::{DateTime, Utc};
+use rstest::*;
+use std::collections::HashMap;
+
+fn today() -> DateTime<Utc> {
+    at(2024, 6, 1)
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.set_clock(today);
+    p.transact(
+        IBM,
+        10,
+        TransactionType::Purchase,
+        Some(usd(150)),
+        at(2022, 1, 3),
+    )
+    .unwrap();
+    p.transact(
+        IBM,
+        10,
+        TransactionType::Purchase,
+        Some(usd(130)),
+        at(2024, 5, 20),
+    )
+    .unwrap();
+    p.transact(
+        VTI,
+        10,
+        TransactionType::Purchase,
+        Some(usd(200)),
+        at(2024, 1, 2),
+    )
+    .unwrap();
+    p
+}
+
+#[fixture]
+fn quotes() -> Quotes {
+    Quotes::from([(IBM.to_string(), usd(120)), (VTI.to_string(), usd(198))])
+}
+
+#[rstest]
+fn lists_lots_with_losses_above_threshold(
+    portfolio: Portfolio,
+    quotes: Quotes,
+) -> PortfolioResult<()> {
+    let candidates = portfolio.harvest_candidates(&quotes, usd(50))?;
+    let summary: Vec<_> = candidates
+        .iter()
+        .map(|c| (c.symbol.as_str(), c.loss, c.term, c.tax_savings))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (IBM, usd(300), HoldingTerm::LongTerm, usd(45)),
+            (IBM, usd(100), HoldingTerm::ShortTerm, usd(24)),
+        ]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn dates_when_short_term_losses_turn_long_term(
+    mut portfolio: Portfolio,
+    quotes: Quotes,
+) -> PortfolioResult<()> {
+    portfolio.set_calendar(FixedHolidays::new([date(2025, 5, 21)]));
+    let candidates = portfolio.harvest_candidates(&quotes, usd(50))?;
+    let long_term_on: Vec<_> = candidates.iter().map(|c| c.long_term_on).collect();
+    assert_eq!(long_term_on, vec![None, Some(date(2025, 5, 22))]);
+    Ok(())
+}
+
+#[rstest]
+fn flags_wash_sale_window(portfolio: Portfolio, quotes: Quotes) -> PortfolioResult<()> {
+    let candidates = portfolio.harvest_candidates(&quotes, usd(0))?;
+    let ibm = &candidates[0];
+    assert_eq!(ibm.wash_sale_window.start, at(2024, 5, 2).date_naive());
+    assert_eq!(ibm.wash_sale_window.end, at(2024, 7, 1).date_naive());
+    assert!(ibm.recent_purchase_in_window);
+    let vti = candidates.iter().find(|c| c.symbol == VTI).unwrap();
+    assert!(!vti.recent_purchase_in_window);
+    Ok(())
+}
+
+#[rstest]
+fn pairs_replacement_symbols(portfolio: Portfolio, quotes: Quotes) -> PortfolioResult<()> {
+    let replacements = HashMap::from([(IBM.to_string(), "MSFT".to_string())]);
+    let candidates = pair_replacements(
+        portfolio.harvest_candidates(&quotes, usd(0))?,
+        &replacements,
+    );
+    assert_eq!(candidates[0].replacement.as_deref(), Some("MSFT"));
+    assert_eq!(candidates.last().unwrap().replacement, None);
+    Ok(())
+}
+
+#[rstest]
+fn error_when_quote_missing(portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.harvest_candidates(&Quotes::new(), usd(0)),
+        Err(PortfolioError::MissingPrice(_))
+    ));
+}
@@ -0,0 +1,41 @@
+use crate::money::{Currency, Money};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+
+pub const IBM: &str = "IBM";
+pub const VTI: &str = "VTI";
+pub const AAPL: &str = "AAPL";
+
+pub fn usd(dollars: i64) -> Money {
+    Money::new(Decimal::from(dollars), Currency::Usd)
+}
+
+pub fn usd_amount(amount: Decimal) -> Money {
+    Money::new(amount, Currency::Usd)
+}
+
+pub fn cents(cents: i64) -> Money {
+    Money::new(Decimal::new(cents, 2), Currency::Usd)
+}
+
+pub fn eur(euros: i64) -> Money {
+    Money::new(Decimal::from(euros), Currency::Eur)
+}
+
+pub fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+pub fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    date(year, month, day)
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+}
+
+pub fn noon(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    date(year, month, day)
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+        .and_utc()
+}
@@ -0,0 +1,75 @@
+#[cfg(feature = "toml")]
+use crate::config::PortfolioConfig;
+use crate::i18n::*;
+use crate::money::Currency;
+use crate::*;
+use rstest::*;
+
+#[rstest]
+#[case(Locale::En, "Cannot sell more shares than owned")]
+#[case(Locale::Es, "No se pueden vender más acciones de las que se poseen")]
+#[case(
+    Locale::De,
+    "Es können nicht mehr Anteile verkauft werden als vorhanden"
+)]
+fn translates_error_messages(#[case] locale: Locale, #[case] expected: &str) {
+    assert_eq!(PortfolioError::InvalidSell.localized(locale), expected);
+}
+
+#[rstest]
+fn substitutes_error_arguments() {
+    assert_eq!(
+        PortfolioError::TradeLimitExceeded(50).localized(Locale::De),
+        "Der Handel überschreitet das konfigurierte Limit von 50 Anteilen"
+    );
+}
+
+#[rstest]
+#[case(Locale::Es, "Divisas no coinciden: se esperaba USD, se encontró EUR")]
+#[case(Locale::De, "Währungskonflikt: erwartet USD, gefunden EUR")]
+fn translates_every_error_argument(#[case] locale: Locale, #[case] expected: &str) {
+    let error = PortfolioError::CurrencyMismatch {
+        expected: Currency::Usd,
+        found: Currency::Eur,
+    };
+    assert_eq!(error.localized(locale), expected);
+}
+
+#[rstest]
+fn english_catalog_matches_display(
+    #[values(
+        PortfolioError::ZeroShares,
+        PortfolioError::InvalidSell,
+        PortfolioError::NoSymbolHistory,
+        PortfolioError::InvalidPurchase,
+        PortfolioError::TradeLimitExceeded(1),
+        PortfolioError::CurrencyMismatch {
+            expected: Currency::Usd,
+            found: Currency::Eur,
+        },
+        PortfolioError::InvalidConfig("bad".to_string())
+    )]
+    error: PortfolioError,
+) {
+    assert_eq!(error.localized(Locale::En), error.to_string());
+}
+
+#[rstest]
+fn translates_report_labels() {
+    assert_eq!(label(Label::Shares, Locale::En), "Shares");
+    assert_eq!(label(Label::Shares, Locale::Es), "Acciones");
+    assert_eq!(label(Label::Purchase, Locale::De), "Kauf");
+}
+
+#[cfg(feature = "toml")]
+#[rstest]
+fn portfolio_uses_configured_locale() -> PortfolioResult<()> {
+    let config = PortfolioConfig::from_toml_str("locale = \"es\"")?;
+    let mut portfolio = Portfolio::with_config(config);
+    let error = portfolio.sell("IBM", 1).unwrap_err();
+    assert_eq!(
+        portfolio.localize_error(&error),
+        "No se pueden vender más acciones de las que se poseen"
+    );
+    Ok(())
+}
@@ -0,0 +1,447 @@
+use crate::config::{FutureDatedPolicy, PortfolioConfig, RuleSettings};
+use crate::export::ExportFilter;
+use crate::import::*;
+use crate::load::{LoadMode, LoadOptions};
+use crate::money::{Currency, Money};
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn trade(on: DateTime<Utc>, transaction_type: TransactionType, shares: u32) -> ImportedTransaction {
+    ImportedTransaction {
+        symbol: IBM.to_string(),
+        date: on,
+        transaction_type,
+        shares,
+        price: Some(usd(100)),
+        net_amount: None,
+    }
+}
+
+fn january_file() -> Vec<ImportedTransaction> {
+    vec![
+        trade(at(2024, 1, 2), TransactionType::Purchase, 10),
+        trade(at(2024, 1, 15), TransactionType::Sell, 4),
+    ]
+}
+
+fn overlapping_file() -> Vec<ImportedTransaction> {
+    vec![
+        trade(at(2024, 1, 15), TransactionType::Sell, 4),
+        trade(at(2024, 2, 1), TransactionType::Purchase, 5),
+    ]
+}
+
+#[fixture]
+fn skip_duplicates() -> ImportOptions {
+    ImportOptions {
+        skip_duplicates: true,
+        ..ImportOptions::default()
+    }
+}
+
+#[rstest]
+fn imports_dated_transactions() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let report = portfolio.import(january_file(), &ImportOptions::default())?;
+    assert_eq!(report.imported, 2);
+    assert_eq!(portfolio.get_share_count(IBM), 6);
+    assert_eq!(portfolio.get_purchase_record(IBM)?[0].date, at(2024, 1, 2));
+    Ok(())
+}
+
+#[rstest]
+fn skips_transactions_already_recorded(skip_duplicates: ImportOptions) -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.import(january_file(), &skip_duplicates)?;
+    let report = portfolio.import(overlapping_file(), &skip_duplicates)?;
+    assert_eq!(report.imported, 1);
+    assert_eq!(
+        report.skipped_duplicates,
+        vec![trade(at(2024, 1, 15), TransactionType::Sell, 4)]
+    );
+    assert_eq!(portfolio.get_share_count(IBM), 11);
+    Ok(())
+}
+
+#[rstest]
+fn identical_trades_are_only_skipped_as_often_as_recorded(
+    skip_duplicates: ImportOptions,
+) -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let fill = trade(at(2024, 1, 2), TransactionType::Purchase, 10);
+    portfolio.import(vec![fill.clone()], &skip_duplicates)?;
+    let report = portfolio.import(vec![fill.clone(), fill], &skip_duplicates)?;
+    assert_eq!(report.imported, 1);
+    assert_eq!(report.skipped_duplicates.len(), 1);
+    assert_eq!(portfolio.get_share_count(IBM), 20);
+    Ok(())
+}
+
+#[rstest]
+fn duplicates_are_imported_unless_skipping_is_enabled() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.import(january_file(), &ImportOptions::default())?;
+    let report = portfolio.import(january_file(), &ImportOptions::default())?;
+    assert_eq!(report.imported, 2);
+    assert!(report.skipped_duplicates.is_empty());
+    assert_eq!(portfolio.get_share_count(IBM), 12);
+    Ok(())
+}
+
+fn file_with_invalid_sell() -> Vec<ImportedTransaction> {
+    vec![
+        trade(at(2024, 1, 2), TransactionType::Purchase, 10),
+        trade(at(2024, 1, 10), TransactionType::Sell, 50),
+        trade(at(2024, 1, 15), TransactionType::Sell, 4),
+    ]
+}
+
+#[rstest]
+fn strict_load_aborts_on_first_invalid_record() {
+    let mut portfolio = Portfolio::new();
+    assert!(matches!(
+        portfolio.import(file_with_invalid_sell(), &ImportOptions::default()),
+        Err(PortfolioError::InvalidSell)
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+}
+
+#[rstest]
+fn lenient_load_reports_skipped_records() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let options = ImportOptions {
+        load: LoadOptions {
+            mode: LoadMode::Lenient,
+        },
+        ..ImportOptions::default()
+    };
+    let report = portfolio.import(file_with_invalid_sell(), &options)?;
+    assert_eq!(report.imported, 2);
+    assert!(!report.load.is_clean());
+    assert_eq!(report.load.skipped.len(), 1);
+    assert_eq!(report.load.skipped[0].index, 1);
+    assert!(matches!(
+        report.load.skipped[0].error,
+        PortfolioError::InvalidSell
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 6);
+    Ok(())
+}
+
+fn mid_january() -> DateTime<Utc> {
+    at(2024, 1, 10)
+}
+
+fn end_of_january() -> DateTime<Utc> {
+    at(2024, 1, 31)
+}
+
+fn portfolio_with_future_policy(policy: FutureDatedPolicy) -> Portfolio {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        rules: RuleSettings {
+            future_dated: policy,
+            ..RuleSettings::default()
+        },
+        ..PortfolioConfig::default()
+    });
+    portfolio.set_clock(mid_january);
+    portfolio
+}
+
+#[rstest]
+fn warns_about_future_dated_transactions_but_records_them() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_future_policy(FutureDatedPolicy::Warn);
+    let report = portfolio.import(january_file(), &ImportOptions::default())?;
+    assert_eq!(report.imported, 2);
+    assert_eq!(report.future_dated, vec![january_file()[1].clone()]);
+    assert_eq!(portfolio.get_share_count(IBM), 6);
+    Ok(())
+}
+
+#[rstest]
+fn rejects_future_dated_transactions() {
+    let mut portfolio = portfolio_with_future_policy(FutureDatedPolicy::Reject);
+    let lenient = ImportOptions {
+        load: LoadOptions {
+            mode: LoadMode::Lenient,
+        },
+        ..ImportOptions::default()
+    };
+    let report = portfolio.import(january_file(), &lenient).unwrap();
+    assert_eq!(report.imported, 1);
+    assert!(matches!(
+        report.load.skipped[0].error,
+        PortfolioError::FutureDated(on) if on == at(2024, 1, 15)
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+}
+
+#[rstest]
+fn queues_future_dated_transactions_until_due() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_future_policy(FutureDatedPolicy::Queue);
+    let report = portfolio.import(january_file(), &ImportOptions::default())?;
+    assert_eq!(report.imported, 1);
+    assert_eq!(report.queued.len(), 1);
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+
+    assert!(portfolio.release_due_transactions()?.is_empty());
+    assert_eq!(portfolio.queued_transactions().len(), 1);
+
+    portfolio.set_clock(end_of_january);
+    let confirmations = portfolio.release_due_transactions()?;
+    assert_eq!(confirmations.len(), 1);
+    assert!(portfolio.queued_transactions().is_empty());
+    assert_eq!(portfolio.get_share_count(IBM), 6);
+    Ok(())
+}
+
+const BASIS_FILE: &str = "symbol,acquired,shares,cost_basis,covered
+IBM,2024-01-02,10,1000,covered
+IBM,2024-01-10,5,525.50,covered
+
+IBM,2020-05-01,5,400,noncovered
+";
+
+fn portfolio_with_purchases() -> Portfolio {
+    let mut portfolio = Portfolio::new();
+    portfolio.set_clock(end_of_january);
+    portfolio
+        .import(
+            vec![
+                trade(at(2024, 1, 2), TransactionType::Purchase, 10),
+                trade(at(2024, 1, 10), TransactionType::Purchase, 5),
+            ],
+            &ImportOptions::default(),
+        )
+        .unwrap();
+    portfolio
+}
+
+fn cost_basis_total(portfolio: &Portfolio) -> Decimal {
+    portfolio
+        .iter_lots(IBM)
+        .map(|(_, _, shares, per_share)| per_share * Decimal::from(shares))
+        .sum()
+}
+
+#[rstest]
+fn parses_broker_reported_lots() -> PortfolioResult<()> {
+    let lots = basis_1099b(BASIS_FILE.as_bytes())?;
+    assert_eq!(lots.len(), 3);
+    assert_eq!(
+        lots[1],
+        BrokerLot {
+            symbol: IBM.to_string(),
+            acquired: NaiveDate::from_ymd_opt(2024, 1, 10).unwrap(),
+            shares: 5,
+            cost_basis: Money::new(Decimal::new(52550, 2), Currency::Usd),
+            covered: true,
+        }
+    );
+    assert!(!lots[2].covered);
+    Ok(())
+}
+
+#[rstest]
+#[case("symbol,shares\nIBM,10\n", "expected header")]
+#[case(
+    "symbol,acquired,shares,cost_basis,covered\nIBM,2024-13-01,10,1000,covered\n",
+    "line 2: invalid acquisition date"
+)]
+#[case(
+    "symbol,acquired,shares,cost_basis,covered\nIBM,2024-01-02,10,1000,maybe\n",
+    "line 2: invalid covered flag"
+)]
+fn rejects_malformed_basis_files(#[case] contents: &str, #[case] expected: &str) {
+    assert!(matches!(
+        basis_1099b(contents.as_bytes()),
+        Err(PortfolioError::InvalidBasisImport(detail)) if detail.starts_with(expected)
+    ));
+}
+
+#[rstest]
+fn reconciles_broker_basis_without_changing_lots() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_purchases();
+    let lots = basis_1099b(BASIS_FILE.as_bytes())?;
+    let report = portfolio.apply_broker_basis(&lots, BasisMode::Reconcile)?;
+    assert_eq!(
+        report
+            .iter()
+            .map(|reconciliation| reconciliation.status.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            BasisStatus::Matched,
+            BasisStatus::Mismatched { computed: usd(500) },
+            BasisStatus::Missing,
+        ]
+    );
+    assert_eq!(report[0].transaction_id, Some(0));
+    assert_eq!(cost_basis_total(&portfolio), Decimal::from(1_500));
+    assert_eq!(portfolio.get_share_count(IBM), 15);
+    Ok(())
+}
+
+#[rstest]
+fn overrides_computed_basis_and_adds_missing_lots() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_purchases();
+    let lots = basis_1099b(BASIS_FILE.as_bytes())?;
+    let report = portfolio.apply_broker_basis(&lots, BasisMode::Override)?;
+    assert_eq!(report[2].transaction_id, Some(2));
+    assert_eq!(portfolio.get_share_count(IBM), 20);
+    assert_eq!(cost_basis_total(&portfolio), Decimal::new(192_550, 2));
+    assert_eq!(
+        portfolio.broker_basis_of(2),
+        Some(BrokerBasis {
+            cost_basis: usd(400),
+            covered: false,
+        })
+    );
+
+    portfolio.rebuild()?;
+    assert_eq!(cost_basis_total(&portfolio), Decimal::new(192_550, 2));
+
+    let again = portfolio.apply_broker_basis(&lots, BasisMode::Reconcile)?;
+    assert!(again
+        .iter()
+        .all(|reconciliation| reconciliation.status == BasisStatus::Matched));
+    Ok(())
+}
+
+#[rstest]
+fn reported_gains_use_overridden_broker_basis() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.transact(
+        IBM,
+        10,
+        TransactionType::Purchase,
+        Some(usd(100)),
+        at(2024, 1, 2),
+    )?;
+    let lots = [BrokerLot {
+        symbol: IBM.to_string(),
+        acquired: date(2024, 1, 2),
+        shares: 10,
+        cost_basis: usd(500),
+        covered: true,
+    }];
+    portfolio.apply_broker_basis(&lots, BasisMode::Override)?;
+    let sale = portfolio.transact(
+        IBM,
+        10,
+        TransactionType::Sell,
+        Some(usd(150)),
+        at(2024, 3, 1),
+    )?;
+    assert_eq!(sale.realized_gain, Some(usd(1_000)));
+    assert_eq!(portfolio.realized_gains(IBM)?.total_gain, usd(1_000));
+    Ok(())
+}
+
+const TRANSACTIONS_FILE: &str = "\
+date,symbol,type,shares,price
+2024-01-02,IBM,buy,10,100
+2024-01-15T14:30:00+00:00,IBM,Sell,4,110.50
+
+2024-02-01,VTI,Purchase,3,
+";
+
+fn lenient() -> ImportOptions {
+    ImportOptions {
+        load: LoadOptions {
+            mode: LoadMode::Lenient,
+        },
+        ..ImportOptions::default()
+    }
+}
+
+#[rstest]
+fn imports_transactions_from_csv() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let report = portfolio.import_csv(TRANSACTIONS_FILE.as_bytes(), &ImportOptions::default())?;
+    assert_eq!(report.imported, 3);
+    assert_eq!(portfolio.get_share_count(IBM), 6);
+    assert_eq!(portfolio.get_share_count("VTI"), 3);
+    let sell = &portfolio.get_purchase_record(IBM)?[1];
+    assert_eq!(
+        sell.date,
+        at(2024, 1, 15) + chrono::Duration::minutes(14 * 60 + 30)
+    );
+    assert_eq!(
+        sell.price,
+        Some(Money::new(Decimal::new(11050, 2), Currency::Usd))
+    );
+    assert_eq!(portfolio.get_purchase_record("VTI")?[0].price, None);
+    Ok(())
+}
+
+#[rstest]
+fn csv_export_round_trips_through_import() -> PortfolioResult<()> {
+    let mut original = Portfolio::new();
+    original.import_csv(TRANSACTIONS_FILE.as_bytes(), &ImportOptions::default())?;
+    let exported = original.export_csv(&ExportFilter::default());
+    let mut restored = Portfolio::new();
+    restored.import_csv(exported.as_bytes(), &ImportOptions::default())?;
+    assert_eq!(restored.export_csv(&ExportFilter::default()), exported);
+    assert_eq!(restored.get_share_count(IBM), 6);
+    Ok(())
+}
+
+#[rstest]
+fn csv_import_requires_transaction_header() {
+    let mut portfolio = Portfolio::new();
+    assert!(matches!(
+        portfolio.import_csv(
+            "symbol,shares\nIBM,1\n".as_bytes(),
+            &ImportOptions::default()
+        ),
+        Err(PortfolioError::InvalidTransactionImport(_))
+    ));
+}
+
+#[rstest]
+fn strict_csv_import_reports_the_failing_line() {
+    let contents =
+        "date,symbol,type,shares,price\n2024-01-02,IBM,buy,10,100\n\n2024-01-03,IBM,sell,50,100\n";
+    let mut portfolio = Portfolio::new();
+    let Err(PortfolioError::InvalidTransactionImport(detail)) =
+        portfolio.import_csv(contents.as_bytes(), &ImportOptions::default())
+    else {
+        panic!("expected a located import error");
+    };
+    assert_eq!(detail, format!("line 4: {}", PortfolioError::InvalidSell));
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+}
+
+#[rstest]
+fn lenient_csv_import_collects_row_errors() -> PortfolioResult<()> {
+    let contents = "\
+date,symbol,type,shares,price
+2024-01-02,IBM,buy,10,100
+2024-01-03,IBM,transfer,5,100
+2024-01-04,IBM,sell,50,100
+2024-01-05,IBM,sell,4
+2024-01-06,IBM,sell,4,100
+";
+    let mut portfolio = Portfolio::new();
+    let report = portfolio.import_csv(contents.as_bytes(), &lenient())?;
+    assert_eq!(report.imported, 2);
+    let errors: Vec<String> = report
+        .load
+        .skipped
+        .iter()
+        .map(|skipped| skipped.error.to_string())
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            "Invalid transaction import: line 3: invalid transaction type",
+            "Invalid transaction import: line 4: Cannot sell more shares than owned",
+            "Invalid transaction import: line 5: expected 5 columns",
+        ]
+    );
+    assert_eq!(portfolio.get_share_count(IBM), 6);
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use crate::income::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+const FUND: &str = "VFIAX";
+
+#[fixture]
+fn portfolio_with_fund() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(FUND, 10, usd(400)).unwrap();
+    p
+}
+
+#[rstest]
+fn records_capital_gain_distributions_per_symbol(
+    mut portfolio_with_fund: Portfolio,
+) -> PortfolioResult<()> {
+    portfolio_with_fund.record_capital_gain_distribution(
+        FUND,
+        usd(12),
+        usd(30),
+        at(2023, 12, 15),
+    )?;
+    assert_eq!(
+        portfolio_with_fund.get_capital_gain_distributions(FUND),
+        [CapitalGainDistribution {
+            date: at(2023, 12, 15),
+            short_term: usd(12),
+            long_term: usd(30),
+        }]
+    );
+    assert!(portfolio_with_fund
+        .get_capital_gain_distributions("IBM")
+        .is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn totals_distributions_by_year_and_term(
+    mut portfolio_with_fund: Portfolio,
+) -> PortfolioResult<()> {
+    portfolio_with_fund.record_capital_gain_distribution(
+        FUND,
+        usd(12),
+        usd(30),
+        at(2023, 6, 15),
+    )?;
+    portfolio_with_fund.record_capital_gain_distribution(
+        FUND,
+        usd(3),
+        usd(70),
+        at(2023, 12, 15),
+    )?;
+    portfolio_with_fund.record_capital_gain_distribution(
+        FUND,
+        usd(100),
+        usd(100),
+        at(2024, 12, 15),
+    )?;
+    assert_eq!(
+        portfolio_with_fund.capital_gain_distribution_totals(2023)?,
+        CapitalGainDistributionTotals {
+            short_term: usd(15),
+            long_term: usd(100),
+        }
+    );
+    Ok(())
+}
+
+#[rstest]
+fn distributions_do_not_change_basis(mut portfolio_with_fund: Portfolio) -> PortfolioResult<()> {
+    portfolio_with_fund.record_capital_gain_distribution(
+        FUND,
+        usd(12),
+        usd(30),
+        at(2023, 12, 15),
+    )?;
+    assert_eq!(portfolio_with_fund.lots[FUND][0].cost_basis, usd(4000));
+    Ok(())
+}
+
+#[rstest]
+fn error_on_distribution_for_unheld_symbol_or_negative_amount(mut portfolio_with_fund: Portfolio) {
+    assert!(matches!(
+        portfolio_with_fund.record_capital_gain_distribution("IBM", usd(1), usd(1), at(2023, 1, 1)),
+        Err(PortfolioError::NoOpenLots)
+    ));
+    assert!(matches!(
+        portfolio_with_fund.record_capital_gain_distribution(FUND, usd(-1), usd(1), at(2023, 1, 1)),
+        Err(PortfolioError::NegativeAmount)
+    ));
+}
@@ -0,0 +1,332 @@
+use crate::calendar::{FixedHolidays, WeekendsOnly};
+use crate::config::{CostBasisMethod, PortfolioConfig};
+use crate::instruments::*;
+use crate::integrity::ImportedTransaction;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::NaiveDate;
+use rstest::*;
+use rust_decimal::Decimal;
+
+const FUND: &str = "VFIAX";
+
+#[rstest]
+fn registry_answers_registered_kind() {
+    let mut registry = InstrumentRegistry::new();
+    registry.register(FUND, InstrumentKind::MutualFund);
+    assert_eq!(registry.kind(FUND), Some(InstrumentKind::MutualFund));
+    assert_eq!(registry.kind(IBM), None);
+}
+
+#[rstest]
+#[case(InstrumentKind::Stock, 1)]
+#[case(InstrumentKind::Etf, 1)]
+#[case(InstrumentKind::MutualFund, 1)]
+#[case(InstrumentKind::Bond, 2)]
+#[case(InstrumentKind::Crypto, 0)]
+fn kind_drives_settlement(
+    #[case] kind: InstrumentKind,
+    #[case] settlement_days: u64,
+) -> PortfolioResult<()> {
+    let trade_date = NaiveDate::from_ymd_opt(2024, 6, 3)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+    let instrument = Instrument::new(kind);
+    assert_eq!(kind.settlement_days(), settlement_days);
+    assert_eq!(
+        (instrument.settlement_date(trade_date, &WeekendsOnly)? - trade_date).num_days(),
+        settlement_days as i64
+    );
+    Ok(())
+}
+
+#[rstest]
+fn mutual_funds_default_to_average_cost() {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        cost_basis_method: CostBasisMethod::Lifo,
+        ..PortfolioConfig::default()
+    });
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(FUND, InstrumentKind::MutualFund);
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(IBM, InstrumentKind::Stock);
+    assert_eq!(
+        portfolio.cost_basis_method_for(FUND),
+        CostBasisMethod::AverageCost
+    );
+    assert_eq!(portfolio.cost_basis_method_for(IBM), CostBasisMethod::Lifo);
+    assert_eq!(
+        portfolio.cost_basis_method_for("AAPL"),
+        CostBasisMethod::Lifo
+    );
+}
+
+#[rstest]
+fn confirmations_settle_on_the_portfolio_calendar() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(FUND, InstrumentKind::Bond);
+    portfolio.set_calendar(FixedHolidays::new([date(2024, 7, 4)]));
+    let bond = portfolio.transact(
+        FUND,
+        10,
+        TransactionType::Purchase,
+        Some(usd(100)),
+        noon(2024, 7, 3),
+    )?;
+    assert_eq!(bond.settlement_date, noon(2024, 7, 8));
+    let stock = portfolio.transact(
+        IBM,
+        10,
+        TransactionType::Purchase,
+        Some(usd(100)),
+        noon(2024, 7, 3),
+    )?;
+    assert_eq!(stock.settlement_date, noon(2024, 7, 5));
+    Ok(())
+}
+
+#[rstest]
+fn sells_of_mutual_funds_use_pooled_basis() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(FUND, InstrumentKind::MutualFund);
+    portfolio.purchase_at(FUND, 10, usd(100))?;
+    portfolio.purchase_at(FUND, 10, usd(200))?;
+    portfolio.sell(FUND, 15)?;
+    assert_eq!(portfolio.lots[FUND][0].cost_basis, usd(750));
+    Ok(())
+}
+
+#[rstest]
+fn stores_expense_ratio_for_registered_funds() -> PortfolioResult<()> {
+    let mut registry = InstrumentRegistry::new();
+    registry.register(FUND, InstrumentKind::MutualFund);
+    registry.set_expense_ratio(FUND, Decimal::new(4, 4))?;
+    assert_eq!(registry.expense_ratio(FUND), Some(Decimal::new(4, 4)));
+    assert_eq!(registry.expense_ratio(IBM), None);
+    assert!(matches!(
+        registry.set_expense_ratio(IBM, Decimal::ONE),
+        Err(PortfolioError::UnknownInstrument)
+    ));
+    assert!(matches!(
+        registry.set_expense_ratio(FUND, Decimal::NEGATIVE_ONE),
+        Err(PortfolioError::NegativeAmount)
+    ));
+    Ok(())
+}
+
+#[rstest]
+fn stores_sector_for_registered_instruments() -> PortfolioResult<()> {
+    let mut registry = InstrumentRegistry::new();
+    registry.register(IBM, InstrumentKind::Stock);
+    registry.set_sector(IBM, "Technology")?;
+    assert_eq!(registry.sector(IBM), Some("Technology"));
+    assert_eq!(registry.sector(FUND), None);
+    assert!(matches!(
+        registry.set_sector(FUND, "Broad Market"),
+        Err(PortfolioError::UnknownInstrument)
+    ));
+    Ok(())
+}
+
+#[rstest]
+fn stores_lot_size_for_registered_instruments() -> PortfolioResult<()> {
+    let mut registry = InstrumentRegistry::new();
+    registry.register(IBM, InstrumentKind::Stock);
+    registry.set_lot_size(IBM, 100)?;
+    assert_eq!(registry.lot_size(IBM), Some(100));
+    assert_eq!(registry.lot_size(FUND), None);
+    assert!(matches!(
+        registry.set_lot_size(IBM, 0),
+        Err(PortfolioError::ZeroShares)
+    ));
+    assert!(matches!(
+        registry.set_lot_size(FUND, 100),
+        Err(PortfolioError::UnknownInstrument)
+    ));
+    Ok(())
+}
+
+#[rstest]
+fn trades_must_be_multiples_of_lot_size() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(IBM, InstrumentKind::Stock);
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .set_lot_size(IBM, 100)?;
+    assert!(matches!(
+        portfolio.purchase_at(IBM, 150, usd(10)),
+        Err(PortfolioError::InvalidLotSize(100))
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 0);
+    portfolio.purchase_at(IBM, 200, usd(10))?;
+    assert!(matches!(
+        portfolio.sell_at(IBM, 50, usd(12)),
+        Err(PortfolioError::InvalidLotSize(100))
+    ));
+    portfolio.sell_at(IBM, 100, usd(12))?;
+    assert_eq!(portfolio.get_share_count(IBM), 100);
+    Ok(())
+}
+
+#[rstest]
+fn stock_dividends_are_exempt_from_lot_size() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(IBM, InstrumentKind::Stock);
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .set_lot_size(IBM, 100)?;
+    portfolio.purchase_at(IBM, 100, usd(10))?;
+    portfolio.record_stock_dividend(IBM, 5, Portfolio::fixed_date_time())?;
+    assert_eq!(portfolio.get_share_count(IBM), 105);
+    Ok(())
+}
+
+#[rstest]
+fn closing_a_position_is_exempt_from_lot_size() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(IBM, InstrumentKind::Stock);
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .set_lot_size(IBM, 100)?;
+    portfolio.purchase_at(IBM, 100, usd(10))?;
+    portfolio.record_stock_dividend(IBM, 5, Portfolio::fixed_date_time())?;
+    portfolio.sell_all(IBM, usd(12))?;
+    assert_eq!(portfolio.get_share_count(IBM), 0);
+    Ok(())
+}
+
+#[rstest]
+fn lot_size_does_not_apply_to_earlier_history() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(IBM, InstrumentKind::Stock);
+    portfolio.transact(
+        IBM,
+        150,
+        TransactionType::Purchase,
+        Some(usd(10)),
+        at(2024, 1, 2),
+    )?;
+    portfolio.transact(
+        IBM,
+        30,
+        TransactionType::Sell,
+        Some(usd(12)),
+        at(2024, 2, 1),
+    )?;
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .set_lot_size(IBM, 100)?;
+    assert_eq!(portfolio.realized_gains(IBM)?.total_gain, usd(60));
+    Ok(())
+}
+
+#[rstest]
+fn halted_symbols_reject_trades_until_resumed() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(IBM, InstrumentKind::Stock);
+    portfolio.purchase_at(IBM, 10, usd(10))?;
+    portfolio.instruments_mut().unwrap().set_halted(IBM, true)?;
+    assert!(portfolio.instruments().is_halted(IBM));
+    assert!(matches!(
+        portfolio.sell_at(IBM, 5, usd(12)),
+        Err(PortfolioError::TradingHalted(symbol)) if symbol == IBM
+    ));
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .set_halted(IBM, false)?;
+    portfolio.sell_at(IBM, 5, usd(12))?;
+    assert_eq!(portfolio.get_share_count(IBM), 5);
+    assert!(!portfolio.instruments().is_halted(FUND));
+    assert!(matches!(
+        portfolio.instruments_mut().unwrap().set_halted(FUND, true),
+        Err(PortfolioError::UnknownInstrument)
+    ));
+    Ok(())
+}
+
+#[fixture]
+fn halted_after_trading() -> Portfolio {
+    let mut portfolio = Portfolio::new();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .register(IBM, InstrumentKind::Stock);
+    portfolio
+        .transact(
+            IBM,
+            10,
+            TransactionType::Purchase,
+            Some(usd(10)),
+            at(2024, 1, 2),
+        )
+        .unwrap();
+    portfolio
+        .transact(IBM, 4, TransactionType::Sell, Some(usd(15)), at(2024, 2, 1))
+        .unwrap();
+    portfolio
+        .instruments_mut()
+        .unwrap()
+        .set_halted(IBM, true)
+        .unwrap();
+    portfolio
+}
+
+#[rstest]
+fn halts_do_not_block_reports_that_replay_history(
+    halted_after_trading: Portfolio,
+) -> PortfolioResult<()> {
+    assert_eq!(
+        halted_after_trading.realized_gains(IBM)?.total_gain,
+        usd(20)
+    );
+    Ok(())
+}
+
+#[rstest]
+fn halts_reject_backdated_trades(mut halted_after_trading: Portfolio) {
+    let backdated = ImportedTransaction {
+        symbol: IBM.to_string(),
+        date: at(2024, 1, 1),
+        transaction_type: TransactionType::Purchase,
+        shares: 5,
+        price: Some(usd(9)),
+        net_amount: None,
+    };
+    assert!(matches!(
+        halted_after_trading.insert_backdated(backdated),
+        Err(PortfolioError::TradingHalted(symbol)) if symbol == IBM
+    ));
+    assert_eq!(halted_after_trading.get_share_count(IBM), 6);
+}
@@ -0,0 +1,265 @@
+use crate::clock::FixedClock;
+#[cfg(feature = "import")]
+use crate::import::ImportOptions;
+use crate::integrity::*;
+use crate::ledger::Transaction;
+#[cfg(feature = "import")]
+use crate::money::Money;
+use crate::position::Position;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(IBM, 5, usd(110)).unwrap();
+    p.sell(IBM, 7).unwrap();
+    p.purchase(AAPL, 3).unwrap();
+    p
+}
+
+fn void(portfolio: &mut Portfolio, id: TransactionId) {
+    let trade = portfolio.ledger.trade(id).unwrap().clone();
+    portfolio.ledger.append(Transaction::Void(trade));
+}
+
+#[rstest]
+fn consistent_portfolio_has_no_issues(portfolio: Portfolio) {
+    assert_eq!(portfolio.verify_integrity(), vec![]);
+}
+
+#[rstest]
+fn detects_holdings_that_diverge_from_records(mut portfolio: Portfolio) {
+    portfolio
+        .holdings
+        .insert(AAPL.to_string(), Position::Long(5));
+    assert_eq!(
+        portfolio.verify_integrity(),
+        vec![
+            IntegrityIssue::HoldingsDivergeFromRecords {
+                symbol: AAPL.to_string(),
+                holdings: 5,
+                records: 3,
+            },
+            IntegrityIssue::LotsDivergeFromHoldings {
+                symbol: AAPL.to_string(),
+                holdings: 5,
+                lots: 3,
+            },
+        ]
+    );
+}
+
+#[rstest]
+fn detects_lots_that_diverge_from_holdings(mut portfolio: Portfolio) {
+    portfolio.lots.get_mut(IBM).unwrap().remove(0);
+    assert_eq!(
+        portfolio.verify_integrity(),
+        vec![IntegrityIssue::LotsDivergeFromHoldings {
+            symbol: IBM.to_string(),
+            holdings: 8,
+            lots: 5,
+        }]
+    );
+}
+
+#[rstest]
+fn detects_records_without_holdings(mut portfolio: Portfolio) {
+    portfolio.holdings.remove(IBM);
+    let issues = portfolio.verify_integrity();
+    assert!(
+        issues.contains(&IntegrityIssue::HoldingsDivergeFromRecords {
+            symbol: IBM.to_string(),
+            holdings: 0,
+            records: 8,
+        })
+    );
+}
+
+#[rstest]
+fn rebuild_restores_holdings_and_lots_from_records(
+    mut portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    let lots = portfolio.lots.clone();
+    portfolio
+        .holdings
+        .insert(AAPL.to_string(), Position::Long(5));
+    portfolio.lots.clear();
+    portfolio.rebuild()?;
+    assert_eq!(portfolio.verify_integrity(), vec![]);
+    assert_eq!(portfolio.get_share_count(IBM), 8);
+    assert_eq!(portfolio.get_share_count(AAPL), 3);
+    assert_eq!(portfolio.lots[IBM][0].cost_basis, lots[IBM][0].cost_basis);
+    assert_eq!(portfolio.lots[IBM][1].cost_basis, lots[IBM][1].cost_basis);
+    Ok(())
+}
+
+#[rstest]
+fn rebuild_reflects_amended_ledger(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    void(&mut portfolio, 2);
+    portfolio.rebuild()?;
+    assert_eq!(portfolio.get_share_count(IBM), 15);
+    assert_eq!(portfolio.lots[IBM].len(), 2);
+    Ok(())
+}
+
+#[rstest]
+fn rebuild_discards_edits_to_projections(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    portfolio.purchase_records.get_mut(IBM).unwrap().remove(2);
+    portfolio.rebuild()?;
+    assert_eq!(portfolio.get_purchase_record(IBM)?.len(), 3);
+    assert_eq!(portfolio.get_share_count(IBM), 8);
+    Ok(())
+}
+
+#[rstest]
+fn rebuild_reapplies_return_of_capital(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 6, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+    portfolio.apply_return_of_capital(IBM, usd(10), date)?;
+    let lots = portfolio.lots.clone();
+    portfolio.rebuild()?;
+    assert_eq!(portfolio.lots, lots);
+    Ok(())
+}
+
+#[rstest]
+fn failed_rebuild_leaves_state_untouched(mut portfolio: Portfolio) {
+    void(&mut portfolio, 0);
+    let holdings = portfolio.holdings.clone();
+    assert!(matches!(
+        portfolio.rebuild(),
+        Err(PortfolioError::InvalidSell)
+    ));
+    assert_eq!(portfolio.holdings, holdings);
+}
+
+#[cfg(feature = "import")]
+fn dated(
+    month: u32,
+    day: u32,
+    transaction_type: TransactionType,
+    shares: u32,
+    price: i64,
+) -> ImportedTransaction {
+    ImportedTransaction {
+        symbol: IBM.to_string(),
+        date: at(2024, month, day),
+        transaction_type,
+        shares,
+        price: Some(usd(price)),
+        net_amount: None,
+    }
+}
+
+#[cfg(feature = "import")]
+#[fixture]
+fn dated_portfolio() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.import(
+        vec![
+            dated(1, 2, TransactionType::Purchase, 10, 100),
+            dated(3, 1, TransactionType::Sell, 5, 130),
+        ],
+        &ImportOptions::default(),
+    )
+    .unwrap();
+    p
+}
+
+#[cfg(feature = "import")]
+#[rstest]
+fn backdated_trade_is_inserted_chronologically_and_lots_rematched(
+    mut dated_portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    let id = dated_portfolio.insert_backdated(dated(1, 1, TransactionType::Purchase, 5, 80))?;
+    let records = dated_portfolio.get_purchase_record(IBM)?;
+    assert_eq!(records[0].id, id);
+    assert_eq!(records[0].date, at(2024, 1, 1));
+    let lots: Vec<(u32, Money)> = dated_portfolio.lots[IBM]
+        .iter()
+        .map(|lot| (lot.shares, lot.cost_basis))
+        .collect();
+    assert_eq!(lots, vec![(10, usd(1000))]);
+    assert_eq!(dated_portfolio.get_share_count(IBM), 10);
+    Ok(())
+}
+
+#[cfg(feature = "import")]
+#[rstest]
+fn error_when_backdated_trade_invalidates_later_sell(mut dated_portfolio: Portfolio) {
+    assert!(matches!(
+        dated_portfolio.insert_backdated(dated(2, 1, TransactionType::Sell, 8, 120)),
+        Err(PortfolioError::InvalidSell)
+    ));
+    assert_eq!(dated_portfolio.get_purchase_record(IBM).unwrap().len(), 2);
+    assert_eq!(dated_portfolio.get_share_count(IBM), 5);
+}
+
+#[rstest]
+fn events_list_transactions_in_journal_order(portfolio: Portfolio) {
+    let events = portfolio.events();
+    assert_eq!(
+        events
+            .iter()
+            .filter_map(Transaction::as_trade)
+            .map(|trade| (trade.symbol.as_str(), trade.record.id))
+            .collect::<Vec<_>>(),
+        vec![(IBM, 0), (IBM, 1), (IBM, 2), (AAPL, 3)]
+    );
+}
+
+#[rstest]
+fn from_events_projects_holdings_and_lots(portfolio: Portfolio) -> PortfolioResult<()> {
+    let mut events = portfolio.events();
+    events.reverse();
+    let mut restored = Portfolio::from_events(events)?;
+    assert_eq!(restored.holdings, portfolio.holdings);
+    assert_eq!(restored.lots, portfolio.lots);
+    assert_eq!(restored.journal(), portfolio.journal());
+    assert_eq!(restored.purchase(AAPL, 1)?.transaction_id, 4);
+    Ok(())
+}
+
+#[rstest]
+fn replaying_a_prefix_undoes_later_transactions(portfolio: Portfolio) -> PortfolioResult<()> {
+    let mut events = portfolio.events();
+    events.truncate(2);
+    let undone = Portfolio::from_events(events)?;
+    assert_eq!(undone.get_share_count(IBM), 15);
+    assert_eq!(undone.get_share_count(AAPL), 0);
+    Ok(())
+}
+
+#[rstest]
+fn from_events_rejects_duplicate_transaction_ids(portfolio: Portfolio) {
+    let mut events = portfolio.events();
+    events.push(events[0].clone());
+    assert!(matches!(
+        Portfolio::from_events(events),
+        Err(PortfolioError::InvalidSerializedPortfolio(_))
+    ));
+}
+
+#[rstest]
+fn from_events_rejects_inconsistent_history(portfolio: Portfolio) {
+    let events = portfolio.events().into_iter().skip(1);
+    assert!(matches!(
+        Portfolio::from_events(events),
+        Err(PortfolioError::InvalidSell)
+    ));
+}
+
+#[rstest]
+fn rebuild_bumps_version(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let version = portfolio.version();
+    portfolio.rebuild()?;
+    assert!(portfolio.version() > version);
+    assert_eq!(portfolio.verify_integrity(), vec![]);
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use crate::config::{Jurisdiction, PortfolioConfig};
+use crate::jurisdiction::*;
+use crate::money::Money;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{DateTime, Utc};
+use rstest::*;
+
+const VOD: &str = "VOD";
+
+fn portfolio_in(jurisdiction: Jurisdiction) -> Portfolio {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        jurisdiction,
+        ..PortfolioConfig::default()
+    });
+    portfolio.set_clock(|| noon(2025, 1, 1));
+    portfolio
+}
+
+fn trade(
+    portfolio: &mut Portfolio,
+    kind: TransactionType,
+    shares: u32,
+    price: i64,
+    at: DateTime<Utc>,
+) {
+    portfolio
+        .transact(VOD, shares, kind, Some(usd(price)), at)
+        .unwrap();
+}
+
+fn summary(matches: &[ShareMatch]) -> Vec<(MatchRule, u32, Money)> {
+    matches
+        .iter()
+        .map(|share_match| (share_match.rule, share_match.shares, share_match.cost))
+        .collect()
+}
+
+#[rstest]
+fn us_rules_follow_lot_consumption() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_in(Jurisdiction::Us);
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        10,
+        100,
+        noon(2024, 1, 2),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        10,
+        120,
+        noon(2024, 2, 1),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Sell,
+        15,
+        130,
+        noon(2024, 3, 1),
+    );
+
+    let disposals = portfolio.disposals(VOD)?;
+    assert_eq!(disposals.len(), 1);
+    assert_eq!(
+        summary(&disposals[0].matches),
+        vec![
+            (MatchRule::Lot, 10, usd(1_000)),
+            (MatchRule::Lot, 5, usd(600)),
+        ]
+    );
+    assert_eq!(disposals[0].gain, usd(350));
+    Ok(())
+}
+
+#[rstest]
+fn uk_rules_match_same_day_then_bed_and_breakfast_then_pool() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_in(Jurisdiction::Uk);
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        100,
+        10,
+        noon(2024, 1, 10),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Sell,
+        50,
+        15,
+        noon(2024, 5, 1),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        20,
+        12,
+        noon(2024, 5, 1),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        10,
+        14,
+        noon(2024, 5, 20),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Sell,
+        80,
+        20,
+        noon(2024, 9, 2),
+    );
+
+    let disposals = portfolio.disposals(VOD)?;
+    assert_eq!(
+        summary(&disposals[0].matches),
+        vec![
+            (MatchRule::SameDay, 20, usd(240)),
+            (MatchRule::BedAndBreakfast, 10, usd(140)),
+            (MatchRule::Section104, 20, usd(200)),
+        ]
+    );
+    assert_eq!(disposals[0].allowable_cost, usd(580));
+    assert_eq!(disposals[0].gain, usd(170));
+    assert_eq!(
+        summary(&disposals[1].matches),
+        vec![(MatchRule::Section104, 80, usd(800))]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn canada_denies_superficial_loss_and_adds_it_to_replacement_basis() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_in(Jurisdiction::Canada);
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        100,
+        10,
+        noon(2024, 1, 10),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Sell,
+        100,
+        8,
+        noon(2024, 3, 1),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        50,
+        9,
+        noon(2024, 3, 15),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Sell,
+        50,
+        12,
+        noon(2024, 6, 3),
+    );
+
+    let disposals = portfolio.disposals(VOD)?;
+    assert_eq!(disposals[0].denied_loss, usd(100));
+    assert_eq!(disposals[0].gain, usd(-100));
+    assert_eq!(disposals[1].allowable_cost, usd(550));
+    assert_eq!(disposals[1].gain, usd(50));
+    assert!(disposals[1].denied_loss.is_zero());
+    Ok(())
+}
+
+#[rstest]
+fn canada_allows_loss_without_repurchase() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_in(Jurisdiction::Canada);
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        100,
+        10,
+        noon(2024, 1, 10),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Sell,
+        100,
+        8,
+        noon(2024, 3, 1),
+    );
+
+    let disposals = portfolio.disposals(VOD)?;
+    assert!(disposals[0].denied_loss.is_zero());
+    assert_eq!(disposals[0].gain, usd(-200));
+    Ok(())
+}
+
+#[rstest]
+fn rules_can_be_applied_independently_of_config() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_in(Jurisdiction::Us);
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        10,
+        10,
+        noon(2024, 1, 10),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Purchase,
+        10,
+        20,
+        noon(2024, 2, 10),
+    );
+    trade(
+        &mut portfolio,
+        TransactionType::Sell,
+        10,
+        20,
+        noon(2024, 6, 3),
+    );
+
+    let pooled = UkShareMatching.disposals(&portfolio, VOD)?;
+    assert_eq!(pooled[0].allowable_cost, usd(150));
+    assert_eq!(portfolio.disposals(VOD)?[0].allowable_cost, usd(100));
+    Ok(())
+}
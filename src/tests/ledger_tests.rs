@@ -0,0 +1,166 @@
+use crate::clock::FixedClock;
+use crate::config::{PortfolioConfig, RuleSettings};
+use crate::ledger::Transaction;
+use crate::position::Position;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+fn short_selling() -> PortfolioConfig {
+    PortfolioConfig {
+        rules: RuleSettings {
+            allow_short_selling: true,
+            ..RuleSettings::default()
+        },
+        ..PortfolioConfig::default()
+    }
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::with_config(short_selling());
+    p.set_clock(FixedClock(noon(2025, 1, 1)));
+    p.transact(
+        IBM,
+        10,
+        TransactionType::Purchase,
+        Some(usd(100)),
+        at(2024, 1, 2),
+    )
+    .unwrap();
+    p.receive_gift(VTI, 5, usd(250), at(2020, 1, 1), usd(400), at(2024, 2, 1))
+        .unwrap();
+    p.apply_return_of_capital(IBM, usd(5), at(2024, 3, 1))
+        .unwrap();
+    p.record_dividend(VTI, usd(2), at(2024, 4, 1), None)
+        .unwrap();
+    p.record_rsu_vest(AAPL, 4, usd(150), at(2024, 5, 1))
+        .unwrap();
+    p.sell_at("TSLA", 3, usd(200)).unwrap();
+    p
+}
+
+#[rstest]
+fn records_every_change_in_order(portfolio: Portfolio) {
+    let kinds: Vec<&str> = portfolio
+        .ledger()
+        .transactions()
+        .iter()
+        .map(|transaction| match transaction {
+            Transaction::Trade(_) => "trade",
+            Transaction::ReturnOfCapital { .. } => "return_of_capital",
+            Transaction::Dividend { .. } => "dividend",
+            _ => "other",
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "trade",
+            "trade",
+            "return_of_capital",
+            "dividend",
+            "trade",
+            "trade"
+        ]
+    );
+}
+
+#[rstest]
+fn from_events_restores_side_state(portfolio: Portfolio) -> PortfolioResult<()> {
+    let restored = Portfolio::from_events(portfolio.events())?;
+    assert_eq!(restored.lots, portfolio.lots);
+    assert_eq!(restored.acquisitions, portfolio.acquisitions);
+    assert_eq!(restored.equity_awards, portfolio.equity_awards);
+    assert_eq!(restored.get_return_of_capital_history(IBM).len(), 1);
+    assert_eq!(restored.get_dividends(VTI), portfolio.get_dividends(VTI));
+    assert_eq!(restored.open_lots(VTI)[0].cost_basis, usd(1250));
+    assert_eq!(restored.open_lots(IBM)[0].cost_basis, usd(950));
+    assert_eq!(restored.rsu_vest_income()?, usd(600));
+    Ok(())
+}
+
+#[rstest]
+fn from_events_takes_the_config_from_the_leading_event(
+    portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    let events = portfolio.events();
+    assert!(matches!(&events[0], Transaction::Configure { config } if **config == short_selling()));
+    let restored = Portfolio::from_events(events.clone())?;
+    assert_eq!(restored.get_position("TSLA"), Position::Short(3));
+    assert!(matches!(
+        Portfolio::from_events(events.into_iter().skip(1)),
+        Err(PortfolioError::InvalidSell)
+    ));
+    Ok(())
+}
+
+#[rstest]
+fn from_events_restores_every_mutation(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    portfolio.record_capital_gain_distribution(VTI, usd(1), usd(3), at(2024, 6, 1))?;
+    portfolio.record_deposit(usd(1000), at(2024, 6, 2))?;
+    portfolio.record_withdrawal(usd(200), at(2024, 6, 3))?;
+    portfolio.lend_shares(IBM, 6)?;
+    portfolio.accrue_lending_income(IBM, usd(7))?;
+    portfolio.recall_shares(IBM, 6)?;
+    portfolio.tag_transaction(0, "core")?;
+    portfolio.reverse_transaction(0, "booked twice")?;
+    let restored = Portfolio::from_events(portfolio.events())?;
+    assert_eq!(
+        restored.get_capital_gain_distributions(VTI),
+        portfolio.get_capital_gain_distributions(VTI)
+    );
+    assert_eq!(restored.deposits(), portfolio.deposits());
+    assert_eq!(restored.withdrawals(), portfolio.withdrawals());
+    assert_eq!(restored.get_shares_on_loan(IBM), 0);
+    assert_eq!(restored.get_lending_income(IBM), usd(7));
+    assert_eq!(
+        restored.transaction_tags(0).collect::<Vec<_>>(),
+        vec!["core"]
+    );
+    assert_eq!(restored.reversals(), portfolio.reversals());
+    assert_eq!(restored.get_share_count(IBM), 0);
+    Ok(())
+}
+
+#[rstest]
+fn rebuild_derives_every_projection_from_the_ledger(
+    mut portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    let lots = portfolio.lots.clone();
+    portfolio.lots.clear();
+    portfolio.acquisitions.clear();
+    portfolio.equity_awards.clear();
+    portfolio.return_of_capital.clear();
+    portfolio.dividends.clear();
+    portfolio.rebuild()?;
+    assert_eq!(portfolio.lots, lots);
+    assert_eq!(portfolio.open_lots(VTI)[0].cost_basis, usd(1250));
+    assert_eq!(portfolio.rsu_vest_income()?, usd(600));
+    assert_eq!(portfolio.get_return_of_capital_history(IBM).len(), 1);
+    assert_eq!(portfolio.get_dividends(VTI).len(), 1);
+    Ok(())
+}
+
+#[rstest]
+fn rebuild_replays_renamed_history(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    portfolio.rename_symbol(IBM, "KD")?;
+    portfolio.sell_at("KD", 4, usd(120))?;
+    let gain = portfolio.realized_gains("KD")?.total_gain;
+    portfolio.rebuild()?;
+    assert_eq!(portfolio.get_share_count("KD"), 6);
+    assert_eq!(portfolio.get_share_count(IBM), 0);
+    assert_eq!(gain, usd(100));
+    assert_eq!(portfolio.realized_gains("KD")?.total_gain, gain);
+    Ok(())
+}
+
+#[rstest]
+fn failed_trades_leave_the_ledger_unchanged(mut portfolio: Portfolio) {
+    let length = portfolio.ledger().len();
+    assert!(portfolio.sell_at(VTI, 1, usd(-1)).is_err());
+    assert!(portfolio
+        .sell_lots(VTI, &[], usd(1), at(2024, 6, 1))
+        .is_err());
+    assert_eq!(portfolio.ledger().len(), length);
+}
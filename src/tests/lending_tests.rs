@@ -0,0 +1,121 @@
+use crate::money::{Currency, Money};
+use crate::sync::{apply_delta, export_delta};
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+#[fixture]
+fn portfolio_with_ibm_on_loan() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase(IBM, 10).unwrap();
+    p.lend_shares(IBM, 6).unwrap();
+    p
+}
+
+#[rstest]
+fn shares_on_loan_remain_owned_but_unavailable(portfolio_with_ibm_on_loan: Portfolio) {
+    assert_eq!(portfolio_with_ibm_on_loan.get_share_count(IBM), 10);
+    assert_eq!(portfolio_with_ibm_on_loan.get_shares_on_loan(IBM), 6);
+    assert_eq!(portfolio_with_ibm_on_loan.get_available_shares(IBM), 4);
+}
+
+#[rstest]
+fn cannot_sell_shares_on_loan(mut portfolio_with_ibm_on_loan: Portfolio) -> PortfolioResult<()> {
+    assert!(matches!(
+        portfolio_with_ibm_on_loan.sell(IBM, 5),
+        Err(PortfolioError::SharesOnLoan)
+    ));
+    portfolio_with_ibm_on_loan.sell(IBM, 4)?;
+    assert_eq!(portfolio_with_ibm_on_loan.get_share_count(IBM), 6);
+    Ok(())
+}
+
+#[rstest]
+fn recalled_shares_become_sellable(
+    mut portfolio_with_ibm_on_loan: Portfolio,
+) -> PortfolioResult<()> {
+    portfolio_with_ibm_on_loan.recall_shares(IBM, 6)?;
+    assert_eq!(portfolio_with_ibm_on_loan.get_shares_on_loan(IBM), 0);
+    portfolio_with_ibm_on_loan.sell(IBM, 10)?;
+    assert_eq!(portfolio_with_ibm_on_loan.get_share_count(IBM), 0);
+    Ok(())
+}
+
+#[rstest]
+fn cannot_lend_more_than_available(mut portfolio_with_ibm_on_loan: Portfolio) {
+    assert!(matches!(
+        portfolio_with_ibm_on_loan.lend_shares(IBM, 5),
+        Err(PortfolioError::InvalidLend)
+    ));
+    assert!(matches!(
+        portfolio_with_ibm_on_loan.lend_shares(AAPL, 1),
+        Err(PortfolioError::InvalidLend)
+    ));
+    assert!(matches!(
+        portfolio_with_ibm_on_loan.lend_shares(IBM, 0),
+        Err(PortfolioError::ZeroShares)
+    ));
+}
+
+#[rstest]
+fn cannot_recall_more_than_on_loan(mut portfolio_with_ibm_on_loan: Portfolio) {
+    assert!(matches!(
+        portfolio_with_ibm_on_loan.recall_shares(IBM, 7),
+        Err(PortfolioError::InsufficientSharesOnLoan)
+    ));
+    assert!(matches!(
+        portfolio_with_ibm_on_loan.recall_shares(AAPL, 1),
+        Err(PortfolioError::InsufficientSharesOnLoan)
+    ));
+}
+
+#[rstest]
+fn accrues_lending_income_per_symbol(
+    mut portfolio_with_ibm_on_loan: Portfolio,
+) -> PortfolioResult<()> {
+    portfolio_with_ibm_on_loan.accrue_lending_income(IBM, cents(125))?;
+    portfolio_with_ibm_on_loan.accrue_lending_income(IBM, cents(250))?;
+    assert_eq!(
+        portfolio_with_ibm_on_loan.get_lending_income(IBM),
+        cents(375)
+    );
+    assert_eq!(
+        portfolio_with_ibm_on_loan.get_lending_income(AAPL),
+        cents(0)
+    );
+    assert_eq!(
+        portfolio_with_ibm_on_loan.total_lending_income()?,
+        cents(375)
+    );
+    Ok(())
+}
+
+#[rstest]
+fn cannot_accrue_income_without_shares_on_loan(mut portfolio_with_ibm_on_loan: Portfolio) {
+    assert!(matches!(
+        portfolio_with_ibm_on_loan.accrue_lending_income(AAPL, cents(100)),
+        Err(PortfolioError::InsufficientSharesOnLoan)
+    ));
+    assert!(matches!(
+        portfolio_with_ibm_on_loan
+            .accrue_lending_income(IBM, Money::new(Decimal::ONE, Currency::Eur)),
+        Err(PortfolioError::CurrencyMismatch { .. })
+    ));
+}
+
+#[rstest]
+fn synced_sells_recall_loans_beyond_the_holding() -> PortfolioResult<()> {
+    let mut server = Portfolio::new();
+    server.purchase(IBM, 10)?;
+    let mut mobile = Portfolio::new();
+    let first = export_delta(&server, 0);
+    apply_delta(&mut mobile, &first)?;
+    mobile.lend_shares(IBM, 10)?;
+    server.sell(IBM, 5)?;
+    apply_delta(&mut mobile, &export_delta(&server, first.version))?;
+    assert_eq!(mobile.get_share_count(IBM), 5);
+    assert_eq!(mobile.get_shares_on_loan(IBM), 5);
+    assert_eq!(mobile.get_available_shares(IBM), 0);
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::config::{PortfolioConfig, RuleSettings};
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+
+const TSLA: &str = "TSLA";
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::with_config(PortfolioConfig {
+        rules: RuleSettings {
+            allow_short_selling: true,
+            ..RuleSettings::default()
+        },
+        ..PortfolioConfig::default()
+    });
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(AAPL, 5, usd(50)).unwrap();
+    p.sell(TSLA, 2).unwrap();
+    p
+}
+
+fn quotes() -> Quotes {
+    Quotes::from([
+        (IBM.to_string(), usd(120)),
+        (AAPL.to_string(), usd(40)),
+        (TSLA.to_string(), usd(30)),
+    ])
+}
+
+#[rstest]
+fn liquidates_every_position(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let liquidation = portfolio.liquidate(&quotes())?;
+    assert_eq!(liquidation.confirmations.len(), 3);
+    assert_eq!(liquidation.net_proceeds, usd(1340));
+    assert_eq!(liquidation.realized_gain, usd(150));
+    assert!(portfolio.market_value(&Quotes::new())?.is_zero());
+    Ok(())
+}
+
+#[rstest]
+fn liquidation_is_atomic_when_a_price_is_missing(mut portfolio: Portfolio) {
+    let mut quotes = quotes();
+    quotes.remove(TSLA);
+    assert!(matches!(
+        portfolio.liquidate(&quotes),
+        Err(PortfolioError::MissingPrice(symbol)) if symbol == TSLA
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+    assert_eq!(portfolio.get_share_count(AAPL), 5);
+    assert_eq!(portfolio.get_purchase_record(IBM).unwrap().len(), 1);
+}
@@ -0,0 +1,402 @@
+use crate::clock::FixedClock;
+use crate::config::{CostBasisMethod, PortfolioConfig};
+use crate::lots::{ConsolidationPolicy, LotConsolidation, LotId, LotSelection};
+use crate::money::{Currency, Money};
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn portfolio_with_two_lots(method: CostBasisMethod) -> Portfolio {
+    let mut p = Portfolio::with_config(PortfolioConfig {
+        cost_basis_method: method,
+        ..PortfolioConfig::default()
+    });
+    p.set_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(IBM, 10, usd(200)).unwrap();
+    p
+}
+
+fn remaining_basis(portfolio: &Portfolio) -> Vec<(u32, Money)> {
+    portfolio.lots[IBM]
+        .iter()
+        .map(|lot| (lot.shares, lot.cost_basis))
+        .collect()
+}
+
+#[rstest]
+fn purchase_at_opens_lot_with_cost_basis() {
+    let portfolio = portfolio_with_two_lots(CostBasisMethod::Fifo);
+    assert_eq!(
+        remaining_basis(&portfolio),
+        vec![(10, usd(1000)), (10, usd(2000))]
+    );
+    assert_ne!(portfolio.lots[IBM][0].id, portfolio.lots[IBM][1].id);
+}
+
+#[rstest]
+#[case(CostBasisMethod::Fifo, vec![(5, usd(1000))])]
+#[case(CostBasisMethod::Lifo, vec![(5, usd(500))])]
+fn sell_consumes_lots_in_configured_order(
+    #[case] method: CostBasisMethod,
+    #[case] expected: Vec<(u32, Money)>,
+) -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_two_lots(method);
+    portfolio.sell(IBM, 15)?;
+    assert_eq!(remaining_basis(&portfolio), expected);
+    Ok(())
+}
+
+#[rstest]
+fn average_cost_pools_basis_before_selling() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_two_lots(CostBasisMethod::AverageCost);
+    portfolio.sell(IBM, 15)?;
+    assert_eq!(remaining_basis(&portfolio), vec![(5, usd(750))]);
+    Ok(())
+}
+
+#[rstest]
+fn unpriced_purchase_opens_zero_basis_lot() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    portfolio.purchase(IBM, 3)?;
+    assert_eq!(remaining_basis(&portfolio), vec![(3, usd(0))]);
+    Ok(())
+}
+
+#[rstest]
+fn rejects_invalid_prices() {
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    assert!(matches!(
+        portfolio.purchase_at(IBM, 1, usd(-1)),
+        Err(PortfolioError::NegativeAmount)
+    ));
+    assert!(matches!(
+        portfolio.purchase_at(IBM, 1, Money::new(Decimal::ONE, Currency::Eur)),
+        Err(PortfolioError::CurrencyMismatch { .. })
+    ));
+    assert!(portfolio.is_empty());
+}
+
+#[rstest]
+fn answers_cost_basis_and_average_price() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_two_lots(CostBasisMethod::Fifo);
+    assert_eq!(portfolio.cost_basis(IBM)?, usd(3_000));
+    assert_eq!(portfolio.average_price(IBM)?, Some(usd(150)));
+    portfolio.sell_at(IBM, 15, usd(250))?;
+    assert_eq!(portfolio.cost_basis(IBM)?, usd(1_000));
+    assert_eq!(portfolio.average_price(IBM)?, Some(usd(200)));
+    assert_eq!(portfolio.cost_basis("AAPL")?, usd(0));
+    assert_eq!(portfolio.average_price("AAPL")?, None);
+    Ok(())
+}
+
+#[rstest]
+fn iterates_open_lots_with_per_share_basis() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_two_lots(CostBasisMethod::Fifo);
+    portfolio.sell(IBM, 4)?;
+    let epoch = Portfolio::fixed_date_time().date_naive();
+    let lots: Vec<_> = portfolio.iter_lots(IBM).collect();
+    assert_eq!(
+        lots,
+        vec![
+            (2, epoch, 6, Decimal::from(100)),
+            (1, epoch, 10, Decimal::from(200)),
+        ]
+    );
+    assert_eq!(portfolio.iter_lots("AAPL").count(), 0);
+    Ok(())
+}
+
+#[rstest]
+fn partial_sell_splits_lot_and_records_lineage() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_two_lots(CostBasisMethod::Fifo);
+    let original = portfolio.lots[IBM][0].clone();
+    portfolio.sell(IBM, 4)?;
+    portfolio.sell(IBM, 1)?;
+    let remainder = &portfolio.lots[IBM][0];
+    assert_ne!(remainder.id, original.id);
+    assert_eq!(remainder.acquired, original.acquired);
+    assert_eq!((remainder.shares, remainder.cost_basis), (5, usd(500)));
+    assert_eq!(
+        portfolio.lot_lineage(remainder.id),
+        vec![remainder.id, 2, original.id]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn fully_consumed_lots_are_not_split() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_two_lots(CostBasisMethod::Fifo);
+    portfolio.sell(IBM, 10)?;
+    let remaining: Vec<LotId> = portfolio.iter_lots(IBM).map(|(id, ..)| id).collect();
+    assert_eq!(remaining, vec![1]);
+    assert_eq!(portfolio.lot_lineage(1), vec![1]);
+    Ok(())
+}
+
+fn portfolio_with_drip_lots() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(IBM, 1, usd(100)).unwrap();
+    p.purchase_at(IBM, 1, usd(100)).unwrap();
+    p.purchase_at(IBM, 1, usd(101)).unwrap();
+    p.purchase_at(IBM, 5, usd(150)).unwrap();
+    p
+}
+
+#[rstest]
+fn consolidates_identical_adjacent_lots() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_drip_lots();
+    let consolidations = portfolio.consolidate_lots(IBM, &ConsolidationPolicy::default())?;
+    assert_eq!(
+        consolidations,
+        vec![LotConsolidation {
+            merged: vec![0, 1, 2],
+            into: 5,
+        }]
+    );
+    assert_eq!(
+        remaining_basis(&portfolio),
+        vec![(12, usd(1200)), (1, usd(101)), (5, usd(750))]
+    );
+    assert_eq!(portfolio.get_lot_consolidations(IBM), consolidations);
+    Ok(())
+}
+
+#[rstest]
+fn consolidates_lots_within_price_tolerance() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_drip_lots();
+    let policy = ConsolidationPolicy {
+        price_tolerance: Decimal::ONE,
+        ..ConsolidationPolicy::default()
+    };
+    portfolio.consolidate_lots(IBM, &policy)?;
+    assert_eq!(
+        remaining_basis(&portfolio),
+        vec![(13, usd(1301)), (5, usd(750))]
+    );
+    assert_eq!(portfolio.get_share_count(IBM), 18);
+    Ok(())
+}
+
+#[rstest]
+fn error_when_consolidating_symbol_without_lots() {
+    assert!(matches!(
+        Portfolio::new().consolidate_lots(IBM, &ConsolidationPolicy::default()),
+        Err(PortfolioError::NoOpenLots)
+    ));
+}
+
+fn sell_consolidated_lot(portfolio: &mut Portfolio) -> PortfolioResult<()> {
+    portfolio.consolidate_lots(IBM, &ConsolidationPolicy::default())?;
+    let merged = LotSelection {
+        lot_id: 5,
+        shares: 11,
+    };
+    portfolio.sell_lots(IBM, &[merged], usd(120), Portfolio::fixed_date_time())?;
+    Ok(())
+}
+
+#[rstest]
+fn rebuild_replays_consolidations() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_drip_lots();
+    sell_consolidated_lot(&mut portfolio)?;
+    let lots = portfolio.lots.clone();
+    let consolidations = portfolio.get_lot_consolidations(IBM).to_vec();
+    portfolio.rebuild()?;
+    assert_eq!(portfolio.lots, lots);
+    assert_eq!(portfolio.get_lot_consolidations(IBM), consolidations);
+    assert_eq!(
+        remaining_basis(&portfolio),
+        vec![(1, usd(100)), (1, usd(101)), (5, usd(750))]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn lot_matching_follows_sequence_when_timestamps_tie() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_two_lots(CostBasisMethod::Fifo);
+    portfolio.lots.get_mut(IBM).unwrap().reverse();
+    portfolio.sell(IBM, 10)?;
+    assert_eq!(remaining_basis(&portfolio), vec![(10, usd(2000))]);
+    Ok(())
+}
+
+#[rstest]
+fn journal_orders_records_by_date_then_sequence() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    portfolio.purchase(IBM, 1)?;
+    portfolio.purchase("AAPL", 2)?;
+    portfolio.sell(IBM, 1)?;
+    let ids: Vec<TransactionId> = portfolio
+        .journal()
+        .iter()
+        .map(|(_, record)| record.id)
+        .collect();
+    assert_eq!(ids, vec![0, 1, 2]);
+    Ok(())
+}
+
+#[rstest]
+fn stock_dividend_spreads_shares_across_lots_without_basis() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_two_lots(CostBasisMethod::Fifo);
+    let acquired: Vec<_> = portfolio.lots[IBM].iter().map(|lot| lot.acquired).collect();
+    portfolio.record_stock_dividend(IBM, 5, Portfolio::fixed_date_time())?;
+
+    assert_eq!(portfolio.get_share_count(IBM), 25);
+    assert_eq!(
+        remaining_basis(&portfolio),
+        vec![(13, usd(1000)), (12, usd(2000))]
+    );
+    assert_eq!(
+        portfolio.lots[IBM]
+            .iter()
+            .map(|lot| lot.acquired)
+            .collect::<Vec<_>>(),
+        acquired
+    );
+    assert!(portfolio.verify_integrity().is_empty());
+
+    portfolio.rebuild()?;
+    assert_eq!(
+        remaining_basis(&portfolio),
+        vec![(13, usd(1000)), (12, usd(2000))]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn error_when_stock_dividend_has_no_open_lots() {
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    assert!(matches!(
+        portfolio.record_stock_dividend(IBM, 5, Portfolio::fixed_date_time()),
+        Err(PortfolioError::NoOpenLots)
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 0);
+}
+
+fn portfolio_with_mixed_lots(method: CostBasisMethod) -> Portfolio {
+    let mut p = Portfolio::with_config(PortfolioConfig {
+        cost_basis_method: method,
+        ..PortfolioConfig::default()
+    });
+    p.set_clock(|| at(2024, 12, 31));
+    for (price, acquired) in [
+        (100, at(2022, 1, 3)),
+        (150, at(2023, 1, 3)),
+        (160, at(2024, 3, 1)),
+        (110, at(2024, 4, 1)),
+    ] {
+        p.transact(
+            IBM,
+            10,
+            TransactionType::Purchase,
+            Some(usd(price)),
+            acquired,
+        )
+        .unwrap();
+    }
+    p
+}
+
+#[rstest]
+#[case(CostBasisMethod::Fifo, 130, vec![(10, usd(300)), (5, usd(-100))])]
+#[case(CostBasisMethod::Lifo, 130, vec![(10, usd(200)), (5, usd(-150))])]
+#[case(CostBasisMethod::HighestCost, 130, vec![(10, usd(-300)), (5, usd(-100))])]
+#[case(CostBasisMethod::MinimizeTax, 130, vec![(10, usd(-200)), (5, usd(-150))])]
+#[case(CostBasisMethod::MinimizeTax, 200, vec![(10, usd(500)), (5, usd(500))])]
+fn lot_matching_strategies_on_same_history(
+    #[case] method: CostBasisMethod,
+    #[case] price: i64,
+    #[case] expected: Vec<(u32, Money)>,
+) -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_mixed_lots(method);
+    let confirmation = portfolio.transact(
+        IBM,
+        15,
+        TransactionType::Sell,
+        Some(usd(price)),
+        at(2024, 6, 3),
+    )?;
+    let realized: Vec<(u32, Money)> = confirmation
+        .lot_gains
+        .iter()
+        .map(|gain| (gain.consumption.shares, gain.gain))
+        .collect();
+    assert_eq!(realized, expected);
+    Ok(())
+}
+
+#[rstest]
+fn minimize_tax_falls_back_to_highest_cost_without_price() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_mixed_lots(CostBasisMethod::MinimizeTax);
+    portfolio.sell(IBM, 10)?;
+    let bases: Vec<Money> = portfolio.lots[IBM]
+        .iter()
+        .map(|lot| lot.cost_basis)
+        .collect();
+    assert_eq!(bases, vec![usd(1000), usd(1500), usd(1100)]);
+    Ok(())
+}
+
+#[rstest]
+fn sells_specific_lots_regardless_of_method() -> PortfolioResult<()> {
+    let mut portfolio = portfolio_with_two_lots(CostBasisMethod::Fifo);
+    let expensive = portfolio.open_lots(IBM)[1].id;
+    let confirmation = portfolio.sell_lots(
+        IBM,
+        &[LotSelection {
+            lot_id: expensive,
+            shares: 4,
+        }],
+        usd(250),
+        Portfolio::fixed_date_time(),
+    )?;
+    assert_eq!(confirmation.realized_gain, Some(usd(200)));
+    assert_eq!(
+        remaining_basis(&portfolio),
+        vec![(10, usd(1_000)), (6, usd(1_200))]
+    );
+
+    let sale = confirmation.transaction_id;
+    let replayed = portfolio.replay_symbol(IBM)?;
+    assert_eq!(
+        replayed.trades.last().unwrap().1.realized_gain,
+        Some(usd(200))
+    );
+    portfolio.rebuild()?;
+    assert_eq!(
+        remaining_basis(&portfolio),
+        vec![(10, usd(1_000)), (6, usd(1_200))]
+    );
+    assert_eq!(portfolio.lot_selection_of(sale).unwrap()[0].shares, 4);
+    Ok(())
+}
+
+#[rstest]
+fn rejects_unknown_or_oversized_lot_selections() {
+    let mut portfolio = portfolio_with_two_lots(CostBasisMethod::Fifo);
+    let first = portfolio.open_lots(IBM)[0].id;
+    let sell = |portfolio: &mut Portfolio, lot_id, shares| {
+        portfolio.sell_lots(
+            IBM,
+            &[LotSelection { lot_id, shares }],
+            usd(250),
+            Portfolio::fixed_date_time(),
+        )
+    };
+    assert!(matches!(
+        sell(&mut portfolio, 99, 1),
+        Err(PortfolioError::UnknownLot(99))
+    ));
+    assert!(matches!(
+        sell(&mut portfolio, first, 11),
+        Err(PortfolioError::InvalidSell)
+    ));
+    assert!(matches!(
+        sell(&mut portfolio, first, 0),
+        Err(PortfolioError::ZeroShares)
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 20);
+}
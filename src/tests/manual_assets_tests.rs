@@ -0,0 +1,95 @@
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.add_manual_asset("House", usd(400_000), date(2023, 1, 1))
+        .unwrap();
+    p
+}
+
+#[rstest]
+fn valuation_history_is_kept_in_date_order(mut portfolio: Portfolio) {
+    portfolio
+        .update_valuation(0, usd(450_000), date(2024, 1, 1))
+        .unwrap();
+    portfolio
+        .update_valuation(0, usd(420_000), date(2023, 6, 1))
+        .unwrap();
+    let asset = &portfolio.manual_assets()[0];
+    assert_eq!(
+        asset
+            .valuations
+            .iter()
+            .map(|valuation| valuation.date)
+            .collect::<Vec<_>>(),
+        vec![date(2023, 1, 1), date(2023, 6, 1), date(2024, 1, 1)]
+    );
+    assert_eq!(asset.value_on(date(2023, 12, 31)), Some(usd(420_000)));
+    assert_eq!(asset.value_on(date(2022, 12, 31)), None);
+}
+
+#[rstest]
+fn revaluing_the_same_day_replaces_it(mut portfolio: Portfolio) {
+    portfolio
+        .update_valuation(0, usd(390_000), date(2023, 1, 1))
+        .unwrap();
+    assert_eq!(portfolio.manual_assets()[0].valuations.len(), 1);
+    assert_eq!(
+        portfolio.manual_asset_value(date(2023, 1, 1)).unwrap(),
+        usd(390_000)
+    );
+}
+
+#[rstest]
+fn net_worth_adds_manual_assets_to_market_value(mut portfolio: Portfolio) {
+    portfolio
+        .add_manual_asset("Private shares", usd(25_000), date(2024, 3, 1))
+        .unwrap();
+    let quotes = Quotes::from([(IBM.to_string(), usd(150))]);
+    assert_eq!(
+        portfolio
+            .net_worth(&quotes, date(2024, 2, 1))
+            .unwrap()
+            .total,
+        usd(401_500)
+    );
+    assert_eq!(
+        portfolio
+            .net_worth(&quotes, date(2024, 3, 1))
+            .unwrap()
+            .total,
+        usd(426_500)
+    );
+}
+
+#[rstest]
+fn unknown_assets_are_rejected(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.update_valuation(7, usd(1), date(2024, 1, 1)),
+        Err(PortfolioError::UnknownManualAsset(7))
+    ));
+    assert!(matches!(
+        portfolio.remove_manual_asset(7),
+        Err(PortfolioError::UnknownManualAsset(7))
+    ));
+}
+
+#[rstest]
+fn removes_manual_assets(mut portfolio: Portfolio) {
+    let removed = portfolio.remove_manual_asset(0).unwrap();
+    assert_eq!(removed.name, "House");
+    assert!(portfolio.manual_assets().is_empty());
+}
+
+#[rstest]
+fn rejects_negative_valuations(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.update_valuation(0, usd(-1), date(2024, 1, 1)),
+        Err(PortfolioError::NegativeAmount)
+    ));
+}
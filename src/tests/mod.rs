@@ -1,20 +1,140 @@
+#[cfg(all(test, feature = "pricing"))]
+mod advisory_tests;
+#[cfg(all(test, feature = "pricing"))]
+mod alerts_tests;
+#[cfg(test)]
+mod auth_tests;
+#[cfg(all(test, feature = "toml"))]
+mod automation_tests;
+#[cfg(test)]
+mod basis_tests;
+#[cfg(test)]
+mod blotter_tests;
+#[cfg(test)]
+mod calendar_tests;
+#[cfg(all(test, feature = "toml", feature = "import", feature = "pricing"))]
+mod canonical_tests;
+#[cfg(test)]
+mod clock_tests;
+#[cfg(all(test, feature = "toml"))]
+mod config_tests;
+#[cfg(test)]
+mod corporate_actions_tests;
+#[cfg(test)]
+mod dividends_tests;
+#[cfg(test)]
+mod equity_tests;
+#[cfg(test)]
+mod events_tests;
+#[cfg(test)]
+mod execution_tests;
+#[cfg(all(test, feature = "import"))]
+mod export_tests;
+#[cfg(test)]
+mod external_tests;
+#[cfg(test)]
+mod fx_tests;
+#[cfg(test)]
+mod gains_tests;
+#[cfg(test)]
+mod goals_tests;
+#[cfg(all(test, feature = "graphql"))]
+mod graphql_tests;
+#[cfg(test)]
+mod harvest_tests;
+#[cfg(test)]
+mod helpers;
+#[cfg(test)]
+mod i18n_tests;
+#[cfg(all(test, feature = "import"))]
+mod import_tests;
+#[cfg(test)]
+mod income_tests;
+#[cfg(test)]
+mod instruments_tests;
+#[cfg(test)]
+mod integrity_tests;
+#[cfg(test)]
+mod jurisdiction_tests;
+#[cfg(test)]
+mod ledger_tests;
+#[cfg(test)]
+mod lending_tests;
+#[cfg(test)]
+mod liquidation_tests;
+#[cfg(test)]
+mod lots_tests;
+#[cfg(test)]
+mod manual_assets_tests;
+#[cfg(test)]
+mod money_tests;
+#[cfg(test)]
+mod net_worth_tests;
+#[cfg(test)]
+mod notifications_tests;
+#[cfg(test)]
+mod numeric_tests;
+#[cfg(test)]
+mod paper_tests;
+#[cfg(all(test, feature = "pricing"))]
+mod performance_tests;
+#[cfg(test)]
+mod period_tests;
+#[cfg(all(test, feature = "plaid"))]
+mod plaid_tests;
+#[cfg(test)]
+mod position_tests;
+#[cfg(all(test, feature = "pricing"))]
+mod prices_tests;
+#[cfg(test)]
+mod publishing_tests;
+#[cfg(all(test, feature = "import"))]
+mod reconcile_tests;
+#[cfg(test)]
+mod report_tests;
+#[cfg(test)]
+mod reversal_tests;
+#[cfg(all(test, feature = "pricing"))]
+mod rmd_tests;
+#[cfg(test)]
+mod shared_tests;
+#[cfg(all(test, feature = "pricing"))]
+mod snapshots_tests;
+#[cfg(test)]
+mod summary_tests;
+#[cfg(test)]
+mod sync_tests;
+#[cfg(test)]
+mod tax_tests;
+#[cfg(test)]
+mod timestamps_tests;
+#[cfg(test)]
+mod versions_tests;
+#[cfg(test)]
+mod view_tests;
+#[cfg(all(test, feature = "webhooks"))]
+mod webhooks_tests;
+
 #[cfg(test)]
 mod portfolio_tests {
+    use crate::clock::FixedClock;
+
+    use crate::position::Position;
+    use crate::tests::helpers::*;
+    use crate::Quotes;
     use crate::*;
     use rstest::*;
 
-    const IBM: &str = "IBM";
-    const AAPL: &str = "AAPL";
     const UNPURCHASED_SYMBOL: &str = "unpurchased_symbol";
 
     #[fixture]
     fn portfolio() -> Portfolio {
-        Portfolio::new()
+        Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()))
     }
 
     #[fixture]
     fn portfolio_with_ibm() -> Portfolio {
-        let mut p = Portfolio::new();
+        let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
         p.purchase(IBM, 2).unwrap();
         p
     }
@@ -30,6 +150,29 @@ mod portfolio_tests {
         Ok(())
     }
 
+    #[rstest]
+    fn positions_iterate_open_holdings(mut portfolio: Portfolio) -> PortfolioResult<()> {
+        portfolio.purchase_at(IBM, 10, usd(100))?;
+        portfolio.purchase_at(AAPL, 5, usd(150))?;
+        portfolio.sell_at(AAPL, 5, usd(160))?;
+        let positions: Vec<(&str, Position)> = portfolio.positions().collect();
+        assert_eq!(positions, vec![(IBM, Position::Long(10))]);
+        Ok(())
+    }
+
+    #[rstest]
+    fn records_answer_signed_shares(mut portfolio: Portfolio) -> PortfolioResult<()> {
+        portfolio.purchase_at(IBM, 10, usd(100))?;
+        portfolio.sell_at(IBM, 4, usd(110))?;
+        let signed: Vec<i64> = portfolio
+            .get_purchase_record(IBM)?
+            .iter()
+            .map(PurchaseRecord::signed_shares)
+            .collect();
+        assert_eq!(signed, vec![10, -4]);
+        Ok(())
+    }
+
     #[rstest]
     fn answers_zero_for_share_count_of_unpurchased_symbol(portfolio: Portfolio) {
         assert_eq!(portfolio.get_share_count(UNPURCHASED_SYMBOL), 0);
@@ -100,6 +243,147 @@ mod portfolio_tests {
         ));
     }
 
+    #[rstest]
+    fn total_share_count_widens_to_avoid_overflow(mut portfolio: Portfolio) -> PortfolioResult<()> {
+        portfolio.purchase(IBM, u32::MAX)?;
+        portfolio.purchase(AAPL, u32::MAX)?;
+        assert_eq!(portfolio.total_share_count(), 2 * u128::from(u32::MAX));
+        Ok(())
+    }
+
+    #[rstest]
+    fn market_value_prices_every_position(mut portfolio: Portfolio) -> PortfolioResult<()> {
+        portfolio.purchase(IBM, 2)?;
+        portfolio.purchase(AAPL, 3)?;
+        let quotes = Quotes::from([(IBM.to_string(), usd(100)), (AAPL.to_string(), usd(10))]);
+        assert_eq!(portfolio.market_value(&quotes)?, usd(230));
+        Ok(())
+    }
+
+    #[rstest]
+    fn market_value_requires_price_for_every_position(portfolio_with_ibm: Portfolio) {
+        assert!(matches!(
+            portfolio_with_ibm.market_value(&Quotes::new()),
+            Err(PortfolioError::MissingPrice(symbol)) if symbol == IBM
+        ));
+    }
+
+    #[rstest]
+    fn purchase_returns_trade_confirmation(
+        mut portfolio_with_ibm: Portfolio,
+    ) -> PortfolioResult<()> {
+        let confirmation = portfolio_with_ibm.purchase_at(IBM, 3, usd(100))?;
+        assert_eq!(
+            confirmation,
+            TradeConfirmation {
+                transaction_id: 1,
+                symbol: IBM.to_string(),
+                transaction_type: TransactionType::Purchase,
+                shares: 3,
+                price: Some(usd(100)),
+                fees: usd(0),
+                resulting_position: Position::Long(5),
+                realized_gain: None,
+                lot_gains: vec![],
+                settlement_date: portfolio_with_ibm
+                    .settlement_date(IBM, portfolio_with_ibm.get_purchase_record(IBM)?[1].date())?,
+            }
+        );
+        Ok(())
+    }
+
+    #[rstest]
+    fn priced_sell_confirms_realized_gain(mut portfolio: Portfolio) -> PortfolioResult<()> {
+        portfolio.purchase_at(IBM, 4, usd(100))?;
+        portfolio.purchase_at(IBM, 4, usd(120))?;
+        let confirmation = portfolio.sell_at(IBM, 6, usd(130))?;
+        assert_eq!(confirmation.transaction_type, TransactionType::Sell);
+        assert_eq!(confirmation.resulting_position, Position::Long(2));
+        assert_eq!(confirmation.realized_gain, Some(usd(140)));
+        assert_eq!(portfolio.sell(IBM, 1)?.realized_gain, None);
+        Ok(())
+    }
+
+    #[rstest]
+    fn sell_all_liquidates_long_position(mut portfolio: Portfolio) -> PortfolioResult<()> {
+        portfolio.purchase_at(IBM, 4, usd(100))?;
+        portfolio.purchase_at(IBM, 6, usd(110))?;
+        let confirmation = portfolio.sell_all(IBM, usd(120))?;
+        assert_eq!(confirmation.shares, 10);
+        assert_eq!(confirmation.resulting_position, Position::Flat);
+        assert_eq!(confirmation.realized_gain, Some(usd(140)));
+        assert!(matches!(
+            portfolio.sell_all(IBM, usd(120)),
+            Err(PortfolioError::ZeroShares)
+        ));
+        Ok(())
+    }
+
+    #[rstest]
+    fn close_position_sells_long_position(
+        mut portfolio_with_ibm: Portfolio,
+    ) -> PortfolioResult<()> {
+        let confirmation = portfolio_with_ibm.close_position(IBM, usd(90))?;
+        assert_eq!(confirmation.transaction_type, TransactionType::Sell);
+        assert_eq!(portfolio_with_ibm.get_position(IBM), Position::Flat);
+        assert!(matches!(
+            portfolio_with_ibm.close_position(IBM, usd(90)),
+            Err(PortfolioError::ZeroShares)
+        ));
+        Ok(())
+    }
+
+    fn keyed_purchase(key: &str, shares: u32) -> Order {
+        Order {
+            symbol: IBM.to_string(),
+            transaction_type: TransactionType::Purchase,
+            shares,
+            price: Some(usd(100)),
+            idempotency_key: Some(key.to_string()),
+        }
+    }
+
+    #[rstest]
+    fn replayed_idempotency_key_returns_original_confirmation(
+        mut portfolio: Portfolio,
+    ) -> PortfolioResult<()> {
+        let original = portfolio.submit(keyed_purchase("req-1", 3))?;
+        let replay = portfolio.submit(keyed_purchase("req-1", 3))?;
+        assert_eq!(replay, original);
+        assert_eq!(portfolio.get_share_count(IBM), 3);
+        assert_eq!(portfolio.get_purchase_record(IBM)?.len(), 1);
+        portfolio.submit(keyed_purchase("req-2", 3))?;
+        assert_eq!(portfolio.get_share_count(IBM), 6);
+        Ok(())
+    }
+
+    #[rstest]
+    fn error_when_idempotency_key_is_reused_for_different_order(
+        mut portfolio: Portfolio,
+    ) -> PortfolioResult<()> {
+        portfolio.submit(keyed_purchase("req-1", 3))?;
+        assert!(matches!(
+            portfolio.submit(keyed_purchase("req-1", 4)),
+            Err(PortfolioError::IdempotencyKeyConflict(key)) if key == "req-1"
+        ));
+        assert_eq!(portfolio.get_share_count(IBM), 3);
+        Ok(())
+    }
+
+    #[rstest]
+    fn orders_without_idempotency_key_always_execute(
+        mut portfolio: Portfolio,
+    ) -> PortfolioResult<()> {
+        let order = Order {
+            idempotency_key: None,
+            ..keyed_purchase("unused", 2)
+        };
+        portfolio.submit(order.clone())?;
+        portfolio.submit(order)?;
+        assert_eq!(portfolio.get_share_count(IBM), 4);
+        Ok(())
+    }
+
     #[rstest]
     fn answers_purchase_record_for_existing_share(mut portfolio: Portfolio) -> PortfolioResult<()> {
         let num_shares = 3u32;
@@ -108,14 +392,32 @@ mod portfolio_tests {
         assert_eq!(
             record,
             vec![PurchaseRecord {
+                id: 0,
                 date: Portfolio::fixed_date_time(),
+                day_sequence: 0,
                 shares: num_shares,
                 transaction_type: TransactionType::Purchase,
+                price: None,
+                fx: None,
+                net_amount: None,
             }]
         );
         Ok(())
     }
 
+    #[rstest]
+    fn exposes_purchase_record_through_accessors(mut portfolio: Portfolio) -> PortfolioResult<()> {
+        portfolio.purchase_at(IBM, 3, usd(100))?;
+        let record = &portfolio.get_purchase_record(IBM)?[0];
+        assert_eq!(record.id(), 0);
+        assert_eq!(record.date(), Portfolio::fixed_date_time());
+        assert_eq!(record.shares(), 3);
+        assert_eq!(record.transaction_type(), &TransactionType::Purchase);
+        assert_eq!(record.price(), Some(usd(100)));
+        assert_eq!(record.net_amount(), None);
+        Ok(())
+    }
+
     #[rstest]
     fn error_when_accessing_purchase_record_for_symbol_with_no_history(portfolio: Portfolio) {
         assert!(matches!(
@@ -145,23 +447,38 @@ mod portfolio_tests {
         assert_eq!(
             portfolio.get_purchase_record(IBM)?,
             vec![PurchaseRecord {
+                id: 0,
                 date: Portfolio::fixed_date_time(),
+                day_sequence: 0,
                 shares: ibm_shares,
-                transaction_type: TransactionType::Purchase
+                transaction_type: TransactionType::Purchase,
+                price: None,
+                fx: None,
+                net_amount: None,
             }]
         );
         assert_eq!(
             portfolio.get_purchase_record(AAPL)?,
             vec![
                 PurchaseRecord {
+                    id: 1,
                     date: Portfolio::fixed_date_time(),
+                    day_sequence: 1,
                     shares: aapl_shares,
-                    transaction_type: TransactionType::Purchase
+                    transaction_type: TransactionType::Purchase,
+                    price: None,
+                    fx: None,
+                    net_amount: None,
                 },
                 PurchaseRecord {
+                    id: 2,
                     date: Portfolio::fixed_date_time(),
+                    day_sequence: 2,
                     shares: aapl_shares_sell,
-                    transaction_type: TransactionType::Sell
+                    transaction_type: TransactionType::Sell,
+                    price: None,
+                    fx: None,
+                    net_amount: None,
                 }
             ]
         );
@@ -0,0 +1,89 @@
+use crate::config::{RoundingMode, RoundingPolicy};
+use crate::i18n::Locale;
+use crate::money::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn eur_cents(cents: i64) -> Money {
+    Money::new(Decimal::new(cents, 2), Currency::Eur)
+}
+
+#[rstest]
+#[case(cents(123456), "$1,234.56")]
+#[case(eur_cents(123456), "1.234,56 €")]
+#[case(cents(-100), "-$1.00")]
+#[case(cents(5), "$0.05")]
+#[case(cents(100000000), "$1,000,000.00")]
+#[case(Money::new(Decimal::new(98765, 0), Currency::Jpy), "¥98,765")]
+fn displays_in_currency_convention(#[case] money: Money, #[case] expected: &str) {
+    assert_eq!(money.to_string(), expected);
+}
+
+#[rstest]
+fn formats_for_requested_locale() {
+    assert_eq!(cents(123456).format(Locale::De), "1.234,56 $");
+    assert_eq!(eur_cents(123456).format(Locale::En), "€1,234.56");
+}
+
+#[rstest]
+fn adds_and_subtracts_same_currency() -> PortfolioResult<()> {
+    assert_eq!(cents(150).checked_add(&cents(275))?, cents(425));
+    assert_eq!(cents(150).checked_sub(&cents(275))?, cents(-125));
+    Ok(())
+}
+
+#[rstest]
+fn refuses_to_combine_mismatched_currencies() {
+    assert!(matches!(
+        cents(100).checked_add(&eur_cents(100)),
+        Err(PortfolioError::CurrencyMismatch {
+            expected: Currency::Usd,
+            found: Currency::Eur,
+        })
+    ));
+    assert!(matches!(
+        eur_cents(100).checked_sub(&cents(100)),
+        Err(PortfolioError::CurrencyMismatch { .. })
+    ));
+}
+
+#[rstest]
+#[case(RoundingMode::HalfEven, cents(12))]
+#[case(RoundingMode::HalfUp, cents(13))]
+#[case(RoundingMode::Truncate, cents(12))]
+fn rounds_according_to_policy(#[case] mode: RoundingMode, #[case] expected: Money) {
+    let money = Money::new(Decimal::new(125, 3), Currency::Usd);
+    let policy = RoundingPolicy {
+        mode,
+        decimal_places: 2,
+    };
+    assert_eq!(money.rounded(&policy), expected);
+}
+
+#[rstest]
+fn error_on_amount_overflow() {
+    let max = Money::new(Decimal::MAX, Currency::Usd);
+    assert!(matches!(
+        max.checked_add(&cents(100)),
+        Err(PortfolioError::Overflow)
+    ));
+    assert!(matches!(
+        max.checked_mul(Decimal::TWO),
+        Err(PortfolioError::Overflow)
+    ));
+}
+
+#[rstest]
+fn sums_amounts_with_checked_arithmetic() -> PortfolioResult<()> {
+    assert_eq!(
+        Money::checked_sum(Currency::Usd, &[cents(100), cents(250), cents(-50)])?,
+        cents(300)
+    );
+    assert!(matches!(
+        Money::checked_sum(Currency::Usd, &[cents(100), eur_cents(100)]),
+        Err(PortfolioError::CurrencyMismatch { .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::liabilities::LiabilityKind;
+use crate::manager::PortfolioManager;
+use crate::money::Currency;
+use crate::net_worth::*;
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.add_manual_asset("House", usd(400_000), date(2023, 1, 1))
+        .unwrap();
+    p.add_liability(
+        "Mortgage",
+        LiabilityKind::Loan,
+        usd(300_000),
+        date(2023, 1, 1),
+    )
+    .unwrap();
+    p
+}
+
+#[fixture]
+fn quotes() -> Quotes {
+    Quotes::from([(IBM.to_string(), usd(150))])
+}
+
+#[rstest]
+fn net_worth_breaks_down_assets_and_liabilities(portfolio: Portfolio, quotes: Quotes) {
+    assert_eq!(
+        portfolio.net_worth(&quotes, date(2024, 1, 1)).unwrap(),
+        NetWorth {
+            investments: usd(1_500),
+            manual_assets: usd(400_000),
+            liabilities: usd(300_000),
+            total: usd(101_500),
+        }
+    );
+}
+
+#[rstest]
+fn liability_balances_follow_their_history(mut portfolio: Portfolio, quotes: Quotes) {
+    portfolio
+        .update_liability_balance(0, usd(290_000), date(2024, 1, 1))
+        .unwrap();
+    portfolio
+        .add_liability("Margin", LiabilityKind::Margin, usd(500), date(2024, 2, 1))
+        .unwrap();
+    assert_eq!(
+        portfolio.total_liabilities(date(2023, 12, 31)).unwrap(),
+        usd(300_000)
+    );
+    assert_eq!(
+        portfolio
+            .net_worth(&quotes, date(2024, 2, 1))
+            .unwrap()
+            .total,
+        usd(111_000)
+    );
+}
+
+#[rstest]
+fn manager_reports_net_worth_per_account(portfolio: Portfolio, quotes: Quotes) {
+    let mut brokerage = Portfolio::new();
+    brokerage.purchase_at(IBM, 20, usd(100)).unwrap();
+    let mut manager = PortfolioManager::new(Currency::Usd);
+    manager.add_account("household", portfolio);
+    manager.add_account("brokerage", brokerage);
+    let report = manager.net_worth(&quotes, date(2024, 1, 1)).unwrap();
+    assert_eq!(report.accounts["brokerage"].total, usd(3_000));
+    assert_eq!(report.accounts["household"].total, usd(101_500));
+    assert_eq!(
+        report.total,
+        NetWorth {
+            investments: usd(4_500),
+            manual_assets: usd(400_000),
+            liabilities: usd(300_000),
+            total: usd(104_500),
+        }
+    );
+}
+
+#[rstest]
+fn unknown_liabilities_are_rejected(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.update_liability_balance(3, usd(1), date(2024, 1, 1)),
+        Err(PortfolioError::UnknownLiability(3))
+    ));
+}
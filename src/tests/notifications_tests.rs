@@ -0,0 +1,122 @@
+#[cfg(feature = "pricing")]
+use crate::alerts::AlertCondition;
+use crate::automation::{Action, Condition, Rule, RuleMode};
+use crate::notifications::*;
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+use std::cell::RefCell;
+
+#[derive(Default)]
+struct RecordingSink {
+    sent: RefCell<Vec<Notification>>,
+}
+
+impl NotificationSink for RecordingSink {
+    fn notify(&self, notification: &Notification) -> PortfolioResult<()> {
+        self.sent.borrow_mut().push(notification.clone());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "pricing")]
+struct FailingSink;
+
+#[cfg(feature = "pricing")]
+impl NotificationSink for FailingSink {
+    fn notify(&self, _: &Notification) -> PortfolioResult<()> {
+        Err(PortfolioError::NotificationFailed("offline".to_string()))
+    }
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p
+}
+
+#[cfg(feature = "pricing")]
+#[fixture]
+fn prices() -> PriceHistory {
+    let mut h = PriceHistory::new();
+    h.insert(IBM, date(2024, 1, 2), usd(90));
+    h.insert(IBM, date(2024, 1, 3), usd(130));
+    h
+}
+
+#[cfg(feature = "pricing")]
+fn price_above() -> AlertCondition {
+    AlertCondition::PriceAbove {
+        symbol: IBM.to_string(),
+        price: Decimal::from(120),
+    }
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn sends_notification_for_each_triggered_alert(
+    mut portfolio: Portfolio,
+    prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let sink = RecordingSink::default();
+    portfolio.add_alert("IBM breakout", price_above())?;
+    assert!(portfolio
+        .check_alerts_and_notify(&prices, date(2024, 1, 2), &sink)?
+        .is_empty());
+    assert_eq!(
+        portfolio
+            .check_alerts_and_notify(&prices, date(2024, 1, 3), &sink)?
+            .len(),
+        1
+    );
+    assert_eq!(
+        *sink.sent.borrow(),
+        vec![Notification {
+            title: "Alert triggered: IBM breakout".to_string(),
+            body: "IBM breakout triggered for IBM".to_string(),
+        }]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn sends_notification_for_each_automation_outcome(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let sink = RecordingSink::default();
+    portfolio.add_automation_rule(Rule {
+        name: "trim".to_string(),
+        when: Condition::PriceAbove {
+            symbol: IBM.to_string(),
+            price: Decimal::from(120),
+        },
+        then: Action::Sell {
+            symbol: IBM.to_string(),
+            shares: 2,
+        },
+        mode: RuleMode::Propose,
+    })?;
+    let quotes = Quotes::from([(IBM.to_string(), usd(130))]);
+    portfolio.run_automation_and_notify(&quotes, &sink)?;
+    assert_eq!(
+        *sink.sent.borrow(),
+        vec![Notification {
+            title: "Automation rule: trim".to_string(),
+            body: "Proposed: sell 2 IBM".to_string(),
+        }]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn surfaces_sink_failures(mut portfolio: Portfolio, prices: PriceHistory) {
+    portfolio.add_alert("IBM breakout", price_above()).unwrap();
+    assert!(matches!(
+        portfolio.check_alerts_and_notify(&prices, date(2024, 1, 3), &FailingSink),
+        Err(PortfolioError::NotificationFailed(reason)) if reason == "offline"
+    ));
+}
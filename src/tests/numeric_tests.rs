@@ -0,0 +1,134 @@
+#[cfg(feature = "toml")]
+use crate::config::PortfolioConfig;
+use crate::config::RoundingPolicy;
+use crate::money::{Currency, Money};
+use crate::numeric::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn accumulator(backend: NumericBackend) -> MoneyAccumulator {
+    MoneyAccumulator::new(backend, Currency::Usd, RoundingPolicy::default())
+}
+
+#[rstest]
+fn fixed_point_backends_reconcile_long_fee_accumulations(
+    #[values(
+        NumericBackend::Decimal,
+        NumericBackend::Cents,
+        NumericBackend::MicroUnits
+    )]
+    backend: NumericBackend,
+) -> PortfolioResult<()> {
+    let mut total = accumulator(backend);
+    let fee = cents(10);
+    for _ in 0..100_000 {
+        total.add(&fee)?;
+    }
+    assert_eq!(total.total()?, usd(10_000));
+    Ok(())
+}
+
+#[rstest]
+#[case(NumericBackend::Cents, Decimal::new(1235, 2))]
+#[case(NumericBackend::MicroUnits, Decimal::new(12_345_679, 6))]
+#[case(NumericBackend::Decimal, Decimal::new(123_456_789, 7))]
+fn rounds_to_backend_precision_on_entry(
+    #[case] backend: NumericBackend,
+    #[case] expected: Decimal,
+) -> PortfolioResult<()> {
+    let mut total = accumulator(backend);
+    total.add(&usd_amount(Decimal::new(123_456_789, 7)))?;
+    assert_eq!(total.total()?, usd_amount(expected));
+    Ok(())
+}
+
+#[rstest]
+fn round_trips_amounts_at_backend_precision(
+    #[values(NumericBackend::Cents, NumericBackend::MicroUnits)] backend: NumericBackend,
+    #[values(0, 1, -1, 99, 123_456_789, -987_654_321)] amount_cents: i64,
+) -> PortfolioResult<()> {
+    let amount = cents(amount_cents);
+    let mut total = accumulator(backend);
+    total.add(&amount)?;
+    assert_eq!(total.total()?.amount, amount.amount);
+    Ok(())
+}
+
+#[rstest]
+fn refuses_foreign_currency() {
+    let mut total = accumulator(NumericBackend::Cents);
+    assert!(matches!(
+        total.add(&Money::new(Decimal::ONE, Currency::Eur)),
+        Err(PortfolioError::CurrencyMismatch { .. })
+    ));
+}
+
+#[cfg(feature = "toml")]
+#[rstest]
+fn portfolio_selects_configured_backend() -> PortfolioResult<()> {
+    let config = PortfolioConfig::from_toml_str("numeric_backend = \"cents\"")?;
+    let portfolio = Portfolio::with_config(config);
+    let mut total = portfolio.money_accumulator();
+    total.add(&usd_amount(Decimal::new(5, 3)))?;
+    assert_eq!(total.total()?, usd_amount(Decimal::ZERO));
+    Ok(())
+}
+
+#[rstest]
+fn error_instead_of_wrapping_on_cents_overflow() -> PortfolioResult<()> {
+    let mut total = accumulator(NumericBackend::Cents);
+    let near_max = cents(i64::MAX);
+    total.add(&near_max)?;
+    assert!(matches!(
+        total.add(&usd_amount(Decimal::ONE)),
+        Err(PortfolioError::Overflow)
+    ));
+    assert_eq!(total.total()?, near_max);
+    Ok(())
+}
+
+#[rstest]
+fn micro_units_hold_totals_beyond_i64_cents() -> PortfolioResult<()> {
+    let mut total = accumulator(NumericBackend::MicroUnits);
+    let large = cents(i64::MAX);
+    total.add(&large)?;
+    total.add(&large)?;
+    assert_eq!(
+        total.total()?,
+        usd_amount(Decimal::new(i64::MAX, 2) * Decimal::TWO)
+    );
+    Ok(())
+}
+
+#[rstest]
+#[case(NumericBackend::Decimal, Decimal::new(8, 3))]
+#[case(NumericBackend::Cents, Decimal::ZERO)]
+#[case(NumericBackend::MicroUnits, Decimal::new(8, 3))]
+fn income_totals_use_the_configured_backend(
+    #[case] backend: NumericBackend,
+    #[case] expected: Decimal,
+) -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_config(crate::config::PortfolioConfig {
+        numeric_backend: backend,
+        ..Default::default()
+    });
+    for symbol in [IBM, VTI] {
+        portfolio.purchase_at(symbol, 10, usd(100))?;
+        portfolio.lend_shares(symbol, 1)?;
+        portfolio.accrue_lending_income(symbol, usd_amount(Decimal::new(4, 3)))?;
+    }
+    assert_eq!(portfolio.total_lending_income()?, usd_amount(expected));
+    Ok(())
+}
+
+#[rstest]
+fn error_when_micro_units_total_exceeds_decimal_range() -> PortfolioResult<()> {
+    let mut total = accumulator(NumericBackend::MicroUnits);
+    let huge = usd_amount(Decimal::from(70_000_000_000_000_000_000_000_i128));
+    total.add(&huge)?;
+    total.add(&huge)?;
+    assert!(matches!(total.total(), Err(PortfolioError::Overflow)));
+    Ok(())
+}
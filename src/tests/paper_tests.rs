@@ -0,0 +1,115 @@
+use crate::calendar::FixedHolidays;
+use crate::clock::FixedClock;
+use crate::execution::paper::PaperBroker;
+use crate::execution::Broker;
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use chrono::{DateTime, Utc};
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn order(transaction_type: TransactionType, shares: u32, limit: Option<i64>) -> Order {
+    Order {
+        symbol: IBM.to_string(),
+        transaction_type,
+        shares,
+        price: limit.map(usd),
+        idempotency_key: None,
+    }
+}
+
+#[fixture]
+fn broker() -> PaperBroker {
+    let quotes = Quotes::from([(IBM.to_string(), usd(100))]);
+    PaperBroker::new(quotes).with_clock(Portfolio::fixed_date_time)
+}
+
+#[rstest]
+fn fills_market_orders_with_slippage(broker: PaperBroker) -> PortfolioResult<()> {
+    let mut broker = broker.with_slippage(Decimal::new(1, 2));
+    let mut portfolio = Portfolio::new();
+    let buy = portfolio.route_order(&mut broker, order(TransactionType::Purchase, 10, None))?;
+    let confirmations = portfolio.collect_fills(&mut broker, &buy)?;
+    assert_eq!(confirmations[0].price, Some(usd(101)));
+
+    let sell = portfolio.route_order(&mut broker, order(TransactionType::Sell, 4, None))?;
+    let confirmations = portfolio.collect_fills(&mut broker, &sell)?;
+    assert_eq!(confirmations[0].price, Some(usd(99)));
+    assert_eq!(portfolio.get_share_count(IBM), 6);
+    Ok(())
+}
+
+#[rstest]
+fn partially_fills_over_successive_checks(broker: PaperBroker) -> PortfolioResult<()> {
+    let mut broker = broker.with_partial_fills(4);
+    let mut portfolio = Portfolio::new();
+    let id = portfolio.route_order(&mut broker, order(TransactionType::Purchase, 10, None))?;
+    let filled: Vec<u32> = (0..4)
+        .map(|_| portfolio.collect_fills(&mut broker, &id).unwrap().len() as u32)
+        .collect();
+    assert_eq!(filled, vec![1, 1, 1, 0]);
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+    assert!(portfolio.pending_order(&id).is_none());
+    Ok(())
+}
+
+#[rstest]
+fn limit_orders_wait_for_a_marketable_quote(mut broker: PaperBroker) -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let id = portfolio.route_order(&mut broker, order(TransactionType::Purchase, 5, Some(95)))?;
+    assert!(portfolio.collect_fills(&mut broker, &id)?.is_empty());
+    broker.set_quote(IBM, usd(94));
+    let confirmations = portfolio.collect_fills(&mut broker, &id)?;
+    assert_eq!(confirmations[0].price, Some(usd(94)));
+    Ok(())
+}
+
+fn july_fifth() -> DateTime<Utc> {
+    noon(2024, 7, 5)
+}
+
+#[rstest]
+fn rejects_fills_after_the_order_session(broker: PaperBroker) -> PortfolioResult<()> {
+    let mut broker = broker.with_clock(july_fifth);
+    let mut portfolio = Portfolio::with_clock(FixedClock(noon(2024, 7, 3)));
+    portfolio.set_calendar(FixedHolidays::new([date(2024, 7, 4)]));
+    let id = portfolio.route_order(&mut broker, order(TransactionType::Purchase, 10, None))?;
+    assert_eq!(
+        portfolio.pending_order(&id).unwrap().expires_on,
+        date(2024, 7, 3)
+    );
+    assert!(matches!(
+        portfolio.collect_fills(&mut broker, &id),
+        Err(PortfolioError::BrokerError(_))
+    ));
+    assert_eq!(portfolio.get_share_count(IBM), 0);
+    Ok(())
+}
+
+#[rstest]
+fn expires_unfilled_orders_after_their_trading_session(
+    mut broker: PaperBroker,
+) -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_clock(FixedClock(noon(2024, 7, 6)));
+    let id = portfolio.route_order(&mut broker, order(TransactionType::Purchase, 5, Some(95)))?;
+    assert_eq!(
+        portfolio.pending_order(&id).unwrap().expires_on,
+        date(2024, 7, 8)
+    );
+
+    portfolio.set_clock(FixedClock(noon(2024, 7, 8)));
+    assert!(portfolio.expire_orders()?.is_empty());
+    portfolio.set_clock(FixedClock(noon(2024, 7, 9)));
+    assert_eq!(portfolio.expire_orders()?, vec![id.clone()]);
+    assert!(portfolio.pending_order(&id).is_none());
+    Ok(())
+}
+
+#[rstest]
+fn error_for_unknown_order(mut broker: PaperBroker) {
+    assert!(matches!(
+        broker.fills("paper-99"),
+        Err(PortfolioError::BrokerError(_))
+    ));
+}
@@ -0,0 +1,537 @@
+use crate::clock::FixedClock;
+use crate::config::PortfolioConfig;
+use crate::instruments::InstrumentKind;
+use crate::money::{Currency, Money};
+use crate::performance::*;
+use crate::period::{DayCountConvention, Period};
+use crate::prices::PriceHistory;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::NaiveDate;
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn backdate_last_record(portfolio: &mut Portfolio, symbol: &str, on: NaiveDate) {
+    let record = portfolio
+        .purchase_records
+        .get_mut(symbol)
+        .and_then(|records| records.last_mut())
+        .unwrap();
+    record.date = on.and_hms_opt(0, 0, 0).unwrap().and_utc();
+}
+
+#[fixture]
+fn prices() -> PriceHistory {
+    let mut h = PriceHistory::new();
+    h.insert(VTI, date(2023, 12, 29), usd(100));
+    h.insert(VTI, date(2024, 3, 1), usd(110));
+    h.insert(VTI, date(2024, 12, 31), usd(120));
+    h
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(VTI, 10, usd(95)).unwrap();
+    backdate_last_record(&mut p, VTI, date(2023, 6, 1));
+    p.purchase(VTI, 5).unwrap();
+    backdate_last_record(&mut p, VTI, date(2024, 3, 1));
+    p.record_capital_gain_distribution(
+        VTI,
+        usd(4),
+        usd(6),
+        date(2024, 12, 20).and_hms_opt(0, 0, 0).unwrap().and_utc(),
+    )
+    .unwrap();
+    p
+}
+
+#[rstest]
+fn values_holdings_as_of_date(portfolio: Portfolio, prices: PriceHistory) -> PortfolioResult<()> {
+    assert_eq!(
+        value_as_of(&portfolio, &prices, date(2024, 1, 15))?,
+        usd(1000)
+    );
+    assert_eq!(
+        value_as_of(&portfolio, &prices, date(2024, 3, 1))?,
+        usd(1650)
+    );
+    Ok(())
+}
+
+#[rstest]
+fn splits_value_change_into_contributions_growth_and_income(
+    portfolio: Portfolio,
+    prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let period = Period::new(date(2024, 1, 1), date(2024, 12, 31));
+    let attribution = growth_attribution(&portfolio, &prices, &period)?;
+    assert_eq!(
+        attribution,
+        GrowthAttribution {
+            period,
+            start_value: usd(1000),
+            end_value: usd(1800),
+            net_contributions: usd(550),
+            market_growth: usd(250),
+            income: usd(10),
+        }
+    );
+    Ok(())
+}
+
+#[rstest]
+fn sells_count_as_withdrawals(
+    mut portfolio: Portfolio,
+    prices: PriceHistory,
+) -> PortfolioResult<()> {
+    portfolio.sell(VTI, 3)?;
+    backdate_last_record(&mut portfolio, VTI, date(2024, 12, 31));
+    let period = Period::new(date(2024, 1, 1), date(2024, 12, 31));
+    let attribution = growth_attribution(&portfolio, &prices, &period)?;
+    assert_eq!(attribution.net_contributions, usd(190));
+    assert_eq!(attribution.end_value, usd(1440));
+    assert_eq!(attribution.market_growth, usd(250));
+    Ok(())
+}
+
+#[rstest]
+fn income_includes_dividends_and_lending_income(prices: PriceHistory) -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_clock(FixedClock(at(2024, 6, 3)));
+    portfolio.purchase_at(VTI, 10, usd(100))?;
+    portfolio.record_dividend(VTI, usd(1), at(2024, 6, 3), None)?;
+    portfolio.lend_shares(VTI, 5)?;
+    portfolio.accrue_lending_income(VTI, usd(2))?;
+    let period = Period::new(date(2024, 1, 1), date(2024, 12, 31));
+    let attribution = growth_attribution(&portfolio, &prices, &period)?;
+    assert_eq!(attribution.income, usd(12));
+    Ok(())
+}
+
+#[rstest]
+fn error_when_price_is_missing(portfolio: Portfolio) {
+    let period = Period::new(date(2024, 1, 1), date(2024, 12, 31));
+    assert!(matches!(
+        growth_attribution(&portfolio, &PriceHistory::new(), &period),
+        Err(PortfolioError::MissingPrice(_))
+    ));
+}
+
+fn portfolio_with_sectors(mut portfolio: Portfolio, prices: &mut PriceHistory) -> Portfolio {
+    portfolio.purchase_at(IBM, 10, usd(100)).unwrap();
+    backdate_last_record(&mut portfolio, IBM, date(2023, 6, 1));
+    prices.insert(IBM, date(2023, 12, 29), usd(100));
+    prices.insert(IBM, date(2024, 12, 31), usd(90));
+    let instruments = portfolio.instruments_mut().unwrap();
+    instruments.register(VTI, InstrumentKind::Etf);
+    instruments.register(IBM, InstrumentKind::Stock);
+    instruments.set_sector(VTI, "Broad Market").unwrap();
+    instruments.set_sector(IBM, "Technology").unwrap();
+    portfolio
+}
+
+#[rstest]
+fn attributes_period_return_by_sector(
+    portfolio: Portfolio,
+    mut prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let portfolio = portfolio_with_sectors(portfolio, &mut prices);
+    let period = Period::new(date(2024, 1, 1), date(2024, 12, 31));
+    let attributions = attribution(&portfolio, &prices, &period, AttributionGrouping::Sector)?;
+    assert_eq!(
+        attributions,
+        vec![
+            GroupAttribution {
+                group: AttributionGroup::Sector("Broad Market".to_string()),
+                start_value: usd(1000),
+                end_value: usd(1800),
+                net_contributions: usd(550),
+                gain: usd(260),
+                contribution_percent: Decimal::from(13),
+            },
+            GroupAttribution {
+                group: AttributionGroup::Sector("Technology".to_string()),
+                start_value: usd(1000),
+                end_value: usd(900),
+                net_contributions: usd(0),
+                gain: usd(-100),
+                contribution_percent: Decimal::from(-5),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn attributes_period_return_by_asset_class(
+    portfolio: Portfolio,
+    mut prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let portfolio = portfolio_with_sectors(portfolio, &mut prices);
+    let period = Period::new(date(2024, 1, 1), date(2024, 12, 31));
+    let groups: Vec<(AttributionGroup, Decimal)> = attribution(
+        &portfolio,
+        &prices,
+        &period,
+        AttributionGrouping::AssetClass,
+    )?
+    .into_iter()
+    .map(|attribution| (attribution.group, attribution.contribution_percent))
+    .collect();
+    assert_eq!(
+        groups,
+        vec![
+            (
+                AttributionGroup::AssetClass(InstrumentKind::Stock),
+                Decimal::from(-5)
+            ),
+            (
+                AttributionGroup::AssetClass(InstrumentKind::Etf),
+                Decimal::from(13)
+            ),
+        ]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn unregistered_symbols_are_unclassified(
+    portfolio: Portfolio,
+    prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let period = Period::new(date(2024, 1, 1), date(2024, 12, 31));
+    let attributions = attribution(&portfolio, &prices, &period, AttributionGrouping::Sector)?;
+    assert_eq!(attributions.len(), 1);
+    assert_eq!(attributions[0].group, AttributionGroup::Unclassified);
+    assert_eq!(attributions[0].contribution_percent, Decimal::from(26));
+    Ok(())
+}
+
+fn monthly_series() -> ValueSeries {
+    ValueSeries::from([
+        (date(2021, 12, 31), usd(100)),
+        (date(2023, 12, 31), usd(121)),
+        (date(2024, 9, 30), usd(125)),
+        (date(2024, 11, 30), usd(140)),
+        (date(2024, 12, 31), usd(150)),
+    ])
+}
+
+#[rstest]
+fn rolling_returns_cover_standard_trailing_windows() -> PortfolioResult<()> {
+    let returns = rolling_returns(&monthly_series(), &ReturnWindow::STANDARD)?;
+    let summary: Vec<(ReturnWindow, NaiveDate, Decimal)> = returns
+        .iter()
+        .map(|r| (r.window, r.start_date, r.total_percent.round_dp(2)))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                ReturnWindow::Months(1),
+                date(2024, 11, 30),
+                Decimal::new(714, 2)
+            ),
+            (
+                ReturnWindow::Months(3),
+                date(2024, 9, 30),
+                Decimal::from(20)
+            ),
+            (
+                ReturnWindow::Years(1),
+                date(2023, 12, 31),
+                Decimal::new(2397, 2)
+            ),
+            (
+                ReturnWindow::Years(3),
+                date(2021, 12, 31),
+                Decimal::from(50)
+            ),
+        ]
+    );
+    assert_eq!(returns[2].annualized_percent, returns[2].total_percent);
+    assert_eq!(
+        returns[3].annualized_percent.round_dp(2),
+        Decimal::new(1446, 2)
+    );
+    Ok(())
+}
+
+#[rstest]
+fn rolling_returns_skip_windows_longer_than_history() -> PortfolioResult<()> {
+    let mut series = monthly_series();
+    series.remove(&date(2021, 12, 31));
+    let windows: Vec<ReturnWindow> = rolling_returns(&series, &ReturnWindow::STANDARD)?
+        .into_iter()
+        .map(|r| r.window)
+        .collect();
+    assert_eq!(windows, ReturnWindow::STANDARD[..3]);
+    Ok(())
+}
+
+#[rstest]
+fn annualizes_return_over_whole_series() -> PortfolioResult<()> {
+    let series = ValueSeries::from([(date(2021, 1, 1), usd(100)), (date(2023, 1, 1), usd(121))]);
+    assert_eq!(annualized_return(&series)?.round_dp(4), Decimal::from(10));
+    Ok(())
+}
+
+#[rstest]
+fn annualized_return_requires_history() {
+    let series = ValueSeries::from([(date(2024, 1, 1), usd(100))]);
+    assert!(matches!(
+        annualized_return(&series),
+        Err(PortfolioError::InsufficientHistory)
+    ));
+    assert!(matches!(
+        annualized_return(&ValueSeries::new()),
+        Err(PortfolioError::InsufficientHistory)
+    ));
+}
+
+#[rstest]
+fn builds_daily_value_series(portfolio: Portfolio, prices: PriceHistory) -> PortfolioResult<()> {
+    let period = Period::new(date(2024, 2, 29), date(2024, 3, 1));
+    let series = value_series(&portfolio, &prices, &period)?;
+    assert_eq!(
+        series,
+        ValueSeries::from([
+            (date(2024, 2, 29), usd(1000)),
+            (date(2024, 3, 1), usd(1650))
+        ])
+    );
+    Ok(())
+}
+
+#[rstest]
+fn cagr_compounds_over_fractional_years() -> PortfolioResult<()> {
+    let rate = cagr(&usd(100), &usd(121), date(2021, 1, 1), date(2023, 1, 1))?;
+    assert_eq!(rate.round_dp(4), Decimal::from(10));
+    Ok(())
+}
+
+#[rstest]
+#[case(DayCountConvention::ActualActual, Decimal::new(100000, 4))]
+#[case(DayCountConvention::Actual365Fixed, Decimal::new(99857, 4))]
+#[case(DayCountConvention::Actual360, Decimal::new(98423, 4))]
+fn cagr_respects_day_count_convention(
+    #[case] day_count: DayCountConvention,
+    #[case] expected: Decimal,
+) -> PortfolioResult<()> {
+    let rate = cagr_with_day_count(
+        &usd(100),
+        &usd(121),
+        date(2024, 1, 1),
+        date(2026, 1, 1),
+        day_count,
+    )?;
+    assert_eq!(rate.round_dp(4), expected);
+    Ok(())
+}
+
+#[rstest]
+fn portfolio_cagr_measures_from_mid_year_inception() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        day_count: DayCountConvention::ActualActual,
+        ..PortfolioConfig::default()
+    });
+    portfolio.purchase_at(VTI, 10, usd(100))?;
+    backdate_last_record(&mut portfolio, VTI, date(2023, 7, 1));
+    let mut prices = PriceHistory::new();
+    prices.insert(VTI, date(2023, 7, 1), usd(100));
+    prices.insert(VTI, date(2025, 7, 1), usd(121));
+    let rate = portfolio_cagr(&portfolio, &prices, date(2025, 7, 1))?;
+    assert_eq!(rate.round_dp(4), Decimal::from(10));
+    Ok(())
+}
+
+#[rstest]
+fn cagr_requires_elapsed_time_and_history() {
+    assert!(matches!(
+        cagr(&usd(100), &usd(121), date(2024, 1, 1), date(2024, 1, 1)),
+        Err(PortfolioError::InsufficientHistory)
+    ));
+    assert!(matches!(
+        portfolio_cagr(&Portfolio::new(), &PriceHistory::new(), date(2024, 1, 1)),
+        Err(PortfolioError::InsufficientHistory)
+    ));
+}
+
+#[rstest]
+fn real_return_deflates_nominal_growth_by_cpi() -> PortfolioResult<()> {
+    let nominal = ValueSeries::from([
+        (date(2020, 1, 31), usd(1000)),
+        (date(2024, 1, 31), usd(1320)),
+    ]);
+    let cpi = CpiSeries::from([
+        (date(2020, 1, 1), Decimal::from(250)),
+        (date(2022, 1, 1), Decimal::from(270)),
+        (date(2024, 1, 1), Decimal::from(300)),
+    ]);
+    assert_eq!(
+        real_return(&nominal, &cpi)?,
+        RealReturn {
+            nominal_percent: Decimal::from(32),
+            inflation_percent: Decimal::from(20),
+            real_percent: Decimal::from(10),
+        }
+    );
+    Ok(())
+}
+
+#[rstest]
+fn real_return_requires_cpi_covering_series_start() {
+    let nominal = ValueSeries::from([
+        (date(2020, 1, 31), usd(1000)),
+        (date(2024, 1, 31), usd(1320)),
+    ]);
+    let cpi = CpiSeries::from([(date(2024, 1, 1), Decimal::from(300))]);
+    assert!(matches!(
+        real_return(&nominal, &cpi),
+        Err(PortfolioError::InsufficientHistory)
+    ));
+}
+
+fn dividend_payer() -> Portfolio {
+    let now = date(2025, 3, 1).and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let mut p = Portfolio::with_clock(FixedClock(now));
+    p.purchase_at(IBM, 100, usd(50)).unwrap();
+    backdate_last_record(&mut p, IBM, date(2020, 6, 1));
+    for (paid, per_share) in [
+        (date(2021, 6, 1), 50),
+        (date(2021, 12, 1), 50),
+        (date(2022, 6, 1), 110),
+        (date(2023, 6, 1), 121),
+        (date(2025, 1, 15), 130),
+    ] {
+        let paid = paid.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        p.record_dividend(IBM, cents(per_share), paid, None)
+            .unwrap();
+    }
+    p
+}
+
+#[rstest]
+fn dividend_growth_compares_completed_years() -> PortfolioResult<()> {
+    let growth = dividend_growth(&dividend_payer(), IBM, 3)?;
+    assert_eq!(
+        growth,
+        vec![
+            DividendGrowth {
+                year: 2022,
+                per_share: cents(110),
+                growth_percent: Some(Decimal::from(10)),
+            },
+            DividendGrowth {
+                year: 2023,
+                per_share: cents(121),
+                growth_percent: Some(Decimal::from(10)),
+            },
+            DividendGrowth {
+                year: 2024,
+                per_share: usd(0),
+                growth_percent: Some(Decimal::from(-100)),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn dividend_growth_is_undefined_after_a_year_without_dividends() -> PortfolioResult<()> {
+    let growth = dividend_growth(&dividend_payer(), IBM, 5)?;
+    assert_eq!(growth[0].year, 2020);
+    assert_eq!(growth[0].growth_percent, None);
+    assert_eq!(growth[1].growth_percent, None);
+    assert_eq!(growth[2].growth_percent, Some(Decimal::from(10)));
+    Ok(())
+}
+
+#[rstest]
+fn dividend_growth_counts_only_eligible_shares() -> PortfolioResult<()> {
+    let mut p = dividend_payer();
+    p.purchase_at(IBM, 100, usd(50))?;
+    backdate_last_record(&mut p, IBM, date(2024, 5, 30));
+    let paid = date(2024, 6, 1).and_hms_opt(0, 0, 0).unwrap().and_utc();
+    p.record_dividend(IBM, usd(2), paid, Some(date(2024, 5, 15)))?;
+    let growth = dividend_growth(&p, IBM, 1)?;
+    assert_eq!(growth[0].per_share, usd(1));
+    Ok(())
+}
+
+fn monthly(values: &[i64]) -> ValueSeries {
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, cents)| {
+            let month = u32::try_from(index).unwrap() + 1;
+            (
+                date(2024, month, 1),
+                Money::new(Decimal::new(*cents, 2), Currency::Usd),
+            )
+        })
+        .collect()
+}
+
+#[rstest]
+fn capture_ratios_compare_up_and_down_periods() -> PortfolioResult<()> {
+    let series = monthly(&[100_000, 112_000, 106_400, 117_040, 111_188]);
+    let benchmark = monthly(&[100_000, 110_000, 104_500, 114_950, 109_202]);
+    let capture = capture_ratios(&series, &benchmark)?;
+    assert_eq!(capture.up_periods, 2);
+    assert_eq!(capture.down_periods, 2);
+    assert_eq!(
+        capture.upside_percent.map(|p| p.round_dp(2)),
+        Some(Decimal::from(110))
+    );
+    assert_eq!(
+        capture.downside_percent.map(|p| p.round_dp(2)),
+        Some(Decimal::from(100))
+    );
+    Ok(())
+}
+
+#[rstest]
+fn capture_ratios_only_use_dates_in_both_series() -> PortfolioResult<()> {
+    let series = monthly(&[100_000, 90_000, 108_000]);
+    let mut benchmark = monthly(&[100_000, 50_000, 120_000]);
+    benchmark.remove(&date(2024, 2, 1));
+    let capture = capture_ratios(&series, &benchmark)?;
+    assert_eq!(capture.up_periods, 1);
+    assert_eq!(capture.upside_percent, Some(Decimal::from(40)));
+    assert_eq!(capture.downside_percent, None);
+    Ok(())
+}
+
+#[rstest]
+fn capture_ratios_require_overlapping_history() {
+    let series = monthly(&[100_000, 110_000]);
+    let benchmark = ValueSeries::from([(date(2024, 1, 1), usd(1000))]);
+    assert!(matches!(
+        capture_ratios(&series, &benchmark),
+        Err(PortfolioError::InsufficientHistory)
+    ));
+}
+
+#[rstest]
+fn relative_drawdown_tracks_worst_underperformance() -> PortfolioResult<()> {
+    let series = monthly(&[10_000, 12_000, 9_000, 10_000]);
+    let benchmark = monthly(&[10_000, 10_000, 10_000, 10_000]);
+    assert_eq!(
+        relative_drawdown(&series, &benchmark)?,
+        Some(RelativeDrawdown {
+            peak_date: date(2024, 2, 1),
+            trough_date: date(2024, 3, 1),
+            percent: Decimal::from(25),
+        })
+    );
+    Ok(())
+}
+
+#[rstest]
+fn relative_drawdown_is_none_while_keeping_pace() -> PortfolioResult<()> {
+    let series = monthly(&[10_000, 11_000, 9_900]);
+    let benchmark = monthly(&[20_000, 22_000, 19_800]);
+    assert_eq!(relative_drawdown(&series, &benchmark)?, None);
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::period::*;
+use crate::tests::helpers::*;
+use crate::PortfolioError;
+use chrono::NaiveDate;
+use rstest::*;
+use rust_decimal::Decimal;
+
+#[rstest]
+#[case(DayCountConvention::Actual365Fixed, Decimal::from(366) / Decimal::from(365))]
+#[case(DayCountConvention::Actual360, Decimal::from(366) / Decimal::from(360))]
+#[case(DayCountConvention::ActualActual, Decimal::ONE)]
+fn year_fraction_follows_day_count_convention(
+    #[case] day_count: DayCountConvention,
+    #[case] expected: Decimal,
+) {
+    assert_eq!(
+        day_count.year_fraction(date(2024, 1, 1), date(2025, 1, 1)),
+        expected
+    );
+}
+
+#[rstest]
+fn actual_actual_weights_days_by_length_of_their_year() {
+    let fraction =
+        DayCountConvention::ActualActual.year_fraction(date(2023, 7, 1), date(2025, 7, 1));
+    assert_eq!(fraction.round_dp(10), Decimal::from(2));
+}
+
+#[rstest]
+#[case(date(2024, 4, 5), 2023)]
+#[case(date(2024, 4, 6), 2024)]
+#[case(date(2024, 12, 31), 2024)]
+#[case(date(2025, 1, 1), 2024)]
+fn fiscal_year_is_labelled_by_the_year_it_starts(#[case] on: NaiveDate, #[case] expected: i32) {
+    let uk = FiscalYear::new(4, 6).unwrap();
+    assert_eq!(uk.year_of(on), expected);
+}
+
+#[rstest]
+fn fiscal_year_period_runs_to_the_day_before_the_next_start() {
+    let uk = FiscalYear::new(4, 6).unwrap();
+    assert_eq!(
+        uk.period(2024),
+        Period::new(date(2024, 4, 6), date(2025, 4, 5))
+    );
+    assert_eq!(
+        FiscalYear::default().period(2024),
+        Period::new(date(2024, 1, 1), date(2024, 12, 31))
+    );
+}
+
+#[rstest]
+#[case(13, 1)]
+#[case(2, 29)]
+#[case(4, 31)]
+fn fiscal_year_rejects_invalid_start(#[case] month: u32, #[case] day: u32) {
+    assert!(matches!(
+        FiscalYear::new(month, day),
+        Err(PortfolioError::InvalidConfig(_))
+    ));
+}
@@ -0,0 +1,191 @@
+use crate::money::{Currency, Money};
+use crate::plaid::*;
+use crate::reconcile::Discrepancy;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use rstest::*;
+use rust_decimal::Decimal;
+use std::cell::RefCell;
+
+fn march_first() -> DateTime<Utc> {
+    date(2024, 3, 1).and_hms_opt(12, 0, 0).unwrap().and_utc()
+}
+
+fn plaid_trade(id: &str, on: NaiveDate, kind: &str, quantity: i64) -> PlaidInvestmentTransaction {
+    PlaidInvestmentTransaction {
+        investment_transaction_id: id.to_string(),
+        security_id: Some("sec-ibm".to_string()),
+        date: on,
+        quantity: Decimal::from(quantity),
+        price: Decimal::from(100),
+        transaction_type: kind.to_string(),
+        iso_currency_code: Some("USD".to_string()),
+    }
+}
+
+fn ibm_security() -> Vec<PlaidSecurity> {
+    vec![PlaidSecurity {
+        security_id: "sec-ibm".to_string(),
+        ticker_symbol: Some(IBM.to_string()),
+    }]
+}
+
+struct FakePlaid {
+    transactions: Vec<PlaidInvestmentTransaction>,
+    holding: i64,
+    requests: RefCell<Vec<(NaiveDate, usize)>>,
+}
+
+impl PlaidApi for FakePlaid {
+    fn investment_transactions(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        offset: usize,
+        _count: usize,
+    ) -> PortfolioResult<PlaidTransactionsPage> {
+        self.requests.borrow_mut().push((start, offset));
+        let in_range: Vec<_> = self
+            .transactions
+            .iter()
+            .filter(|transaction| start <= transaction.date && transaction.date <= end)
+            .cloned()
+            .collect();
+        Ok(PlaidTransactionsPage {
+            total_investment_transactions: in_range.len(),
+            investment_transactions: in_range.into_iter().skip(offset).take(2).collect(),
+            securities: ibm_security(),
+        })
+    }
+
+    fn holdings(&self) -> PortfolioResult<PlaidHoldings> {
+        Ok(PlaidHoldings {
+            holdings: vec![PlaidHolding {
+                security_id: "sec-ibm".to_string(),
+                quantity: Decimal::from(self.holding),
+            }],
+            securities: ibm_security(),
+        })
+    }
+}
+
+#[fixture]
+fn plaid() -> FakePlaid {
+    FakePlaid {
+        transactions: vec![
+            plaid_trade("t1", date(2024, 1, 2), "buy", 10),
+            plaid_trade("t2", date(2024, 1, 9), "sell", -4),
+            plaid_trade("t3", date(2024, 1, 10), "dividend", 0),
+            plaid_trade("t4", date(2024, 2, 1), "buy", 5),
+        ],
+        holding: 11,
+        requests: RefCell::new(Vec::new()),
+    }
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.set_clock(march_first);
+    p
+}
+
+#[rstest]
+fn imports_paged_transactions_and_reconciles_holdings(
+    mut portfolio: Portfolio,
+    plaid: FakePlaid,
+) -> PortfolioResult<()> {
+    let mut cursor = PlaidCursor::default();
+    let report = portfolio.sync_plaid(&plaid, &mut cursor)?;
+    assert_eq!(report.import.imported, 3);
+    assert_eq!(report.unsupported, vec!["t3".to_string()]);
+    assert_eq!(
+        plaid
+            .requests
+            .borrow()
+            .iter()
+            .map(|(_, offset)| *offset)
+            .collect::<Vec<_>>(),
+        vec![0, 2]
+    );
+    assert_eq!(portfolio.get_share_count(IBM), 11);
+    assert!(report.discrepancies.is_empty());
+    assert_eq!(cursor.synced_through, Some(date(2024, 3, 1)));
+    assert_eq!(cursor.seen.len(), 3);
+    assert!(!cursor.seen.contains("t3"));
+    Ok(())
+}
+
+#[rstest]
+fn delta_sync_skips_already_synced_transactions(
+    mut portfolio: Portfolio,
+    mut plaid: FakePlaid,
+) -> PortfolioResult<()> {
+    let mut cursor = PlaidCursor::default();
+    portfolio.sync_plaid(&plaid, &mut cursor)?;
+    plaid
+        .transactions
+        .push(plaid_trade("t5", date(2024, 3, 1), "buy", 2));
+    plaid.holding = 12;
+    let report = portfolio.sync_plaid(&plaid, &mut cursor)?;
+    assert_eq!(plaid.requests.borrow().last().unwrap().0, date(2024, 3, 1));
+    assert_eq!(report.import.imported, 1);
+    assert_eq!(portfolio.get_share_count(IBM), 13);
+    assert_eq!(
+        report.discrepancies,
+        vec![Discrepancy::QuantityMismatch {
+            symbol: IBM.to_string(),
+            statement: 12,
+            portfolio: 13,
+        }]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn skips_transactions_already_entered_by_hand(
+    mut portfolio: Portfolio,
+    plaid: FakePlaid,
+) -> PortfolioResult<()> {
+    portfolio.import(
+        vec![crate::integrity::ImportedTransaction {
+            symbol: IBM.to_string(),
+            date: date(2024, 1, 2).and_hms_opt(0, 0, 0).unwrap().and_utc(),
+            transaction_type: TransactionType::Purchase,
+            shares: 10,
+            price: Some(Money::new(Decimal::from(100), Currency::Usd)),
+            net_amount: None,
+        }],
+        &crate::import::ImportOptions::default(),
+    )?;
+    let report = portfolio.sync_plaid(&plaid, &mut PlaidCursor::default())?;
+    assert_eq!(report.import.skipped_duplicates.len(), 1);
+    assert_eq!(portfolio.get_share_count(IBM), 11);
+    Ok(())
+}
+
+#[rstest]
+fn retries_transactions_that_failed_to_apply(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let mut plaid = FakePlaid {
+        transactions: vec![plaid_trade("t2", date(2024, 1, 9), "sell", -4)],
+        holding: 6,
+        requests: RefCell::new(Vec::new()),
+    };
+    let mut cursor = PlaidCursor::default();
+    let report = portfolio.sync_plaid(&plaid, &mut cursor)?;
+    assert_eq!(report.import.imported, 0);
+    assert_eq!(report.import.load.skipped.len(), 1);
+    assert!(cursor.seen.is_empty());
+    assert_eq!(cursor.synced_through, Some(date(2024, 1, 9)));
+
+    plaid
+        .transactions
+        .push(plaid_trade("t0", date(2024, 1, 9), "buy", 10));
+    let report = portfolio.sync_plaid(&plaid, &mut cursor)?;
+    assert_eq!(report.import.imported, 2);
+    assert_eq!(portfolio.get_share_count(IBM), 6);
+    assert_eq!(cursor.seen.len(), 2);
+    assert_eq!(cursor.synced_through, Some(date(2024, 3, 1)));
+    Ok(())
+}
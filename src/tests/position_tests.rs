@@ -0,0 +1,78 @@
+use crate::config::{PortfolioConfig, RuleSettings};
+use crate::money::{Currency, Money};
+use crate::position::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+#[fixture]
+fn shorting_portfolio() -> Portfolio {
+    Portfolio::with_config(PortfolioConfig {
+        rules: RuleSettings {
+            allow_short_selling: true,
+            ..RuleSettings::default()
+        },
+        ..PortfolioConfig::default()
+    })
+}
+
+#[rstest]
+#[case(0, Some(Position::Flat))]
+#[case(5, Some(Position::Long(5)))]
+#[case(-5, Some(Position::Short(5)))]
+#[case(i64::from(u32::MAX) + 1, None)]
+#[case(-i64::from(u32::MAX) - 1, None)]
+fn builds_position_from_signed_quantity(#[case] quantity: i64, #[case] expected: Option<Position>) {
+    assert_eq!(Position::from_signed(quantity), expected);
+}
+
+#[rstest]
+fn unheld_symbol_is_flat() {
+    assert_eq!(Portfolio::new().get_position(IBM), Position::Flat);
+}
+
+#[rstest]
+fn short_selling_is_rejected_unless_enabled() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.purchase(IBM, 1)?;
+    assert!(matches!(
+        portfolio.sell(IBM, 2),
+        Err(PortfolioError::InvalidSell)
+    ));
+    assert_eq!(portfolio.get_position(IBM), Position::Long(1));
+    Ok(())
+}
+
+#[rstest]
+fn selling_past_zero_opens_short_when_enabled(
+    mut shorting_portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    shorting_portfolio.purchase(IBM, 2)?;
+    shorting_portfolio.sell(IBM, 5)?;
+    assert_eq!(shorting_portfolio.get_position(IBM), Position::Short(3));
+    assert_eq!(shorting_portfolio.get_share_count(IBM), 0);
+    assert_eq!(shorting_portfolio.get_signed_share_count(IBM), -3);
+    Ok(())
+}
+
+#[rstest]
+fn purchasing_covers_short(mut shorting_portfolio: Portfolio) -> PortfolioResult<()> {
+    shorting_portfolio.sell(IBM, 3)?;
+    shorting_portfolio.purchase(IBM, 3)?;
+    assert_eq!(shorting_portfolio.get_position(IBM), Position::Flat);
+    shorting_portfolio.purchase(IBM, 4)?;
+    assert_eq!(shorting_portfolio.get_position(IBM), Position::Long(4));
+    Ok(())
+}
+
+#[rstest]
+fn close_position_covers_short_position(mut shorting_portfolio: Portfolio) -> PortfolioResult<()> {
+    shorting_portfolio.sell(IBM, 5)?;
+    let price = Money::new(Decimal::from(90), Currency::Usd);
+    let confirmation = shorting_portfolio.close_position(IBM, price)?;
+    assert_eq!(confirmation.transaction_type, TransactionType::Purchase);
+    assert_eq!(confirmation.shares, 5);
+    assert_eq!(confirmation.resulting_position, Position::Flat);
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::period::Period;
+use crate::prices::*;
+use crate::tests::helpers::*;
+use crate::PortfolioError;
+use chrono::NaiveDate;
+use rstest::*;
+use rust_decimal::Decimal;
+
+#[fixture]
+fn history() -> PriceHistory {
+    let mut h = PriceHistory::new();
+    h.insert(IBM, date(2024, 1, 2), usd(100));
+    h.insert(IBM, date(2024, 1, 5), usd(110));
+    h
+}
+
+#[rstest]
+fn answers_exact_close(history: PriceHistory) {
+    assert_eq!(history.close_on(IBM, date(2024, 1, 2)), Some(usd(100)));
+    assert_eq!(history.close_on(IBM, date(2024, 1, 3)), None);
+    assert_eq!(history.close_on("AAPL", date(2024, 1, 2)), None);
+}
+
+#[rstest]
+fn answers_latest_close_on_or_before(history: PriceHistory) {
+    assert_eq!(
+        history.latest_on_or_before(IBM, date(2024, 1, 4)),
+        Some((date(2024, 1, 2), usd(100)))
+    );
+    assert_eq!(
+        history.latest_on_or_before(IBM, date(2024, 1, 5)),
+        Some((date(2024, 1, 5), usd(110)))
+    );
+    assert_eq!(history.latest_on_or_before(IBM, date(2024, 1, 1)), None);
+}
+
+#[rstest]
+fn iterates_series_in_date_order(history: PriceHistory) {
+    let series: Vec<_> = history.series(IBM).collect();
+    assert_eq!(
+        series,
+        vec![(date(2024, 1, 2), usd(100)), (date(2024, 1, 5), usd(110))]
+    );
+}
+
+#[rstest]
+fn carries_forward_last_close_by_default(history: PriceHistory) {
+    assert_eq!(history.price_on(IBM, date(2024, 1, 4)).unwrap(), usd(100));
+    assert_eq!(history.price_on(IBM, date(2024, 1, 9)).unwrap(), usd(110));
+    assert!(matches!(
+        history.price_on(IBM, date(2024, 1, 1)),
+        Err(PortfolioError::MissingPrice(symbol)) if symbol == IBM
+    ));
+}
+
+#[rstest]
+fn interpolates_between_known_closes(mut history: PriceHistory) {
+    history.set_policy(PricePolicy {
+        missing: MissingPricePolicy::Interpolate,
+        ..PricePolicy::default()
+    });
+    assert_eq!(
+        history
+            .price_on(IBM, date(2024, 1, 3))
+            .unwrap()
+            .amount
+            .round_dp(4),
+        Decimal::new(1033333, 4)
+    );
+    assert_eq!(history.price_on(IBM, date(2024, 1, 5)).unwrap(), usd(110));
+    assert_eq!(history.price_on(IBM, date(2024, 1, 7)).unwrap(), usd(110));
+}
+
+#[rstest]
+fn error_policy_requires_exact_close(mut history: PriceHistory) {
+    history.set_policy(PricePolicy {
+        missing: MissingPricePolicy::Error,
+        ..PricePolicy::default()
+    });
+    assert_eq!(history.price_on(IBM, date(2024, 1, 2)).unwrap(), usd(100));
+    assert!(matches!(
+        history.price_on(IBM, date(2024, 1, 3)),
+        Err(PortfolioError::MissingPrice(_))
+    ));
+}
+
+#[rstest]
+#[case(MissingPricePolicy::CarryForward)]
+#[case(MissingPricePolicy::Interpolate)]
+fn rejects_prices_older_than_staleness_threshold(
+    mut history: PriceHistory,
+    #[case] missing: MissingPricePolicy,
+) {
+    history.set_policy(PricePolicy {
+        missing,
+        max_staleness_days: Some(2),
+    });
+    assert!(history.price_on(IBM, date(2024, 1, 4)).is_ok());
+    assert!(history.price_on(IBM, date(2024, 1, 7)).is_ok());
+    assert!(matches!(
+        history.price_on(IBM, date(2024, 1, 8)),
+        Err(PortfolioError::StalePrice(symbol)) if symbol == IBM
+    ));
+}
+
+#[rstest]
+fn period_is_inclusive() {
+    let period = Period::new(date(2024, 1, 1), date(2024, 1, 3));
+    assert_eq!(period.days(), 3);
+    assert_eq!(
+        period.iter_days().collect::<Vec<_>>(),
+        vec![date(2024, 1, 1), date(2024, 1, 2), date(2024, 1, 3)]
+    );
+    assert!(period.contains(date(2024, 1, 3)));
+    assert!(!period.contains(date(2024, 1, 4)));
+}
+
+#[rstest]
+fn detects_price_drops_matching_split_factors() {
+    let mut history = PriceHistory::new();
+    history.insert(IBM, date(2024, 1, 2), usd(300));
+    history.insert(IBM, date(2024, 1, 3), usd(302));
+    history.insert(IBM, date(2024, 1, 4), usd(152));
+    history.insert(IBM, date(2024, 1, 5), usd(120));
+    history.insert("AAPL", date(2024, 1, 2), usd(10));
+    history.insert("AAPL", date(2024, 1, 3), usd(99));
+    let splits = detect_splits(&history);
+    let found: Vec<(&str, NaiveDate, u32, u32)> = splits
+        .iter()
+        .map(|split| {
+            (
+                split.symbol.as_str(),
+                split.date,
+                split.numerator,
+                split.denominator,
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("AAPL", date(2024, 1, 3), 1, 10),
+            (IBM, date(2024, 1, 4), 2, 1)
+        ]
+    );
+    assert_eq!(splits[1].previous_close, usd(302));
+}
+
+#[rstest]
+fn ignores_ordinary_moves(history: PriceHistory) {
+    assert!(detect_splits(&history).is_empty());
+}
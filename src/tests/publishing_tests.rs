@@ -0,0 +1,78 @@
+use crate::events::PortfolioEvent;
+use crate::publishing::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use std::cell::{Cell, RefCell};
+
+#[derive(Default)]
+struct InMemoryPublisher {
+    published: RefCell<Vec<PortfolioEvent>>,
+    fail: Cell<bool>,
+}
+
+impl EventPublisher for InMemoryPublisher {
+    fn publish(&self, event: &PortfolioEvent) -> PortfolioResult<()> {
+        if self.fail.get() {
+            return Err(PortfolioError::PublishFailed(
+                "broker unavailable".to_string(),
+            ));
+        }
+        self.published.borrow_mut().push(event.clone());
+        Ok(())
+    }
+}
+
+#[rstest]
+fn forwards_subscribed_events_to_publisher() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let events = portfolio.subscribe();
+    portfolio.purchase(IBM, 2)?;
+    portfolio.sell(IBM, 1)?;
+    let publisher = InMemoryPublisher::default();
+    let mut forwarder = EventForwarder::new();
+    assert_eq!(forwarder.forward(&events, &publisher)?, 2);
+    let published = publisher.published.borrow();
+    assert_eq!(
+        published
+            .iter()
+            .map(PortfolioEvent::kind)
+            .collect::<Vec<_>>(),
+        vec!["transaction", "transaction"]
+    );
+    assert!(published.iter().all(|event| event.symbol() == IBM));
+    assert_eq!(forwarder.forward(&events, &publisher)?, 0);
+    Ok(())
+}
+
+#[rstest]
+fn keeps_failed_events_for_retry() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    let events = portfolio.subscribe();
+    portfolio.purchase(IBM, 2)?;
+    portfolio.purchase(VTI, 3)?;
+    let publisher = InMemoryPublisher {
+        fail: Cell::new(true),
+        ..InMemoryPublisher::default()
+    };
+    let mut forwarder = EventForwarder::new();
+    assert!(matches!(
+        forwarder.forward(&events, &publisher),
+        Err(PortfolioError::PublishFailed(_))
+    ));
+    assert_eq!(forwarder.pending().count(), 2);
+
+    publisher.fail.set(false);
+    assert_eq!(forwarder.forward(&events, &publisher)?, 2);
+    assert_eq!(
+        publisher
+            .published
+            .borrow()
+            .iter()
+            .map(PortfolioEvent::symbol)
+            .collect::<Vec<_>>(),
+        vec![IBM, VTI]
+    );
+    assert_eq!(forwarder.pending().count(), 0);
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use crate::import::*;
+use crate::money::{Currency, Money};
+use crate::period::Period;
+use crate::reconcile::*;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{DateTime, Utc};
+use rstest::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+fn trade(
+    symbol: &str,
+    on: DateTime<Utc>,
+    transaction_type: TransactionType,
+    shares: u32,
+) -> ImportedTransaction {
+    ImportedTransaction {
+        symbol: symbol.to_string(),
+        date: on,
+        transaction_type,
+        shares,
+        price: Some(usd(100)),
+        net_amount: None,
+    }
+}
+
+fn january_trades() -> Vec<ImportedTransaction> {
+    let on = |day| date(2024, 1, day).and_hms_opt(0, 0, 0).unwrap().and_utc();
+    vec![
+        trade(IBM, on(2), TransactionType::Purchase, 10),
+        trade(IBM, on(15), TransactionType::Sell, 4),
+        trade(AAPL, on(20), TransactionType::Purchase, 3),
+    ]
+}
+
+#[fixture]
+fn statement() -> BrokerStatement {
+    BrokerStatement {
+        period: Period::new(date(2024, 1, 1), date(2024, 1, 31)),
+        transactions: january_trades(),
+        positions: HashMap::from([(IBM.to_string(), 6), (AAPL.to_string(), 3)]),
+        net_trade_cash: usd(-900),
+    }
+}
+
+#[rstest]
+fn matching_statement_has_no_discrepancies(statement: BrokerStatement) -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.import(january_trades(), &ImportOptions::default())?;
+    assert_eq!(portfolio.reconcile(&statement)?, vec![]);
+    Ok(())
+}
+
+#[rstest]
+fn reports_missing_trades_quantities_and_cash(statement: BrokerStatement) -> PortfolioResult<()> {
+    let mut trades = january_trades();
+    let missing = trades.remove(2);
+    let extra = trade(
+        IBM,
+        date(2024, 1, 22).and_hms_opt(0, 0, 0).unwrap().and_utc(),
+        TransactionType::Purchase,
+        1,
+    );
+    trades.push(extra.clone());
+    let mut portfolio = Portfolio::new();
+    portfolio.import(trades, &ImportOptions::default())?;
+
+    assert_eq!(
+        portfolio.reconcile(&statement)?,
+        vec![
+            Discrepancy::MissingFromPortfolio(missing),
+            Discrepancy::MissingFromStatement(extra),
+            Discrepancy::QuantityMismatch {
+                symbol: AAPL.to_string(),
+                statement: 3,
+                portfolio: 0,
+            },
+            Discrepancy::QuantityMismatch {
+                symbol: IBM.to_string(),
+                statement: 6,
+                portfolio: 7,
+            },
+            Discrepancy::CashMismatch {
+                statement: usd(-900),
+                portfolio: usd(-700),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[rstest]
+fn ignores_trades_outside_statement_period(statement: BrokerStatement) -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.import(january_trades(), &ImportOptions::default())?;
+    portfolio.import(
+        vec![trade(
+            IBM,
+            date(2024, 2, 5).and_hms_opt(0, 0, 0).unwrap().and_utc(),
+            TransactionType::Purchase,
+            2,
+        )],
+        &ImportOptions::default(),
+    )?;
+    assert_eq!(portfolio.reconcile(&statement)?, vec![]);
+    Ok(())
+}
+
+#[rstest]
+fn net_trade_cash_uses_confirmed_net_amounts(statement: BrokerStatement) -> PortfolioResult<()> {
+    let cents = |amount: i64| Money::new(Decimal::new(amount, 2), Currency::Usd);
+    let with_fees = |mut transactions: Vec<ImportedTransaction>| {
+        transactions[0].net_amount = Some(cents(100_495));
+        transactions[1].net_amount = Some(cents(39_990));
+        transactions
+    };
+    let mut portfolio = Portfolio::new();
+    portfolio.import(with_fees(january_trades()), &ImportOptions::default())?;
+    let statement = BrokerStatement {
+        transactions: with_fees(statement.transactions),
+        net_trade_cash: cents(-90_505),
+        ..statement
+    };
+    assert_eq!(portfolio.reconcile(&statement)?, vec![]);
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use crate::clock::FixedClock;
+use crate::corporate_actions::StockSplit;
+#[cfg(feature = "pricing")]
+use crate::instruments::InstrumentKind;
+use crate::period::Period;
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+use crate::report::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+use rust_decimal::Decimal;
+
+const FUND: &str = "VFIAX";
+
+#[cfg(feature = "pricing")]
+#[fixture]
+fn portfolio_with_fund() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.instruments_mut()
+        .unwrap()
+        .register(FUND, InstrumentKind::MutualFund);
+    p.instruments_mut()
+        .unwrap()
+        .set_expense_ratio(FUND, Decimal::new(4, 4))
+        .unwrap();
+    p.purchase(FUND, 100).unwrap();
+    p.purchase(IBM, 100).unwrap();
+    p
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn estimates_embedded_fund_fees_over_period(portfolio_with_fund: Portfolio) -> PortfolioResult<()> {
+    let mut prices = PriceHistory::new();
+    prices.insert(FUND, date(2022, 12, 30), usd(10));
+    prices.insert(IBM, date(2022, 12, 30), usd(150));
+    let period = Period::new(date(2023, 1, 1), date(2023, 12, 31));
+
+    let report = fee_drag(&portfolio_with_fund, &prices, &period)?;
+
+    assert_eq!(
+        report.funds,
+        vec![FundFeeDrag {
+            symbol: FUND.to_string(),
+            expense_ratio: Decimal::new(4, 4),
+            average_value: usd(1000),
+            estimated_fees: cents(40),
+        }]
+    );
+    assert_eq!(report.total_fees, cents(40));
+    Ok(())
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn skips_days_without_a_known_price(portfolio_with_fund: Portfolio) -> PortfolioResult<()> {
+    let mut prices = PriceHistory::new();
+    prices.insert(FUND, date(2023, 7, 2), usd(10));
+    let period = Period::new(date(2023, 1, 1), date(2023, 12, 31));
+
+    let report = fee_drag(&portfolio_with_fund, &prices, &period)?;
+
+    assert_eq!(report.total_fees.amount.round_dp(2), Decimal::new(20, 2));
+    Ok(())
+}
+
+#[fixture]
+fn portfolio_with_cash() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.set_clock(|| at(2024, 12, 31));
+    p.record_deposit(usd(5_000), at(2024, 1, 2)).unwrap();
+    p.transact(
+        IBM,
+        20,
+        TransactionType::Purchase,
+        Some(usd(100)),
+        at(2024, 1, 3),
+    )
+    .unwrap();
+    p.record_capital_gain_distribution(IBM, usd(10), usd(30), at(2024, 3, 1))
+        .unwrap();
+    p.transact(
+        IBM,
+        5,
+        TransactionType::Sell,
+        Some(usd(120)),
+        at(2024, 4, 1),
+    )
+    .unwrap();
+    p.apply_return_of_capital(IBM, usd(1), at(2024, 5, 1))
+        .unwrap();
+    p.record_withdrawal(usd(700), at(2024, 6, 1)).unwrap();
+    p
+}
+
+#[rstest]
+fn cash_flows_list_movements_with_running_balance(
+    portfolio_with_cash: Portfolio,
+) -> PortfolioResult<()> {
+    let period = Period::new(date(2024, 1, 1), date(2024, 12, 31));
+
+    let statement = cash_flows(&portfolio_with_cash, &period)?;
+
+    let ledger: Vec<_> = statement
+        .entries
+        .iter()
+        .map(|entry| (entry.kind, entry.amount, entry.balance))
+        .collect();
+    assert_eq!(
+        ledger,
+        vec![
+            (CashFlowKind::Deposit, usd(5_000), usd(5_000)),
+            (CashFlowKind::Buy, usd(-2_000), usd(3_000)),
+            (CashFlowKind::Dividend, usd(40), usd(3_040)),
+            (CashFlowKind::Sell, usd(600), usd(3_640)),
+            (CashFlowKind::ReturnOfCapital, usd(15), usd(3_655)),
+            (CashFlowKind::Withdrawal, usd(-700), usd(2_955)),
+        ]
+    );
+    assert_eq!(statement.opening_balance, usd(0));
+    assert_eq!(statement.closing_balance, usd(2_955));
+    Ok(())
+}
+
+#[rstest]
+fn cash_flows_include_interest_lending_income_and_cash_in_lieu(
+    mut portfolio_with_cash: Portfolio,
+) -> PortfolioResult<()> {
+    portfolio_with_cash.record_interest(usd(12), at(2024, 7, 1))?;
+    portfolio_with_cash.record_split(
+        IBM,
+        StockSplit {
+            date: at(2024, 8, 1),
+            numerator: 1,
+            denominator: 2,
+            cash_in_lieu_price: Some(usd(240)),
+        },
+    )?;
+    portfolio_with_cash.lend_shares(IBM, 5)?;
+    portfolio_with_cash.accrue_lending_income(IBM, usd(3))?;
+    let period = Period::new(date(2024, 7, 1), date(2024, 12, 31));
+
+    let statement = cash_flows(&portfolio_with_cash, &period)?;
+
+    let ledger: Vec<_> = statement
+        .entries
+        .iter()
+        .map(|entry| (entry.kind, entry.amount, entry.balance))
+        .collect();
+    assert_eq!(
+        ledger,
+        vec![
+            (CashFlowKind::Interest, usd(12), usd(2_967)),
+            (CashFlowKind::Sell, usd(120), usd(3_087)),
+            (CashFlowKind::LendingIncome, usd(3), usd(3_090)),
+        ]
+    );
+    assert!(statement
+        .to_csv()
+        .contains("2024-12-31,lending_income,IBM,3,3090\n"));
+    Ok(())
+}
+
+#[rstest]
+fn cash_flows_carry_earlier_movements_into_opening_balance(
+    portfolio_with_cash: Portfolio,
+) -> PortfolioResult<()> {
+    let period = Period::new(date(2024, 3, 1), date(2024, 4, 30));
+
+    let statement = cash_flows(&portfolio_with_cash, &period)?;
+
+    assert_eq!(statement.opening_balance, usd(3_000));
+    assert_eq!(statement.entries.len(), 2);
+    assert_eq!(statement.closing_balance, usd(3_640));
+    Ok(())
+}
+
+#[rstest]
+fn cash_flows_export_to_csv(portfolio_with_cash: Portfolio) -> PortfolioResult<()> {
+    let period = Period::new(date(2024, 1, 1), date(2024, 1, 31));
+
+    let csv = cash_flows(&portfolio_with_cash, &period)?.to_csv();
+
+    assert_eq!(
+        csv,
+        "date,kind,symbol,amount,balance\n\
+         2024-01-02,deposit,,5000,5000\n\
+         2024-01-03,buy,IBM,-2000,3000\n"
+    );
+    Ok(())
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn monthly_statement_summarizes_the_month(portfolio_with_cash: Portfolio) -> PortfolioResult<()> {
+    let mut prices = PriceHistory::new();
+    prices.insert(IBM, date(2024, 2, 29), usd(110));
+    prices.insert(IBM, date(2024, 3, 29), usd(115));
+
+    let statement = monthly_statement(&portfolio_with_cash, 2024, 3, &prices)?;
+
+    assert_eq!(
+        statement.period,
+        Period::new(date(2024, 3, 1), date(2024, 3, 31))
+    );
+    assert_eq!(statement.opening_value, usd(2_200));
+    assert_eq!(statement.closing_value, usd(2_300));
+    assert_eq!(statement.activity.len(), 1);
+    assert_eq!(statement.income, usd(40));
+    assert_eq!(statement.realized_gain, usd(0));
+    assert_eq!(statement.fees, usd(0));
+    Ok(())
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn monthly_statement_reports_realized_gains(portfolio_with_cash: Portfolio) -> PortfolioResult<()> {
+    let mut prices = PriceHistory::new();
+    prices.insert(IBM, date(2024, 3, 29), usd(115));
+    prices.insert(IBM, date(2024, 4, 30), usd(125));
+
+    let statement = monthly_statement(&portfolio_with_cash, 2024, 4, &prices)?;
+
+    assert_eq!(statement.realized_gain, usd(100));
+    assert_eq!(statement.closing_value, usd(1_875));
+    Ok(())
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn monthly_statement_rejects_invalid_month(portfolio_with_cash: Portfolio) {
+    assert!(matches!(
+        monthly_statement(&portfolio_with_cash, 2024, 13, &PriceHistory::new()),
+        Err(PortfolioError::InvalidConfig(_))
+    ));
+}
+
+#[rstest]
+fn rounding_audit_reports_sub_cent_residue() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    portfolio.purchase_at(IBM, 3, usd_amount(Decimal::new(33_335, 3)))?;
+    portfolio.purchase_at(FUND, 10, usd(100))?;
+
+    let audit = rounding_audit(&portfolio)?;
+
+    assert_eq!(
+        audit
+            .entries
+            .iter()
+            .map(|entry| (entry.source, entry.transaction_id, entry.residue))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                RoundingSource::TradeValue,
+                Some(0),
+                usd_amount(Decimal::new(5, 3))
+            ),
+            (
+                RoundingSource::LotBasis,
+                Some(0),
+                usd_amount(Decimal::new(5, 3))
+            ),
+        ]
+    );
+    assert_eq!(audit.entries[0].settled, cents(10_000));
+    assert_eq!(audit.total_residue, usd_amount(Decimal::new(10, 3)));
+    Ok(())
+}
+
+#[rstest]
+fn rounding_audit_is_empty_for_whole_cent_activity(
+    portfolio_with_cash: Portfolio,
+) -> PortfolioResult<()> {
+    let audit = rounding_audit(&portfolio_with_cash)?;
+    assert!(audit.entries.is_empty());
+    assert!(audit.total_residue.is_zero());
+    Ok(())
+}
+
+#[rstest]
+fn stock_dividends_are_not_cash_flows(mut portfolio_with_cash: Portfolio) -> PortfolioResult<()> {
+    let year = Period::new(date(2024, 1, 1), date(2024, 12, 31));
+    let before = cash_flows(&portfolio_with_cash, &year)?;
+    portfolio_with_cash.record_stock_dividend(IBM, 2, at(2024, 7, 1))?;
+    assert_eq!(portfolio_with_cash.get_share_count(IBM), 17);
+    assert_eq!(cash_flows(&portfolio_with_cash, &year)?, before);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use crate::config::{CostBasisMethod, PortfolioConfig};
+use crate::lots::Lot;
+use crate::money::Money;
+use crate::reversal::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(IBM, 5, usd(110)).unwrap();
+    p
+}
+
+#[rstest]
+fn books_offsetting_contra_entry(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let contra = portfolio.reverse_transaction(1, "duplicate fill")?;
+    assert_eq!(contra.transaction_type, TransactionType::Sell);
+    assert_eq!((contra.shares, contra.price), (5, Some(usd(110))));
+    assert_eq!(portfolio.get_share_count(IBM), 10);
+    assert_eq!(portfolio.get_purchase_record(IBM)?.len(), 3);
+    assert_eq!(
+        portfolio.reversal_of(1),
+        Some(&Reversal {
+            original: 1,
+            contra: contra.transaction_id,
+            reason: "duplicate fill".to_string(),
+        })
+    );
+    Ok(())
+}
+
+#[rstest]
+fn reversing_a_sell_books_a_purchase(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    let sell = portfolio.sell_at(IBM, 3, usd(120))?;
+    let contra = portfolio.reverse_transaction(sell.transaction_id, "wrong account")?;
+    assert_eq!(contra.transaction_type, TransactionType::Purchase);
+    assert_eq!(portfolio.get_share_count(IBM), 15);
+    Ok(())
+}
+
+#[rstest]
+fn error_when_reversing_twice_or_unknown_id(mut portfolio: Portfolio) -> PortfolioResult<()> {
+    portfolio.reverse_transaction(0, "typo")?;
+    assert!(matches!(
+        portfolio.reverse_transaction(0, "typo"),
+        Err(PortfolioError::TransactionAlreadyReversed(0))
+    ));
+    assert!(matches!(
+        portfolio.reverse_transaction(99, "typo"),
+        Err(PortfolioError::UnknownTransaction(99))
+    ));
+    assert_eq!(portfolio.reversals().len(), 1);
+    Ok(())
+}
+
+#[rstest]
+fn reversing_a_purchase_removes_its_own_lot() -> PortfolioResult<()> {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100))?;
+    let second = p.purchase_at(IBM, 10, usd(200))?;
+    let contra = p.reverse_transaction(second.transaction_id, "wrong price")?;
+    assert_eq!(contra.realized_gain, Some(usd(0)));
+    let lots = p.open_lots(IBM).to_vec();
+    assert_eq!(lots.len(), 1);
+    assert_eq!((lots[0].shares, lots[0].cost_basis), (10, usd(1000)));
+    assert_eq!(p.realized_gains(IBM)?.total_gain, usd(0));
+    p.rebuild()?;
+    assert_eq!(p.open_lots(IBM), lots);
+    Ok(())
+}
+
+#[rstest]
+fn reversing_a_sell_restores_the_consumed_lots() -> PortfolioResult<()> {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100))?;
+    p.purchase_at(IBM, 10, usd(200))?;
+    let lots = p.open_lots(IBM).to_vec();
+    let sell = p.sell_at(IBM, 15, usd(300))?;
+    p.reverse_transaction(sell.transaction_id, "wrong account")?;
+    let restored = p.open_lots(IBM).to_vec();
+    let basis = |lots: &[Lot]| -> Vec<(u32, Money)> {
+        let mut basis: Vec<_> = lots.iter().map(|l| (l.shares, l.cost_basis)).collect();
+        basis.sort_by_key(|(shares, basis)| (*shares, basis.amount));
+        basis
+    };
+    assert_eq!(basis(&restored), basis(&lots));
+    assert_eq!(p.realized_gains(IBM)?.total_gain, usd(0));
+    p.rebuild()?;
+    assert_eq!(basis(p.open_lots(IBM)), basis(&lots));
+    Ok(())
+}
+
+#[rstest]
+fn reversing_a_partly_sold_purchase_is_rejected() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        cost_basis_method: CostBasisMethod::Lifo,
+        ..PortfolioConfig::default()
+    });
+    portfolio.purchase_at(IBM, 10, usd(100))?;
+    portfolio.purchase_at(IBM, 5, usd(110))?;
+    portfolio.sell_at(IBM, 2, usd(120))?;
+    assert!(portfolio.reverse_transaction(1, "typo").is_err());
+    assert_eq!(portfolio.get_share_count(IBM), 13);
+    assert!(portfolio.reversals().is_empty());
+    Ok(())
+}
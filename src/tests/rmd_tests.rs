@@ -0,0 +1,165 @@
+use crate::config::{AccountType, PortfolioConfig};
+use crate::manager::PortfolioManager;
+use crate::money::Currency;
+use crate::prices::PriceHistory;
+use crate::rmd::*;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::NaiveDate;
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn born(year: i32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, 3, 15).unwrap()
+}
+
+fn account(account_type: AccountType) -> Portfolio {
+    let mut p = Portfolio::with_config(PortfolioConfig {
+        account_type,
+        ..PortfolioConfig::default()
+    });
+    p.set_clock(|| at(2025, 6, 1));
+    p.transact(
+        IBM,
+        100,
+        TransactionType::Purchase,
+        Some(usd(100)),
+        at(2020, 1, 2),
+    )
+    .unwrap();
+    p
+}
+
+#[fixture]
+fn prices() -> PriceHistory {
+    let mut h = PriceHistory::new();
+    h.insert(
+        IBM,
+        NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+        usd(265),
+    );
+    h
+}
+
+#[rstest]
+#[case(1950, 72)]
+#[case(1951, 73)]
+#[case(1959, 73)]
+#[case(1960, 75)]
+fn beginning_age_depends_on_birth_year(#[case] year: i32, #[case] age: i32) {
+    assert_eq!(required_beginning_age(born(year)), age);
+}
+
+#[rstest]
+#[case(71, None)]
+#[case(73, Some(Decimal::new(265, 1)))]
+#[case(100, Some(Decimal::new(64, 1)))]
+#[case(125, Some(Decimal::from(2)))]
+fn uniform_lifetime_table(#[case] age: i32, #[case] period: Option<Decimal>) {
+    assert_eq!(distribution_period(age), period);
+}
+
+#[rstest]
+fn traditional_ira_divides_prior_year_end_balance(prices: PriceHistory) {
+    let p = account(AccountType::TraditionalIra);
+    let report = p.rmd_for_year(2025, born(1952), &prices).unwrap();
+    assert_eq!(report.age, 73);
+    assert_eq!(report.prior_year_end_balance, usd(26_500));
+    assert_eq!(report.required, usd(1_000));
+    assert_eq!(report.remaining, usd(1_000));
+}
+
+#[rstest]
+fn withdrawals_in_the_year_count_toward_the_requirement(prices: PriceHistory) {
+    let mut p = account(AccountType::TraditionalIra);
+    p.record_withdrawal(usd(300), at(2024, 12, 20)).unwrap();
+    p.record_withdrawal(usd(400), at(2025, 2, 1)).unwrap();
+    p.record_withdrawal(usd(250), at(2025, 5, 1)).unwrap();
+    let report = p.rmd_for_year(2025, born(1952), &prices).unwrap();
+    assert_eq!(report.withdrawn, usd(650));
+    assert_eq!(report.remaining, usd(350));
+}
+
+#[rstest]
+fn remaining_never_goes_negative(prices: PriceHistory) {
+    let mut p = account(AccountType::TraditionalIra);
+    p.record_withdrawal(usd(1_500), at(2025, 2, 1)).unwrap();
+    let report = p.rmd_for_year(2025, born(1952), &prices).unwrap();
+    assert_eq!(report.remaining, usd(0));
+}
+
+#[rstest]
+fn nothing_is_required_before_the_beginning_age(prices: PriceHistory) {
+    let p = account(AccountType::TraditionalIra);
+    let report = p.rmd_for_year(2025, born(1960), &prices).unwrap();
+    assert_eq!(report.distribution_period, None);
+    assert_eq!(report.required, usd(0));
+}
+
+#[rstest]
+fn roth_ira_has_no_requirement(prices: PriceHistory) {
+    let p = account(AccountType::RothIra);
+    let report = p.rmd_for_year(2025, born(1940), &prices).unwrap();
+    assert_eq!(report.required, usd(0));
+}
+
+#[rstest]
+fn taxable_account_is_rejected(prices: PriceHistory) {
+    let p = account(AccountType::Taxable);
+    assert!(matches!(
+        p.rmd_for_year(2025, born(1940), &prices),
+        Err(PortfolioError::NotRetirementAccount)
+    ));
+}
+
+#[rstest]
+fn negative_withdrawal_is_rejected() {
+    let mut p = account(AccountType::TraditionalIra);
+    assert!(matches!(
+        p.record_withdrawal(usd(-1), at(2025, 2, 1)),
+        Err(PortfolioError::NegativeAmount)
+    ));
+}
+
+fn household() -> PortfolioManager {
+    let mut manager = PortfolioManager::new(Currency::Usd);
+    manager.add_account("brokerage", account(AccountType::Taxable));
+    manager.add_account("ira", account(AccountType::TraditionalIra));
+    manager.add_account("rollover", account(AccountType::TraditionalIra));
+    manager.add_account("roth", account(AccountType::RothIra));
+    manager
+}
+
+#[rstest]
+fn manager_aggregates_requirements_across_iras(prices: PriceHistory) -> PortfolioResult<()> {
+    let mut manager = household();
+    manager
+        .account_mut("rollover")
+        .unwrap()
+        .record_withdrawal(usd(1_200), at(2025, 2, 1))?;
+    manager
+        .account_mut("roth")
+        .unwrap()
+        .record_withdrawal(usd(500), at(2025, 2, 1))?;
+    let summary = manager.rmd_for_year(2025, born(1952), &prices)?;
+    assert_eq!(
+        summary.accounts.keys().collect::<Vec<_>>(),
+        vec!["ira", "rollover", "roth"]
+    );
+    assert_eq!(summary.accounts["ira"].required, usd(1_000));
+    assert_eq!(summary.accounts["rollover"].remaining, usd(0));
+    assert_eq!(summary.required, usd(2_000));
+    assert_eq!(summary.withdrawn, usd(1_200));
+    assert_eq!(summary.remaining, usd(800));
+    Ok(())
+}
+
+#[rstest]
+fn manager_without_retirement_accounts_is_rejected(prices: PriceHistory) {
+    let mut manager = PortfolioManager::new(Currency::Usd);
+    manager.add_account("brokerage", account(AccountType::Taxable));
+    assert!(matches!(
+        manager.rmd_for_year(2025, born(1940), &prices),
+        Err(PortfolioError::NotRetirementAccount)
+    ));
+}
@@ -0,0 +1,20 @@
+use crate::shared::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+#[rstest]
+fn clones_share_one_portfolio() -> PortfolioResult<()> {
+    let shared = SharedPortfolio::new(Portfolio::new());
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let shared = shared.clone();
+            std::thread::spawn(move || shared.write().purchase(IBM, 5).map(|_| ()))
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap()?;
+    }
+    assert_eq!(shared.read().get_share_count(IBM), 20);
+    Ok(())
+}
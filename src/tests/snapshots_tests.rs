@@ -0,0 +1,142 @@
+use crate::clock::FixedClock;
+use crate::config::{PortfolioConfig, SnapshotRetention, StorageSettings};
+use crate::period::Period;
+use crate::prices::PriceHistory;
+use crate::snapshots::*;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{Datelike, NaiveDate};
+use rstest::*;
+use std::collections::BTreeMap;
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.purchase(IBM, 10).unwrap();
+    p.purchase(AAPL, 2).unwrap();
+    p.sell(AAPL, 2).unwrap();
+    p
+}
+
+#[fixture]
+fn prices() -> PriceHistory {
+    let mut h = PriceHistory::new();
+    h.insert(IBM, date(2024, 1, 2), usd(100));
+    h.insert(IBM, date(2024, 1, 3), usd(105));
+    h
+}
+
+#[rstest]
+fn records_observed_end_of_day_value(
+    mut portfolio: Portfolio,
+    prices: PriceHistory,
+) -> PortfolioResult<()> {
+    let snapshot = portfolio
+        .record_eod_snapshot(date(2024, 1, 2), &prices)?
+        .clone();
+    assert_eq!(
+        snapshot,
+        ValuationSnapshot {
+            date: date(2024, 1, 2),
+            market_value: usd(1000),
+            shares: BTreeMap::from([(IBM.to_string(), 10)]),
+        }
+    );
+    assert_eq!(portfolio.snapshots().collect::<Vec<_>>(), vec![&snapshot]);
+    Ok(())
+}
+
+#[rstest]
+fn snapshot_series_reads_back_recorded_values(
+    mut portfolio: Portfolio,
+    mut prices: PriceHistory,
+) -> PortfolioResult<()> {
+    portfolio.record_eod_snapshot(date(2024, 1, 3), &prices)?;
+    portfolio.record_eod_snapshot(date(2024, 1, 2), &prices)?;
+    prices.insert(IBM, date(2024, 1, 3), usd(90));
+    portfolio.record_eod_snapshot(date(2024, 1, 3), &prices)?;
+    assert_eq!(
+        portfolio.snapshot_series(),
+        BTreeMap::from([(date(2024, 1, 2), usd(1000)), (date(2024, 1, 3), usd(900))])
+    );
+    Ok(())
+}
+
+#[rstest]
+fn error_when_snapshot_cannot_be_valued(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.record_eod_snapshot(date(2024, 1, 2), &PriceHistory::new()),
+        Err(PortfolioError::MissingPrice(symbol)) if symbol == IBM
+    ));
+    assert!(portfolio.snapshot_series().is_empty());
+}
+
+fn new_years_eve() -> chrono::DateTime<chrono::Utc> {
+    NaiveDate::from_ymd_opt(2024, 12, 31)
+        .unwrap()
+        .and_hms_opt(22, 0, 0)
+        .unwrap()
+        .and_utc()
+}
+
+fn portfolio_with_daily_snapshots(retention: SnapshotRetention) -> Portfolio {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        storage: StorageSettings {
+            snapshot_retention: retention,
+            ..StorageSettings::default()
+        },
+        ..PortfolioConfig::default()
+    });
+    portfolio.set_clock(new_years_eve);
+    portfolio.purchase(IBM, 1).unwrap();
+    let mut prices = PriceHistory::new();
+    prices.insert(IBM, NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(), usd(100));
+    let period = Period::new(
+        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+        new_years_eve().date_naive(),
+    );
+    for date in period.iter_days() {
+        portfolio.record_eod_snapshot(date, &prices).unwrap();
+    }
+    portfolio
+}
+
+#[rstest]
+fn compaction_keeps_daily_then_weekly_then_monthly() {
+    let mut portfolio = portfolio_with_daily_snapshots(SnapshotRetention::default());
+    let before = portfolio.snapshots().count();
+    let removed = portfolio.compact_snapshots().unwrap();
+    let dates: Vec<NaiveDate> = portfolio.snapshot_series().into_keys().collect();
+    assert_eq!(before - removed, dates.len());
+
+    let today = new_years_eve().date_naive();
+    let age = |date: &NaiveDate| (today - *date).num_days();
+    assert_eq!(dates.iter().filter(|date| age(date) <= 90).count(), 91);
+    let weekly: Vec<_> = dates
+        .iter()
+        .filter(|date| (91..=730).contains(&age(date)))
+        .map(|date| date.iso_week())
+        .collect();
+    assert!(weekly.windows(2).all(|pair| pair[0] != pair[1]));
+    let monthly: Vec<_> = dates
+        .iter()
+        .filter(|date| age(date) > 730)
+        .map(|date| (date.year(), date.month()))
+        .collect();
+    assert!(monthly.windows(2).all(|pair| pair[0] != pair[1]));
+    assert_eq!(dates[0], NaiveDate::from_ymd_opt(2021, 1, 31).unwrap());
+}
+
+#[rstest]
+fn compaction_drops_snapshots_past_monthly_retention() {
+    let mut portfolio = portfolio_with_daily_snapshots(SnapshotRetention {
+        daily_days: 0,
+        weekly_days: 0,
+        monthly_days: Some(365),
+    });
+    portfolio.compact_snapshots().unwrap();
+    let dates: Vec<NaiveDate> = portfolio.snapshot_series().into_keys().collect();
+    assert_eq!(dates.len(), 13);
+    assert_eq!(dates[0], NaiveDate::from_ymd_opt(2024, 1, 31).unwrap());
+    assert_eq!(dates[12], new_years_eve().date_naive());
+}
@@ -0,0 +1,146 @@
+#[cfg(feature = "pricing")]
+use crate::prices::PriceHistory;
+use crate::tests::helpers::*;
+use crate::Quotes;
+use crate::*;
+use chrono::NaiveDate;
+use rstest::*;
+use rust_decimal::Decimal;
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(IBM, 10, usd(120)).unwrap();
+    p.sell_at(IBM, 5, usd(130)).unwrap();
+    p.purchase_at(VTI, 10, usd(150)).unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 6, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+    p.record_capital_gain_distribution(IBM, usd(3), usd(4), date)
+        .unwrap();
+    p.lend_shares(IBM, 2).unwrap();
+    p.accrue_lending_income(IBM, usd(1)).unwrap();
+    let now = p.now();
+    p.record_dividend(IBM, usd(1), now, None).unwrap();
+    p
+}
+
+#[fixture]
+fn quotes() -> Quotes {
+    Quotes::from([(IBM.to_string(), usd(140)), (VTI.to_string(), usd(150))])
+}
+
+#[rstest]
+fn summarizes_position(portfolio: Portfolio, quotes: Quotes) -> PortfolioResult<()> {
+    let summary = portfolio.position_summary(IBM, usd(140), &quotes)?;
+    assert_eq!(summary.shares, 15);
+    assert_eq!(summary.cost_basis, usd(1700));
+    assert_eq!(summary.market_value, usd(2100));
+    assert_eq!(summary.unrealized_gain, usd(400));
+    assert_eq!(summary.realized_gain, usd(150));
+    assert_eq!(summary.income, usd(23));
+    assert_eq!(
+        summary.weight.map(|weight| weight.round_dp(2)),
+        Some(Decimal::new(5833, 2))
+    );
+    Ok(())
+}
+
+#[rstest]
+fn weight_is_unknown_without_quotes_for_other_positions(
+    portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    let summary = portfolio.position_summary(IBM, usd(140), &Quotes::new())?;
+    assert_eq!(summary.market_value, usd(2100));
+    assert_eq!(summary.weight, None);
+    Ok(())
+}
+
+#[rstest]
+fn error_without_history(portfolio: Portfolio, quotes: Quotes) {
+    assert!(matches!(
+        portfolio.position_summary("AAPL", usd(10), &quotes),
+        Err(PortfolioError::NoSymbolHistory)
+    ));
+}
+
+#[rstest]
+fn lists_positions_down_more_than_threshold_from_average_cost(
+    portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    let quotes = Quotes::from([(IBM.to_string(), usd(90)), (VTI.to_string(), usd(120))]);
+    let drawdowns = portfolio.positions_below_basis(&quotes, Decimal::TEN)?;
+    let found: Vec<(&str, Decimal)> = drawdowns
+        .iter()
+        .map(|drawdown| (drawdown.symbol.as_str(), drawdown.decline_pct.round_dp(2)))
+        .collect();
+    assert_eq!(
+        found,
+        vec![(IBM, Decimal::new(2059, 2)), (VTI, Decimal::from(20))]
+    );
+    assert_eq!(drawdowns[1].average_cost, usd(150));
+    assert_eq!(drawdowns[1].market_value, usd(1_200));
+
+    assert!(portfolio
+        .positions_below_basis(&quotes, Decimal::from(25))?
+        .is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn drawdown_needs_a_quote_for_every_position(portfolio: Portfolio) {
+    let quotes = Quotes::from([(IBM.to_string(), usd(90))]);
+    assert!(matches!(
+        portfolio.positions_below_basis(&quotes, Decimal::TEN),
+        Err(PortfolioError::MissingPrice(symbol)) if symbol == VTI
+    ));
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn positions_context_against_trailing_52_week_range(
+    mut portfolio: Portfolio,
+) -> PortfolioResult<()> {
+    let day = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    portfolio.set_clock(|| {
+        NaiveDate::from_ymd_opt(2024, 6, 30)
+            .unwrap()
+            .and_hms_opt(16, 0, 0)
+            .unwrap()
+            .and_utc()
+    });
+    let mut history = PriceHistory::new();
+    history.insert(IBM, day(2023, 6, 1), usd(200));
+    history.insert(IBM, day(2023, 9, 1), usd(160));
+    history.insert(IBM, day(2024, 1, 2), usd(80));
+    history.insert(IBM, day(2024, 6, 28), usd(120));
+    history.insert(IBM, day(2024, 7, 1), usd(300));
+
+    let context = portfolio.position_context(IBM, &history)?;
+    assert_eq!(context.as_of, day(2024, 6, 28));
+    assert_eq!(context.price, usd(120));
+    assert_eq!(
+        (context.high_date, context.high),
+        (day(2023, 9, 1), usd(160))
+    );
+    assert_eq!((context.low_date, context.low), (day(2024, 1, 2), usd(80)));
+    assert_eq!(context.from_high_pct, Decimal::from(-25));
+    assert_eq!(context.from_low_pct, Decimal::from(50));
+    Ok(())
+}
+
+#[cfg(feature = "pricing")]
+#[rstest]
+fn position_context_needs_recent_prices(portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.position_context(IBM, &PriceHistory::new()),
+        Err(PortfolioError::MissingPrice(symbol)) if symbol == IBM
+    ));
+    assert!(matches!(
+        portfolio.position_context("AAPL", &PriceHistory::new()),
+        Err(PortfolioError::NoSymbolHistory)
+    ));
+}
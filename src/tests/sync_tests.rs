@@ -0,0 +1,314 @@
+use crate::clock::FixedClock;
+use crate::config::{PortfolioConfig, RuleSettings};
+#[cfg(feature = "import")]
+use crate::import::{BasisMode, BrokerLot};
+use crate::ledger::Transaction;
+#[cfg(feature = "import")]
+use crate::lots::Acquisition;
+use crate::lots::LotSelection;
+use crate::sync::*;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+#[fixture]
+fn server() -> Portfolio {
+    let mut p = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(AAPL, 5, usd(150)).unwrap();
+    p
+}
+
+#[rstest]
+fn applies_new_events_to_replica(server: Portfolio) -> PortfolioResult<()> {
+    let mut mobile = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    let report = apply_delta(&mut mobile, &export_delta(&server, 0))?;
+    assert_eq!(report.applied, vec![0, 1]);
+    assert_eq!(mobile.get_share_count(IBM), 10);
+    assert_eq!(mobile.get_share_count(AAPL), 5);
+    assert_eq!(mobile.journal().len(), 2);
+    assert!(mobile.verify_integrity().is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn deltas_carry_acquisition_and_lot_selection(mut server: Portfolio) -> PortfolioResult<()> {
+    server.receive_gift(IBM, 5, usd(40), at(2020, 1, 1), usd(90), at(2024, 2, 1))?;
+    server.transact_with_net_amount(
+        AAPL,
+        5,
+        TransactionType::Purchase,
+        usd(150),
+        usd(760),
+        at(2024, 3, 1),
+    )?;
+    let gifted = server.open_lots(IBM)[1].clone();
+    let selection = LotSelection {
+        lot_id: gifted.id,
+        shares: 3,
+    };
+    let sale = server.sell_lots(IBM, &[selection], usd(120), at(2024, 4, 1))?;
+    let mut mobile = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    apply_delta(&mut mobile, &export_delta(&server, 0))?;
+    assert_eq!(mobile.journal(), server.journal());
+    assert_eq!(mobile.lots, server.lots);
+    assert_eq!(mobile.acquisition_of(gifted.sequence), gifted.acquisition);
+    assert_eq!(
+        mobile.lot_selection_of(sale.transaction_id),
+        server.lot_selection_of(sale.transaction_id)
+    );
+    assert_eq!(
+        mobile.realized_gains(IBM)?.total_gain,
+        server.realized_gains(IBM)?.total_gain
+    );
+    Ok(())
+}
+
+#[rstest]
+fn exchanges_only_events_since_last_sync(mut server: Portfolio) -> PortfolioResult<()> {
+    let mut mobile = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    let delta = export_delta(&server, 0);
+    apply_delta(&mut mobile, &delta)?;
+    server.sell_at(IBM, 4, usd(120))?;
+    let next = export_delta(&server, delta.version);
+    assert_eq!(next.transactions.len(), 1);
+    assert_eq!(apply_delta(&mut mobile, &next)?.applied, vec![2]);
+    assert_eq!(mobile.get_share_count(IBM), 6);
+    Ok(())
+}
+
+#[rstest]
+fn echoed_events_are_reported_as_duplicates(server: Portfolio) -> PortfolioResult<()> {
+    let mut mobile = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    apply_delta(&mut mobile, &export_delta(&server, 0))?;
+    let mut server = server;
+    let report = apply_delta(&mut server, &export_delta(&mobile, 0))?;
+    assert_eq!(report.applied, Vec::<TransactionId>::new());
+    assert_eq!(report.duplicates, vec![0, 1]);
+    assert_eq!(server.get_share_count(IBM), 10);
+    Ok(())
+}
+
+#[rstest]
+fn detects_concurrent_edits_to_the_same_transaction(mut server: Portfolio) -> PortfolioResult<()> {
+    let mut mobile = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    let delta = export_delta(&server, 0);
+    apply_delta(&mut mobile, &delta)?;
+    server.sell_at(IBM, 1, usd(120))?;
+    mobile.sell_at(AAPL, 2, usd(160))?;
+    assert!(matches!(
+        apply_delta(&mut mobile, &export_delta(&server, delta.version)),
+        Err(PortfolioError::SyncConflict(ids)) if ids == vec![2]
+    ));
+    assert_eq!(mobile.get_share_count(IBM), 10);
+    Ok(())
+}
+
+#[rstest]
+fn rejects_delta_that_breaks_the_journal(mut server: Portfolio) {
+    let mut other = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    other.purchase(AAPL, 1).unwrap();
+    other.purchase(AAPL, 1).unwrap();
+    other.purchase(IBM, 20).unwrap();
+    other.sell(IBM, 20).unwrap();
+    let delta = export_delta(&other, 3);
+    let version = server.version();
+    assert!(matches!(
+        apply_delta(&mut server, &delta),
+        Err(PortfolioError::InvalidSell)
+    ));
+    assert_eq!(server.version(), version);
+    assert_eq!(server.get_share_count(IBM), 10);
+}
+
+#[rstest]
+fn deltas_carry_every_ledger_entry(mut server: Portfolio) -> PortfolioResult<()> {
+    server.record_dividend(IBM, usd(1), at(2024, 3, 1), None)?;
+    server.apply_return_of_capital(AAPL, usd(2), at(2024, 4, 1))?;
+    let mut mobile = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    let report = apply_delta(&mut mobile, &export_delta(&server, 0))?;
+    assert_eq!(report.applied_entries, 2);
+    assert_eq!(mobile.events(), server.events());
+    assert_eq!(mobile.get_dividends(IBM), server.get_dividends(IBM));
+    assert_eq!(mobile.lots, server.lots);
+    let echoed = apply_delta(&mut server, &export_delta(&mobile, 0))?;
+    assert_eq!(echoed.duplicate_entries, 2);
+    assert_eq!(server.ledger().len(), 4);
+    Ok(())
+}
+
+#[rstest]
+fn rejects_trades_that_break_the_replica_rules(server: Portfolio) {
+    let mut mobile = Portfolio::with_config(PortfolioConfig {
+        rules: RuleSettings {
+            max_shares_per_trade: Some(5),
+            ..RuleSettings::default()
+        },
+        ..PortfolioConfig::default()
+    });
+    assert!(matches!(
+        apply_delta(&mut mobile, &export_delta(&server, 0)),
+        Err(PortfolioError::TradeLimitExceeded(5))
+    ));
+    assert!(mobile.journal().is_empty());
+    assert_eq!(mobile.version(), 0);
+}
+
+fn trades(portfolio: &Portfolio) -> Vec<(TransactionId, String, u32)> {
+    portfolio
+        .journal()
+        .into_iter()
+        .map(|(symbol, record)| (record.id, symbol.to_string(), record.shares))
+        .collect()
+}
+
+fn diverged(server: &Portfolio) -> (Portfolio, Portfolio) {
+    let mut mobile = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    apply_delta(&mut mobile, &export_delta(server, 0)).unwrap();
+    let mut server = server.clone();
+    server.sell_at(IBM, 4, usd(120)).unwrap();
+    mobile.purchase_at(AAPL, 2, usd(160)).unwrap();
+    (server, mobile)
+}
+
+#[rstest]
+fn merge_combines_divergent_journals(server: Portfolio) -> PortfolioResult<()> {
+    let (mut server, mobile) = diverged(&server);
+    let report = merge(&mut server, &mobile)?;
+    assert_eq!(report.merged, vec![3, 4]);
+    assert!(report.conflicts.is_empty());
+    assert!(server
+        .ledger()
+        .transactions()
+        .iter()
+        .any(|entry| matches!(entry, Transaction::Void(trade) if trade.record.id == 2)));
+    assert_eq!(server.get_share_count(IBM), 6);
+    assert_eq!(server.get_share_count(AAPL), 7);
+    assert!(server.verify_integrity().is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn merge_is_deterministic_in_either_direction(server: Portfolio) -> PortfolioResult<()> {
+    let (server, mobile) = diverged(&server);
+    let mut left = server.clone();
+    merge(&mut left, &mobile)?;
+    let mut right = mobile.clone();
+    merge(&mut right, &server)?;
+    assert_eq!(trades(&left), trades(&right));
+    Ok(())
+}
+
+#[rstest]
+fn merge_eliminates_duplicate_edits(server: Portfolio) -> PortfolioResult<()> {
+    let mut mobile = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    apply_delta(&mut mobile, &export_delta(&server, 0))?;
+    let mut server = server;
+    server.purchase_at(AAPL, 1, usd(150))?;
+    server.sell_at(IBM, 4, usd(120))?;
+    mobile.sell_at(IBM, 4, usd(120))?;
+    let report = merge(&mut server, &mobile)?;
+    assert_eq!(report.duplicates, 1);
+    assert_eq!(server.get_share_count(IBM), 6);
+    assert_eq!(server.get_share_count(AAPL), 6);
+    Ok(())
+}
+
+#[rstest]
+fn merge_reports_edits_that_cannot_both_apply(server: Portfolio) -> PortfolioResult<()> {
+    let mut mobile = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    apply_delta(&mut mobile, &export_delta(&server, 0))?;
+    let mut server = server;
+    server.sell_at(IBM, 8, usd(120))?;
+    mobile.sell_at(IBM, 6, usd(110))?;
+    let report = merge(&mut server, &mobile)?;
+    assert_eq!(report.merged, vec![3]);
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].record.shares, 8);
+    assert_eq!(server.get_share_count(IBM), 4);
+    assert!(server.verify_integrity().is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn merge_carries_tags_to_renumbered_transactions(server: Portfolio) -> PortfolioResult<()> {
+    let (server, mut mobile) = diverged(&server);
+    mobile.tag_transaction(2, "offline")?;
+    merge(&mut mobile, &server)?;
+    let (_, record) = mobile
+        .journal()
+        .into_iter()
+        .find(|(symbol, record)| *symbol == AAPL && record.shares == 2)
+        .unwrap();
+    assert_eq!(
+        mobile.transaction_tags(record.id).collect::<Vec<_>>(),
+        vec!["offline"]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "import")]
+fn diverged_with_metadata(server: &Portfolio) -> (Portfolio, Portfolio) {
+    let mut server = server.clone();
+    server.set_clock(FixedClock(noon(2025, 1, 1)));
+    let mut mobile = server.clone();
+    server
+        .receive_gift(IBM, 5, usd(40), at(2020, 1, 1), usd(90), at(2024, 1, 10))
+        .unwrap();
+    let inherited = mobile.inherit(AAPL, 4, usd(140), at(2024, 2, 1)).unwrap();
+    let broker = BrokerLot {
+        symbol: AAPL.to_string(),
+        acquired: date(2024, 2, 1),
+        shares: 4,
+        cost_basis: usd(600),
+        covered: true,
+    };
+    mobile
+        .apply_broker_basis(&[broker], BasisMode::Override)
+        .unwrap();
+    mobile
+        .record_rsu_vest(AAPL, 3, usd(155), at(2024, 3, 1))
+        .unwrap();
+    let lot = mobile
+        .open_lots(AAPL)
+        .iter()
+        .find(|lot| lot.sequence == inherited.transaction_id)
+        .unwrap()
+        .id;
+    let selection = LotSelection {
+        lot_id: lot,
+        shares: 2,
+    };
+    mobile
+        .sell_lots(AAPL, &[selection], usd(200), at(2024, 4, 1))
+        .unwrap();
+    (server, mobile)
+}
+
+#[cfg(feature = "import")]
+#[rstest]
+fn merge_keeps_metadata_of_renumbered_transactions(server: Portfolio) -> PortfolioResult<()> {
+    let (server, mobile) = diverged_with_metadata(&server);
+    let mut left = server.clone();
+    let mut right = mobile.clone();
+    for (local, remote) in [(&mut left, &mobile), (&mut right, &server)] {
+        let report = merge(local, remote)?;
+        assert!(report.conflicts.is_empty());
+        assert_eq!(report.merged, vec![5, 6, 7, 8]);
+        assert!(matches!(local.acquisition_of(5), Acquisition::Gift { .. }));
+        assert_eq!(local.acquisition_of(6), Acquisition::Inheritance);
+        assert_eq!(local.broker_basis_of(6).unwrap().cost_basis, usd(600));
+        assert_eq!(local.broker_basis_of(5), None);
+        assert_eq!(local.rsu_vest_income()?, usd(465));
+        assert_eq!(
+            local.lot_selection_of(8).unwrap()[0].sequence,
+            6,
+            "sale stays pinned to the inherited lot"
+        );
+        assert_eq!(local.realized_gains(AAPL)?.total_gain, usd(100));
+        assert!(local.verify_integrity().is_empty());
+    }
+    assert_eq!(trades(&left), trades(&right));
+    assert_eq!(left.lots, right.lots);
+    Ok(())
+}
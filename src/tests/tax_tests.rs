@@ -0,0 +1,282 @@
+use crate::config::TaxSettings;
+use crate::money::{Currency, Money};
+use crate::tax::*;
+use crate::tests::helpers::*;
+use crate::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use rstest::*;
+use rust_decimal::Decimal;
+
+fn percent(value: i64) -> Decimal {
+    Decimal::new(value, 2)
+}
+
+fn bracket(floor: i64, rate: i64) -> TaxBracket {
+    TaxBracket {
+        floor: Decimal::from(floor),
+        rate: percent(rate),
+    }
+}
+
+fn trade(
+    p: &mut Portfolio,
+    symbol: &str,
+    kind: TransactionType,
+    shares: u32,
+    price: i64,
+    at: DateTime<Utc>,
+) {
+    p.transact(symbol, shares, kind, Some(usd(price)), at)
+        .unwrap();
+}
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.set_clock(|| at(2024, 12, 1));
+    trade(
+        &mut p,
+        IBM,
+        TransactionType::Purchase,
+        10,
+        100,
+        at(2022, 1, 3),
+    );
+    trade(
+        &mut p,
+        IBM,
+        TransactionType::Purchase,
+        10,
+        150,
+        at(2024, 3, 1),
+    );
+    trade(&mut p, IBM, TransactionType::Sell, 15, 200, at(2024, 6, 3));
+    p
+}
+
+#[fixture]
+fn flat() -> TaxProfile {
+    TaxProfile::from(&TaxSettings::default())
+}
+
+#[rstest]
+fn flat_rates_apply_by_holding_term(portfolio: Portfolio, flat: TaxProfile) {
+    let estimate = estimate_liability(&portfolio, 2024, &flat).unwrap();
+    assert_eq!(estimate.short_term_gain, usd(250));
+    assert_eq!(estimate.long_term_gain, usd(1_000));
+    assert_eq!(estimate.ordinary_tax, usd(60));
+    assert_eq!(estimate.long_term_tax, usd(150));
+    assert_eq!(estimate.total, usd(210));
+}
+
+#[rstest]
+fn other_years_are_ignored(portfolio: Portfolio, flat: TaxProfile) {
+    let estimate = estimate_liability(&portfolio, 2023, &flat).unwrap();
+    assert_eq!(estimate.total, usd(0));
+}
+
+#[rstest]
+fn brackets_stack_long_term_gains_on_ordinary_income(portfolio: Portfolio) {
+    let profile = TaxProfile {
+        ordinary_brackets: vec![bracket(100, 20), bracket(0, 10)],
+        long_term_brackets: vec![bracket(0, 0), bracket(200, 15)],
+        other_ordinary_income: Decimal::from(50),
+    };
+    let estimate = estimate_liability(&portfolio, 2024, &profile).unwrap();
+    assert_eq!(estimate.ordinary_tax, usd(45));
+    assert_eq!(estimate.long_term_tax, usd(150));
+}
+
+#[rstest]
+fn short_term_losses_offset_long_term_gains(mut portfolio: Portfolio, flat: TaxProfile) {
+    trade(
+        &mut portfolio,
+        VTI,
+        TransactionType::Purchase,
+        10,
+        300,
+        at(2024, 7, 1),
+    );
+    trade(
+        &mut portfolio,
+        VTI,
+        TransactionType::Sell,
+        10,
+        250,
+        at(2024, 8, 1),
+    );
+    let estimate = estimate_liability(&portfolio, 2024, &flat).unwrap();
+    assert_eq!(estimate.short_term_gain, usd(-250));
+    assert_eq!(estimate.ordinary_tax, usd(0));
+    assert_eq!(
+        estimate.long_term_tax,
+        Money::new(Decimal::new(11250, 2), Currency::Usd)
+    );
+}
+
+#[rstest]
+fn distributions_and_vests_are_taxed(mut portfolio: Portfolio, flat: TaxProfile) {
+    portfolio
+        .record_capital_gain_distribution(IBM, usd(50), usd(100), at(2024, 9, 1))
+        .unwrap();
+    portfolio
+        .record_rsu_vest(VTI, 2, usd(100), at(2024, 10, 1))
+        .unwrap();
+    let estimate = estimate_liability(&portfolio, 2024, &flat).unwrap();
+    assert_eq!(estimate.ordinary_income, usd(200));
+    assert_eq!(estimate.ordinary_tax, usd(120));
+    assert_eq!(estimate.long_term_tax, usd(165));
+}
+
+#[rstest]
+fn dividends_and_lending_income_are_taxed(mut portfolio: Portfolio, flat: TaxProfile) {
+    portfolio
+        .record_dividend(IBM, usd(2), at(2024, 7, 1), Some(date(2024, 6, 20)))
+        .unwrap();
+    trade(
+        &mut portfolio,
+        VTI,
+        TransactionType::Purchase,
+        10,
+        100,
+        at(2024, 9, 3),
+    );
+    portfolio
+        .record_dividend(VTI, usd(1), at(2024, 10, 1), None)
+        .unwrap();
+    portfolio.lend_shares(IBM, 5).unwrap();
+    portfolio.accrue_lending_income(IBM, usd(20)).unwrap();
+    let estimate = estimate_liability(&portfolio, 2024, &flat).unwrap();
+    assert_eq!(estimate.qualified_dividends, usd(10));
+    assert_eq!(estimate.ordinary_income, usd(30));
+    assert_eq!(estimate.ordinary_tax, cents(6720));
+    assert_eq!(estimate.long_term_tax, cents(15150));
+}
+
+#[rstest]
+fn return_of_capital_gains_follow_the_lot_holding_period(
+    mut portfolio: Portfolio,
+    flat: TaxProfile,
+) {
+    trade(
+        &mut portfolio,
+        VTI,
+        TransactionType::Purchase,
+        10,
+        10,
+        at(2022, 1, 3),
+    );
+    trade(
+        &mut portfolio,
+        VTI,
+        TransactionType::Purchase,
+        10,
+        10,
+        at(2024, 6, 3),
+    );
+    portfolio
+        .apply_return_of_capital(VTI, usd(15), at(2024, 9, 3))
+        .unwrap();
+    let estimate = estimate_liability(&portfolio, 2024, &flat).unwrap();
+    assert_eq!(estimate.short_term_gain, usd(300));
+    assert_eq!(estimate.long_term_gain, usd(1_050));
+}
+
+#[rstest]
+fn form_8949_segments_lots_by_term(portfolio: Portfolio) {
+    let form = form_8949(&portfolio, 2024).unwrap();
+    assert_eq!(
+        form.rows,
+        vec![
+            Form8949Row {
+                form_box: Form8949Box::A,
+                symbol: IBM.to_string(),
+                shares: 5,
+                acquired: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                sold: NaiveDate::from_ymd_opt(2024, 6, 3).unwrap(),
+                proceeds: usd(1_000),
+                cost_basis: usd(750),
+                gain: usd(250),
+            },
+            Form8949Row {
+                form_box: Form8949Box::D,
+                symbol: IBM.to_string(),
+                shares: 10,
+                acquired: NaiveDate::from_ymd_opt(2022, 1, 3).unwrap(),
+                sold: NaiveDate::from_ymd_opt(2024, 6, 3).unwrap(),
+                proceeds: usd(2_000),
+                cost_basis: usd(1_000),
+                gain: usd(1_000),
+            },
+        ]
+    );
+}
+
+#[rstest]
+fn form_8949_reports_noncovered_lots_separately(mut portfolio: Portfolio) {
+    portfolio
+        .receive_gift(VTI, 10, usd(50), at(2020, 1, 2), usd(80), at(2024, 2, 1))
+        .unwrap();
+    trade(
+        &mut portfolio,
+        VTI,
+        TransactionType::Sell,
+        10,
+        100,
+        at(2024, 7, 1),
+    );
+    let form = form_8949(&portfolio, 2024).unwrap();
+    let noncovered: Vec<_> = form.rows_in(Form8949Box::E).collect();
+    assert_eq!(noncovered.len(), 1);
+    assert_eq!(noncovered[0].symbol, VTI);
+    assert_eq!(noncovered[0].gain, usd(500));
+}
+
+#[rstest]
+fn form_8949_exports_to_csv(portfolio: Portfolio) {
+    let csv = form_8949(&portfolio, 2024).unwrap().to_csv();
+    assert_eq!(
+        csv,
+        "box,description,date_acquired,date_sold,proceeds,cost_basis,gain\n\
+         A,5 sh IBM,2024-03-01,2024-06-03,1000,750,250\n\
+         D,10 sh IBM,2022-01-03,2024-06-03,2000,1000,1000\n"
+    );
+}
+
+#[rstest]
+fn fiscal_year_regroups_gains(mut portfolio: Portfolio, flat: TaxProfile) -> PortfolioResult<()> {
+    trade(
+        &mut portfolio,
+        VTI,
+        TransactionType::Purchase,
+        10,
+        100,
+        at(2024, 1, 2),
+    );
+    trade(
+        &mut portfolio,
+        VTI,
+        TransactionType::Sell,
+        10,
+        110,
+        at(2024, 3, 1),
+    );
+    portfolio.set_fiscal_year(4, 6)?;
+
+    let fiscal_2023 = estimate_liability(&portfolio, 2023, &flat)?;
+    assert_eq!(fiscal_2023.short_term_gain, usd(100));
+    let fiscal_2024 = estimate_liability(&portfolio, 2024, &flat)?;
+    assert_eq!(fiscal_2024.short_term_gain, usd(250));
+    assert_eq!(fiscal_2024.long_term_gain, usd(1_000));
+    assert_eq!(form_8949(&portfolio, 2023)?.rows.len(), 1);
+    Ok(())
+}
+
+#[rstest]
+fn set_fiscal_year_rejects_invalid_start(mut portfolio: Portfolio) {
+    assert!(matches!(
+        portfolio.set_fiscal_year(2, 29),
+        Err(PortfolioError::InvalidConfig(_))
+    ));
+    assert_eq!(portfolio.config().tax.fiscal_year, Default::default());
+}
@@ -0,0 +1,102 @@
+use crate::clock::FixedClock;
+#[cfg(feature = "import")]
+use crate::config::{DateGranularity, PortfolioConfig};
+#[cfg(feature = "import")]
+use crate::import::ImportOptions;
+#[cfg(feature = "import")]
+use crate::integrity::ImportedTransaction;
+use crate::tests::helpers::*;
+use crate::timestamps::*;
+use crate::*;
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use rstest::*;
+
+fn legacy(hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 3, 10)
+        .unwrap()
+        .and_hms_opt(hour, 30, 0)
+        .unwrap()
+}
+
+#[rstest]
+fn legacy_naive_times_are_read_as_utc() {
+    assert_eq!(
+        from_legacy_utc(legacy(9)),
+        Utc.with_ymd_and_hms(2024, 3, 10, 9, 30, 0).unwrap()
+    );
+}
+
+#[rstest]
+fn legacy_local_times_convert_through_their_zone() -> PortfolioResult<()> {
+    let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+    assert_eq!(
+        from_legacy_local(legacy(9), &tokyo)?,
+        Utc.with_ymd_and_hms(2024, 3, 10, 0, 30, 0).unwrap()
+    );
+    Ok(())
+}
+
+#[rstest]
+fn records_are_stamped_in_utc() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    portfolio.purchase(IBM, 1)?;
+    let record = &portfolio.get_purchase_record(IBM)?[0];
+    assert_eq!(record.date.timezone(), Utc);
+    assert_eq!(record.date.timestamp_millis(), 0);
+    Ok(())
+}
+
+#[cfg(feature = "import")]
+#[rstest]
+fn daily_granularity_keys_records_on_date_with_sequence() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_config(PortfolioConfig {
+        date_granularity: DateGranularity::Daily,
+        ..PortfolioConfig::default()
+    });
+    let afternoon = NaiveDate::from_ymd_opt(2024, 1, 2)
+        .unwrap()
+        .and_hms_opt(15, 45, 0)
+        .unwrap()
+        .and_utc();
+    let trades = vec![
+        ImportedTransaction {
+            symbol: IBM.to_string(),
+            date: afternoon,
+            transaction_type: TransactionType::Purchase,
+            shares: 10,
+            price: None,
+            net_amount: None,
+        },
+        ImportedTransaction {
+            symbol: IBM.to_string(),
+            date: afternoon - chrono::Duration::hours(6),
+            transaction_type: TransactionType::Sell,
+            shares: 4,
+            price: None,
+            net_amount: None,
+        },
+    ];
+    portfolio.import(trades, &ImportOptions::default())?;
+    let records = portfolio.records_in_order(IBM);
+    assert_eq!(
+        records
+            .iter()
+            .map(|record| (portfolio.format_record_date(record), record.day_sequence))
+            .collect::<Vec<_>>(),
+        vec![("2024-01-02".to_string(), 0), ("2024-01-02".to_string(), 1)]
+    );
+    assert_eq!(records[1].transaction_type, TransactionType::Sell);
+    Ok(())
+}
+
+#[rstest]
+fn timestamp_granularity_keeps_time_of_day() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::with_clock(FixedClock(Portfolio::fixed_date_time()));
+    portfolio.purchase(IBM, 1)?;
+    let record = &portfolio.get_purchase_record(IBM)?[0];
+    assert_eq!(
+        portfolio.format_record_date(record),
+        "1970-01-01T00:00:00+00:00"
+    );
+    Ok(())
+}
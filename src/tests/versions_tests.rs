@@ -0,0 +1,59 @@
+use crate::events::PortfolioEvent;
+use crate::tests::helpers::*;
+use crate::*;
+use rstest::*;
+
+fn transaction_ids(portfolio: &Portfolio, since: versions::Version) -> Vec<TransactionId> {
+    portfolio
+        .changes_since(since)
+        .iter()
+        .filter_map(|change| match change.event {
+            PortfolioEvent::Transaction { transaction_id, .. } => Some(transaction_id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[rstest]
+fn new_portfolio_starts_at_version_zero() {
+    let portfolio = Portfolio::new();
+    assert_eq!(portfolio.version(), 0);
+    assert!(portfolio.changes_since(0).is_empty());
+}
+
+#[rstest]
+fn every_transaction_bumps_the_version() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.purchase_at(IBM, 10, usd(100))?;
+    let checkpoint = portfolio.version();
+    portfolio.sell_at(IBM, 2, usd(110))?;
+    portfolio.sell_at(IBM, 3, usd(120))?;
+    assert_eq!(portfolio.version(), checkpoint + 2);
+    assert_eq!(transaction_ids(&portfolio, checkpoint), vec![1, 2]);
+    assert_eq!(transaction_ids(&portfolio, 0), vec![0, 1, 2]);
+    assert!(portfolio.changes_since(portfolio.version()).is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn ledger_entries_are_recorded_as_changes() -> PortfolioResult<()> {
+    let mut portfolio = Portfolio::new();
+    portfolio.purchase_at(IBM, 10, usd(100))?;
+    let checkpoint = portfolio.version();
+    portfolio.lend_shares(IBM, 5)?;
+    portfolio.recall_shares(IBM, 5)?;
+    assert_eq!(portfolio.version(), checkpoint + 2);
+    assert!(portfolio
+        .changes_since(checkpoint)
+        .iter()
+        .all(|change| change.event.kind() == "ledger_entry"));
+    assert_eq!(portfolio.changes_since(checkpoint).len(), 2);
+    Ok(())
+}
+
+#[rstest]
+fn failed_mutations_leave_the_version_unchanged() {
+    let mut portfolio = Portfolio::new();
+    assert!(portfolio.sell(IBM, 1).is_err());
+    assert_eq!(portfolio.version(), 0);
+}
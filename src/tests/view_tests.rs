@@ -0,0 +1,49 @@
+use crate::money::Money;
+use crate::position::Position;
+use crate::tests::helpers::*;
+use crate::view::PortfolioView;
+use crate::Quotes;
+use crate::*;
+use rstest::*;
+
+#[fixture]
+fn portfolio() -> Portfolio {
+    let mut p = Portfolio::new();
+    p.purchase_at(IBM, 10, usd(100)).unwrap();
+    p.purchase_at(AAPL, 3, usd(150)).unwrap();
+    p.sell_at(AAPL, 3, usd(160)).unwrap();
+    p
+}
+
+fn value_of(view: PortfolioView<'_>) -> Money {
+    view.market_value(&Quotes::from([(IBM.to_string(), usd(120))]))
+        .unwrap()
+}
+
+#[rstest]
+fn exposes_open_holdings_in_symbol_order(portfolio: Portfolio) {
+    let view = portfolio.read_view();
+    assert_eq!(view.holdings(), vec![(IBM, Position::Long(10))]);
+    assert_eq!(view.traded_symbols(), vec![AAPL, IBM]);
+    assert_eq!(view.get_share_count(IBM), 10);
+}
+
+#[rstest]
+fn exposes_records_and_valuations(portfolio: Portfolio) -> PortfolioResult<()> {
+    let view = portfolio.read_view();
+    assert_eq!(view.get_purchase_record(AAPL)?.len(), 2);
+    assert_eq!(view.journal().len(), 3);
+    assert_eq!(view.iter_lots(IBM).count(), 1);
+    assert_eq!(value_of(view), usd(1200));
+    assert!(view.verify_integrity().is_empty());
+    Ok(())
+}
+
+#[rstest]
+fn view_outlives_its_own_handle(portfolio: Portfolio) {
+    let records = {
+        let view = portfolio.read_view();
+        view.records_in_order(IBM)
+    };
+    assert_eq!(records[0].shares, 10);
+}
@@ -0,0 +1,150 @@
+use crate::events::PortfolioEvent;
+use crate::tests::helpers::*;
+use crate::webhooks::*;
+use crate::*;
+use rstest::*;
+use std::cell::RefCell;
+use std::time::Duration;
+
+const HOOK: &str = "https://example.test/hooks/portfolio";
+
+struct Request {
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+#[derive(Default)]
+struct RecordingTransport {
+    statuses: RefCell<Vec<Result<u16, String>>>,
+    requests: RefCell<Vec<Request>>,
+}
+
+impl RecordingTransport {
+    fn responding(statuses: Vec<Result<u16, String>>) -> Self {
+        Self {
+            statuses: RefCell::new(statuses),
+            ..Self::default()
+        }
+    }
+}
+
+impl WebhookTransport for &RecordingTransport {
+    fn post(&self, url: &str, headers: &[(&str, String)], body: &[u8]) -> Result<u16, String> {
+        self.requests.borrow_mut().push(Request {
+            url: url.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+            body: body.to_vec(),
+        });
+        self.statuses.borrow_mut().remove(0)
+    }
+}
+
+fn no_sleep(_: Duration) {}
+
+fn alert() -> PortfolioEvent {
+    PortfolioEvent::Alert {
+        symbol: IBM.to_string(),
+        message: "down 10%".to_string(),
+    }
+}
+
+#[rstest]
+fn delivers_signed_json_payload() {
+    let transport = RecordingTransport::responding(vec![Ok(204)]);
+    let mut dispatcher = WebhookDispatcher::new(&transport);
+    dispatcher.register(HOOK, "s3cret").unwrap();
+    let reports = dispatcher.deliver(&alert());
+    assert!(reports[0].delivered);
+    assert_eq!(reports[0].attempts, 1);
+
+    let requests = transport.requests.borrow();
+    let request = &requests[0];
+    assert_eq!(request.url, HOOK);
+    assert_eq!(
+        String::from_utf8(request.body.clone()).unwrap(),
+        r#"{"type":"alert","symbol":"IBM","message":"down 10%"}"#
+    );
+    assert!(request
+        .headers
+        .contains(&(SIGNATURE_HEADER.to_string(), sign("s3cret", &request.body))));
+}
+
+#[rstest]
+fn signature_is_hex_hmac_sha256() {
+    assert_eq!(
+        sign("key", b"The quick brown fox jumps over the lazy dog"),
+        "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+}
+
+#[rstest]
+fn retries_failed_deliveries_with_backoff() {
+    let transport =
+        RecordingTransport::responding(vec![Err("timeout".to_string()), Ok(503), Ok(200)]);
+    let mut dispatcher = WebhookDispatcher::new(&transport).with_retry_policy(
+        RetryPolicy {
+            max_attempts: 3,
+            ..RetryPolicy::default()
+        },
+        no_sleep,
+    );
+    dispatcher.register(HOOK, "s3cret").unwrap();
+    let report = &dispatcher.deliver(&alert())[0];
+    assert!(report.delivered);
+    assert_eq!(report.attempts, 3);
+}
+
+#[rstest]
+fn gives_up_after_max_attempts() {
+    let transport = RecordingTransport::responding(vec![Ok(500), Ok(502)]);
+    let mut dispatcher = WebhookDispatcher::new(&transport).with_retry_policy(
+        RetryPolicy {
+            max_attempts: 2,
+            ..RetryPolicy::default()
+        },
+        no_sleep,
+    );
+    dispatcher.register(HOOK, "s3cret").unwrap();
+    let report = &dispatcher.deliver(&alert())[0];
+    assert!(!report.delivered);
+    assert_eq!(report.last_error.as_deref(), Some("HTTP 502"));
+}
+
+#[rstest]
+fn backoff_grows_geometrically() {
+    let policy = RetryPolicy::default();
+    assert_eq!(policy.backoff(0), Duration::from_millis(500));
+    assert_eq!(policy.backoff(2), Duration::from_millis(2000));
+}
+
+#[rstest]
+fn delivers_events_from_subscription() -> PortfolioResult<()> {
+    let transport = RecordingTransport::responding(vec![Ok(200)]);
+    let mut dispatcher = WebhookDispatcher::new(&transport);
+    dispatcher.register(HOOK, "s3cret")?;
+    let mut portfolio = Portfolio::new();
+    let events = portfolio.subscribe();
+    portfolio.purchase(IBM, 1)?;
+    let reports = dispatcher.deliver_pending(&events);
+    assert_eq!(reports.len(), 1);
+    let body = &transport.requests.borrow()[0].body;
+    assert!(String::from_utf8(body.clone())
+        .unwrap()
+        .starts_with(r#"{"type":"transaction","transaction_id":0,"symbol":"IBM""#));
+    Ok(())
+}
+
+#[rstest]
+fn rejects_plain_http_endpoints() {
+    let transport = RecordingTransport::default();
+    let mut dispatcher = WebhookDispatcher::new(&transport);
+    assert!(matches!(
+        dispatcher.register("http://example.test/hook", "s3cret"),
+        Err(PortfolioError::InvalidWebhookUrl(_))
+    ));
+    assert!(dispatcher.endpoints().is_empty());
+}
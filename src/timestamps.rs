@@ -0,0 +1,16 @@
+use crate::{PortfolioError, PortfolioResult};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+pub fn from_legacy_utc(naive: NaiveDateTime) -> DateTime<Utc> {
+    naive.and_utc()
+}
+
+pub fn from_legacy_local<Tz: TimeZone>(
+    naive: NaiveDateTime,
+    zone: &Tz,
+) -> PortfolioResult<DateTime<Utc>> {
+    zone.from_local_datetime(&naive)
+        .single()
+        .map(|local| local.with_timezone(&Utc))
+        .ok_or_else(|| PortfolioError::AmbiguousLocalTime(naive.to_string()))
+}
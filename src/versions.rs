@@ -0,0 +1,38 @@
+use crate::events::PortfolioEvent;
+use crate::Portfolio;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub type Version = u64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct VersionedEvent {
+    pub version: Version,
+    pub event: PortfolioEvent,
+}
+
+impl Portfolio {
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    pub fn changes_since(&self, version: Version) -> &[VersionedEvent] {
+        let start = self
+            .changes
+            .partition_point(|change| change.version <= version);
+        &self.changes[start..]
+    }
+
+    pub(crate) fn bump_version(&mut self) {
+        self.version += 1;
+    }
+
+    pub(crate) fn record_change(&mut self, event: &PortfolioEvent) {
+        self.bump_version();
+        self.changes.push(VersionedEvent {
+            version: self.version,
+            event: event.clone(),
+        });
+    }
+}
@@ -0,0 +1,107 @@
+use crate::alerts::Alert;
+use crate::goals::Goal;
+use crate::integrity::IntegrityIssue;
+use crate::lots::LotId;
+use crate::money::Money;
+use crate::position::Position;
+use crate::reversal::Reversal;
+use crate::Quotes;
+use crate::{Portfolio, PortfolioResult, PurchaseRecord};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+#[derive(Clone, Copy)]
+pub struct PortfolioView<'a> {
+    portfolio: &'a Portfolio,
+}
+
+impl<'a> PortfolioView<'a> {
+    pub fn is_empty(&self) -> bool {
+        self.portfolio.is_empty()
+    }
+
+    pub fn holdings(&self) -> Vec<(&'a str, Position)> {
+        let mut holdings: Vec<(&str, Position)> = self
+            .portfolio
+            .holdings
+            .iter()
+            .map(|(symbol, position)| (symbol.as_str(), *position))
+            .filter(|(_, position)| *position != Position::Flat)
+            .collect();
+        holdings.sort_unstable_by_key(|(symbol, _)| *symbol);
+        holdings
+    }
+
+    pub fn get_share_count(&self, symbol: &str) -> u32 {
+        self.portfolio.get_share_count(symbol)
+    }
+
+    pub fn get_share_count_as_of(&self, symbol: &str, date: NaiveDate) -> u32 {
+        self.portfolio.get_share_count_as_of(symbol, date)
+    }
+
+    pub fn get_position(&self, symbol: &str) -> Position {
+        self.portfolio.get_position(symbol)
+    }
+
+    pub fn get_available_shares(&self, symbol: &str) -> u32 {
+        self.portfolio.get_available_shares(symbol)
+    }
+
+    pub fn total_share_count(&self) -> u128 {
+        self.portfolio.total_share_count()
+    }
+
+    pub fn traded_symbols(&self) -> Vec<&'a str> {
+        self.portfolio.traded_symbols()
+    }
+
+    pub fn get_purchase_record(&self, symbol: &str) -> PortfolioResult<&'a [PurchaseRecord]> {
+        self.portfolio.get_purchase_record(symbol)
+    }
+
+    pub fn records_in_order(&self, symbol: &str) -> Vec<&'a PurchaseRecord> {
+        self.portfolio.records_in_order(symbol)
+    }
+
+    pub fn journal(&self) -> Vec<(&'a str, &'a PurchaseRecord)> {
+        self.portfolio.journal()
+    }
+
+    pub fn iter_lots(
+        &self,
+        symbol: &str,
+    ) -> impl Iterator<Item = (LotId, NaiveDate, u32, Decimal)> + 'a {
+        self.portfolio.iter_lots(symbol)
+    }
+
+    pub fn market_value(&self, quotes: &Quotes) -> PortfolioResult<Money> {
+        self.portfolio.market_value(quotes)
+    }
+
+    pub fn total_lending_income(&self) -> PortfolioResult<Money> {
+        self.portfolio.total_lending_income()
+    }
+
+    pub fn reversals(&self) -> &'a [Reversal] {
+        self.portfolio.reversals()
+    }
+
+    pub fn goals(&self) -> &'a [Goal] {
+        self.portfolio.goals()
+    }
+
+    pub fn alerts(&self) -> &'a [Alert] {
+        self.portfolio.alerts()
+    }
+
+    pub fn verify_integrity(&self) -> Vec<IntegrityIssue> {
+        self.portfolio.verify_integrity()
+    }
+}
+
+impl Portfolio {
+    pub fn read_view(&self) -> PortfolioView<'_> {
+        PortfolioView { portfolio: self }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::events::PortfolioEvent;
+use crate::{PortfolioError, PortfolioResult};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt::Write;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+pub const SIGNATURE_HEADER: &str = "X-Portfolio-Signature";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    pub secret: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(self.multiplier.saturating_pow(retry))
+    }
+}
+
+pub trait WebhookTransport {
+    fn post(&self, url: &str, headers: &[(&str, String)], body: &[u8]) -> Result<u16, String>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub url: String,
+    pub attempts: u32,
+    pub delivered: bool,
+    pub last_error: Option<String>,
+}
+
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let mut signature = String::from("sha256=");
+    for byte in digest {
+        write!(signature, "{byte:02x}").unwrap();
+    }
+    signature
+}
+
+pub struct WebhookDispatcher<T: WebhookTransport> {
+    endpoints: Vec<WebhookEndpoint>,
+    retry: RetryPolicy,
+    transport: T,
+    sleep: fn(Duration),
+}
+
+impl<T: WebhookTransport> WebhookDispatcher<T> {
+    pub fn new(transport: T) -> Self {
+        Self {
+            endpoints: Vec::new(),
+            retry: RetryPolicy::default(),
+            transport,
+            sleep: std::thread::sleep,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy, sleep: fn(Duration)) -> Self {
+        self.retry = retry;
+        self.sleep = sleep;
+        self
+    }
+
+    pub fn register(&mut self, url: &str, secret: &str) -> PortfolioResult<()> {
+        if !url.starts_with("https://") {
+            return Err(PortfolioError::InvalidWebhookUrl(url.to_string()));
+        }
+        self.endpoints.push(WebhookEndpoint {
+            url: url.to_string(),
+            secret: secret.to_string(),
+        });
+        Ok(())
+    }
+
+    pub fn endpoints(&self) -> &[WebhookEndpoint] {
+        &self.endpoints
+    }
+
+    pub fn deliver(&self, event: &PortfolioEvent) -> Vec<DeliveryReport> {
+        let body = serde_json::to_vec(event).expect("portfolio events serialize to JSON");
+        self.endpoints
+            .iter()
+            .map(|endpoint| self.deliver_to(endpoint, &body))
+            .collect()
+    }
+
+    pub fn deliver_pending(&self, events: &Receiver<PortfolioEvent>) -> Vec<DeliveryReport> {
+        events
+            .try_iter()
+            .flat_map(|event| self.deliver(&event))
+            .collect()
+    }
+
+    fn deliver_to(&self, endpoint: &WebhookEndpoint, body: &[u8]) -> DeliveryReport {
+        let headers = [
+            ("Content-Type", "application/json".to_string()),
+            (SIGNATURE_HEADER, sign(&endpoint.secret, body)),
+        ];
+        let mut report = DeliveryReport {
+            url: endpoint.url.clone(),
+            attempts: 0,
+            delivered: false,
+            last_error: None,
+        };
+        while report.attempts < self.retry.max_attempts {
+            if report.attempts > 0 {
+                (self.sleep)(self.retry.backoff(report.attempts - 1));
+            }
+            report.attempts += 1;
+            match self.transport.post(&endpoint.url, &headers, body) {
+                Ok(status) if (200..300).contains(&status) => {
+                    report.delivered = true;
+                    report.last_error = None;
+                    break;
+                }
+                Ok(status) => report.last_error = Some(format!("HTTP {status}")),
+                Err(error) => report.last_error = Some(error),
+            }
+        }
+        report
+    }
+}